@@ -0,0 +1,16 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Bearer token providers for services that use `@httpBearerAuth`
+//!
+//! These providers implement [`ProvideToken`](aws_credential_types::provider::token::ProvideToken),
+//! so they can be combined with other token providers in a
+//! [`TokenProviderChain`](crate::meta::token::TokenProviderChain), and the tokens they load are
+//! cached by the client's identity cache until they expire.
+
+pub mod client_credentials;
+pub mod file;
+#[cfg(feature = "credentials-process")]
+pub mod process;
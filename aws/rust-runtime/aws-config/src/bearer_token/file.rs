@@ -0,0 +1,198 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Bearer token provider that reads the token from a file
+
+use crate::provider_config::ProviderConfig;
+use aws_credential_types::provider::{error::TokenError, future, token::ProvideToken};
+use aws_credential_types::Token;
+use aws_smithy_async::time::SharedTimeSource;
+use aws_types::os_shim_internal::Fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Bearer token provider that reads the token from a file
+///
+/// The file is expected to contain only the token, and leading or trailing whitespace is
+/// ignored. This suits tokens that are written to disk and rotated by another process, such as a
+/// sidecar or a mounted secret.
+///
+/// Tokens loaded from the file are given an expiration of [`refresh_interval`](Builder::refresh_interval)
+/// after they were read, so the identity cache rereads the file on that interval and picks up
+/// a rotated token without restarting the client.
+///
+/// If the file doesn't exist, this provider returns [`TokenError::TokenNotLoaded`] so that a
+/// [`TokenProviderChain`](crate::meta::token::TokenProviderChain) moves on to the next provider.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::bearer_token::file::TokenFileProvider;
+/// use std::time::Duration;
+///
+/// let provider = TokenFileProvider::builder()
+///     .path("/var/run/secrets/service-token")
+///     .refresh_interval(Duration::from_secs(30))
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct TokenFileProvider {
+    path: PathBuf,
+    refresh_interval: Duration,
+    fs: Fs,
+    time_source: SharedTimeSource,
+}
+
+impl TokenFileProvider {
+    /// Creates a new builder for [`TokenFileProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn token(&self) -> Result<Token, TokenError> {
+        let contents = match self.fs.read_to_end(&self.path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Err(TokenError::not_loaded(format!(
+                    "token file `{}` does not exist",
+                    self.path.display()
+                )))
+            }
+            Err(err) => return Err(TokenError::provider_error(err)),
+        };
+        let contents = String::from_utf8(contents)
+            .map_err(|_| TokenError::provider_error("the token file was not valid UTF-8"))?;
+        let token = contents.trim();
+        if token.is_empty() {
+            return Err(TokenError::provider_error("the token file was empty"));
+        }
+        tracing::debug!(path = %self.path.display(), "loaded bearer token from file");
+        Ok(Token::new(
+            token,
+            Some(self.time_source.now() + self.refresh_interval),
+        ))
+    }
+}
+
+impl ProvideToken for TokenFileProvider {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideToken::new(self.token())
+    }
+}
+
+/// Builder for [`TokenFileProvider`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    path: Option<PathBuf>,
+    refresh_interval: Option<Duration>,
+    provider_config: Option<ProviderConfig>,
+}
+
+impl Builder {
+    /// Set the path of the file containing the token
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Set how often the file is reread to pick up a rotated token
+    ///
+    /// Defaults to 60 seconds.
+    pub fn refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = Some(refresh_interval);
+        self
+    }
+
+    /// Override the configuration used for this provider
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Create a [`TokenFileProvider`] from this builder.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if no path was set.
+    pub fn build(self) -> TokenFileProvider {
+        let provider_config = self.provider_config.unwrap_or_default();
+        TokenFileProvider {
+            path: self
+                .path
+                .expect("a path is required to build a TokenFileProvider"),
+            refresh_interval: self.refresh_interval.unwrap_or(DEFAULT_REFRESH_INTERVAL),
+            fs: provider_config.fs(),
+            time_source: provider_config.time_source(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aws_smithy_async::time::StaticTimeSource;
+    use std::time::UNIX_EPOCH;
+
+    fn provider(fs: Fs) -> TokenFileProvider {
+        TokenFileProvider::builder()
+            .path("/token")
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_fs(fs)
+                    .with_time_source(StaticTimeSource::new(UNIX_EPOCH)),
+            )
+            .build()
+    }
+
+    #[tokio::test]
+    async fn loads_token_with_refresh_expiration() {
+        let token = provider(Fs::from_slice(&[("/token", "  secret-token\n")]))
+            .provide_token()
+            .await
+            .expect("valid token");
+        assert_eq!("secret-token", token.token());
+        assert_eq!(
+            Some(UNIX_EPOCH + DEFAULT_REFRESH_INTERVAL),
+            token.expiration()
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_file_is_not_loaded() {
+        let err = provider(Fs::from_slice(&[]))
+            .provide_token()
+            .await
+            .expect_err("no token file");
+        assert!(matches!(err, TokenError::TokenNotLoaded(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn empty_file_is_an_error() {
+        let err = provider(Fs::from_slice(&[("/token", "\n")]))
+            .provide_token()
+            .await
+            .expect_err("empty token file");
+        assert!(matches!(err, TokenError::ProviderError(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn rereads_rotated_tokens() {
+        let path = std::env::temp_dir().join(format!("token-file-test-{}", std::process::id()));
+        std::fs::write(&path, "first").unwrap();
+        let provider = TokenFileProvider::builder().path(&path).build();
+        assert_eq!("first", provider.provide_token().await.unwrap().token());
+
+        std::fs::write(&path, "second").unwrap();
+        assert_eq!("second", provider.provide_token().await.unwrap().token());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
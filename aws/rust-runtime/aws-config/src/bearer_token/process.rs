@@ -0,0 +1,262 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Bearer token provider that runs an external process
+
+use crate::credential_process::parse_expiration;
+use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
+use crate::provider_config::ProviderConfig;
+use crate::sensitive_command::CommandWithSensitiveArgs;
+use aws_credential_types::provider::{error::TokenError, future, token::ProvideToken};
+use aws_credential_types::Token;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_json::deserialize::Token as JsonToken;
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+
+/// Bearer token provider that runs an external process
+///
+/// The external process must exit with status 0 and output the following JSON format to
+/// `stdout` to provide a token:
+///
+/// ```json
+/// {
+///     "Token": "the bearer token",
+///     "Expiration": "time that the token will expire"
+/// }
+/// ```
+///
+/// `Token` is required. `Expiration` is optional, and must be given in the RFC 3339 date time
+/// format (e.g., `2022-05-26T12:34:56.789Z`). Keys are case insensitive. Tokens with an
+/// `Expiration` are cached by the identity cache until they are about to expire, at which point
+/// the process is run again.
+///
+/// If the external process exits with a non-zero status, then the contents of `stderr`
+/// will be included in the error.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::bearer_token::process::TokenProcessProvider;
+///
+/// let provider = TokenProcessProvider::new("/usr/local/bin/get-token --audience my-service");
+/// ```
+#[derive(Debug)]
+pub struct TokenProcessProvider {
+    command: CommandWithSensitiveArgs<String>,
+    timeout: Option<Duration>,
+    sleep_impl: Option<SharedAsyncSleep>,
+}
+
+impl TokenProcessProvider {
+    /// Create new [`TokenProcessProvider`] with the `command` needed to execute the external process.
+    pub fn new(command: impl Into<String>) -> Self {
+        Self::builder().command(command).build()
+    }
+
+    /// Creates a new builder for [`TokenProcessProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn token(&self) -> Result<Token, TokenError> {
+        // Security: command arguments must be redacted at debug level
+        tracing::debug!(command = %self.command, "loading bearer token from external process");
+
+        let output = match self.timeout {
+            Some(timeout) => {
+                let sleep_impl = self.sleep_impl.as_ref().ok_or_else(|| {
+                    TokenError::invalid_configuration(
+                        "a timeout was configured for the external process, but no async sleep implementation is available",
+                    )
+                })?;
+                Timeout::new(self.run(), sleep_impl.sleep(timeout))
+                    .await
+                    .map_err(|_| TokenError::provider_timed_out(timeout))?
+            }
+            None => self.run().await,
+        }?;
+
+        parse_token_process_output(&output).map_err(TokenError::provider_error)
+    }
+
+    /// Runs the process and returns its `stdout`.
+    ///
+    /// The process is killed if the returned future is dropped before it exits.
+    async fn run(&self) -> Result<String, TokenError> {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd.exe");
+            command.args(["/C", self.command.unredacted()]);
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.args(["-c", self.command.unredacted()]);
+            command
+        };
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let Output {
+            status,
+            stdout,
+            stderr,
+        } = tokio::process::Command::from(command)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(TokenError::provider_error)?
+            .wait_with_output()
+            .await
+            .map_err(TokenError::provider_error)?;
+
+        // Security: command arguments can be logged at trace level
+        tracing::trace!(command = ?self.command, status = ?status, "executed command (unredacted)");
+
+        if !status.success() {
+            return Err(TokenError::provider_error(format!(
+                "Error retrieving token: external process exited with code {}. Stderr: {}",
+                status,
+                String::from_utf8_lossy(&stderr)
+            )));
+        }
+        String::from_utf8(stdout).map_err(|_| {
+            TokenError::unhandled("the output of the external process was not valid UTF-8")
+        })
+    }
+}
+
+impl ProvideToken for TokenProcessProvider {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideToken::new(self.token())
+    }
+}
+
+/// Builder for [`TokenProcessProvider`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    command: Option<CommandWithSensitiveArgs<String>>,
+    provider_config: Option<ProviderConfig>,
+    timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Set the command used to run the external process
+    ///
+    /// The command is run with `sh -c`, or `cmd.exe /C` on Windows.
+    pub fn command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(CommandWithSensitiveArgs::new(command.into()));
+        self
+    }
+
+    /// Override the configuration used for this provider
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Set the maximum amount of time the external process may run for
+    ///
+    /// The process is killed if it doesn't exit within this time. By default, there is no
+    /// timeout other than the one applied by the identity cache.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Create a [`TokenProcessProvider`] from this builder.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if no command was set.
+    pub fn build(self) -> TokenProcessProvider {
+        let provider_config = self.provider_config.unwrap_or_default();
+        TokenProcessProvider {
+            command: self
+                .command
+                .expect("a command is required to build a TokenProcessProvider"),
+            timeout: self.timeout,
+            sleep_impl: provider_config.sleep_impl(),
+        }
+    }
+}
+
+/// Deserialize the token printed by the external process
+fn parse_token_process_output(output: &str) -> Result<Token, InvalidJsonCredentials> {
+    let mut token = None;
+    let mut expiration = None;
+    json_parse_loop(output.as_bytes(), |key, value| {
+        match (key, value) {
+            (key, JsonToken::ValueString { value, .. }) if key.eq_ignore_ascii_case("Token") => {
+                token = Some(value.to_unescaped()?.into_owned())
+            }
+            (key, JsonToken::ValueString { value, .. })
+                if key.eq_ignore_ascii_case("Expiration") =>
+            {
+                expiration = Some(value.to_unescaped()?.into_owned())
+            }
+            _ => {}
+        };
+        Ok(())
+    })?;
+    let token = token.ok_or(InvalidJsonCredentials::MissingField("Token"))?;
+    let expiration = expiration.map(parse_expiration).transpose()?;
+    Ok(Token::new(token, expiration))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // TODO(https://github.com/awslabs/aws-sdk-rust/issues/1117) This test is ignored on Windows because it uses Unix-style paths
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn loads_token_with_expiration() {
+        let provider = TokenProcessProvider::new(
+            r#"echo '{ "Token": "secret-token", "Expiration": "2016-02-25T06:03:31Z" }'"#,
+        );
+        let token = provider.provide_token().await.expect("valid token");
+        assert_eq!("secret-token", token.token());
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(1456380211)),
+            token.expiration()
+        );
+    }
+
+    #[test]
+    fn parses_tokens_without_expiration() {
+        let token = parse_token_process_output(r#"{ "token": "secret-token", "Other": 1 }"#)
+            .expect("valid token");
+        assert_eq!("secret-token", token.token());
+        assert_eq!(None::<SystemTime>, token.expiration());
+
+        assert!(parse_token_process_output(r#"{ "Expiration": "2016-02-25T06:03:31Z" }"#).is_err());
+        assert!(
+            parse_token_process_output(r#"{ "Token": "secret-token", "Expiration": "soon" }"#)
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn includes_stderr_when_process_fails() {
+        let provider = TokenProcessProvider::new("echo 'not logged in' >&2; exit 3");
+        let err = provider.provide_token().await.expect_err("process failed");
+        assert!(matches!(err, TokenError::ProviderError(_)), "{:?}", err);
+        assert!(
+            format!(
+                "{}",
+                aws_smithy_types::error::display::DisplayErrorContext(&err)
+            )
+            .contains("not logged in"),
+            "{:?}",
+            err
+        );
+    }
+}
@@ -8,14 +8,24 @@
 //! Credentials Provider for external process
 
 use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
+use crate::provider_config::ProviderConfig;
 use crate::sensitive_command::CommandWithSensitiveArgs;
 use aws_credential_types::provider::{self, error::CredentialsError, future, ProvideCredentials};
 use aws_credential_types::Credentials;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::SharedTimeSource;
 use aws_smithy_json::deserialize::Token;
-use std::process::Command;
-use std::time::SystemTime;
+use std::error::Error;
+use std::fmt;
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::time::{Duration, SystemTime};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
+use tokio::sync::Mutex;
+
+/// Cached credentials are discarded once they are this close to expiring.
+const CACHE_EXPIRY_BUFFER: Duration = Duration::from_secs(5 * 60);
 
 /// External process credentials provider
 ///
@@ -41,7 +51,11 @@ use time::OffsetDateTime;
 /// `2022-05-26T12:34:56.789Z`).
 ///
 /// If the external process exits with a non-zero status, then the contents of `stderr`
-/// will be output as part of the credentials provider error message.
+/// will be output as part of the credentials provider error message. See [`StderrBehavior`]
+/// for passing `stderr` through to the terminal instead.
+///
+/// Credentials with an `Expiration` are cached, and the process is not run again until they
+/// are about to expire. Credentials without an `Expiration` are never cached by this provider.
 ///
 /// This credentials provider is included in the profile credentials provider, and can be
 /// configured using the `credential_process` attribute. For example:
@@ -50,9 +64,28 @@ use time::OffsetDateTime;
 /// [profile example]
 /// credential_process = /path/to/my/process --some --arguments
 /// ```
+///
+/// # Interactive processes
+///
+/// Processes that prompt for input, such as an MFA code from a hardware token, need both
+/// [`inherit_stdin`](Builder::inherit_stdin) and [`StderrBehavior::Inherit`] so that the user
+/// can see the prompt and answer it. Since the identity cache gives up on loading credentials
+/// after five seconds by default, such processes also need a larger
+/// [`load_timeout`](crate::identity::LazyCacheBuilder::load_timeout).
+///
+/// For a `credential_process` in a profile, the same options are set with
+/// [`credential_process_stderr`](crate::profile::credentials::Builder::credential_process_stderr),
+/// [`credential_process_inherit_stdin`](crate::profile::credentials::Builder::credential_process_inherit_stdin)
+/// and [`credential_process_timeout`](crate::profile::credentials::Builder::credential_process_timeout).
 #[derive(Debug)]
 pub struct CredentialProcessProvider {
     command: CommandWithSensitiveArgs<String>,
+    stderr: StderrBehavior,
+    inherit_stdin: bool,
+    timeout: Option<Duration>,
+    time_source: SharedTimeSource,
+    sleep_impl: Option<SharedAsyncSleep>,
+    cached: Mutex<Option<Credentials>>,
 }
 
 impl ProvideCredentials for CredentialProcessProvider {
@@ -67,22 +100,71 @@ impl ProvideCredentials for CredentialProcessProvider {
 impl CredentialProcessProvider {
     /// Create new [`CredentialProcessProvider`] with the `command` needed to execute the external process.
     pub fn new(command: String) -> Self {
-        Self {
-            command: CommandWithSensitiveArgs::new(command),
-        }
+        Self::builder().command(command).build()
     }
 
-    pub(crate) fn from_command(command: &CommandWithSensitiveArgs<&str>) -> Self {
-        Self {
-            command: command.to_owned_string(),
-        }
+    /// Creates a new builder for [`CredentialProcessProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    pub(crate) fn from_command(
+        provider_config: &ProviderConfig,
+        command: &CommandWithSensitiveArgs<&str>,
+        options: &ProcessOptions,
+    ) -> Self {
+        let mut builder = Self::builder()
+            .configure(provider_config)
+            .stderr(options.stderr)
+            .inherit_stdin(options.inherit_stdin);
+        builder.command = Some(command.to_owned_string());
+        builder.set_timeout(options.timeout);
+        builder.build()
     }
 
     async fn credentials(&self) -> provider::Result {
+        // Held while the process runs so that concurrent callers don't run it again, which
+        // would prompt an interactive user more than once
+        let mut cached = self.cached.lock().await;
+        if let Some(credentials) = cached.as_ref() {
+            let still_valid = credentials.expiry().map_or(false, |expiry| {
+                expiry > self.time_source.now() + CACHE_EXPIRY_BUFFER
+            });
+            if still_valid {
+                tracing::debug!("using cached credentials from external process");
+                return Ok(credentials.clone());
+            }
+        }
+
         // Security: command arguments must be redacted at debug level
         tracing::debug!(command = %self.command, "loading credentials from external process");
 
-        let command = if cfg!(windows) {
+        let output = match self.timeout {
+            Some(timeout) => {
+                let sleep_impl = self.sleep_impl.as_ref().ok_or_else(|| {
+                    CredentialsError::invalid_configuration(
+                        "a timeout was configured for the external process, but no async sleep implementation is available",
+                    )
+                })?;
+                Timeout::new(self.run(), sleep_impl.sleep(timeout))
+                    .await
+                    .map_err(|_| CredentialsError::provider_timed_out(timeout))?
+            }
+            None => self.run().await,
+        }
+        .map_err(CredentialsError::provider_error)?;
+
+        let credentials = parse_credential_process_json_credentials(&output)
+            .map_err(|err| CredentialsError::provider_error(CredentialProcessError::from(err)))?;
+        *cached = credentials.expiry().map(|_| credentials.clone());
+        Ok(credentials)
+    }
+
+    /// Runs the process and returns its `stdout`.
+    ///
+    /// The process is killed if the returned future is dropped before it exits.
+    async fn run(&self) -> Result<String, CredentialProcessError> {
+        let mut command = if cfg!(windows) {
             let mut command = Command::new("cmd.exe");
             command.args(["/C", self.command.unredacted()]);
             command
@@ -91,41 +173,241 @@ impl CredentialProcessProvider {
             command.args(["-c", self.command.unredacted()]);
             command
         };
-        let output = tokio::process::Command::from(command)
-            .output()
+        command
+            .stdin(if self.inherit_stdin {
+                Stdio::inherit()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(match self.stderr {
+                StderrBehavior::Capture => Stdio::piped(),
+                StderrBehavior::Inherit => Stdio::inherit(),
+            });
+        let Output {
+            status,
+            stdout,
+            stderr,
+        } = tokio::process::Command::from(command)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(ErrorKind::Spawn)?
+            .wait_with_output()
             .await
-            .map_err(|e| {
-                CredentialsError::provider_error(format!(
-                    "Error retrieving credentials from external process: {}",
-                    e
-                ))
-            })?;
+            .map_err(ErrorKind::Spawn)?;
 
         // Security: command arguments can be logged at trace level
-        tracing::trace!(command = ?self.command, status = ?output.status, "executed command (unredacted)");
+        tracing::trace!(command = ?self.command, status = ?status, "executed command (unredacted)");
+
+        if !status.success() {
+            let stderr = match self.stderr {
+                StderrBehavior::Capture => Some(String::from_utf8_lossy(&stderr).into_owned()),
+                StderrBehavior::Inherit => None,
+            };
+            return Err(ErrorKind::Exited { status, stderr }.into());
+        }
+
+        String::from_utf8(stdout).map_err(|err| ErrorKind::InvalidUtf8(err.utf8_error()).into())
+    }
+}
+
+/// How the `stderr` of the external process is handled
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StderrBehavior {
+    /// Capture `stderr` and include it in the error when the process fails
+    #[default]
+    Capture,
+    /// Pass `stderr` through to the `stderr` of the current process
+    ///
+    /// This allows the user to see prompts written by interactive processes, but the error
+    /// returned when the process fails won't contain its output.
+    Inherit,
+}
+
+/// How the external process of a profile's `credential_process` is run
+///
+/// Set with the `credential_process_*` methods of the
+/// [profile credentials provider builder](crate::profile::credentials::Builder).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ProcessOptions {
+    pub(crate) stderr: StderrBehavior,
+    pub(crate) inherit_stdin: bool,
+    pub(crate) timeout: Option<Duration>,
+}
+
+/// Builder for [`CredentialProcessProvider`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    command: Option<CommandWithSensitiveArgs<String>>,
+    provider_config: Option<ProviderConfig>,
+    stderr: StderrBehavior,
+    inherit_stdin: bool,
+    timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Set the command used to run the external process
+    ///
+    /// The command is run with `sh -c`, or `cmd.exe /C` on Windows.
+    pub fn command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(CommandWithSensitiveArgs::new(command.into()));
+        self
+    }
+
+    /// Override the configuration used for this provider
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Set how the `stderr` of the external process is handled
+    ///
+    /// Defaults to [`StderrBehavior::Capture`].
+    pub fn stderr(mut self, stderr: StderrBehavior) -> Self {
+        self.stderr = stderr;
+        self
+    }
+
+    /// Let the external process read from the `stdin` of the current process
+    ///
+    /// This is required for processes that prompt for input. By default, the process is given
+    /// an empty `stdin`.
+    pub fn inherit_stdin(mut self, inherit_stdin: bool) -> Self {
+        self.inherit_stdin = inherit_stdin;
+        self
+    }
+
+    /// Set the maximum amount of time the external process may run for
+    ///
+    /// The process is killed if it doesn't exit within this time. By default, there is no
+    /// timeout other than the one applied by the identity cache.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum amount of time the external process may run for
+    ///
+    /// See [`timeout`](Self::timeout).
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Create a [`CredentialProcessProvider`] from this builder.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if no command was set.
+    pub fn build(self) -> CredentialProcessProvider {
+        let provider_config = self.provider_config.unwrap_or_default();
+        CredentialProcessProvider {
+            command: self
+                .command
+                .expect("a command is required to build a CredentialProcessProvider"),
+            stderr: self.stderr,
+            inherit_stdin: self.inherit_stdin,
+            timeout: self.timeout,
+            time_source: provider_config.time_source(),
+            sleep_impl: provider_config.sleep_impl(),
+            cached: Mutex::new(None),
+        }
+    }
+}
+
+/// Error returned when credentials can't be loaded from the external process
+///
+/// This is the source of the [`CredentialsError`] returned by [`CredentialProcessProvider`].
+#[derive(Debug)]
+pub struct CredentialProcessError {
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+enum ErrorKind {
+    Spawn(std::io::Error),
+    Exited {
+        status: ExitStatus,
+        stderr: Option<String>,
+    },
+    InvalidUtf8(std::str::Utf8Error),
+    InvalidResponse(InvalidJsonCredentials),
+}
+
+impl CredentialProcessError {
+    /// The exit code of the process, if it exited unsuccessfully
+    ///
+    /// This is `None` if the process failed for another reason, or if it was terminated by a
+    /// signal.
+    pub fn exit_code(&self) -> Option<i32> {
+        match &self.kind {
+            ErrorKind::Exited { status, .. } => status.code(),
+            _ => None,
+        }
+    }
+
+    /// The `stderr` of the process, if it exited unsuccessfully and `stderr` was captured
+    pub fn stderr(&self) -> Option<&str> {
+        match &self.kind {
+            ErrorKind::Exited { stderr, .. } => stderr.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorKind> for CredentialProcessError {
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+}
 
-        if !output.status.success() {
-            let reason =
-                std::str::from_utf8(&output.stderr).unwrap_or("could not decode stderr as UTF-8");
-            return Err(CredentialsError::provider_error(format!(
+impl From<InvalidJsonCredentials> for CredentialProcessError {
+    fn from(err: InvalidJsonCredentials) -> Self {
+        ErrorKind::InvalidResponse(err).into()
+    }
+}
+
+impl fmt::Display for CredentialProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Spawn(_) => write!(f, "Error retrieving credentials from external process"),
+            ErrorKind::Exited {
+                status,
+                stderr: Some(stderr),
+            } => write!(
+                f,
                 "Error retrieving credentials: external process exited with code {}. Stderr: {}",
-                output.status, reason
-            )));
+                status, stderr
+            ),
+            ErrorKind::Exited {
+                status,
+                stderr: None,
+            } => write!(
+                f,
+                "Error retrieving credentials: external process exited with code {}",
+                status
+            ),
+            ErrorKind::InvalidUtf8(_) => write!(
+                f,
+                "Error retrieving credentials from external process: could not decode output as UTF-8"
+            ),
+            ErrorKind::InvalidResponse(_) => write!(
+                f,
+                "Error retrieving credentials from external process, could not parse response"
+            ),
         }
+    }
+}
 
-        let output = std::str::from_utf8(&output.stdout).map_err(|e| {
-            CredentialsError::provider_error(format!(
-                "Error retrieving credentials from external process: could not decode output as UTF-8: {}",
-                e
-            ))
-        })?;
-
-        parse_credential_process_json_credentials(output).map_err(|invalid| {
-            CredentialsError::provider_error(format!(
-                "Error retrieving credentials from external process, could not parse response: {}",
-                invalid
-            ))
-        })
+impl Error for CredentialProcessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Spawn(err) => Some(err),
+            ErrorKind::Exited { .. } => None,
+            ErrorKind::InvalidUtf8(err) => Some(err),
+            ErrorKind::InvalidResponse(err) => Some(err),
+        }
     }
 }
 
@@ -142,6 +424,7 @@ pub(crate) fn parse_credential_process_json_credentials(
     let mut secret_access_key = None;
     let mut session_token = None;
     let mut expiration = None;
+    let mut account_id = None;
     json_parse_loop(credentials_response.as_bytes(), |key, value| {
         match (key, value) {
             /*
@@ -149,7 +432,8 @@ pub(crate) fn parse_credential_process_json_credentials(
              "AccessKeyId": "ASIARTESTID",
              "SecretAccessKey": "TESTSECRETKEY",
              "SessionToken": "TESTSESSIONTOKEN",
-             "Expiration": "2022-05-02T18:36:00+00:00"
+             "Expiration": "2022-05-02T18:36:00+00:00",
+             "AccountId": "123456789012"
             */
             (key, Token::ValueNumber { value, .. }) if key.eq_ignore_ascii_case("Version") => {
                 version = Some(i32::try_from(*value).map_err(|err| {
@@ -173,6 +457,9 @@ pub(crate) fn parse_credential_process_json_credentials(
             (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Expiration") => {
                 expiration = Some(value.to_unescaped()?)
             }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("AccountId") => {
+                account_id = Some(value.to_unescaped()?)
+            }
 
             _ => {}
         };
@@ -197,16 +484,22 @@ pub(crate) fn parse_credential_process_json_credentials(
     if expiration.is_none() {
         tracing::debug!("no expiration provided for credentials provider credentials. these credentials will never be refreshed.")
     }
-    Ok(Credentials::new(
+    let credentials = Credentials::new(
         access_key_id,
         secret_access_key,
         session_token.map(|tok| tok.to_string()),
         expiration,
         "CredentialProcess",
-    ))
+    );
+    Ok(match account_id {
+        Some(account_id) => credentials.with_account_id(account_id),
+        None => credentials,
+    })
 }
 
-fn parse_expiration(expiration: impl AsRef<str>) -> Result<SystemTime, InvalidJsonCredentials> {
+pub(crate) fn parse_expiration(
+    expiration: impl AsRef<str>,
+) -> Result<SystemTime, InvalidJsonCredentials> {
     OffsetDateTime::parse(expiration.as_ref(), &Rfc3339)
         .map(SystemTime::from)
         .map_err(|err| InvalidJsonCredentials::InvalidField {
@@ -217,8 +510,12 @@ fn parse_expiration(expiration: impl AsRef<str>) -> Result<SystemTime, InvalidJs
 
 #[cfg(test)]
 mod test {
-    use crate::credential_process::CredentialProcessProvider;
+    use crate::credential_process::{
+        CredentialProcessError, CredentialProcessProvider, StderrBehavior,
+    };
+    use aws_credential_types::provider::error::CredentialsError;
     use aws_credential_types::provider::ProvideCredentials;
+    use std::error::Error;
     use std::time::{Duration, SystemTime};
     use time::format_description::well_known::Rfc3339;
     use time::OffsetDateTime;
@@ -259,6 +556,19 @@ mod test {
         assert_eq!(creds.secret_access_key(), "TESTSECRETKEY");
         assert_eq!(creds.session_token(), None);
         assert_eq!(creds.expiry(), None);
+        assert_eq!(creds.account_id(), None);
+    }
+
+    // TODO(https://github.com/awslabs/aws-sdk-rust/issues/1117) This test is ignored on Windows because it uses Unix-style paths
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn test_credential_process_account_id() {
+        let provider = CredentialProcessProvider::new(String::from(
+            r#"echo '{ "Version": 1, "AccessKeyId": "ASIARTESTID", "SecretAccessKey": "TESTSECRETKEY", "AccountId": "123456789012" }'"#,
+        ));
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(creds.account_id(), Some("123456789012"));
+        assert_eq!(creds.provenance()[0].provider_name(), "CredentialProcess");
     }
 
     #[tokio::test]
@@ -268,4 +578,118 @@ mod test {
             .await
             .expect_err("timeout forced");
     }
+
+    fn process_error(err: &CredentialsError) -> &CredentialProcessError {
+        err.source()
+            .and_then(|source| source.downcast_ref::<CredentialProcessError>())
+            .expect("source is a CredentialProcessError")
+    }
+
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn captures_stderr_when_process_fails() {
+        let provider = CredentialProcessProvider::new(String::from(
+            "echo 'no hardware token found' >&2; exit 3",
+        ));
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("process failed");
+        let process_error = process_error(&err);
+        assert_eq!(Some(3), process_error.exit_code());
+        assert_eq!(Some("no hardware token found\n"), process_error.stderr());
+        assert!(
+            format!("{}", process_error).contains("Stderr: no hardware token found"),
+            "{}",
+            process_error
+        );
+    }
+
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn inherited_stderr_is_not_captured() {
+        let provider = CredentialProcessProvider::builder()
+            .command("echo 'shown to the user' >&2; exit 1")
+            .stderr(StderrBehavior::Inherit)
+            .build();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("process failed");
+        let process_error = process_error(&err);
+        assert_eq!(Some(1), process_error.exit_code());
+        assert_eq!(None, process_error.stderr());
+    }
+
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn stdin_is_empty_by_default() {
+        // `read` fails on EOF rather than waiting for input
+        let provider = CredentialProcessProvider::builder()
+            .command("read code || exit 7")
+            .timeout(Duration::from_secs(5))
+            .build();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("process failed");
+        assert_eq!(Some(7), process_error(&err).exit_code());
+    }
+
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn configured_timeout_kills_the_process() {
+        let provider = CredentialProcessProvider::builder()
+            .command("sleep 1000")
+            .timeout(Duration::from_millis(50))
+            .build();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("process timed out");
+        match err {
+            CredentialsError::ProviderTimedOut(details) => {
+                assert_eq!(Duration::from_millis(50), details.timeout_duration())
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    // TODO(https://github.com/awslabs/aws-sdk-rust/issues/1117) This test is ignored on Windows because it uses Unix-style paths
+    #[tokio::test]
+    #[cfg_attr(windows, ignore)]
+    async fn caches_credentials_until_expiration() {
+        let runs = std::env::temp_dir().join(format!(
+            "credential-process-cache-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&runs);
+        let command = |expiration: &str| {
+            format!(
+                r#"echo run >> {}; echo '{{ "Version": 1, "AccessKeyId": "ASIARTESTID", "SecretAccessKey": "TESTSECRETKEY"{} }}'"#,
+                runs.display(),
+                expiration
+            )
+        };
+        let run_count = || {
+            std::fs::read_to_string(&runs)
+                .expect("process ran")
+                .lines()
+                .count()
+        };
+
+        let provider =
+            CredentialProcessProvider::new(command(r#", "Expiration": "2100-01-01T00:00:00Z""#));
+        provider.provide_credentials().await.expect("valid creds");
+        provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(1, run_count());
+
+        // credentials that never expire are not cached, so that they can still be rotated
+        let provider = CredentialProcessProvider::new(command(""));
+        provider.provide_credentials().await.expect("valid creds");
+        provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(3, run_count());
+
+        let _ = std::fs::remove_file(&runs);
+    }
 }
@@ -63,3 +63,6 @@ pub mod disable_request_compression;
 
 /// Default "request minimum compression size bytes" provider chain
 pub mod request_min_compression_size_bytes;
+
+/// Default "SigV4a signing region set" provider chain
+pub mod sigv4a_signing_region_set;
@@ -60,9 +60,7 @@ impl Builder {
         let env = self.provider_config.env();
         let profiles = self.provider_config.profile().await;
 
-        EnvConfigValue::new()
-            .profile("sdk-ua-app-id")
-            .validate(&env, profiles, |name| AppName::new(name.to_string()))
+        legacy_env_config_value().validate(&env, profiles, validate_app_name)
     }
 
     /// Build an [`AppName`] from the default chain
@@ -70,10 +68,7 @@ impl Builder {
         let env = self.provider_config.env();
         let profiles = self.provider_config.profile().await;
 
-        let standard = EnvConfigValue::new()
-            .env("AWS_SDK_UA_APP_ID")
-            .profile("sdk_ua_app_id")
-            .validate(&env, profiles, |name| AppName::new(name.to_string()));
+        let standard = env_config_value().validate(&env, profiles, validate_app_name);
         let with_fallback = match standard {
             Ok(None) => self.fallback_app_name().await,
             other => other,
@@ -86,6 +81,22 @@ impl Builder {
     }
 }
 
+/// The environment variable and profile key that set the app name
+pub(crate) fn env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env("AWS_SDK_UA_APP_ID")
+        .profile("sdk_ua_app_id")
+}
+
+/// The profile key that set the app name before it was standardized, checked last
+pub(crate) fn legacy_env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new().profile("sdk-ua-app-id")
+}
+
+pub(crate) fn validate_app_name(name: &str) -> Result<AppName, InvalidAppName> {
+    AppName::new(name.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
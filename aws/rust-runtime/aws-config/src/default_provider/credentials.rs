@@ -13,6 +13,8 @@ use crate::environment::credentials::EnvironmentVariableCredentialsProvider;
 use crate::meta::credentials::CredentialsProviderChain;
 use crate::meta::region::ProvideRegion;
 use crate::provider_config::ProviderConfig;
+use crate::resolution::ChainRecorder;
+use crate::sts::mfa::MfaTokenProvider;
 
 #[cfg(feature = "rustls")]
 /// Default Credentials Provider chain
@@ -100,6 +102,7 @@ pub struct Builder {
     region_override: Option<Box<dyn ProvideRegion>>,
     region_chain: crate::default_provider::region::Builder,
     conf: Option<ProviderConfig>,
+    recorder: Option<ChainRecorder>,
 }
 
 impl Builder {
@@ -152,6 +155,14 @@ impl Builder {
         self
     }
 
+    /// Set the source of MFA token codes for profiles that assume a role with an `mfa_serial`
+    ///
+    /// See [`mfa_token_provider`](crate::profile::credentials::Builder::mfa_token_provider)
+    pub fn mfa_token_provider(mut self, provider: MfaTokenProvider) -> Self {
+        self.profile_file_builder = self.profile_file_builder.mfa_token_provider(provider);
+        self
+    }
+
     /// Override the IMDS client used for this provider
     ///
     /// When unset, the default IMDS client will be used.
@@ -167,6 +178,12 @@ impl Builder {
         self
     }
 
+    /// Record the outcome of each step of the chain whenever credentials are loaded
+    pub(crate) fn set_recorder(&mut self, recorder: Option<ChainRecorder>) -> &mut Self {
+        self.recorder = recorder;
+        self
+    }
+
     /// Creates a `DefaultCredentialsChain`
     ///
     /// ## Panics
@@ -191,6 +208,10 @@ impl Builder {
             .or_else("WebIdentityToken", web_identity_token_provider)
             .or_else("EcsContainer", ecs_provider)
             .or_else("Ec2InstanceMetadata", imds_provider);
+        let provider_chain = match self.recorder {
+            Some(recorder) => provider_chain.record_outcomes(recorder),
+            None => provider_chain,
+        };
 
         DefaultCredentialsChain { provider_chain }
     }
@@ -16,6 +16,13 @@ mod profile_key {
     pub(super) const DISABLE_REQUEST_COMPRESSION: &str = "disable_request_compression";
 }
 
+/// The environment variable and profile key that set "disable request compression"
+pub(crate) fn env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::DISABLE_REQUEST_COMPRESSION)
+        .profile(profile_key::DISABLE_REQUEST_COMPRESSION)
+}
+
 /// Load the value for "disable request compression".
 ///
 /// This checks the following sources:
@@ -29,9 +36,7 @@ pub(crate) async fn disable_request_compression_provider(
     let env = provider_config.env();
     let profiles = provider_config.profile().await;
 
-    EnvConfigValue::new()
-        .env(env::DISABLE_REQUEST_COMPRESSION)
-        .profile(profile_key::DISABLE_REQUEST_COMPRESSION)
+    env_config_value()
         .validate(&env, profiles, parse_bool)
         .map_err(
             |err| tracing::warn!(err = %DisplayErrorContext(&err), "invalid value for `disable request compression` setting"),
@@ -17,6 +17,13 @@ mod profile_key {
     pub(super) const ENDPOINT_URL: &str = "endpoint_url";
 }
 
+/// The environment variable and profile key that set the endpoint URL
+pub(crate) fn env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::ENDPOINT_URL)
+        .profile(profile_key::ENDPOINT_URL)
+}
+
 /// Load the value for an endpoint URL
 ///
 /// This checks the following sources:
@@ -28,9 +35,7 @@ pub async fn endpoint_url_provider(provider_config: &ProviderConfig) -> Option<S
     let env = provider_config.env();
     let profiles = provider_config.profile().await;
 
-    EnvConfigValue::new()
-        .env(env::ENDPOINT_URL)
-        .profile(profile_key::ENDPOINT_URL)
+    env_config_value()
         .validate(&env, profiles, parse_url)
         .map_err(
             |err| tracing::warn!(err = %DisplayErrorContext(&err), "invalid value for endpoint URL setting"),
@@ -51,9 +56,7 @@ pub async fn endpoint_url_provider_with_origin(
     let env = provider_config.env();
     let profiles = provider_config.profile().await;
 
-    EnvConfigValue::new()
-        .env(env::ENDPOINT_URL)
-        .profile(profile_key::ENDPOINT_URL)
+    env_config_value()
         .validate_and_return_origin(&env, profiles, parse_url)
         .map_err(
             |err| tracing::warn!(err = %DisplayErrorContext(&err), "invalid value for endpoint URL setting"),
@@ -8,6 +8,7 @@ use aws_types::region::Region;
 use crate::environment::region::EnvironmentVariableRegionProvider;
 use crate::meta::region::{ProvideRegion, RegionProviderChain};
 use crate::provider_config::ProviderConfig;
+use crate::resolution::RegionRecorder;
 use crate::{imds, profile};
 
 /// Default Region Provider chain
@@ -61,6 +62,14 @@ impl Builder {
         self
     }
 
+    /// Record the sources each provider in the chain consults whenever a region is loaded
+    pub(crate) fn recorder(mut self, recorder: RegionRecorder) -> Self {
+        self.env_provider = self.env_provider.recorder(recorder.clone());
+        self.profile_file = self.profile_file.recorder(recorder.clone());
+        self.imds = self.imds.recorder(recorder);
+        self
+    }
+
     /// Build a [DefaultRegionChain]
     pub fn build(self) -> DefaultRegionChain {
         DefaultRegionChain(
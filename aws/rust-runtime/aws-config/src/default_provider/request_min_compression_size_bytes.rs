@@ -18,6 +18,13 @@ mod profile_key {
         "request_min_compression_size_bytes";
 }
 
+/// The environment variable and profile key that set "request minimum compression size bytes"
+pub(crate) fn env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::REQUEST_MIN_COMPRESSION_SIZE_BYTES)
+        .profile(profile_key::REQUEST_MIN_COMPRESSION_SIZE_BYTES)
+}
+
 /// Load the value for "request minimum compression size bytes".
 ///
 /// This checks the following sources:
@@ -31,9 +38,7 @@ pub(crate) async fn request_min_compression_size_bytes_provider(
     let env = provider_config.env();
     let profiles = provider_config.profile().await;
 
-    EnvConfigValue::new()
-        .env(env::REQUEST_MIN_COMPRESSION_SIZE_BYTES)
-        .profile(profile_key::REQUEST_MIN_COMPRESSION_SIZE_BYTES)
+    env_config_value()
         .validate(&env, profiles, parse_uint)
         .map_err(
             |err| tracing::warn!(err = %DisplayErrorContext(&err), "invalid value for `request minimum compression size bytes` setting"),
@@ -108,18 +108,8 @@ impl Builder {
         // hence, we'll panic if any config values are invalid (missing values are OK though)
         // We match this instead of unwrapping, so we can print the error with the `Display` impl instead of the `Debug` impl that unwrap uses
         let mut retry_config = RetryConfig::standard();
-        let max_attempts = EnvConfigValue::new()
-            .env(env::MAX_ATTEMPTS)
-            .profile(profile_keys::MAX_ATTEMPTS)
-            .validate(&env, profiles, validate_max_attempts);
-
-        let retry_mode = EnvConfigValue::new()
-            .env(env::RETRY_MODE)
-            .profile(profile_keys::RETRY_MODE)
-            .validate(&env, profiles, |s| {
-                RetryMode::from_str(s)
-                    .map_err(|err| RetryConfigErrorKind::InvalidRetryMode { source: err }.into())
-            });
+        let max_attempts = max_attempts().validate(&env, profiles, validate_max_attempts);
+        let retry_mode = retry_mode().validate(&env, profiles, validate_retry_mode);
 
         if let Some(max_attempts) = max_attempts? {
             retry_config = retry_config.with_max_attempts(max_attempts);
@@ -133,7 +123,21 @@ impl Builder {
     }
 }
 
-fn validate_max_attempts(max_attempts: &str) -> Result<u32, RetryConfigError> {
+/// The environment variable and profile key that set the maximum number of attempts
+pub(crate) fn max_attempts() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::MAX_ATTEMPTS)
+        .profile(profile_keys::MAX_ATTEMPTS)
+}
+
+/// The environment variable and profile key that set the retry mode
+pub(crate) fn retry_mode() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::RETRY_MODE)
+        .profile(profile_keys::RETRY_MODE)
+}
+
+pub(crate) fn validate_max_attempts(max_attempts: &str) -> Result<u32, RetryConfigError> {
     match max_attempts.parse::<u32>() {
         Ok(0) => Err(RetryConfigErrorKind::MaxAttemptsMustNotBeZero.into()),
         Ok(max_attempts) => Ok(max_attempts),
@@ -141,6 +145,11 @@ fn validate_max_attempts(max_attempts: &str) -> Result<u32, RetryConfigError> {
     }
 }
 
+pub(crate) fn validate_retry_mode(retry_mode: &str) -> Result<RetryMode, RetryConfigError> {
+    RetryMode::from_str(retry_mode)
+        .map_err(|source| RetryConfigErrorKind::InvalidRetryMode { source }.into())
+}
+
 #[cfg(test)]
 mod test {
     use crate::default_provider::retry_config::env;
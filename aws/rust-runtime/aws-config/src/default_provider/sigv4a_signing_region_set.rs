@@ -0,0 +1,128 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::provider_config::ProviderConfig;
+use aws_runtime::env_config::EnvConfigValue;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_types::region::SigningRegionSet;
+
+mod env {
+    pub(super) const SIGV4A_SIGNING_REGION_SET: &str = "AWS_SIGV4A_SIGNING_REGION_SET";
+}
+
+mod profile_key {
+    pub(super) const SIGV4A_SIGNING_REGION_SET: &str = "sigv4a_signing_region_set";
+}
+
+/// The environment variable and profile key that set the "SigV4a signing region set"
+pub(crate) fn env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::SIGV4A_SIGNING_REGION_SET)
+        .profile(profile_key::SIGV4A_SIGNING_REGION_SET)
+}
+
+/// Load the value for the "SigV4a signing region set".
+///
+/// This checks the following sources:
+/// 1. The environment variable `AWS_SIGV4A_SIGNING_REGION_SET=us-east-1,us-west-2`
+/// 2. The profile key `sigv4a_signing_region_set=us-east-1,us-west-2`
+///
+/// If invalid values are found, the provider will return None and an error will be logged.
+pub(crate) async fn sigv4a_signing_region_set_provider(
+    provider_config: &ProviderConfig,
+) -> Option<SigningRegionSet> {
+    let env = provider_config.env();
+    let profiles = provider_config.profile().await;
+
+    env_config_value()
+        .validate(&env, profiles, SigningRegionSet::parse)
+        .map_err(
+            |err| tracing::warn!(err = %DisplayErrorContext(&err), "invalid value for `SigV4a signing region set` setting"),
+        )
+        .unwrap_or(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::sigv4a_signing_region_set_provider;
+    #[allow(deprecated)]
+    use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
+    use crate::provider_config::ProviderConfig;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::region::SigningRegionSet;
+    use tracing_test::traced_test;
+
+    #[tokio::test]
+    #[traced_test]
+    async fn log_error_on_invalid_value() {
+        let conf = ProviderConfig::empty().with_env(Env::from_slice(&[(
+            "AWS_SIGV4A_SIGNING_REGION_SET",
+            "us-east-1,,us-west-2",
+        )]));
+        assert_eq!(sigv4a_signing_region_set_provider(&conf).await, None);
+        assert!(logs_contain(
+            "invalid value for `SigV4a signing region set` setting"
+        ));
+        assert!(logs_contain("AWS_SIGV4A_SIGNING_REGION_SET"));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn environment_priority() {
+        let conf = ProviderConfig::empty()
+            .with_env(Env::from_slice(&[(
+                "AWS_SIGV4A_SIGNING_REGION_SET",
+                "us-east-1, us-west-2",
+            )]))
+            .with_profile_config(
+                Some(
+                    #[allow(deprecated)]
+                    ProfileFiles::builder()
+                        .with_file(
+                            #[allow(deprecated)]
+                            ProfileFileKind::Config,
+                            "conf",
+                        )
+                        .build(),
+                ),
+                None,
+            )
+            .with_fs(Fs::from_slice(&[(
+                "conf",
+                "[default]\nsigv4a_signing_region_set = *",
+            )]));
+        assert_eq!(
+            sigv4a_signing_region_set_provider(&conf).await,
+            Some(SigningRegionSet::from("us-east-1,us-west-2"))
+        );
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn profile_config_works() {
+        let conf = ProviderConfig::empty()
+            .with_profile_config(
+                Some(
+                    #[allow(deprecated)]
+                    ProfileFiles::builder()
+                        .with_file(
+                            #[allow(deprecated)]
+                            ProfileFileKind::Config,
+                            "conf",
+                        )
+                        .build(),
+                ),
+                None,
+            )
+            .with_fs(Fs::from_slice(&[(
+                "conf",
+                "[default]\nsigv4a_signing_region_set = us-*",
+            )]));
+        assert_eq!(
+            sigv4a_signing_region_set_provider(&conf).await,
+            Some(SigningRegionSet::from("us-*"))
+        );
+    }
+}
@@ -16,6 +16,13 @@ mod profile_key {
     pub(super) const USE_DUAL_STACK: &str = "use_dualstack_endpoint";
 }
 
+/// The environment variable and profile key that set "use dual-stack"
+pub(crate) fn env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::USE_DUAL_STACK)
+        .profile(profile_key::USE_DUAL_STACK)
+}
+
 /// Load the value for "use dual-stack"
 ///
 /// This checks the following sources:
@@ -27,9 +34,7 @@ pub async fn use_dual_stack_provider(provider_config: &ProviderConfig) -> Option
     let env = provider_config.env();
     let profiles = provider_config.profile().await;
 
-    EnvConfigValue::new()
-        .env(env::USE_DUAL_STACK)
-        .profile(profile_key::USE_DUAL_STACK)
+    env_config_value()
         .validate(&env, profiles, parse_bool)
         .map_err(
             |err| tracing::warn!(err = %DisplayErrorContext(&err), "invalid value for dual-stack setting"),
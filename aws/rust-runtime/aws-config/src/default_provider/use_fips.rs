@@ -16,6 +16,13 @@ mod profile_key {
     pub(super) const USE_FIPS: &str = "use_fips_endpoint";
 }
 
+/// The environment variable and profile key that set "use FIPS"
+pub(crate) fn env_config_value() -> EnvConfigValue<'static> {
+    EnvConfigValue::new()
+        .env(env::USE_FIPS)
+        .profile(profile_key::USE_FIPS)
+}
+
 /// Load the value for "use FIPS"
 ///
 /// This checks the following sources:
@@ -27,9 +34,7 @@ pub async fn use_fips_provider(provider_config: &ProviderConfig) -> Option<bool>
     let env = provider_config.env();
     let profiles = provider_config.profile().await;
 
-    EnvConfigValue::new()
-        .env(env::USE_FIPS)
-        .profile(profile_key::USE_FIPS)
+    env_config_value()
         .validate(&env, profiles, parse_bool)
         .map_err(
             |err| tracing::warn!(err = %DisplayErrorContext(&err), "invalid value for FIPS setting"),
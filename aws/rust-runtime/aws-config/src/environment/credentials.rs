@@ -15,6 +15,7 @@ use aws_types::os_shim_internal::Env;
 /// - `AWS_ACCESS_KEY_ID`
 /// - `AWS_SECRET_ACCESS_KEY` with fallback to `SECRET_ACCESS_KEY`
 /// - `AWS_SESSION_TOKEN`
+/// - `AWS_ACCOUNT_ID`
 #[derive(Debug, Clone)]
 pub struct EnvironmentVariableCredentialsProvider {
     env: Env,
@@ -34,21 +35,18 @@ impl EnvironmentVariableCredentialsProvider {
             .or_else(|_| self.env.get("SECRET_ACCESS_KEY"))
             .and_then(err_if_blank)
             .map_err(to_cred_error)?;
-        let session_token =
-            self.env
-                .get("AWS_SESSION_TOKEN")
-                .ok()
-                .and_then(|token| match token.trim() {
-                    "" => None,
-                    s => Some(s.to_string()),
-                });
-        Ok(Credentials::new(
-            access_key,
-            secret_key,
-            session_token,
-            None,
-            ENV_PROVIDER,
-        ))
+        let session_token = self
+            .env
+            .get("AWS_SESSION_TOKEN")
+            .ok()
+            .and_then(none_if_blank);
+        let account_id = self.env.get("AWS_ACCOUNT_ID").ok().and_then(none_if_blank);
+        let credentials =
+            Credentials::new(access_key, secret_key, session_token, None, ENV_PROVIDER);
+        Ok(match account_id {
+            Some(account_id) => credentials.with_account_id(account_id),
+            None => credentials,
+        })
     }
 }
 
@@ -98,6 +96,13 @@ fn err_if_blank(value: String) -> Result<String, VarError> {
     }
 }
 
+fn none_if_blank(value: String) -> Option<String> {
+    match value.trim() {
+        "" => None,
+        s => Some(s.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use aws_credential_types::provider::{error::CredentialsError, ProvideCredentials};
@@ -203,6 +208,33 @@ mod test {
         assert_eq!(creds.secret_access_key(), "secret");
     }
 
+    #[test]
+    fn account_id() {
+        let provider = make_provider(&[
+            ("AWS_ACCESS_KEY_ID", "access"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_ACCOUNT_ID", "123456789012"),
+        ]);
+        let creds = provider
+            .provide_credentials()
+            .now_or_never()
+            .unwrap()
+            .expect("valid credentials");
+        assert_eq!(creds.account_id(), Some("123456789012"));
+
+        let provider = make_provider(&[
+            ("AWS_ACCESS_KEY_ID", "access"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_ACCOUNT_ID", " "),
+        ]);
+        let creds = provider
+            .provide_credentials()
+            .now_or_never()
+            .unwrap()
+            .expect("valid credentials");
+        assert_eq!(creds.account_id(), None);
+    }
+
     #[test]
     fn missing() {
         let provider = make_provider(&[]);
@@ -4,6 +4,7 @@
  */
 
 use crate::meta::region::{future, ProvideRegion};
+use crate::resolution::{ConfigSource, RegionRecorder};
 use aws_types::os_shim_internal::Env;
 use aws_types::region::Region;
 
@@ -14,30 +15,52 @@ use aws_types::region::Region;
 #[derive(Debug, Default)]
 pub struct EnvironmentVariableRegionProvider {
     env: Env,
+    recorder: Option<RegionRecorder>,
 }
 
+const REGION_VARIABLES: [&str; 2] = ["AWS_REGION", "AWS_DEFAULT_REGION"];
+
 impl EnvironmentVariableRegionProvider {
     /// Create a new `EnvironmentVariableRegionProvider`
     pub fn new() -> Self {
-        EnvironmentVariableRegionProvider { env: Env::real() }
+        EnvironmentVariableRegionProvider {
+            env: Env::real(),
+            recorder: None,
+        }
     }
 
     /// Create an region provider from a given `Env`
     ///
     /// This method is used for tests that need to override environment variables.
     pub(crate) fn new_with_env(env: Env) -> Self {
-        EnvironmentVariableRegionProvider { env }
+        EnvironmentVariableRegionProvider {
+            env,
+            recorder: None,
+        }
+    }
+
+    /// Record each variable that is read whenever a region is loaded
+    pub(crate) fn recorder(mut self, recorder: RegionRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
     }
 }
 
 impl ProvideRegion for EnvironmentVariableRegionProvider {
     fn region(&self) -> future::ProvideRegion<'_> {
-        let region = self
-            .env
-            .get("AWS_REGION")
-            .or_else(|_| self.env.get("AWS_DEFAULT_REGION"))
-            .map(Region::new)
-            .ok();
+        let region = REGION_VARIABLES.iter().find_map(|variable| {
+            let region = self.env.get(variable).map(Region::new).ok();
+            if let Some(recorder) = &self.recorder {
+                recorder.record(
+                    ConfigSource::Environment {
+                        variable: (*variable).into(),
+                        service_id: None,
+                    },
+                    region.as_ref(),
+                );
+            }
+            region
+        });
         future::ProvideRegion::ready(region)
     }
 }
@@ -11,6 +11,7 @@
 use crate::imds::{self, Client};
 use crate::meta::region::{future, ProvideRegion};
 use crate::provider_config::ProviderConfig;
+use crate::resolution::{ConfigSource, RegionRecorder};
 use aws_smithy_types::error::display::DisplayErrorContext;
 use aws_types::os_shim_internal::Env;
 use aws_types::region::Region;
@@ -23,6 +24,7 @@ use tracing::Instrument;
 pub struct ImdsRegionProvider {
     client: Client,
     env: Env,
+    recorder: Option<RegionRecorder>,
 }
 
 impl Debug for ImdsRegionProvider {
@@ -57,7 +59,7 @@ impl ImdsRegionProvider {
             tracing::debug!("not using IMDS to load region, IMDS is disabled");
             return None;
         }
-        match self.client.get(REGION_PATH).await {
+        let region = match self.client.get(REGION_PATH).await {
             Ok(region) => {
                 tracing::debug!(region = %region.as_ref(), "loaded region from IMDS");
                 Some(Region::new(String::from(region)))
@@ -66,7 +68,11 @@ impl ImdsRegionProvider {
                 tracing::warn!(err = %DisplayErrorContext(&err), "failed to load region from IMDS");
                 None
             }
+        };
+        if let Some(recorder) = &self.recorder {
+            recorder.record(ConfigSource::Imds, region.as_ref());
         }
+        region
     }
 }
 
@@ -84,6 +90,7 @@ impl ProvideRegion for ImdsRegionProvider {
 pub struct Builder {
     provider_config: Option<ProviderConfig>,
     imds_client_override: Option<imds::Client>,
+    recorder: Option<RegionRecorder>,
 }
 
 impl Builder {
@@ -101,6 +108,12 @@ impl Builder {
         self
     }
 
+    /// Record whether IMDS is queried whenever a region is loaded
+    pub(crate) fn recorder(mut self, recorder: RegionRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     /// Create an [`ImdsRegionProvider`] from this builder
     pub fn build(self) -> ImdsRegionProvider {
         let provider_config = self.provider_config.unwrap_or_default();
@@ -110,6 +123,7 @@ impl Builder {
         ImdsRegionProvider {
             client,
             env: provider_config.env(),
+            recorder: self.recorder,
         }
     }
 }
@@ -123,6 +123,7 @@ mod json_credentials;
 #[cfg(test)]
 mod test_case;
 
+pub mod bearer_token;
 pub mod credential_process;
 pub mod default_provider;
 pub mod ecs;
@@ -132,7 +133,10 @@ pub mod imds;
 pub mod meta;
 pub mod profile;
 pub mod provider_config;
+pub mod resolution;
 pub mod retry;
+#[cfg(feature = "rolesanywhere")]
+pub mod rolesanywhere;
 mod sensitive_command;
 #[cfg(feature = "sso")]
 pub mod sso;
@@ -228,24 +232,30 @@ mod loader {
     use aws_smithy_runtime_api::client::identity::{ResolveCachedIdentity, SharedIdentityCache};
     use aws_smithy_runtime_api::client::stalled_stream_protection::StalledStreamProtectionConfig;
     use aws_smithy_runtime_api::shared::IntoShared;
-    use aws_smithy_types::retry::RetryConfig;
+    use aws_smithy_types::retry::{RetryConfig, RetryMode};
     use aws_smithy_types::timeout::TimeoutConfig;
     use aws_types::app_name::AppName;
     use aws_types::docs_for;
     use aws_types::origin::Origin;
     use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::region::SigningRegionSet;
     use aws_types::sdk_config::SharedHttpClient;
     use aws_types::SdkConfig;
 
     use crate::default_provider::{
         app_name, credentials, disable_request_compression, endpoint_url,
         ignore_configured_endpoint_urls as ignore_ep, region, request_min_compression_size_bytes,
-        retry_config, timeout_config, use_dual_stack, use_fips,
+        retry_config, sigv4a_signing_region_set, timeout_config, use_dual_stack, use_fips,
     };
+    use crate::environment::{parse_bool, parse_uint, parse_url};
     use crate::meta::region::ProvideRegion;
     #[allow(deprecated)]
     use crate::profile::profile_file::ProfileFiles;
     use crate::provider_config::ProviderConfig;
+    use crate::resolution::{
+        setting, ChainRecorder, ConfigResolutionReport, CredentialsResolution, CredentialsSource,
+        RegionRecorder, SettingResolution,
+    };
 
     #[derive(Default, Debug)]
     enum TriStateOption<T> {
@@ -258,6 +268,11 @@ mod loader {
         Set(T),
     }
 
+    /// The retry mode as it is written in the environment or a profile
+    fn retry_mode_name(mode: RetryMode) -> String {
+        format!("{mode:?}").to_ascii_lowercase()
+    }
+
     /// Load a cross-service [`SdkConfig`] from the environment
     ///
     /// This builder supports overriding individual components of the generated config. Overriding a component
@@ -285,6 +300,7 @@ mod loader {
         time_source: Option<SharedTimeSource>,
         disable_request_compression: Option<bool>,
         request_min_compression_size_bytes: Option<u32>,
+        sigv4a_signing_region_set: Option<SigningRegionSet>,
         stalled_stream_protection_config: Option<StalledStreamProtectionConfig>,
         env: Option<Env>,
         fs: Option<Fs>,
@@ -684,6 +700,12 @@ mod loader {
             self
         }
 
+        #[doc = docs_for!(sigv4a_signing_region_set)]
+        pub fn sigv4a_signing_region_set(mut self, region_set: SigningRegionSet) -> Self {
+            self.sigv4a_signing_region_set = Some(region_set);
+            self
+        }
+
         /// Override the [`StalledStreamProtectionConfig`] used to build [`SdkConfig`].
         ///
         /// This configures stalled stream protection. When enabled, download streams
@@ -727,6 +749,26 @@ mod loader {
         /// This means that if you provide a region provider that does not return a region, no region will
         /// be set in the resulting [`SdkConfig`].
         pub async fn load(self) -> SdkConfig {
+            self.load_with_report().await.0
+        }
+
+        /// Load the default configuration, along with a report of where each value came from
+        ///
+        /// This behaves exactly like [`load`](Self::load). The [`ConfigResolutionReport`] lists,
+        /// for each setting, the source that was used and why every other source was skipped,
+        /// which helps when a value isn't what you expect.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn create_config() {
+        /// let (config, report) = aws_config::from_env().load_with_report().await;
+        /// // region = us-east-1 (from environment variable `AWS_REGION`)
+        /// //   - `region` in profile `default` = us-west-2: overridden by a higher-precedence source
+        /// //   ...
+        /// println!("{report}");
+        /// # }
+        /// ```
+        pub async fn load_with_report(self) -> (SdkConfig, ConfigResolutionReport) {
             let time_source = self.time_source.unwrap_or_default();
 
             let sleep_impl = if self.sleep.is_some() {
@@ -773,16 +815,20 @@ mod loader {
                 .with_use_fips(use_fips)
                 .with_use_dual_stack(use_dual_stack);
 
+            let region_from_code = self.region.is_some();
+            let region_recorder = RegionRecorder::default();
             let region = if let Some(provider) = self.region {
                 provider.region().await
             } else {
                 region::Builder::default()
                     .configure(&conf)
+                    .recorder(region_recorder.clone())
                     .build()
                     .region()
                     .await
             };
 
+            let retry_from_code = self.retry_config.is_some();
             let retry_config = if let Some(retry_config) = self.retry_config {
                 retry_config
             } else {
@@ -792,6 +838,7 @@ mod loader {
                     .await
             };
 
+            let app_name_from_code = self.app_name.clone();
             let app_name = if self.app_name.is_some() {
                 self.app_name
             } else {
@@ -817,24 +864,42 @@ mod loader {
                     .await
                 };
 
+            let sigv4a_signing_region_set = if self.sigv4a_signing_region_set.is_some() {
+                self.sigv4a_signing_region_set.clone()
+            } else {
+                sigv4a_signing_region_set::sigv4a_signing_region_set_provider(&conf).await
+            };
+
             let base_config = timeout_config::default_provider()
                 .configure(&conf)
                 .timeout_config()
                 .await;
-            let mut timeout_config = self
+            let timeout_from_code = self
                 .timeout_config
                 .unwrap_or_else(|| TimeoutConfig::builder().build());
+            let mut timeout_config = timeout_from_code.clone();
             timeout_config.take_defaults_from(&base_config);
 
-            let credentials_provider = match self.credentials_provider {
-                TriStateOption::Set(provider) => Some(provider),
+            let (credentials_provider, credentials_resolution) = match self.credentials_provider {
+                TriStateOption::Set(provider) => (
+                    Some(provider),
+                    CredentialsResolution::new(CredentialsSource::Code),
+                ),
                 TriStateOption::NotSet => {
+                    let recorder = ChainRecorder::default();
                     let mut builder =
                         credentials::DefaultCredentialsChain::builder().configure(conf.clone());
                     builder.set_region(region.clone());
-                    Some(SharedCredentialsProvider::new(builder.build().await))
+                    builder.set_recorder(Some(recorder.clone()));
+                    (
+                        Some(SharedCredentialsProvider::new(builder.build().await)),
+                        CredentialsResolution::default_chain(recorder),
+                    )
                 }
-                TriStateOption::ExplicitlyUnset => None,
+                TriStateOption::ExplicitlyUnset => (
+                    None,
+                    CredentialsResolution::new(CredentialsSource::Disabled),
+                ),
             };
 
             let token_provider = match self.token_provider {
@@ -860,6 +925,122 @@ mod loader {
                 env: conf.env(),
                 env_config_sections: profiles.cloned().unwrap_or_default(),
             };
+            let env = conf.env();
+            let mut settings = vec![
+                SettingResolution::region(
+                    region_from_code.then_some(region.as_ref()),
+                    &region_recorder,
+                ),
+                SettingResolution::explain(
+                    setting::USE_FIPS,
+                    self.use_fips.map(|v| v.to_string()),
+                    vec![use_fips::env_config_value()],
+                    &env,
+                    profiles,
+                    parse_bool,
+                ),
+                SettingResolution::explain(
+                    setting::USE_DUAL_STACK,
+                    self.use_dual_stack.map(|v| v.to_string()),
+                    vec![use_dual_stack::env_config_value()],
+                    &env,
+                    profiles,
+                    parse_bool,
+                ),
+                SettingResolution::explain(
+                    setting::MAX_ATTEMPTS,
+                    retry_from_code.then(|| retry_config.max_attempts().to_string()),
+                    vec![retry_config::max_attempts()],
+                    &env,
+                    profiles,
+                    retry_config::validate_max_attempts,
+                )
+                .or_default(Some(RetryConfig::standard().max_attempts().to_string())),
+                SettingResolution::explain(
+                    setting::RETRY_MODE,
+                    retry_from_code.then(|| retry_mode_name(retry_config.mode())),
+                    vec![retry_config::retry_mode()],
+                    &env,
+                    profiles,
+                    retry_config::validate_retry_mode,
+                )
+                .or_default(Some(retry_mode_name(RetryConfig::standard().mode()))),
+                SettingResolution::code_or_default(
+                    setting::CONNECT_TIMEOUT,
+                    timeout_from_code
+                        .connect_timeout()
+                        .map(|t| format!("{t:?}")),
+                    base_config.connect_timeout().map(|t| format!("{t:?}")),
+                ),
+                SettingResolution::code_or_default(
+                    setting::READ_TIMEOUT,
+                    timeout_from_code.read_timeout().map(|t| format!("{t:?}")),
+                    base_config.read_timeout().map(|t| format!("{t:?}")),
+                ),
+                SettingResolution::code_or_default(
+                    setting::OPERATION_TIMEOUT,
+                    timeout_from_code
+                        .operation_timeout()
+                        .map(|t| format!("{t:?}")),
+                    base_config.operation_timeout().map(|t| format!("{t:?}")),
+                ),
+                SettingResolution::code_or_default(
+                    setting::OPERATION_ATTEMPT_TIMEOUT,
+                    timeout_from_code
+                        .operation_attempt_timeout()
+                        .map(|t| format!("{t:?}")),
+                    base_config
+                        .operation_attempt_timeout()
+                        .map(|t| format!("{t:?}")),
+                ),
+                SettingResolution::explain(
+                    setting::APP_NAME,
+                    app_name_from_code.map(|name| name.to_string()),
+                    vec![
+                        app_name::env_config_value(),
+                        app_name::legacy_env_config_value(),
+                    ],
+                    &env,
+                    profiles,
+                    app_name::validate_app_name,
+                ),
+                SettingResolution::explain(
+                    setting::DISABLE_REQUEST_COMPRESSION,
+                    self.disable_request_compression.map(|v| v.to_string()),
+                    vec![disable_request_compression::env_config_value()],
+                    &env,
+                    profiles,
+                    parse_bool,
+                ),
+                SettingResolution::explain(
+                    setting::REQUEST_MIN_COMPRESSION_SIZE_BYTES,
+                    self.request_min_compression_size_bytes
+                        .map(|v| v.to_string()),
+                    vec![request_min_compression_size_bytes::env_config_value()],
+                    &env,
+                    profiles,
+                    parse_uint,
+                ),
+                SettingResolution::explain(
+                    setting::SIGV4A_SIGNING_REGION_SET,
+                    self.sigv4a_signing_region_set
+                        .as_ref()
+                        .map(|v| v.to_string()),
+                    vec![sigv4a_signing_region_set::env_config_value()],
+                    &env,
+                    profiles,
+                    SigningRegionSet::parse,
+                ),
+            ];
+            let endpoint_url_resolution = SettingResolution::explain(
+                setting::ENDPOINT_URL,
+                self.endpoint_url.clone(),
+                vec![endpoint_url::env_config_value()],
+                &env,
+                profiles,
+                parse_url,
+            );
+
             let mut builder = SdkConfig::builder()
                 .region(region)
                 .retry_config(retry_config)
@@ -870,6 +1051,7 @@ mod loader {
             // If an endpoint URL is set programmatically, then our work is done.
             let endpoint_url = if self.endpoint_url.is_some() {
                 builder.insert_origin("endpoint_url", Origin::shared_config());
+                settings.push(endpoint_url_resolution);
                 self.endpoint_url
             } else {
                 // Otherwise, check to see if we should ignore EP URLs set in the environment.
@@ -879,6 +1061,10 @@ mod loader {
                         .unwrap_or_default();
 
                 if ignore_configured_endpoint_urls {
+                    settings.push(
+                        endpoint_url_resolution
+                            .ignore_configured("`ignore_configured_endpoint_urls` is set"),
+                    );
                     // If yes, log a trace and return `None`.
                     tracing::trace!(
                         "`ignore_configured_endpoint_urls` is set, any endpoint URLs configured in the environment will be ignored. \
@@ -889,6 +1075,7 @@ mod loader {
                     // Otherwise, attempt to resolve one.
                     let (v, origin) = endpoint_url::endpoint_url_provider_with_origin(&conf).await;
                     builder.insert_origin("endpoint_url", origin);
+                    settings.push(endpoint_url_resolution);
                     v
                 }
             };
@@ -916,8 +1103,15 @@ mod loader {
             builder.set_use_dual_stack(use_dual_stack);
             builder.set_disable_request_compression(disable_request_compression);
             builder.set_request_min_compression_size_bytes(request_min_compression_size_bytes);
+            builder.set_sigv4a_signing_region_set(sigv4a_signing_region_set);
             builder.set_stalled_stream_protection(self.stalled_stream_protection_config);
-            builder.build()
+            let report = ConfigResolutionReport::new(
+                settings,
+                credentials_resolution,
+                env,
+                profiles.cloned().unwrap_or_default(),
+            );
+            (builder.build(), report)
         }
     }
 
@@ -938,6 +1132,9 @@ mod loader {
     mod test {
         #[allow(deprecated)]
         use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
+        use crate::resolution::{
+            setting, ChainStepOutcome, ConfigSource, CredentialsSource, SkipReason,
+        };
         use crate::test_case::{no_traffic_client, InstantSleep};
         use crate::BehaviorVersion;
         use crate::{defaults, ConfigLoader};
@@ -948,6 +1145,8 @@ mod loader {
         use aws_types::app_name::AppName;
         use aws_types::origin::Origin;
         use aws_types::os_shim_internal::{Env, Fs};
+        use aws_types::region::SigningRegionSet;
+        use aws_types::service_config::ServiceConfigKey;
         use std::sync::atomic::{AtomicUsize, Ordering};
         use std::sync::Arc;
 
@@ -1085,6 +1284,165 @@ mod loader {
             );
         }
 
+        #[tokio::test]
+        async fn report_explains_where_values_came_from() {
+            let env = Env::from_slice(&[("AWS_REGION", "us-east-1"), ("AWS_MAX_ATTEMPTS", "5")]);
+            let (_config, report) = base_conf()
+                .test_credentials()
+                .env(env)
+                .profile_name("custom")
+                .profile_files(
+                    #[allow(deprecated)]
+                    ProfileFiles::builder()
+                        .with_contents(
+                            #[allow(deprecated)]
+                            ProfileFileKind::Config,
+                            "[profile custom]\nregion = us-west-2\nservices = dev\n\
+                            [services dev]\ns3 =\n  endpoint_url = http://localhost:4566",
+                        )
+                        .build(),
+                )
+                .use_fips(true)
+                .load_with_report()
+                .await;
+
+            let region = report.setting(setting::REGION).unwrap();
+            assert_eq!(Some("us-east-1"), region.value());
+            let outcomes: Vec<_> = region
+                .candidates()
+                .iter()
+                .map(|c| (c.source().to_string(), c.skip_reason().cloned()))
+                .collect();
+            // the chain stops at `AWS_REGION`, so nothing after it is consulted
+            assert_eq!(
+                vec![("environment variable `AWS_REGION`".to_string(), None)],
+                outcomes
+            );
+
+            let use_fips = report.setting(setting::USE_FIPS).unwrap();
+            assert_eq!(Some(&ConfigSource::Code), use_fips.source());
+            let max_attempts = report.setting(setting::MAX_ATTEMPTS).unwrap();
+            assert_eq!(Some("5"), max_attempts.value());
+            let retry_mode = report.setting(setting::RETRY_MODE).unwrap();
+            assert_eq!(Some(&ConfigSource::Default), retry_mode.source());
+            assert_eq!(Some("standard"), retry_mode.value());
+            let connect_timeout = report.setting(setting::CONNECT_TIMEOUT).unwrap();
+            assert_eq!(Some(&ConfigSource::Default), connect_timeout.source());
+            assert_eq!(CredentialsSource::Code, report.credentials().source());
+
+            let s3_endpoint = report.service_config(
+                ServiceConfigKey::builder()
+                    .service_id("s3")
+                    .profile("endpoint_url")
+                    .env("AWS_ENDPOINT_URL")
+                    .build()
+                    .unwrap(),
+            );
+            assert_eq!(Some("http://localhost:4566"), s3_endpoint.value());
+            assert_eq!(
+                Some(&ConfigSource::ServicesSection {
+                    profile: "custom".into(),
+                    service_id: "s3".into(),
+                    key: "endpoint_url".into()
+                }),
+                s3_endpoint.source()
+            );
+
+            let report = report.to_string();
+            assert!(
+                report.contains("region = us-east-1 (from environment variable `AWS_REGION`)"),
+                "{report}"
+            );
+        }
+
+        #[tokio::test]
+        async fn report_explains_region_from_profile() {
+            let (_config, report) = base_conf()
+                .test_credentials()
+                .env(Env::from_slice(&[]))
+                .profile_files(
+                    #[allow(deprecated)]
+                    ProfileFiles::builder()
+                        .with_contents(
+                            #[allow(deprecated)]
+                            ProfileFileKind::Config,
+                            "[default]\nregion = us-west-2",
+                        )
+                        .build(),
+                )
+                .load_with_report()
+                .await;
+
+            let region = report.setting(setting::REGION).unwrap();
+            assert_eq!(
+                Some(&ConfigSource::Profile {
+                    profile: "default".into(),
+                    key: "region".into()
+                }),
+                region.source()
+            );
+            assert_eq!(
+                vec![Some(&SkipReason::NotSet), Some(&SkipReason::NotSet), None],
+                region
+                    .candidates()
+                    .iter()
+                    .map(|c| c.skip_reason())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[tokio::test]
+        async fn report_explains_ignored_endpoint_urls() {
+            let env = Env::from_slice(&[
+                ("AWS_ENDPOINT_URL", "http://localhost:7878"),
+                ("AWS_IGNORE_CONFIGURED_ENDPOINT_URLS", "true"),
+            ]);
+            let (config, report) = base_conf()
+                .test_credentials()
+                .env(env)
+                .load_with_report()
+                .await;
+            assert_eq!(None, config.endpoint_url());
+            let endpoint_url = report.setting(setting::ENDPOINT_URL).unwrap();
+            assert_eq!(None, endpoint_url.value());
+            assert!(matches!(
+                endpoint_url.candidates()[0].skip_reason(),
+                Some(SkipReason::Ignored { .. })
+            ));
+        }
+
+        #[tokio::test]
+        async fn report_records_the_credentials_chain_step_that_was_used() {
+            let env = Env::from_slice(&[
+                ("AWS_REGION", "us-east-1"),
+                ("AWS_ACCESS_KEY_ID", "akid"),
+                ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ]);
+            let (config, report) = base_conf()
+                .env(env)
+                .fs(Fs::from_slice(&[]))
+                .load_with_report()
+                .await;
+            let credentials = report.credentials();
+            assert_eq!(CredentialsSource::DefaultChain, credentials.source());
+            assert_eq!(None, credentials.provided_by());
+            assert!(credentials
+                .chain_steps()
+                .iter()
+                .all(|step| step.outcome() == &ChainStepOutcome::NotEvaluated));
+
+            config
+                .credentials_provider()
+                .unwrap()
+                .provide_credentials()
+                .await
+                .expect("credentials from the environment");
+            assert_eq!(Some("Environment".to_string()), credentials.provided_by());
+            let steps = credentials.chain_steps();
+            assert_eq!("Profile", steps[1].name());
+            assert_eq!(&ChainStepOutcome::NotReached, steps[1].outcome());
+        }
+
         #[tokio::test]
         async fn load_use_fips() {
             let conf = base_conf().use_fips(true).load().await;
@@ -1121,6 +1479,21 @@ mod loader {
             assert_eq!(None, conf.request_min_compression_size_bytes());
         }
 
+        #[tokio::test]
+        async fn load_sigv4a_signing_region_set() {
+            let conf = base_conf()
+                .sigv4a_signing_region_set(SigningRegionSet::from("us-*"))
+                .load()
+                .await;
+            assert_eq!(
+                Some(&SigningRegionSet::from("us-*")),
+                conf.sigv4a_signing_region_set()
+            );
+
+            let conf = base_conf().load().await;
+            assert_eq!(None, conf.sigv4a_signing_region_set());
+        }
+
         #[tokio::test]
         async fn app_name() {
             let app_name = AppName::new("my-app-name").unwrap();
@@ -3,9 +3,10 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::resolution::{ChainRecorder, ChainStepOutcome};
 use aws_credential_types::{
     provider::{self, error::CredentialsError, future, ProvideCredentials},
-    Credentials,
+    Credentials, ProvenanceStep,
 };
 use aws_smithy_types::error::display::DisplayErrorContext;
 use std::borrow::Cow;
@@ -34,6 +35,7 @@ use tracing::Instrument;
 /// ```
 pub struct CredentialsProviderChain {
     providers: Vec<(Cow<'static, str>, Box<dyn ProvideCredentials>)>,
+    recorder: Option<ChainRecorder>,
 }
 
 impl Debug for CredentialsProviderChain {
@@ -59,6 +61,7 @@ impl CredentialsProviderChain {
     ) -> Self {
         CredentialsProviderChain {
             providers: vec![(name.into(), Box::new(provider))],
+            recorder: None,
         }
     }
 
@@ -90,19 +93,54 @@ impl CredentialsProviderChain {
         )
     }
 
+    /// Record the outcome of each step of the chain whenever credentials are loaded
+    pub(crate) fn record_outcomes(mut self, recorder: ChainRecorder) -> Self {
+        recorder.record(
+            self.providers
+                .iter()
+                .map(|(name, _)| (name.clone(), ChainStepOutcome::NotEvaluated)),
+        );
+        self.recorder = Some(recorder);
+        self
+    }
+
     async fn credentials(&self) -> provider::Result {
+        let mut outcomes = Vec::with_capacity(self.providers.len());
+        let result = self.load_credentials(&mut outcomes).await;
+        if let Some(recorder) = &self.recorder {
+            let outcomes = outcomes
+                .into_iter()
+                .chain(std::iter::repeat(ChainStepOutcome::NotReached));
+            recorder.record(
+                self.providers
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .zip(outcomes),
+            );
+        }
+        result
+    }
+
+    async fn load_credentials(&self, outcomes: &mut Vec<ChainStepOutcome>) -> provider::Result {
         for (name, provider) in &self.providers {
             let span = tracing::debug_span!("load_credentials", provider = %name);
             match provider.provide_credentials().instrument(span).await {
                 Ok(credentials) => {
                     tracing::debug!(provider = %name, "loaded credentials");
-                    return Ok(credentials);
+                    outcomes.push(ChainStepOutcome::Provided);
+                    return Ok(with_chain_step(credentials, name));
                 }
                 Err(err @ CredentialsError::CredentialsNotLoaded(_)) => {
                     tracing::debug!(provider = %name, context = %DisplayErrorContext(&err), "provider in chain did not provide credentials");
+                    outcomes.push(ChainStepOutcome::NotLoaded {
+                        reason: DisplayErrorContext(&err).to_string(),
+                    });
                 }
                 Err(err) => {
                     tracing::warn!(provider = %name, error = %DisplayErrorContext(&err), "provider failed to provide credentials");
+                    outcomes.push(ChainStepOutcome::Failed {
+                        reason: DisplayErrorContext(&err).to_string(),
+                    });
                     return Err(err);
                 }
             }
@@ -122,16 +160,23 @@ impl ProvideCredentials for CredentialsProviderChain {
     }
 
     fn fallback_on_interrupt(&self) -> Option<Credentials> {
-        for (_, provider) in &self.providers {
-            match provider.fallback_on_interrupt() {
-                creds @ Some(_) => return creds,
-                None => {}
+        for (name, provider) in &self.providers {
+            if let Some(creds) = provider.fallback_on_interrupt() {
+                return Some(with_chain_step(creds, name));
             }
         }
         None
     }
 }
 
+/// Records the step of the chain that produced `credentials` at the front of their provenance
+fn with_chain_step(mut credentials: Credentials, name: &Cow<'static, str>) -> Credentials {
+    credentials
+        .provenance_mut()
+        .insert(0, ProvenanceStep::new(name.clone()));
+    credentials
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -229,4 +274,25 @@ mod tests {
             },
         };
     }
+
+    #[tokio::test]
+    async fn provenance_records_chain_step() {
+        let chain = CredentialsProviderChain::first_try(
+            "provider1",
+            provide_credentials_fn(|| async {
+                Err(CredentialsError::not_loaded("no credentials"))
+            }),
+        )
+        .or_else(
+            "provider2",
+            provide_credentials_fn(|| async { Ok(Credentials::for_tests()) }),
+        );
+        let creds = chain.provide_credentials().await.unwrap();
+        let steps: Vec<_> = creds
+            .provenance()
+            .iter()
+            .map(|step| step.provider_name())
+            .collect();
+        assert_eq!(vec!["provider2", "test"], steps);
+    }
 }
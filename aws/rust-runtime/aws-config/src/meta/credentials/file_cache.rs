@@ -0,0 +1,595 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Credentials provider that persists credentials to disk so that they can be shared across processes
+//!
+//! Every process that resolves credentials from an assume role or SSO profile makes its own calls to
+//! STS or SSO. [`FileCacheCredentialsProvider`] wraps another provider and stores the credentials it
+//! returns under `~/.aws/cli/cache` (or a [configured directory](Builder::cache_directory)), so that
+//! short-lived processes can reuse credentials that are still valid.
+//!
+//! Cache files are keyed by the resolved profile chain unless an explicit
+//! [cache key](Builder::cache_key) is given. Only credentials that expire are cached. Cache files are
+//! written atomically and refreshes are serialized across processes with a lock file.
+//!
+//! _Note: cached credentials are stored unencrypted and are readable by the owner of the cache
+//! directory. Only enable this provider where that is acceptable._
+//!
+//! # Examples
+//! ```no_run
+//! # async fn docs() {
+//! use aws_config::default_provider::credentials::DefaultCredentialsChain;
+//! use aws_config::meta::credentials::FileCacheCredentialsProvider;
+//! let provider = FileCacheCredentialsProvider::builder()
+//!     .build(DefaultCredentialsChain::builder().build().await);
+//! # }
+//! ```
+
+use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
+use crate::provider_config::ProviderConfig;
+use aws_credential_types::provider::{self, future, ProvideCredentials, SharedCredentialsProvider};
+use aws_credential_types::{Credentials, ProvenanceStep};
+use aws_runtime::fs_util::{home_dir, Os};
+use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_json::deserialize::Token;
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::{DateTime, Number};
+use aws_types::os_shim_internal::Fs;
+use ring::digest;
+use std::borrow::Cow;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zeroize::Zeroizing;
+
+const PROVIDER_NAME: &str = "FileCache";
+
+const DEFAULT_EXPIRY_BUFFER: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Credentials provider that shares credentials across processes through files on disk
+///
+/// See the [module documentation](crate::meta::credentials::file_cache) for more details.
+#[derive(Debug)]
+pub struct FileCacheCredentialsProvider {
+    provider: SharedCredentialsProvider,
+    provider_config: ProviderConfig,
+    cache_key: Option<String>,
+    cache_directory: Option<PathBuf>,
+    expiry_buffer: Duration,
+    lock_timeout: Duration,
+}
+
+impl FileCacheCredentialsProvider {
+    /// Builder for this credentials provider
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn credentials(&self) -> provider::Result {
+        let Some(path) = self.cache_path().await else {
+            tracing::debug!(
+                "could not determine a credentials cache file; not caching credentials"
+            );
+            return self.provider.provide_credentials().await;
+        };
+        let fs = self.provider_config.fs();
+        if let Some(credentials) = self.load(&fs, &path).await {
+            return Ok(credentials);
+        }
+
+        let lock_path = path.with_extension("lock");
+        let locked = self.acquire_lock(&fs, &lock_path).await;
+        // another process may have refreshed the credentials while we were waiting for the lock
+        let result = match self.load(&fs, &path).await {
+            Some(credentials) => Ok(credentials),
+            None => {
+                let result = self.provider.provide_credentials().await;
+                if let Ok(credentials) = &result {
+                    self.store(&fs, &path, credentials).await;
+                }
+                result
+            }
+        };
+        if locked {
+            if let Err(err) = fs.remove_file(&lock_path).await {
+                tracing::warn!(path = %lock_path.display(), error = %err, "failed to release credentials cache lock");
+            }
+        }
+        result
+    }
+
+    async fn cache_path(&self) -> Option<PathBuf> {
+        let key = match &self.cache_key {
+            Some(key) => Cow::Borrowed(key.as_str()),
+            None => Cow::Owned(self.profile_chain_key().await?),
+        };
+        let mut path = match &self.cache_directory {
+            Some(directory) => directory.clone(),
+            None => {
+                let home = home_dir(&self.provider_config.env(), Os::real())?;
+                let mut path = PathBuf::from(home);
+                path.push(".aws/cli/cache");
+                path
+            }
+        };
+        // hex::encode returns a lowercase string
+        path.push(hex::encode(digest::digest(
+            &digest::SHA1_FOR_LEGACY_USE_ONLY,
+            key.as_bytes(),
+        )));
+        path.set_extension("json");
+        Some(path)
+    }
+
+    async fn profile_chain_key(&self) -> Option<String> {
+        let profile_set = self.provider_config.try_profile().await.ok()?;
+        crate::profile::credentials::repr::resolve_chain(profile_set)
+            .ok()
+            .map(|chain| chain.cache_key())
+    }
+
+    async fn load(&self, fs: &Fs, path: &Path) -> Option<Credentials> {
+        let data = match fs.read_to_end(path).await {
+            Ok(data) => Zeroizing::new(data),
+            Err(err) if err.kind() == ErrorKind::NotFound => return None,
+            Err(err) => {
+                tracing::debug!(path = %path.display(), error = %err, "failed to read credentials cache file");
+                return None;
+            }
+        };
+        let credentials = match parse_cached_credentials(&data, path) {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                tracing::debug!(path = %path.display(), error = %err, "ignoring invalid credentials cache file");
+                return None;
+            }
+        };
+        let now = self.provider_config.time_source().now();
+        match credentials.expiry() {
+            Some(expiry) if expiry > now + self.expiry_buffer => {
+                tracing::debug!(path = %path.display(), "loaded credentials from the credentials cache");
+                Some(credentials)
+            }
+            _ => {
+                tracing::debug!(path = %path.display(), "cached credentials are expired or about to expire");
+                None
+            }
+        }
+    }
+
+    async fn store(&self, fs: &Fs, path: &Path, credentials: &Credentials) {
+        let Some(expiry) = credentials.expiry() else {
+            tracing::debug!("credentials don't expire; not caching them");
+            return;
+        };
+        let contents = match serialize_credentials(credentials, expiry) {
+            Ok(contents) => contents,
+            Err(err) => {
+                tracing::warn!(error = %err, "failed to serialize credentials for the credentials cache");
+                return;
+            }
+        };
+        if let Some(directory) = path.parent() {
+            if let Err(err) = fs.create_dir_all(directory).await {
+                tracing::warn!(path = %directory.display(), error = %err, "failed to create credentials cache directory");
+                return;
+            }
+        }
+        // write to a unique temporary file and rename it so that readers never see a partial file
+        let temp_path = path.with_extension(format!("{:016x}.tmp", fastrand::u64(..)));
+        let result = match fs.create_new(&temp_path, contents.as_bytes()).await {
+            Ok(()) => fs.rename(&temp_path, path).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            tracing::warn!(path = %path.display(), error = %err, "failed to write credentials cache file");
+            let _ = fs.remove_file(&temp_path).await;
+        }
+    }
+
+    /// Acquire the lock file that serializes refreshes across processes
+    ///
+    /// Returns `false` if the lock couldn't be acquired within the lock timeout, in which case
+    /// credentials are refreshed without holding the lock.
+    async fn acquire_lock(&self, fs: &Fs, lock_path: &Path) -> bool {
+        let sleep_impl = self.provider_config.sleep_impl();
+        let attempts = (self.lock_timeout.as_millis() / LOCK_POLL_INTERVAL.as_millis()).max(1);
+        for _ in 0..attempts {
+            let now = self.provider_config.time_source().now();
+            let created_at = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            match fs.create_new(lock_path, created_at.to_string()).await {
+                Ok(()) => return true,
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+                Err(err) => {
+                    tracing::debug!(path = %lock_path.display(), error = %err, "failed to create credentials cache lock");
+                    return false;
+                }
+            }
+            if self.lock_is_stale(fs, lock_path, now).await {
+                tracing::debug!(path = %lock_path.display(), "removing stale credentials cache lock");
+                let _ = fs.remove_file(lock_path).await;
+                continue;
+            }
+            match &sleep_impl {
+                Some(sleep_impl) => sleep_impl.sleep(LOCK_POLL_INTERVAL).await,
+                None => break,
+            }
+        }
+        tracing::debug!(path = %lock_path.display(), "timed out waiting for credentials cache lock");
+        false
+    }
+
+    /// A lock is stale when it's older than the lock timeout or its contents can't be read,
+    /// e.g. because the process holding it crashed.
+    async fn lock_is_stale(&self, fs: &Fs, lock_path: &Path, now: SystemTime) -> bool {
+        let created_at = fs
+            .read_to_end(lock_path)
+            .await
+            .ok()
+            .and_then(|contents| String::from_utf8(contents).ok())
+            .and_then(|contents| contents.trim().parse::<u64>().ok())
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        match created_at {
+            Some(created_at) => created_at + self.lock_timeout < now,
+            None => true,
+        }
+    }
+}
+
+impl ProvideCredentials for FileCacheCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+
+    fn fallback_on_interrupt(&self) -> Option<Credentials> {
+        self.provider.fallback_on_interrupt()
+    }
+}
+
+/// Builder for [`FileCacheCredentialsProvider`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    provider_config: Option<ProviderConfig>,
+    cache_key: Option<String>,
+    cache_directory: Option<PathBuf>,
+    expiry_buffer: Option<Duration>,
+    lock_timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Configure generic options of the [`FileCacheCredentialsProvider`]
+    ///
+    /// The environment, file system and profile of the provider config are used to locate the
+    /// cache directory and to resolve the profile chain the cache is keyed by.
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Key the cache by `cache_key` instead of by the resolved profile chain
+    ///
+    /// Providers sharing a cache directory and cache key share credentials, so the key must
+    /// uniquely identify the credentials returned by the wrapped provider.
+    pub fn cache_key(mut self, cache_key: impl Into<String>) -> Self {
+        self.cache_key = Some(cache_key.into());
+        self
+    }
+
+    /// Directory to store cached credentials in
+    ///
+    /// Defaults to `~/.aws/cli/cache`.
+    pub fn cache_directory(mut self, cache_directory: impl Into<PathBuf>) -> Self {
+        self.cache_directory = Some(cache_directory.into());
+        self
+    }
+
+    /// Duration before expiry at which cached credentials are no longer used
+    ///
+    /// Defaults to 5 minutes.
+    pub fn expiry_buffer(mut self, expiry_buffer: Duration) -> Self {
+        self.expiry_buffer = Some(expiry_buffer);
+        self
+    }
+
+    /// Maximum time to wait for another process to refresh the cached credentials
+    ///
+    /// Locks older than this are considered abandoned and removed. Defaults to 30 seconds.
+    pub fn lock_timeout(mut self, lock_timeout: Duration) -> Self {
+        self.lock_timeout = Some(lock_timeout);
+        self
+    }
+
+    /// Build a [`FileCacheCredentialsProvider`] that caches the credentials of `provider`
+    pub fn build(
+        self,
+        provider: impl ProvideCredentials + 'static,
+    ) -> FileCacheCredentialsProvider {
+        FileCacheCredentialsProvider {
+            provider: SharedCredentialsProvider::new(provider),
+            provider_config: self.provider_config.unwrap_or_default(),
+            cache_key: self.cache_key,
+            cache_directory: self.cache_directory,
+            expiry_buffer: self.expiry_buffer.unwrap_or(DEFAULT_EXPIRY_BUFFER),
+            lock_timeout: self.lock_timeout.unwrap_or(DEFAULT_LOCK_TIMEOUT),
+        }
+    }
+}
+
+fn serialize_credentials(
+    credentials: &Credentials,
+    expiry: SystemTime,
+) -> Result<Zeroizing<String>, InvalidJsonCredentials> {
+    let expiration = DateTime::from(expiry)
+        .fmt(Format::DateTime)
+        .map_err(|err| InvalidJsonCredentials::InvalidField {
+            field: "Expiration",
+            err: err.into(),
+        })?;
+    let mut out = Zeroizing::new(String::new());
+    let mut writer = JsonObjectWriter::new(&mut out);
+    writer.key("Version").number(Number::PosInt(1));
+    writer
+        .key("AccessKeyId")
+        .string(credentials.access_key_id());
+    writer
+        .key("SecretAccessKey")
+        .string(credentials.secret_access_key());
+    if let Some(session_token) = credentials.session_token() {
+        writer.key("SessionToken").string(session_token);
+    }
+    writer.key("Expiration").string(&expiration);
+    if let Some(account_id) = credentials.account_id() {
+        writer.key("AccountId").string(account_id);
+    }
+    writer.finish();
+    Ok(out)
+}
+
+fn parse_cached_credentials(
+    contents: &[u8],
+    path: &Path,
+) -> Result<Credentials, InvalidJsonCredentials> {
+    let mut access_key_id = None;
+    let mut secret_access_key = None;
+    let mut session_token = None;
+    let mut expiration = None;
+    let mut account_id = None;
+    json_parse_loop(contents, |key, value| {
+        match (key, value) {
+            /*
+             "Version": 1,
+             "AccessKeyId": "ASIARTESTID",
+             "SecretAccessKey": "TESTSECRETKEY",
+             "SessionToken": "TESTSESSIONTOKEN",
+             "Expiration": "2022-05-02T18:36:00Z",
+             "AccountId": "123456789012"
+            */
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("AccessKeyId") => {
+                access_key_id = Some(value.to_unescaped()?)
+            }
+            (key, Token::ValueString { value, .. })
+                if key.eq_ignore_ascii_case("SecretAccessKey") =>
+            {
+                secret_access_key = Some(Zeroizing::new(value.to_unescaped()?.into_owned()))
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("SessionToken") => {
+                session_token = Some(value.to_unescaped()?.into_owned())
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Expiration") => {
+                expiration = Some(value.to_unescaped()?)
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("AccountId") => {
+                account_id = Some(value.to_unescaped()?)
+            }
+            _ => {}
+        };
+        Ok(())
+    })?;
+
+    let access_key_id = access_key_id.ok_or(InvalidJsonCredentials::MissingField("AccessKeyId"))?;
+    let secret_access_key =
+        secret_access_key.ok_or(InvalidJsonCredentials::MissingField("SecretAccessKey"))?;
+    let expiration = expiration.ok_or(InvalidJsonCredentials::MissingField("Expiration"))?;
+    let expiration = DateTime::from_str(&expiration, Format::DateTime)
+        .map_err(|err| InvalidJsonCredentials::InvalidField {
+            field: "Expiration",
+            err: err.into(),
+        })
+        .and_then(|date_time| {
+            SystemTime::try_from(date_time).map_err(|_| {
+                InvalidJsonCredentials::Other(
+                    "credential expiration time cannot be represented by a SystemTime".into(),
+                )
+            })
+        })?;
+    let mut credentials = Credentials::new(
+        access_key_id,
+        secret_access_key.as_str(),
+        session_token,
+        Some(expiration),
+        PROVIDER_NAME,
+    );
+    if let Some(account_id) = account_id {
+        credentials = credentials.with_account_id(account_id);
+    }
+    if let Some(step) = credentials.provenance_mut().first_mut() {
+        *step = ProvenanceStep::new(PROVIDER_NAME).with_detail(path.display().to_string());
+    }
+    Ok(credentials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_credential_types::credential_fn::provide_credentials_fn;
+    use aws_runtime::env_config::file::{EnvConfigFileKind, EnvConfigFiles};
+    use aws_smithy_async::time::StaticTimeSource;
+    use aws_types::os_shim_internal::Env;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(NOW)
+    }
+
+    fn provider_config(fs: &Fs, config: &str) -> ProviderConfig {
+        ProviderConfig::no_configuration()
+            .with_fs(fs.clone())
+            .with_env(Env::from_slice(&[("HOME", "/home")]))
+            .with_time_source(StaticTimeSource::new(now()))
+            .with_profile_config(
+                Some(
+                    EnvConfigFiles::builder()
+                        .with_contents(EnvConfigFileKind::Config, config)
+                        .build(),
+                ),
+                None,
+            )
+    }
+
+    fn counting_provider(
+        calls: &Arc<AtomicUsize>,
+        expiry: Option<SystemTime>,
+    ) -> impl ProvideCredentials + 'static {
+        let calls = calls.clone();
+        provide_credentials_fn(move || {
+            let calls = calls.clone();
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(
+                    Credentials::new("AKID", "SECRET", Some("TOKEN".to_string()), expiry, "test")
+                        .with_account_id("123456789012"),
+                )
+            }
+        })
+    }
+
+    const ASSUME_ROLE_CONFIG: &str = "[default]\nrole_arn = arn:aws:iam::123456789012:role/Role\nsource_profile = base\n\n[profile base]\naws_access_key_id = AKIDBASE\naws_secret_access_key = SECRETBASE\n";
+
+    #[tokio::test]
+    async fn credentials_are_shared_across_providers() {
+        let fs = Fs::from_slice(&[]);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let expiry = Some(now() + Duration::from_secs(3600));
+        let build = || {
+            FileCacheCredentialsProvider::builder()
+                .configure(&provider_config(&fs, ASSUME_ROLE_CONFIG))
+                .build(counting_provider(&calls, expiry))
+        };
+
+        let first = build().provide_credentials().await.expect("success");
+        assert_eq!("test", first.provenance()[0].provider_name());
+        let second = build().provide_credentials().await.expect("success");
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+
+        assert_eq!("AKID", second.access_key_id());
+        assert_eq!("SECRET", second.secret_access_key());
+        assert_eq!(Some("TOKEN"), second.session_token());
+        assert_eq!(expiry, second.expiry());
+        assert_eq!(Some("123456789012"), second.account_id());
+        assert_eq!(PROVIDER_NAME, second.provenance()[0].provider_name());
+        let path = build().cache_path().await.expect("path");
+        assert!(path.starts_with("/home/.aws/cli/cache"));
+        assert_eq!(
+            Some(path.display().to_string().as_str()),
+            second.provenance()[0].detail()
+        );
+        fs.read_to_end(path.with_extension("lock"))
+            .await
+            .expect_err("lock is released");
+    }
+
+    #[tokio::test]
+    async fn cache_is_keyed_by_profile_chain() {
+        let fs = Fs::from_slice(&[]);
+        let path = |config: &str| {
+            FileCacheCredentialsProvider::builder()
+                .configure(&provider_config(&fs, config))
+                .build(provide_credentials_fn(|| async {
+                    Ok(Credentials::for_tests())
+                }))
+        };
+        let other_role = ASSUME_ROLE_CONFIG.replace("role/Role", "role/Other");
+        let first = path(ASSUME_ROLE_CONFIG).cache_path().await.expect("path");
+        assert_eq!(
+            Some(first.clone()),
+            path(ASSUME_ROLE_CONFIG).cache_path().await
+        );
+        assert_ne!(Some(first), path(&other_role).cache_path().await);
+        assert_eq!(None, path("").cache_path().await);
+
+        let explicit = FileCacheCredentialsProvider::builder()
+            .configure(&provider_config(&fs, ""))
+            .cache_key("my-key")
+            .cache_directory("/cache")
+            .build(provide_credentials_fn(|| async {
+                Ok(Credentials::for_tests())
+            }));
+        assert!(explicit
+            .cache_path()
+            .await
+            .expect("path")
+            .starts_with("/cache"));
+    }
+
+    #[tokio::test]
+    async fn credentials_near_expiry_are_refreshed() {
+        let fs = Fs::from_slice(&[]);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = FileCacheCredentialsProvider::builder()
+            .configure(&provider_config(&fs, ASSUME_ROLE_CONFIG))
+            .expiry_buffer(Duration::from_secs(600))
+            .build(counting_provider(
+                &calls,
+                Some(now() + Duration::from_secs(300)),
+            ));
+        provider.provide_credentials().await.expect("success");
+        provider.provide_credentials().await.expect("success");
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn credentials_without_expiry_are_not_cached() {
+        let fs = Fs::from_slice(&[]);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = FileCacheCredentialsProvider::builder()
+            .configure(&provider_config(&fs, ASSUME_ROLE_CONFIG))
+            .build(counting_provider(&calls, None));
+        provider.provide_credentials().await.expect("success");
+        let path = provider.cache_path().await.expect("path");
+        fs.read_to_end(&path).await.expect_err("not cached");
+    }
+
+    #[tokio::test]
+    async fn invalid_cache_files_and_stale_locks_are_ignored() {
+        let fs = Fs::from_slice(&[]);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = FileCacheCredentialsProvider::builder()
+            .configure(&provider_config(&fs, ASSUME_ROLE_CONFIG))
+            .build(counting_provider(
+                &calls,
+                Some(now() + Duration::from_secs(3600)),
+            ));
+        let path = provider.cache_path().await.expect("path");
+        fs.write(&path, "not json").await.unwrap();
+        fs.write(path.with_extension("lock"), "0").await.unwrap();
+
+        provider.provide_credentials().await.expect("success");
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+        fs.read_to_end(path.with_extension("lock"))
+            .await
+            .expect_err("stale lock removed");
+        let cached = fs.read_to_end(&path).await.expect("cache file replaced");
+        parse_cached_credentials(&cached, &path).expect("valid cache file");
+    }
+}
@@ -7,3 +7,8 @@
 
 mod chain;
 pub use chain::CredentialsProviderChain;
+
+#[cfg(feature = "credentials-file-cache")]
+pub mod file_cache;
+#[cfg(feature = "credentials-file-cache")]
+pub use file_cache::FileCacheCredentialsProvider;
@@ -22,12 +22,15 @@
 //! - `exec` which contains a chain representation of providers to implement passing bootstrapped credentials
 //! through a series of providers.
 
+#[cfg(feature = "credentials-process")]
+use crate::credential_process::{ProcessOptions, StderrBehavior};
 use crate::profile::cell::ErrorTakingOnceCell;
 #[allow(deprecated)]
 use crate::profile::profile_file::ProfileFiles;
 use crate::profile::Profile;
 use crate::profile::ProfileFileLoadError;
 use crate::provider_config::ProviderConfig;
+use crate::sts::mfa::MfaTokenProvider;
 use aws_credential_types::{
     provider::{self, error::CredentialsError, future, ProvideCredentials},
     Credentials,
@@ -142,6 +145,9 @@ pub struct ProfileFileCredentialsProvider {
 struct Config {
     factory: exec::named::NamedProviderFactory,
     provider_config: ProviderConfig,
+    mfa_token_provider: Option<MfaTokenProvider>,
+    #[cfg(feature = "credentials-process")]
+    process_options: ProcessOptions,
 }
 
 impl ProfileFileCredentialsProvider {
@@ -379,6 +385,9 @@ pub struct Builder {
     #[allow(deprecated)]
     profile_files: Option<ProfileFiles>,
     custom_providers: HashMap<Cow<'static, str>, Arc<dyn ProvideCredentials>>,
+    mfa_token_provider: Option<MfaTokenProvider>,
+    #[cfg(feature = "credentials-process")]
+    process_options: ProcessOptions,
 }
 
 impl Builder {
@@ -450,6 +459,49 @@ impl Builder {
         self
     }
 
+    /// Set the source of MFA token codes for profiles with an `mfa_serial`
+    ///
+    /// Without one, loading credentials for a profile that assumes a role with MFA fails.
+    ///
+    /// ```ini
+    /// [profile mfa]
+    /// role_arn = arn:aws:iam::123456789012:role/RoleA
+    /// source_profile = base
+    /// mfa_serial = arn:aws:iam::123456789012:mfa/user
+    /// ```
+    pub fn mfa_token_provider(mut self, provider: MfaTokenProvider) -> Self {
+        self.mfa_token_provider = Some(provider);
+        self
+    }
+
+    /// Set how the `stderr` of a profile's `credential_process` is handled
+    ///
+    /// See [`CredentialProcessProvider`](crate::credential_process::CredentialProcessProvider)
+    /// for running interactive processes. Defaults to [`StderrBehavior::Capture`].
+    #[cfg(feature = "credentials-process")]
+    pub fn credential_process_stderr(mut self, stderr: StderrBehavior) -> Self {
+        self.process_options.stderr = stderr;
+        self
+    }
+
+    /// Let a profile's `credential_process` read from the `stdin` of the current process
+    ///
+    /// See [`inherit_stdin`](crate::credential_process::Builder::inherit_stdin).
+    #[cfg(feature = "credentials-process")]
+    pub fn credential_process_inherit_stdin(mut self, inherit_stdin: bool) -> Self {
+        self.process_options.inherit_stdin = inherit_stdin;
+        self
+    }
+
+    /// Set the maximum amount of time a profile's `credential_process` may run for
+    ///
+    /// See [`timeout`](crate::credential_process::Builder::timeout).
+    #[cfg(feature = "credentials-process")]
+    pub fn credential_process_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.process_options.timeout = Some(timeout);
+        self
+    }
+
     /// Builds a [`ProfileFileCredentialsProvider`]
     pub fn build(self) -> ProfileFileCredentialsProvider {
         let build_span = tracing::debug_span!("build_profile_provider");
@@ -492,6 +544,9 @@ impl Builder {
             config: Arc::new(Config {
                 factory,
                 provider_config: conf,
+                mfa_token_provider: self.mfa_token_provider,
+                #[cfg(feature = "credentials-process")]
+                process_options: self.process_options,
             }),
             inner_provider: ErrorTakingOnceCell::new(),
         }
@@ -508,7 +563,14 @@ async fn build_provider_chain(
         .map_err(|parse_err| ProfileFileError::InvalidProfile(parse_err.clone()))?;
     let repr = repr::resolve_chain(profile_set)?;
     tracing::info!(chain = ?repr, "constructed abstract provider from config file");
-    exec::ProviderChain::from_repr(&config.provider_config, repr, &config.factory)
+    exec::ProviderChain::from_repr(
+        &config.provider_config,
+        repr,
+        &config.factory,
+        config.mfa_token_provider.as_ref(),
+        #[cfg(feature = "credentials-process")]
+        &config.process_options,
+    )
 }
 
 #[derive(Debug)]
@@ -544,13 +606,16 @@ impl ChainProvider {
             // we won't get things like `service_config()` set appropriately.
             let sdk_config = config.provider_config.client_config();
             for provider in chain.chain().iter() {
+                // each hop records the steps that produced the credentials it was given
+                let source_provenance = creds.provenance().to_vec();
                 let next_creds = provider
                     .credentials(creds, &sdk_config)
                     .instrument(tracing::debug_span!("load_assume_role", provider = ?provider))
                     .await;
                 match next_creds {
-                    Ok(next_creds) => {
+                    Ok(mut next_creds) => {
                         tracing::info!(creds = ?next_creds, "loaded assume role credentials");
+                        next_creds.provenance_mut().extend(source_provenance);
                         creds = next_creds
                     }
                     Err(e) => {
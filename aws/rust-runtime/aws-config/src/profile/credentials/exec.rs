@@ -5,15 +5,18 @@
 
 use super::repr::{self, BaseProvider};
 #[cfg(feature = "credentials-process")]
-use crate::credential_process::CredentialProcessProvider;
+use crate::credential_process::{CredentialProcessProvider, ProcessOptions};
 use crate::profile::credentials::ProfileFileError;
 use crate::provider_config::ProviderConfig;
 use crate::sts;
+use crate::sts::mfa::{MfaSession, MfaTokenProvider};
 use crate::web_identity_token::{StaticConfiguration, WebIdentityTokenCredentialsProvider};
 use aws_credential_types::provider::{
     self, error::CredentialsError, ProvideCredentials, SharedCredentialsProvider,
 };
 use aws_sdk_sts::config::Credentials;
+use aws_sdk_sts::operation::assume_role::builders::AssumeRoleFluentBuilder;
+use aws_sdk_sts::types::Tag;
 use aws_sdk_sts::Client as StsClient;
 use aws_smithy_async::time::SharedTimeSource;
 use aws_types::SdkConfig;
@@ -25,6 +28,11 @@ pub(super) struct AssumeRoleProvider {
     role_arn: String,
     external_id: Option<String>,
     session_name: Option<String>,
+    duration_seconds: Option<i32>,
+    source_identity: Option<String>,
+    session_tags: Vec<Tag>,
+    transitive_tag_keys: Vec<String>,
+    mfa: Option<MfaSession>,
     time_source: SharedTimeSource,
 }
 
@@ -42,19 +50,41 @@ impl AssumeRoleProvider {
         let session_name = &self.session_name.as_ref().cloned().unwrap_or_else(|| {
             sts::util::default_session_name("assume-role-from-profile", self.time_source.now())
         });
-        let assume_role_creds = client
+        let request = client
             .assume_role()
             .role_arn(&self.role_arn)
             .set_external_id(self.external_id.clone())
             .role_session_name(session_name)
-            .send()
-            .await
-            .map_err(CredentialsError::provider_error)?
-            .credentials;
-        sts::util::into_credentials(assume_role_creds, "AssumeRoleProvider")
+            .set_duration_seconds(self.duration_seconds)
+            .set_source_identity(self.source_identity.clone())
+            .set_tags(Some(self.session_tags.clone()).filter(|tags| !tags.is_empty()))
+            .set_transitive_tag_keys(
+                Some(self.transitive_tag_keys.clone()).filter(|keys| !keys.is_empty()),
+            );
+        match &self.mfa {
+            Some(mfa) => {
+                mfa.credentials(&self.time_source, |serial_number, token_code| {
+                    assume_role(request.serial_number(serial_number).token_code(token_code))
+                })
+                .await
+            }
+            None => assume_role(request).await,
+        }
     }
 }
 
+async fn assume_role(request: AssumeRoleFluentBuilder) -> provider::Result {
+    let assumed = request
+        .send()
+        .await
+        .map_err(CredentialsError::provider_error)?;
+    sts::util::into_credentials(
+        assumed.credentials,
+        assumed.assumed_role_user,
+        "AssumeRoleProvider",
+    )
+}
+
 #[derive(Debug)]
 pub(super) struct ProviderChain {
     base: Arc<dyn ProvideCredentials>,
@@ -76,6 +106,8 @@ impl ProviderChain {
         provider_config: &ProviderConfig,
         repr: repr::ProfileChain<'_>,
         factory: &named::NamedProviderFactory,
+        mfa_token_provider: Option<&MfaTokenProvider>,
+        #[cfg(feature = "credentials-process")] process_options: &ProcessOptions,
     ) -> Result<Self, ProfileFileError> {
         let base = match repr.base() {
             BaseProvider::NamedSource(name) => {
@@ -89,7 +121,11 @@ impl ProviderChain {
             BaseProvider::CredentialProcess(_credential_process) => {
                 #[cfg(feature = "credentials-process")]
                 {
-                    Arc::new(CredentialProcessProvider::from_command(_credential_process))
+                    Arc::new(CredentialProcessProvider::from_command(
+                        provider_config,
+                        _credential_process,
+                        process_options,
+                    ))
                 }
                 #[cfg(not(feature = "credentials-process"))]
                 {
@@ -159,6 +195,51 @@ impl ProviderChain {
                     })?
                 }
             }
+            #[allow(unused_variables)]
+            BaseProvider::RolesAnywhere {
+                trust_anchor_arn,
+                profile_arn,
+                role_arn,
+                certificate,
+                private_key,
+                intermediate_certificates,
+                session_name,
+                duration_seconds,
+            } => {
+                #[cfg(feature = "rolesanywhere")]
+                {
+                    use crate::rolesanywhere::credentials::{
+                        region_from_arn, RolesAnywhereConfig, RolesAnywhereCredentialsProvider,
+                    };
+
+                    // the region was validated when the profile was parsed
+                    let region = region_from_arn(trust_anchor_arn)
+                        .expect("trust anchor ARN contains a region");
+                    let config = RolesAnywhereConfig {
+                        trust_anchor_arn: trust_anchor_arn.to_string(),
+                        profile_arn: profile_arn.to_string(),
+                        role_arn: role_arn.to_string(),
+                        certificate: certificate.into(),
+                        private_key: private_key.into(),
+                        intermediate_certificates: intermediate_certificates.map(Into::into),
+                        session_name: session_name.map(|s| s.to_string()),
+                        session_duration: duration_seconds.map(std::time::Duration::from_secs),
+                        region,
+                        endpoint_url: None,
+                    };
+                    Arc::new(RolesAnywhereCredentialsProvider::new(
+                        provider_config,
+                        config,
+                    ))
+                }
+                #[cfg(not(feature = "rolesanywhere"))]
+                {
+                    Err(ProfileFileError::FeatureNotEnabled {
+                        feature: "rolesanywhere".into(),
+                        message: None,
+                    })?
+                }
+            }
         };
         tracing::debug!(base = ?repr.base(), "first credentials will be loaded from {:?}", repr.base());
         let chain = repr
@@ -170,6 +251,27 @@ impl ProviderChain {
                     role_arn: role_arn.role_arn.into(),
                     external_id: role_arn.external_id.map(Into::into),
                     session_name: role_arn.session_name.map(Into::into),
+                    duration_seconds: role_arn.duration_seconds,
+                    source_identity: role_arn.source_identity.map(Into::into),
+                    session_tags: role_arn
+                        .session_tags
+                        .iter()
+                        .map(|(key, value)| {
+                            Tag::builder()
+                                .key(*key)
+                                .value(*value)
+                                .build()
+                                .expect("key and value are set")
+                        })
+                        .collect(),
+                    transitive_tag_keys: role_arn
+                        .transitive_tag_keys
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                    mfa: role_arn.mfa_serial.map(|serial_number| {
+                        MfaSession::new(serial_number.into(), mfa_token_provider.cloned())
+                    }),
                     time_source: provider_config.time_source(),
                 }
             })
@@ -216,6 +318,8 @@ pub(super) mod named {
 
 #[cfg(test)]
 mod test {
+    #[cfg(feature = "credentials-process")]
+    use crate::credential_process::ProcessOptions;
     use crate::profile::credentials::exec::named::NamedProviderFactory;
     use crate::profile::credentials::exec::ProviderChain;
     use crate::profile::credentials::repr::{BaseProvider, ProfileChain};
@@ -250,6 +354,9 @@ mod test {
                 chain: vec![],
             },
             &factory,
+            None,
+            #[cfg(feature = "credentials-process")]
+            &ProcessOptions::default(),
         );
         let err = chain.expect_err("no source by that name");
         assert!(
@@ -260,4 +367,33 @@ mod test {
             err
         );
     }
+
+    #[cfg(feature = "credentials-process")]
+    #[test]
+    fn credential_process_options() {
+        use crate::credential_process::StderrBehavior;
+        use crate::sensitive_command::CommandWithSensitiveArgs;
+        use std::time::Duration;
+
+        let options = ProcessOptions {
+            stderr: StderrBehavior::Inherit,
+            inherit_stdin: true,
+            timeout: Some(Duration::from_secs(30)),
+        };
+        let chain = ProviderChain::from_repr(
+            &ProviderConfig::empty().with_http_client(no_traffic_client()),
+            ProfileChain {
+                base: BaseProvider::CredentialProcess(CommandWithSensitiveArgs::new("program")),
+                chain: vec![],
+            },
+            &NamedProviderFactory::new(HashMap::new()),
+            None,
+            &options,
+        )
+        .expect("valid chain");
+        let base = format!("{:?}", chain.base());
+        assert!(base.contains("stderr: Inherit"), "{base}");
+        assert!(base.contains("inherit_stdin: true"), "{base}");
+        assert!(base.contains("timeout: Some(30s)"), "{base}");
+    }
 }
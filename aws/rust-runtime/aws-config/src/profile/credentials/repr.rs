@@ -38,6 +38,139 @@ impl<'a> ProfileChain<'a> {
     pub(crate) fn chain(&self) -> &[RoleArn<'a>] {
         self.chain.as_slice()
     }
+
+    /// A stable description of this chain for use as a cache key
+    ///
+    /// Two chains produce the same key when they would produce credentials for the same
+    /// identity. The key never contains secrets, but it may contain access key IDs and commands,
+    /// so it should be hashed before being written anywhere.
+    ///
+    /// Every value is length-prefixed, so a value containing separators can't make two different
+    /// chains produce the same key.
+    #[cfg(feature = "credentials-file-cache")]
+    pub(crate) fn cache_key(&self) -> String {
+        let mut key = String::new();
+        match &self.base {
+            BaseProvider::NamedSource(name) => push_key_field(&mut key, "credential_source", *name),
+            BaseProvider::AccessKey(creds) => {
+                push_key_field(&mut key, "aws_access_key_id", creds.access_key_id())
+            }
+            BaseProvider::WebIdentityTokenRole {
+                role_arn,
+                web_identity_token_file,
+                session_name,
+            } => {
+                push_key_field(
+                    &mut key,
+                    "web_identity_token_file",
+                    *web_identity_token_file,
+                );
+                push_key_field(&mut key, "role_arn", *role_arn);
+                push_key_field(&mut key, "role_session_name", *session_name);
+            }
+            BaseProvider::Sso {
+                sso_session_name,
+                sso_region,
+                sso_start_url,
+                sso_account_id,
+                sso_role_name,
+            } => {
+                push_key_field(&mut key, "sso_session", *sso_session_name);
+                push_key_field(&mut key, "sso_region", *sso_region);
+                push_key_field(&mut key, "sso_start_url", *sso_start_url);
+                push_key_field(&mut key, "sso_account_id", *sso_account_id);
+                push_key_field(&mut key, "sso_role_name", *sso_role_name);
+            }
+            BaseProvider::RolesAnywhere {
+                trust_anchor_arn,
+                profile_arn,
+                role_arn,
+                certificate,
+                private_key,
+                intermediate_certificates,
+                session_name,
+                duration_seconds,
+            } => {
+                push_key_field(
+                    &mut key,
+                    "rolesanywhere_trust_anchor_arn",
+                    *trust_anchor_arn,
+                );
+                push_key_field(&mut key, "rolesanywhere_profile_arn", *profile_arn);
+                push_key_field(&mut key, "role_arn", *role_arn);
+                push_key_field(&mut key, "rolesanywhere_certificate", *certificate);
+                push_key_field(&mut key, "rolesanywhere_private_key", *private_key);
+                push_key_field(
+                    &mut key,
+                    "rolesanywhere_intermediate_certificates",
+                    *intermediate_certificates,
+                );
+                push_key_field(&mut key, "role_session_name", *session_name);
+                push_key_field(
+                    &mut key,
+                    "duration_seconds",
+                    duration_seconds.map(|d| d.to_string()).as_deref(),
+                );
+            }
+            BaseProvider::CredentialProcess(command) => {
+                push_key_field(&mut key, "credential_process", command.unredacted())
+            }
+        };
+        for role in &self.chain {
+            // Every field of the role is part of the key, since each of them changes the
+            // credentials that assuming the role returns
+            let RoleArn {
+                role_arn,
+                external_id,
+                session_name,
+                mfa_serial,
+                duration_seconds,
+                source_identity,
+                session_tags,
+                transitive_tag_keys,
+            } = role;
+            key.push('|');
+            push_key_field(&mut key, "role_arn", *role_arn);
+            push_key_field(&mut key, "external_id", *external_id);
+            push_key_field(&mut key, "role_session_name", *session_name);
+            push_key_field(&mut key, "mfa_serial", *mfa_serial);
+            push_key_field(
+                &mut key,
+                "duration_seconds",
+                duration_seconds.map(|d| d.to_string()).as_deref(),
+            );
+            push_key_field(&mut key, "source_identity", *source_identity);
+            push_key_field(
+                &mut key,
+                "session_tags",
+                session_tags.len().to_string().as_str(),
+            );
+            for (tag_key, tag_value) in session_tags {
+                push_key_field(&mut key, "key", *tag_key);
+                push_key_field(&mut key, "value", *tag_value);
+            }
+            push_key_field(
+                &mut key,
+                "transitive_tag_keys",
+                transitive_tag_keys.len().to_string().as_str(),
+            );
+            for tag_key in transitive_tag_keys {
+                push_key_field(&mut key, "key", *tag_key);
+            }
+        }
+        key
+    }
+}
+
+/// Appends `name=<length>:<value>;` to a cache key, or `name;` when the value is unset
+#[cfg(feature = "credentials-file-cache")]
+fn push_key_field<'a>(key: &mut String, name: &str, value: impl Into<Option<&'a str>>) {
+    use std::fmt::Write;
+    match value.into() {
+        Some(value) => write!(key, "{name}={}:{value};", value.len()),
+        None => write!(key, "{name};"),
+    }
+    .expect("writing to a String can't fail")
 }
 
 /// A base member of the profile chain
@@ -87,6 +220,27 @@ pub(crate) enum BaseProvider<'a> {
         sso_role_name: Option<&'a str>,
     },
 
+    /// An IAM Roles Anywhere provider
+    ///
+    /// ```ini
+    /// [profile roles-anywhere]
+    /// role_arn = arn:aws:iam::123456789012:role/MyRole
+    /// rolesanywhere_trust_anchor_arn = arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/abc
+    /// rolesanywhere_profile_arn = arn:aws:rolesanywhere:us-east-1:123456789012:profile/def
+    /// rolesanywhere_certificate = /path/to/certificate.pem
+    /// rolesanywhere_private_key = /path/to/private-key.pem
+    /// ```
+    RolesAnywhere {
+        trust_anchor_arn: &'a str,
+        profile_arn: &'a str,
+        role_arn: &'a str,
+        certificate: &'a str,
+        private_key: &'a str,
+        intermediate_certificates: Option<&'a str>,
+        session_name: Option<&'a str>,
+        duration_seconds: Option<u64>,
+    },
+
     /// A profile that specifies a `credential_process`
     /// ```ini
     /// [profile assume-role]
@@ -108,6 +262,21 @@ pub(crate) struct RoleArn<'a> {
 
     /// session name parameter to pass to the assume role provider
     pub(crate) session_name: Option<&'a str>,
+
+    /// serial number of the MFA device required to assume the role
+    pub(crate) mfa_serial: Option<&'a str>,
+
+    /// session duration parameter to pass to the assume role provider
+    pub(crate) duration_seconds: Option<i32>,
+
+    /// source identity parameter to pass to the assume role provider
+    pub(crate) source_identity: Option<&'a str>,
+
+    /// session tags to pass to the assume role provider, as key-value pairs
+    pub(crate) session_tags: Vec<(&'a str, &'a str)>,
+
+    /// keys of the session tags that are passed on to subsequent roles in the chain
+    pub(crate) transitive_tag_keys: Vec<&'a str>,
 }
 
 /// Resolve a ProfileChain from a ProfileSet or return an error
@@ -170,7 +339,7 @@ pub(crate) fn resolve_chain(
             // The existence of a `role_arn` is the only signal that multiple profiles will be chained.
             // We check for one here and then process the profile accordingly as either a "chain provider"
             // or a "base provider"
-            if let Some(role_provider) = role_arn_from_profile(profile) {
+            if let Some(role_provider) = role_arn_from_profile(profile)? {
                 let next = chain_provider(profile)?;
                 chain.push(role_provider);
                 next
@@ -209,6 +378,11 @@ mod role {
     pub(super) const ROLE_ARN: &str = "role_arn";
     pub(super) const EXTERNAL_ID: &str = "external_id";
     pub(super) const SESSION_NAME: &str = "role_session_name";
+    pub(super) const MFA_SERIAL: &str = "mfa_serial";
+    pub(super) const DURATION_SECONDS: &str = "duration_seconds";
+    pub(super) const SOURCE_IDENTITY: &str = "source_identity";
+    pub(super) const SESSION_TAGS: &str = "session_tags";
+    pub(super) const TRANSITIVE_TAG_KEYS: &str = "transitive_tag_keys";
 
     pub(super) const CREDENTIAL_SOURCE: &str = "credential_source";
     pub(super) const SOURCE_PROFILE: &str = "source_profile";
@@ -226,10 +400,19 @@ mod web_identity_token {
     pub(super) const TOKEN_FILE: &str = "web_identity_token_file";
 }
 
+mod roles_anywhere {
+    pub(super) const TRUST_ANCHOR_ARN: &str = "rolesanywhere_trust_anchor_arn";
+    pub(super) const PROFILE_ARN: &str = "rolesanywhere_profile_arn";
+    pub(super) const CERTIFICATE: &str = "rolesanywhere_certificate";
+    pub(super) const PRIVATE_KEY: &str = "rolesanywhere_private_key";
+    pub(super) const INTERMEDIATE_CERTIFICATES: &str = "rolesanywhere_intermediate_certificates";
+}
+
 mod static_credentials {
     pub(super) const AWS_ACCESS_KEY_ID: &str = "aws_access_key_id";
     pub(super) const AWS_SECRET_ACCESS_KEY: &str = "aws_secret_access_key";
     pub(super) const AWS_SESSION_TOKEN: &str = "aws_session_token";
+    pub(super) const AWS_ACCOUNT_ID: &str = "aws_account_id";
 }
 
 mod credential_process {
@@ -246,6 +429,7 @@ fn base_provider<'a>(
     match profile.get(role::CREDENTIAL_SOURCE) {
         Some(source) => Ok(BaseProvider::NamedSource(source)),
         None => web_identity_token_from_profile(profile)
+            .or_else(|| roles_anywhere_from_profile(profile).transpose())
             .or_else(|| sso_from_profile(profile_set, profile).transpose())
             .or_else(|| credential_process_from_profile(profile))
             .unwrap_or_else(|| Ok(BaseProvider::AccessKey(static_creds_from_profile(profile)?))),
@@ -284,19 +468,100 @@ fn chain_provider(profile: &Profile) -> Result<NextProfile<'_>, ProfileFileError
     }
 }
 
-fn role_arn_from_profile(profile: &Profile) -> Option<RoleArn<'_>> {
-    // Web Identity Tokens are root providers, not chained roles
-    if profile.get(web_identity_token::TOKEN_FILE).is_some() {
-        return None;
+/// Load the role to assume from a profile
+///
+/// Example:
+/// ```ini
+/// [profile A]
+/// role_arn = arn:aws:iam::123456789:role/RoleA
+/// source_profile = B
+/// # optional
+/// role_session_name = my-session
+/// duration_seconds = 3600
+/// source_identity = alice
+/// session_tags = Project=blue, CostCenter=1234
+/// transitive_tag_keys = Project
+/// ```
+fn role_arn_from_profile(profile: &Profile) -> Result<Option<RoleArn<'_>>, ProfileFileError> {
+    // Web Identity Tokens and IAM Roles Anywhere are root providers, not chained roles
+    if profile.get(web_identity_token::TOKEN_FILE).is_some()
+        || profile.get(roles_anywhere::TRUST_ANCHOR_ARN).is_some()
+    {
+        return Ok(None);
     }
-    let role_arn = profile.get(role::ROLE_ARN)?;
-    let session_name = profile.get(role::SESSION_NAME);
-    let external_id = profile.get(role::EXTERNAL_ID);
-    Some(RoleArn {
+    let Some(role_arn) = profile.get(role::ROLE_ARN) else {
+        return Ok(None);
+    };
+    let session_tags = profile
+        .get(role::SESSION_TAGS)
+        .map(|tags| {
+            list_items(tags)
+                .map(|tag| {
+                    tag.split_once('=')
+                        .map(|(key, value)| (key.trim(), value.trim()))
+                        .filter(|(key, _)| !key.is_empty())
+                        .ok_or_else(|| ProfileFileError::InvalidCredentialSource {
+                            profile: profile.name().to_string(),
+                            message: format!(
+                                "`{}` must be a list of `key=value` pairs, got `{tag}`",
+                                role::SESSION_TAGS
+                            )
+                            .into(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    Ok(Some(RoleArn {
         role_arn,
-        external_id,
-        session_name,
-    })
+        external_id: profile.get(role::EXTERNAL_ID),
+        session_name: profile.get(role::SESSION_NAME),
+        mfa_serial: profile.get(role::MFA_SERIAL),
+        duration_seconds: duration_seconds_from_profile(profile)?,
+        source_identity: profile.get(role::SOURCE_IDENTITY),
+        session_tags,
+        transitive_tag_keys: profile
+            .get(role::TRANSITIVE_TAG_KEYS)
+            .map(|keys| list_items(keys).collect())
+            .unwrap_or_default(),
+    }))
+}
+
+/// Splits a comma or newline separated profile value into its trimmed, non-empty items
+///
+/// Newlines allow lists to be written as sub-properties, one item per line.
+fn list_items(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Parses `duration_seconds`, which must fit in the `i32` that STS accepts for a session duration
+fn duration_seconds_from_profile(profile: &Profile) -> Result<Option<i32>, ProfileFileError> {
+    let invalid = |message: String| ProfileFileError::InvalidCredentialSource {
+        profile: profile.name().to_string(),
+        message: message.into(),
+    };
+    profile
+        .get(role::DURATION_SECONDS)
+        .map(|duration| {
+            let secs: u64 = duration.parse().map_err(|_| {
+                invalid(format!(
+                    "`{}` must be a whole number of seconds, got `{duration}`",
+                    role::DURATION_SECONDS
+                ))
+            })?;
+            i32::try_from(secs).map_err(|_| {
+                invalid(format!(
+                    "`{}` must be at most {} seconds, got `{duration}`",
+                    role::DURATION_SECONDS,
+                    i32::MAX
+                ))
+            })
+        })
+        .transpose()
 }
 
 fn sso_from_profile<'a>(
@@ -410,6 +675,57 @@ fn web_identity_token_from_profile(
     }
 }
 
+/// Load IAM Roles Anywhere configuration from a profile
+///
+/// The presence of `rolesanywhere_trust_anchor_arn` selects this provider, after which the profile
+/// ARN, role ARN, certificate and private key are all required.
+fn roles_anywhere_from_profile(
+    profile: &Profile,
+) -> Result<Option<BaseProvider<'_>>, ProfileFileError> {
+    let Some(trust_anchor_arn) = profile.get(roles_anywhere::TRUST_ANCHOR_ARN) else {
+        return Ok(None);
+    };
+    // the IAM Roles Anywhere endpoint is in the region of the trust anchor
+    if trust_anchor_arn
+        .split(':')
+        .nth(3)
+        .map_or(true, |region| region.is_empty())
+    {
+        return Err(ProfileFileError::InvalidCredentialSource {
+            profile: profile.name().to_string(),
+            message: format!(
+                "`{}` must be an ARN that includes a region",
+                roles_anywhere::TRUST_ANCHOR_ARN
+            )
+            .into(),
+        });
+    }
+    let required = |key: &'static str| {
+        profile
+            .get(key)
+            .ok_or_else(|| ProfileFileError::InvalidCredentialSource {
+                profile: profile.name().to_string(),
+                message: format!(
+                    "`{key}` must be set when `{}` is set",
+                    roles_anywhere::TRUST_ANCHOR_ARN
+                )
+                .into(),
+            })
+    };
+    Ok(Some(BaseProvider::RolesAnywhere {
+        trust_anchor_arn,
+        profile_arn: required(roles_anywhere::PROFILE_ARN)?,
+        role_arn: required(role::ROLE_ARN)?,
+        certificate: required(roles_anywhere::CERTIFICATE)?,
+        private_key: required(roles_anywhere::PRIVATE_KEY)?,
+        intermediate_certificates: profile.get(roles_anywhere::INTERMEDIATE_CERTIFICATES),
+        session_name: profile.get(role::SESSION_NAME),
+        // never negative, since it was parsed from a `u64`
+        duration_seconds: duration_seconds_from_profile(profile)?
+            .map(|secs| u64::from(secs.unsigned_abs())),
+    }))
+}
+
 /// Load static credentials from a profile
 ///
 /// Example:
@@ -417,6 +733,8 @@ fn web_identity_token_from_profile(
 /// [profile B]
 /// aws_access_key_id = abc123
 /// aws_secret_access_key = def456
+/// # optional
+/// aws_account_id = 123456789012
 /// ```
 fn static_creds_from_profile(profile: &Profile) -> Result<Credentials, ProfileFileError> {
     use static_credentials::*;
@@ -439,13 +757,17 @@ fn static_creds_from_profile(profile: &Profile) -> Result<Credentials, ProfileFi
         message: "profile missing aws_secret_access_key".into(),
     })?;
     // There might not be an active session token so we don't error out if it's missing
-    Ok(Credentials::new(
+    let credentials = Credentials::new(
         access_key,
         secret_key,
         session_token.map(|s| s.to_string()),
         None,
         PROVIDER_NAME,
-    ))
+    );
+    Ok(match profile.get(AWS_ACCOUNT_ID) {
+        Some(account_id) => credentials.with_account_id(account_id),
+        None => credentials,
+    })
 }
 
 /// Load credentials from `credential_process`
@@ -469,7 +791,8 @@ fn credential_process_from_profile(
 
 #[cfg(test)]
 mod tests {
-    use crate::profile::credentials::repr::BaseProvider;
+    use crate::profile::credentials::repr::{role_arn_from_profile, BaseProvider};
+    use crate::profile::{Profile, Property};
     use crate::sensitive_command::CommandWithSensitiveArgs;
     use serde::Deserialize;
 
@@ -554,6 +877,25 @@ mod tests {
                 web_identity_token_file: web_identity_token_file.into(),
                 role_session_name: session_name.map(|sess| sess.to_string()),
             }),
+            BaseProvider::RolesAnywhere {
+                trust_anchor_arn,
+                profile_arn,
+                role_arn,
+                certificate,
+                private_key,
+                intermediate_certificates,
+                session_name,
+                duration_seconds,
+            } => output.push(Provider::RolesAnywhere {
+                trust_anchor_arn: trust_anchor_arn.into(),
+                profile_arn: profile_arn.into(),
+                role_arn: role_arn.into(),
+                certificate: certificate.into(),
+                private_key: private_key.into(),
+                intermediate_certificates: intermediate_certificates.map(|s| s.to_string()),
+                role_session_name: session_name.map(|s| s.to_string()),
+                duration_seconds: *duration_seconds,
+            }),
             BaseProvider::Sso {
                 sso_region,
                 sso_start_url,
@@ -573,6 +915,19 @@ mod tests {
                 role_arn: role.role_arn.into(),
                 external_id: role.external_id.map(ToString::to_string),
                 role_session_name: role.session_name.map(ToString::to_string),
+                mfa_serial: role.mfa_serial.map(ToString::to_string),
+                duration_seconds: role.duration_seconds,
+                source_identity: role.source_identity.map(ToString::to_string),
+                session_tags: role
+                    .session_tags
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+                transitive_tag_keys: role
+                    .transitive_tag_keys
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
             })
         }
         output
@@ -590,6 +945,13 @@ mod tests {
             role_arn: String,
             external_id: Option<String>,
             role_session_name: Option<String>,
+            mfa_serial: Option<String>,
+            duration_seconds: Option<i32>,
+            source_identity: Option<String>,
+            #[serde(default)]
+            session_tags: Vec<(String, String)>,
+            #[serde(default)]
+            transitive_tag_keys: Vec<String>,
         },
         AccessKey {
             access_key_id: String,
@@ -611,6 +973,16 @@ mod tests {
             sso_account_id: Option<String>,
             sso_role_name: Option<String>,
         },
+        RolesAnywhere {
+            trust_anchor_arn: String,
+            profile_arn: String,
+            role_arn: String,
+            certificate: String,
+            private_key: String,
+            intermediate_certificates: Option<String>,
+            role_session_name: Option<String>,
+            duration_seconds: Option<u64>,
+        },
     }
 
     #[test]
@@ -639,4 +1011,160 @@ mod tests {
             )
         );
     }
+
+    fn profile(properties: &[(&str, &str)]) -> Profile {
+        Profile::new(
+            "A",
+            properties
+                .iter()
+                .map(|(k, v)| (k.to_string(), Property::new(k.to_string(), v.to_string())))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn role_session_settings_from_profile() {
+        let profile = profile(&[
+            ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+            ("source_profile", "B"),
+            ("duration_seconds", "3600"),
+            ("source_identity", "alice"),
+            ("session_tags", "Project=blue, CostCenter = 1234"),
+            ("transitive_tag_keys", "Project"),
+        ]);
+        let role = role_arn_from_profile(&profile)
+            .expect("valid profile")
+            .expect("profile assumes a role");
+        assert_eq!(Some(3600), role.duration_seconds);
+        assert_eq!(Some("alice"), role.source_identity);
+        assert_eq!(
+            vec![("Project", "blue"), ("CostCenter", "1234")],
+            role.session_tags
+        );
+        assert_eq!(vec!["Project"], role.transitive_tag_keys);
+    }
+
+    #[test]
+    fn session_tags_as_sub_properties() {
+        let profile = profile(&[
+            ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+            ("session_tags", "\nProject = blue\nCostCenter = 1234"),
+            ("transitive_tag_keys", "\nProject\nCostCenter"),
+        ]);
+        let role = role_arn_from_profile(&profile).unwrap().unwrap();
+        assert_eq!(
+            vec![("Project", "blue"), ("CostCenter", "1234")],
+            role.session_tags
+        );
+        assert_eq!(vec!["Project", "CostCenter"], role.transitive_tag_keys);
+    }
+
+    #[cfg(feature = "credentials-file-cache")]
+    #[test]
+    fn cache_key_covers_every_role_setting() {
+        use crate::profile::credentials::repr::ProfileChain;
+
+        let settings = [
+            ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+            ("external_id", "external"),
+            ("role_session_name", "session"),
+            ("mfa_serial", "arn:aws:iam::123456789:mfa/user"),
+            ("duration_seconds", "3600"),
+            ("source_identity", "alice"),
+            ("session_tags", "Project=blue"),
+            ("transitive_tag_keys", "Project"),
+        ];
+        let cache_key = |settings: &[(&str, &str)]| {
+            let profile = profile(settings);
+            let role = role_arn_from_profile(&profile).unwrap().unwrap();
+            ProfileChain {
+                base: BaseProvider::NamedSource("Environment"),
+                chain: vec![role],
+            }
+            .cache_key()
+        };
+        let key = cache_key(&settings);
+        assert_eq!(key, cache_key(&settings));
+        for (i, (name, value)) in settings.iter().enumerate() {
+            let new_value = format!("{value}0");
+            let mut changed = settings.to_vec();
+            changed[i] = (*name, new_value.as_str());
+            assert_ne!(
+                key,
+                cache_key(&changed),
+                "changing `{name}` must change the key"
+            );
+        }
+    }
+
+    #[cfg(feature = "credentials-file-cache")]
+    #[test]
+    fn cache_key_is_not_ambiguous() {
+        use crate::profile::credentials::repr::ProfileChain;
+
+        let cache_key = |settings: &[(&str, &str)]| {
+            let profile = profile(settings);
+            let role = role_arn_from_profile(&profile).unwrap().unwrap();
+            ProfileChain {
+                base: BaseProvider::NamedSource("Environment"),
+                chain: vec![role],
+            }
+            .cache_key()
+        };
+        assert_ne!(
+            cache_key(&[
+                ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+                ("external_id", "a;role_session_name=b"),
+            ]),
+            cache_key(&[
+                ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+                ("external_id", "a"),
+                ("role_session_name", "b"),
+            ])
+        );
+        assert_ne!(
+            cache_key(&[
+                ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+                ("session_tags", "Project=blue"),
+            ]),
+            cache_key(&[
+                ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+                ("session_tags", "Project=blue"),
+                ("transitive_tag_keys", "Project"),
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_role_session_settings() {
+        for (key, value, expected) in [
+            (
+                "duration_seconds",
+                "one hour",
+                "`duration_seconds` must be a whole number of seconds, got `one hour`",
+            ),
+            (
+                "duration_seconds",
+                "2147483648",
+                "`duration_seconds` must be at most 2147483647 seconds, got `2147483648`",
+            ),
+            (
+                "session_tags",
+                "Project=blue,CostCenter",
+                "`session_tags` must be a list of `key=value` pairs, got `CostCenter`",
+            ),
+        ] {
+            let profile = profile(&[
+                ("role_arn", "arn:aws:iam::123456789:role/RoleA"),
+                (key, value),
+            ]);
+            let err = role_arn_from_profile(&profile).expect_err("invalid profile");
+            assert!(
+                format!("{}", err).contains(expected),
+                "expected `{}` to contain `{}`",
+                err,
+                expected
+            );
+        }
+    }
 }
@@ -10,6 +10,7 @@ use crate::meta::region::{future, ProvideRegion};
 use crate::profile::profile_file::ProfileFiles;
 use crate::profile::ProfileSet;
 use crate::provider_config::ProviderConfig;
+use crate::resolution::{ConfigSource, RegionRecorder};
 use aws_types::region::Region;
 
 /// Load a region from a profile file
@@ -39,6 +40,7 @@ use aws_types::region::Region;
 #[derive(Debug, Default)]
 pub struct ProfileFileRegionProvider {
     provider_config: ProviderConfig,
+    recorder: Option<RegionRecorder>,
 }
 
 /// Builder for [ProfileFileRegionProvider]
@@ -48,6 +50,7 @@ pub struct Builder {
     profile_override: Option<String>,
     #[allow(deprecated)]
     profile_files: Option<ProfileFiles>,
+    recorder: Option<RegionRecorder>,
 }
 
 impl Builder {
@@ -70,6 +73,12 @@ impl Builder {
         self
     }
 
+    /// Record the profile that is read whenever a region is loaded
+    pub(crate) fn recorder(mut self, recorder: RegionRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     /// Build a [ProfileFileRegionProvider] from this builder
     pub fn build(self) -> ProfileFileRegionProvider {
         let conf = self
@@ -78,6 +87,7 @@ impl Builder {
             .with_profile_config(self.profile_files, self.profile_override);
         ProfileFileRegionProvider {
             provider_config: conf,
+            recorder: self.recorder,
         }
     }
 }
@@ -89,6 +99,7 @@ impl ProfileFileRegionProvider {
     pub fn new() -> Self {
         Self {
             provider_config: ProviderConfig::default(),
+            recorder: None,
         }
     }
 
@@ -100,11 +111,26 @@ impl ProfileFileRegionProvider {
     async fn region(&self) -> Option<Region> {
         let profile_set = self.provider_config.profile().await?;
 
-        resolve_profile_chain_for_region(profile_set)
+        let resolved = resolve_profile_chain_for_region(profile_set);
+        if let Some(recorder) = &self.recorder {
+            let profile = resolved
+                .as_ref()
+                .map_or(profile_set.selected_profile(), |(_, profile)| *profile);
+            recorder.record(
+                ConfigSource::Profile {
+                    profile: profile.into(),
+                    key: "region".into(),
+                },
+                resolved.as_ref().map(|(region, _)| region),
+            );
+        }
+        resolved.map(|(region, _profile)| region)
     }
 }
 
-fn resolve_profile_chain_for_region(profile_set: &'_ ProfileSet) -> Option<Region> {
+/// Returns the region of the selected profile, following `source_profile` until a region is found,
+/// along with the name of the profile it was found in
+fn resolve_profile_chain_for_region(profile_set: &'_ ProfileSet) -> Option<(Region, &'_ str)> {
     if profile_set.is_empty() {
         return None;
     }
@@ -132,7 +158,7 @@ fn resolve_profile_chain_for_region(profile_set: &'_ ProfileSet) -> Option<Regio
         match (selected_profile_region, source_profile) {
             // Profile had a region specified, return it :D
             (Some(region), _) => {
-                return Some(region);
+                return Some((region, selected_profile));
             }
             // No region specified, source_profile is self-referential so we return to avoid infinite loop
             (None, Some(source_profile)) if source_profile == selected_profile => {
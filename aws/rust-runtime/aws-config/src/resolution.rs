@@ -0,0 +1,697 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Explanations of where the values in an [`SdkConfig`](aws_types::SdkConfig) came from
+//!
+//! [`ConfigLoader::load_with_report`](crate::ConfigLoader::load_with_report) returns a
+//! [`ConfigResolutionReport`] alongside the config. For each setting, the report lists every
+//! source that was consulted in order of precedence, which one was used, and why the others
+//! were skipped.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn example() {
+//! let (config, report) = aws_config::from_env().load_with_report().await;
+//! if let Some(region) = report.setting("region") {
+//!     // e.g. `region = us-east-1 (from environment variable `AWS_REGION`)`
+//!     println!("{region}");
+//! }
+//! // or, log everything
+//! println!("{report}");
+//! # }
+//! ```
+
+use aws_runtime::env_config::section::EnvConfigSections;
+use aws_runtime::env_config::{EnvConfigSource, EnvConfigValue};
+use aws_types::origin::Origin;
+use aws_types::os_shim_internal::Env;
+use aws_types::region::Region;
+use aws_types::service_config::ServiceConfigKey;
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// The names of the settings included in a [`ConfigResolutionReport`]
+pub mod setting {
+    /// The region
+    pub const REGION: &str = "region";
+    /// The endpoint URL shared by all services
+    pub const ENDPOINT_URL: &str = "endpoint_url";
+    /// Whether to use FIPS endpoints
+    pub const USE_FIPS: &str = "use_fips";
+    /// Whether to use dual-stack endpoints
+    pub const USE_DUAL_STACK: &str = "use_dual_stack";
+    /// The maximum number of attempts made for a request
+    pub const MAX_ATTEMPTS: &str = "max_attempts";
+    /// The retry mode
+    pub const RETRY_MODE: &str = "retry_mode";
+    /// The connect timeout
+    pub const CONNECT_TIMEOUT: &str = "connect_timeout";
+    /// The read timeout
+    pub const READ_TIMEOUT: &str = "read_timeout";
+    /// The operation timeout
+    pub const OPERATION_TIMEOUT: &str = "operation_timeout";
+    /// The operation attempt timeout
+    pub const OPERATION_ATTEMPT_TIMEOUT: &str = "operation_attempt_timeout";
+    /// The app name
+    pub const APP_NAME: &str = "app_name";
+    /// Whether request compression is disabled
+    pub const DISABLE_REQUEST_COMPRESSION: &str = "disable_request_compression";
+    /// The minimum size of a request body before it is compressed
+    pub const REQUEST_MIN_COMPRESSION_SIZE_BYTES: &str = "request_min_compression_size_bytes";
+    /// The set of regions that SigV4a signatures are valid in
+    pub const SIGV4A_SIGNING_REGION_SET: &str = "sigv4a_signing_region_set";
+}
+
+/// A source that a config value can come from
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigSource {
+    /// Set in code on the [`ConfigLoader`](crate::ConfigLoader)
+    Code,
+    /// An environment variable
+    Environment {
+        /// The name of the environment variable
+        variable: String,
+        /// The service the variable applies to, if it is service-specific
+        service_id: Option<String>,
+    },
+    /// A key in a profile
+    Profile {
+        /// The name of the profile
+        profile: String,
+        /// The key in the profile
+        key: String,
+    },
+    /// A key for a specific service in the `[services]` section referenced by a profile
+    ServicesSection {
+        /// The name of the profile that references the services section
+        profile: String,
+        /// The service the key applies to
+        service_id: String,
+        /// The key in the services section
+        key: String,
+    },
+    /// The EC2 Instance Metadata Service
+    Imds,
+    /// The SDK's default
+    Default,
+}
+
+impl ConfigSource {
+    /// The [`Origin`] corresponding to this source
+    pub fn origin(&self) -> Origin {
+        match self {
+            ConfigSource::Code => Origin::shared_config(),
+            ConfigSource::Environment {
+                service_id: None, ..
+            } => Origin::shared_environment_variable(),
+            ConfigSource::Environment { .. } => Origin::service_environment_variable(),
+            ConfigSource::Profile { .. } => Origin::shared_profile_file(),
+            ConfigSource::ServicesSection { .. } => Origin::service_profile_file(),
+            ConfigSource::Imds => Origin::imds(),
+            ConfigSource::Default => Origin::unknown(),
+        }
+    }
+
+    fn from_env_config(source: &EnvConfigSource<'_>) -> Self {
+        let key = source.key().to_owned();
+        match (source.profile_name(), source.service_id()) {
+            (None, service_id) => ConfigSource::Environment {
+                variable: key,
+                service_id: service_id.map(ToOwned::to_owned),
+            },
+            (Some(profile), None) => ConfigSource::Profile {
+                profile: profile.to_owned(),
+                key,
+            },
+            (Some(profile), Some(service_id)) => ConfigSource::ServicesSection {
+                profile: profile.to_owned(),
+                service_id: service_id.to_owned(),
+                key,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Code => write!(f, "code"),
+            ConfigSource::Environment { variable, .. } => {
+                write!(f, "environment variable `{variable}`")
+            }
+            ConfigSource::Profile { profile, key } => write!(f, "`{key}` in profile `{profile}`"),
+            ConfigSource::ServicesSection {
+                profile,
+                service_id,
+                key,
+            } => write!(
+                f,
+                "`{key}` for `{service_id}` in the services section of profile `{profile}`"
+            ),
+            ConfigSource::Imds => write!(f, "EC2 instance metadata (IMDS)"),
+            ConfigSource::Default => write!(f, "SDK default"),
+        }
+    }
+}
+
+/// Why a candidate source wasn't used
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SkipReason {
+    /// The source had no value
+    NotSet,
+    /// A source with higher precedence was used
+    Overridden,
+    /// The value couldn't be parsed. The SDK doesn't fall back to sources with lower precedence
+    /// when a value is invalid.
+    Invalid {
+        /// Why the value was invalid
+        message: String,
+    },
+    /// The value was ignored because of another setting
+    Ignored {
+        /// Why the value was ignored
+        reason: String,
+    },
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::NotSet => write!(f, "not set"),
+            SkipReason::Overridden => write!(f, "overridden by a higher-precedence source"),
+            SkipReason::Invalid { message } => write!(f, "invalid value: {message}"),
+            SkipReason::Ignored { reason } => write!(f, "ignored: {reason}"),
+        }
+    }
+}
+
+/// A source that was consulted while resolving a setting
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    source: ConfigSource,
+    value: Option<String>,
+    skipped: Option<SkipReason>,
+}
+
+impl Candidate {
+    /// Where this candidate was read from
+    pub fn source(&self) -> &ConfigSource {
+        &self.source
+    }
+
+    /// The value set by this source, if any
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Why this candidate wasn't used, or `None` if it was
+    pub fn skip_reason(&self) -> Option<&SkipReason> {
+        self.skipped.as_ref()
+    }
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+        if let Some(value) = &self.value {
+            write!(f, " = {value}")?;
+        }
+        match &self.skipped {
+            Some(reason) => write!(f, ": {reason}"),
+            None => write!(f, ": used"),
+        }
+    }
+}
+
+/// How a single setting was resolved
+#[derive(Clone, Debug)]
+pub struct SettingResolution {
+    name: Cow<'static, str>,
+    value: Option<String>,
+    source: Option<ConfigSource>,
+    candidates: Vec<Candidate>,
+    /// Whether later candidates can no longer be used
+    decided: bool,
+}
+
+impl SettingResolution {
+    /// The name of this setting. See [`setting`] for the names of the settings in a report.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The resolved value, or `None` if no source set it
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// The source the value came from, or `None` if no source set it
+    pub fn source(&self) -> Option<&ConfigSource> {
+        self.source.as_ref()
+    }
+
+    /// Every source that was consulted, in order of precedence
+    pub fn candidates(&self) -> &[Candidate] {
+        &self.candidates
+    }
+
+    fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            value: None,
+            source: None,
+            candidates: Vec::new(),
+            decided: false,
+        }
+    }
+
+    /// Resolves a setting from code and then from `sources` in order, the same way
+    /// [`EnvConfigValue::validate`] does.
+    pub(crate) fn explain<T, E: fmt::Display>(
+        name: impl Into<Cow<'static, str>>,
+        from_code: Option<String>,
+        sources: Vec<EnvConfigValue<'_>>,
+        env: &Env,
+        profiles: Option<&EnvConfigSections>,
+        validator: impl Fn(&str) -> Result<T, E>,
+    ) -> Self {
+        let mut resolution = Self::new(name);
+        if let Some(value) = from_code {
+            resolution.consider(ConfigSource::Code, Some(value), None);
+        }
+        for value in &sources {
+            for (source, value) in value.candidates(env, profiles) {
+                let invalid = value
+                    .as_deref()
+                    .and_then(|value| validator(value).err())
+                    .map(|err| err.to_string());
+                resolution.consider(
+                    ConfigSource::from_env_config(&source),
+                    value.map(Cow::into_owned),
+                    invalid,
+                );
+            }
+        }
+        resolution
+    }
+
+    /// Explains the region from the sources the default region chain consulted while loading it
+    pub(crate) fn region(from_code: Option<Option<&Region>>, chain: &RegionRecorder) -> Self {
+        let mut resolution = Self::new(setting::REGION);
+        if let Some(region) = from_code {
+            resolution.consider(ConfigSource::Code, region.map(ToString::to_string), None);
+            // a region provider set in code is used even if it doesn't return a region
+            resolution.decided = true;
+        }
+        for (source, value) in chain.sources() {
+            resolution.consider(source, value, None);
+        }
+        resolution
+    }
+
+    /// Resolves a setting that is either set in code or has a default
+    pub(crate) fn code_or_default(
+        name: &'static str,
+        from_code: Option<String>,
+        default: Option<String>,
+    ) -> Self {
+        let mut resolution = Self::new(name);
+        resolution.consider(ConfigSource::Code, from_code, None);
+        resolution.or_default(default)
+    }
+
+    /// Falls back to the SDK's default if no other source set this setting
+    pub(crate) fn or_default(mut self, default: Option<String>) -> Self {
+        self.consider(ConfigSource::Default, default, None);
+        self
+    }
+
+    /// Marks every value that was set outside of code as ignored
+    pub(crate) fn ignore_configured(mut self, reason: &str) -> Self {
+        if self.source == Some(ConfigSource::Code) {
+            return self;
+        }
+        self.value = None;
+        self.source = None;
+        for candidate in &mut self.candidates {
+            if candidate.value.is_some() && candidate.source != ConfigSource::Default {
+                candidate.skipped = Some(SkipReason::Ignored {
+                    reason: reason.into(),
+                });
+            }
+        }
+        self
+    }
+
+    fn consider(&mut self, source: ConfigSource, value: Option<String>, invalid: Option<String>) {
+        let skipped = match (&value, invalid) {
+            (None, _) => Some(SkipReason::NotSet),
+            (Some(_), _) if self.decided => Some(SkipReason::Overridden),
+            (Some(_), Some(message)) => {
+                self.decided = true;
+                Some(SkipReason::Invalid { message })
+            }
+            (Some(value), None) => {
+                self.decided = true;
+                self.value = Some(value.clone());
+                self.source = Some(source.clone());
+                None
+            }
+        };
+        self.candidates.push(Candidate {
+            source,
+            value,
+            skipped,
+        });
+    }
+}
+
+impl fmt::Display for SettingResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.value, &self.source) {
+            (Some(value), Some(source)) => write!(f, "{} = {value} (from {source})", self.name)?,
+            _ => write!(f, "{} is not set", self.name)?,
+        }
+        for candidate in self.candidates.iter().filter(|c| c.skipped.is_some()) {
+            write!(f, "\n  - {candidate}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How a step of the credentials provider chain fared the last time credentials were loaded
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChainStepOutcome {
+    /// Credentials haven't been loaded yet
+    NotEvaluated,
+    /// This step provided the credentials
+    Provided,
+    /// This step didn't provide credentials, so the next step was tried
+    NotLoaded {
+        /// Why no credentials were provided
+        reason: String,
+    },
+    /// This step failed, which stopped the chain
+    Failed {
+        /// Why this step failed
+        reason: String,
+    },
+    /// An earlier step provided credentials or failed, so this step wasn't tried
+    NotReached,
+}
+
+impl fmt::Display for ChainStepOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainStepOutcome::NotEvaluated => write!(f, "not evaluated yet"),
+            ChainStepOutcome::Provided => write!(f, "provided credentials"),
+            ChainStepOutcome::NotLoaded { reason } => write!(f, "skipped: {reason}"),
+            ChainStepOutcome::Failed { reason } => write!(f, "failed: {reason}"),
+            ChainStepOutcome::NotReached => write!(f, "not reached"),
+        }
+    }
+}
+
+/// A step of the credentials provider chain
+#[derive(Clone, Debug)]
+pub struct ChainStep {
+    name: Cow<'static, str>,
+    outcome: ChainStepOutcome,
+}
+
+impl ChainStep {
+    /// The name of this step, like `Environment` or `Profile`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// How this step fared the last time credentials were loaded
+    pub fn outcome(&self) -> &ChainStepOutcome {
+        &self.outcome
+    }
+}
+
+/// Records the outcome of each step of a credentials provider chain
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ChainRecorder {
+    steps: Arc<Mutex<Vec<ChainStep>>>,
+}
+
+impl ChainRecorder {
+    pub(crate) fn record(
+        &self,
+        steps: impl IntoIterator<Item = (Cow<'static, str>, ChainStepOutcome)>,
+    ) {
+        *self.steps.lock().unwrap() = steps
+            .into_iter()
+            .map(|(name, outcome)| ChainStep { name, outcome })
+            .collect();
+    }
+
+    fn steps(&self) -> Vec<ChainStep> {
+        self.steps.lock().unwrap().clone()
+    }
+}
+
+/// Records each source a region provider consulted, in order, along with the value it had
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RegionRecorder {
+    sources: Arc<Mutex<Vec<(ConfigSource, Option<String>)>>>,
+}
+
+impl RegionRecorder {
+    pub(crate) fn record(&self, source: ConfigSource, value: Option<&Region>) {
+        self.sources
+            .lock()
+            .unwrap()
+            .push((source, value.map(ToString::to_string)));
+    }
+
+    fn sources(&self) -> Vec<(ConfigSource, Option<String>)> {
+        self.sources.lock().unwrap().clone()
+    }
+}
+
+/// Where credentials come from
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CredentialsSource {
+    /// A credentials provider set in code
+    Code,
+    /// Credentials were disabled with [`no_credentials`](crate::ConfigLoader::no_credentials)
+    Disabled,
+    /// The [default credentials provider chain](crate::default_provider::credentials)
+    DefaultChain,
+}
+
+/// How the credentials provider was chosen
+///
+/// Credentials are loaded lazily, so the step of the default chain that provides them is only
+/// known after the first request is made, or after credentials are loaded with
+/// [`ProvideCredentials::provide_credentials`](aws_credential_types::provider::ProvideCredentials::provide_credentials).
+/// [`chain_steps`](Self::chain_steps) always reflects the most recent load.
+#[derive(Clone, Debug)]
+pub struct CredentialsResolution {
+    source: CredentialsSource,
+    chain: Option<ChainRecorder>,
+}
+
+impl CredentialsResolution {
+    pub(crate) fn new(source: CredentialsSource) -> Self {
+        Self {
+            source,
+            chain: None,
+        }
+    }
+
+    pub(crate) fn default_chain(recorder: ChainRecorder) -> Self {
+        Self {
+            source: CredentialsSource::DefaultChain,
+            chain: Some(recorder),
+        }
+    }
+
+    /// Where credentials come from
+    pub fn source(&self) -> CredentialsSource {
+        self.source
+    }
+
+    /// The steps of the default chain, in order, and how each fared the last time credentials
+    /// were loaded. Empty unless the default chain is used.
+    pub fn chain_steps(&self) -> Vec<ChainStep> {
+        self.chain
+            .as_ref()
+            .map(ChainRecorder::steps)
+            .unwrap_or_default()
+    }
+
+    /// The step of the default chain that provided the most recently loaded credentials
+    pub fn provided_by(&self) -> Option<String> {
+        self.chain_steps()
+            .into_iter()
+            .find(|step| step.outcome == ChainStepOutcome::Provided)
+            .map(|step| step.name.into_owned())
+    }
+}
+
+impl fmt::Display for CredentialsResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.source {
+            CredentialsSource::Code => write!(f, "credentials are provided by code"),
+            CredentialsSource::Disabled => write!(f, "credentials are disabled"),
+            CredentialsSource::DefaultChain => {
+                write!(f, "credentials are provided by the default chain")?;
+                for step in self.chain_steps() {
+                    write!(f, "\n  - {}: {}", step.name, step.outcome)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Where each value in an [`SdkConfig`](aws_types::SdkConfig) came from
+///
+/// Returned by [`ConfigLoader::load_with_report`](crate::ConfigLoader::load_with_report).
+/// Displaying the report lists every setting, the source it came from, and every source that was
+/// skipped along with the reason.
+#[derive(Clone, Debug)]
+pub struct ConfigResolutionReport {
+    settings: Vec<SettingResolution>,
+    credentials: CredentialsResolution,
+    env: Env,
+    profiles: EnvConfigSections,
+}
+
+impl ConfigResolutionReport {
+    pub(crate) fn new(
+        settings: Vec<SettingResolution>,
+        credentials: CredentialsResolution,
+        env: Env,
+        profiles: EnvConfigSections,
+    ) -> Self {
+        Self {
+            settings,
+            credentials,
+            env,
+            profiles,
+        }
+    }
+
+    /// How each setting was resolved
+    pub fn settings(&self) -> &[SettingResolution] {
+        &self.settings
+    }
+
+    /// How the setting with the given name was resolved. See [`setting`] for the names.
+    pub fn setting(&self, name: &str) -> Option<&SettingResolution> {
+        self.settings.iter().find(|setting| setting.name == name)
+    }
+
+    /// How the credentials provider was chosen
+    pub fn credentials(&self) -> &CredentialsResolution {
+        &self.credentials
+    }
+
+    /// How a service-specific setting is resolved from the environment and profile
+    ///
+    /// Service clients resolve these settings when they are created, and settings made in code
+    /// on the service client's config take precedence over every source listed here.
+    pub fn service_config(&self, key: ServiceConfigKey<'_>) -> SettingResolution {
+        let value = EnvConfigValue::new()
+            .env(key.env())
+            .profile(key.profile())
+            .service_id(key.service_id());
+        SettingResolution::explain(
+            format!("{} ({})", key.profile(), key.service_id()),
+            None,
+            vec![value],
+            &self.env,
+            Some(&self.profiles),
+            |_| Ok::<_, Infallible>(()),
+        )
+    }
+}
+
+impl fmt::Display for ConfigResolutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for setting in &self.settings {
+            writeln!(f, "{setting}")?;
+        }
+        write!(f, "{}", self.credentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{setting, ConfigSource, RegionRecorder, SettingResolution, SkipReason};
+    use crate::environment::parse_bool;
+    use aws_runtime::env_config::EnvConfigValue;
+    use aws_types::os_shim_internal::Env;
+    use aws_types::region::Region;
+
+    fn skip_reasons(resolution: &SettingResolution) -> Vec<Option<&SkipReason>> {
+        resolution
+            .candidates()
+            .iter()
+            .map(|candidate| candidate.skip_reason())
+            .collect()
+    }
+
+    #[test]
+    fn invalid_values_stop_resolution() {
+        let env = Env::from_slice(&[("AWS_USE_FIPS_ENDPOINT", "maybe")]);
+        let resolution = SettingResolution::explain(
+            setting::USE_FIPS,
+            None,
+            vec![EnvConfigValue::new()
+                .env("AWS_USE_FIPS_ENDPOINT")
+                .profile("use_fips_endpoint")],
+            &env,
+            None,
+            parse_bool,
+        )
+        .or_default(Some("false".into()));
+        assert_eq!(None, resolution.value());
+        assert!(matches!(
+            skip_reasons(&resolution)[..],
+            [
+                Some(SkipReason::Invalid { .. }),
+                Some(SkipReason::Overridden)
+            ]
+        ));
+        assert!(format!("{resolution}").contains("use_fips is not set"));
+    }
+
+    #[test]
+    fn region_lists_only_the_sources_that_were_consulted() {
+        let chain = RegionRecorder::default();
+        let env = |variable: &str| ConfigSource::Environment {
+            variable: variable.into(),
+            service_id: None,
+        };
+        chain.record(env("AWS_REGION"), None);
+        chain.record(env("AWS_DEFAULT_REGION"), None);
+        chain.record(ConfigSource::Imds, Some(&Region::new("us-west-2")));
+        let resolution = SettingResolution::region(None, &chain);
+        assert_eq!(Some(&ConfigSource::Imds), resolution.source());
+        assert_eq!(Some("us-west-2"), resolution.value());
+        assert_eq!(
+            vec![Some(&SkipReason::NotSet), Some(&SkipReason::NotSet), None],
+            skip_reasons(&resolution)
+        );
+
+        // a region provider in code is used even if it returns no region
+        let resolution = SettingResolution::region(Some(None), &RegionRecorder::default());
+        assert_eq!(None, resolution.source());
+        assert_eq!(vec![Some(&SkipReason::NotSet)], skip_reasons(&resolution));
+    }
+}
@@ -0,0 +1,12 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! IAM Roles Anywhere credentials provider
+
+pub mod credentials;
+
+pub use credentials::RolesAnywhereCredentialsProvider;
+
+mod x509;
@@ -0,0 +1,680 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! IAM Roles Anywhere Credentials Provider
+//!
+//! This credentials provider exchanges an X.509 certificate and its private key for temporary
+//! credentials by calling the IAM Roles Anywhere `CreateSession` API. The request is signed with the
+//! `AWS4-X509-RSA-SHA256` or `AWS4-X509-ECDSA-SHA256` algorithm depending on the type of the key. For
+//! more information, see [IAM Roles Anywhere](https://docs.aws.amazon.com/rolesanywhere/latest/userguide/introduction.html).
+//!
+//! Certificates may be PEM or DER encoded. Private keys may be PEM encoded PKCS#8, PKCS#1 (RSA) or
+//! SEC1 (EC) keys, or DER encoded PKCS#8 keys. RSA and P-256 EC keys are supported.
+//!
+//! This provider is included automatically when profiles are loaded:
+//! ```ini
+//! [profile roles-anywhere]
+//! role_arn = arn:aws:iam::123456789012:role/MyRole
+//! rolesanywhere_trust_anchor_arn = arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/abc
+//! rolesanywhere_profile_arn = arn:aws:rolesanywhere:us-east-1:123456789012:profile/def
+//! rolesanywhere_certificate = /path/to/certificate.pem
+//! rolesanywhere_private_key = /path/to/private-key.pem
+//! # optional
+//! rolesanywhere_intermediate_certificates = /path/to/intermediates.pem
+//! role_session_name = my-session
+//! duration_seconds = 3600
+//! ```
+
+use super::x509::{self, SigningKey};
+use crate::provider_config::ProviderConfig;
+use aws_credential_types::provider::{self, error::CredentialsError, future, ProvideCredentials};
+use aws_credential_types::{Credentials, ProvenanceStep};
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_json::deserialize::json_token_iter;
+use aws_smithy_json::deserialize::token::expect_document;
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime::client::retries::classifiers::{
+    HttpStatusCodeClassifier, TransientErrorClassifier,
+};
+use aws_smithy_runtime_api::client::interceptors::context::Error;
+use aws_smithy_runtime_api::client::orchestrator::{
+    HttpResponse, OrchestratorError, SensitiveOutput,
+};
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::Layer;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::{DateTime, Document, Number};
+use aws_types::os_shim_internal::Fs;
+use aws_types::region::Region;
+use ring::digest;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const PROVIDER_NAME: &str = "RolesAnywhere";
+const SERVICE_NAME: &str = "rolesanywhere";
+
+/// IAM Roles Anywhere Credentials Provider
+///
+/// _Note: This provider is part of the default credentials chain and is integrated with the profile-file provider._
+///
+/// The certificate, private key and intermediate certificates are read every time credentials are
+/// loaded, so rotated certificates are picked up without rebuilding the provider.
+#[derive(Debug)]
+pub struct RolesAnywhereCredentialsProvider {
+    fs: Fs,
+    time_source: SharedTimeSource,
+    config: RolesAnywhereConfig,
+    host: String,
+    operation: Operation<CreateSessionRequest, Credentials, CredentialsError>,
+}
+
+/// A signed `CreateSession` request
+#[derive(Debug)]
+struct CreateSessionRequest {
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl RolesAnywhereCredentialsProvider {
+    /// Creates a builder for [`RolesAnywhereCredentialsProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    pub(crate) fn new(provider_config: &ProviderConfig, config: RolesAnywhereConfig) -> Self {
+        let endpoint = config.endpoint_url.clone().unwrap_or_else(|| {
+            let dns_suffix = match config.trust_anchor_arn.split(':').nth(1) {
+                Some("aws-cn") => "amazonaws.com.cn",
+                _ => "amazonaws.com",
+            };
+            format!("https://{SERVICE_NAME}.{}.{dns_suffix}", config.region)
+        });
+        let host = endpoint
+            .parse::<http::Uri>()
+            .ok()
+            .and_then(|uri| uri.authority().map(|authority| authority.to_string()))
+            .unwrap_or_default();
+
+        let mut builder = Operation::builder()
+            .service_name("RolesAnywhere")
+            .operation_name("CreateSession")
+            .with_connection_poisoning()
+            .endpoint_url(&endpoint)
+            .no_auth()
+            .runtime_plugin(StaticRuntimePlugin::new().with_config({
+                let mut layer = Layer::new("SensitiveOutput");
+                layer.store_put(SensitiveOutput);
+                layer.freeze()
+            }));
+        if let Some(http_client) = provider_config.http_client() {
+            builder = builder.http_client(http_client);
+        }
+        if let Some(sleep_impl) = provider_config.sleep_impl() {
+            builder = builder
+                .standard_retry(&RetryConfig::standard())
+                .retry_classifier(TransientErrorClassifier::<Error>::new())
+                .retry_classifier(HttpStatusCodeClassifier::default())
+                .sleep_impl(sleep_impl);
+        } else {
+            builder = builder.no_retry();
+        }
+        let operation = builder
+            .serializer(|input: CreateSessionRequest| {
+                let mut request = http::Request::builder().method("POST").uri("/sessions");
+                for (name, value) in input.headers {
+                    request = request.header(name, value);
+                }
+                Ok(request
+                    .body(SdkBody::from(input.body))
+                    .expect("valid request")
+                    .try_into()
+                    .unwrap())
+            })
+            .deserializer(parse_response)
+            .build();
+
+        Self {
+            fs: provider_config.fs(),
+            time_source: provider_config.time_source(),
+            config,
+            host,
+            operation,
+        }
+    }
+
+    async fn credentials(&self) -> provider::Result {
+        let request = self.signed_request().await?;
+        match self.operation.invoke(request).await {
+            Ok(credentials) => Ok(credentials),
+            Err(SdkError::ServiceError(context)) => Err(context.into_err()),
+            Err(other) => Err(CredentialsError::provider_error(other)),
+        }
+    }
+
+    async fn read_file(&self, path: &Path) -> Result<Vec<u8>, CredentialsError> {
+        self.fs.read_to_end(path).await.map_err(|err| {
+            CredentialsError::invalid_configuration(format!(
+                "could not read `{}`: {err}",
+                path.display()
+            ))
+        })
+    }
+
+    async fn signed_request(&self) -> Result<CreateSessionRequest, CredentialsError> {
+        let config = &self.config;
+        let certificate = x509::load_certificates(&self.read_file(&config.certificate).await?)
+            .map_err(CredentialsError::invalid_configuration)?
+            .swap_remove(0);
+        let mut chain = Vec::new();
+        if let Some(path) = &config.intermediate_certificates {
+            chain = x509::load_certificates(&self.read_file(path).await?)
+                .map_err(CredentialsError::invalid_configuration)?;
+        }
+        let key = SigningKey::from_pem_or_der(&self.read_file(&config.private_key).await?)
+            .map_err(CredentialsError::invalid_configuration)?;
+        let serial_number =
+            x509::serial_number(&certificate).map_err(CredentialsError::invalid_configuration)?;
+
+        let body = request_body(config);
+        let amz_date = amz_date(self.time_source.now());
+        let mut headers = vec![
+            ("content-type", "application/json".to_string()),
+            ("host", self.host.clone()),
+            ("x-amz-date", amz_date.clone()),
+            ("x-amz-x509", aws_smithy_types::base64::encode(&certificate)),
+        ];
+        if !chain.is_empty() {
+            let chain: Vec<_> = chain.iter().map(aws_smithy_types::base64::encode).collect();
+            headers.push(("x-amz-x509-chain", chain.join(",")));
+        }
+
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect();
+        let canonical_request = format!(
+            "POST\n/sessions\n\n{canonical_headers}\n{signed_headers}\n{}",
+            sha256_hex(body.as_bytes())
+        );
+        let scope = format!(
+            "{}/{}/{SERVICE_NAME}/aws4_request",
+            &amz_date[..8],
+            config.region
+        );
+        let string_to_sign = format!(
+            "{}\n{amz_date}\n{scope}\n{}",
+            key.algorithm(),
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let signature = key
+            .sign(string_to_sign.as_bytes())
+            .map_err(CredentialsError::unhandled)?;
+        headers.push((
+            "authorization",
+            format!(
+                "{} Credential={serial_number}/{scope}, SignedHeaders={signed_headers}, Signature={}",
+                key.algorithm(),
+                hex::encode(signature)
+            ),
+        ));
+        Ok(CreateSessionRequest { headers, body })
+    }
+}
+
+impl ProvideCredentials for RolesAnywhereCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+/// Builder for [`RolesAnywhereCredentialsProvider`]
+#[derive(Default, Debug, Clone)]
+pub struct Builder {
+    provider_config: Option<ProviderConfig>,
+    trust_anchor_arn: Option<String>,
+    profile_arn: Option<String>,
+    role_arn: Option<String>,
+    certificate: Option<PathBuf>,
+    private_key: Option<PathBuf>,
+    intermediate_certificates: Option<PathBuf>,
+    session_name: Option<String>,
+    session_duration: Option<Duration>,
+    region: Option<Region>,
+    endpoint_url: Option<String>,
+}
+
+impl Builder {
+    /// Override the configuration used for this provider
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Set the ARN of the trust anchor that issued the certificate
+    ///
+    /// This is a required field.
+    pub fn trust_anchor_arn(mut self, trust_anchor_arn: impl Into<String>) -> Self {
+        self.trust_anchor_arn = Some(trust_anchor_arn.into());
+        self
+    }
+
+    /// Set the ARN of the IAM Roles Anywhere profile
+    ///
+    /// This is a required field.
+    pub fn profile_arn(mut self, profile_arn: impl Into<String>) -> Self {
+        self.profile_arn = Some(profile_arn.into());
+        self
+    }
+
+    /// Set the ARN of the role to assume
+    ///
+    /// This is a required field.
+    pub fn role_arn(mut self, role_arn: impl Into<String>) -> Self {
+        self.role_arn = Some(role_arn.into());
+        self
+    }
+
+    /// Set the path of the PEM or DER encoded end-entity certificate
+    ///
+    /// If the file contains several PEM certificates, the first one is used.
+    ///
+    /// This is a required field.
+    pub fn certificate(mut self, path: impl Into<PathBuf>) -> Self {
+        self.certificate = Some(path.into());
+        self
+    }
+
+    /// Set the path of the private key of the certificate
+    ///
+    /// This is a required field.
+    pub fn private_key(mut self, path: impl Into<PathBuf>) -> Self {
+        self.private_key = Some(path.into());
+        self
+    }
+
+    /// Set the path of a PEM file of intermediate certificates to send along with the certificate
+    pub fn intermediate_certificates(mut self, path: impl Into<PathBuf>) -> Self {
+        self.intermediate_certificates = Some(path.into());
+        self
+    }
+
+    /// Set the name of the role session
+    pub fn session_name(mut self, session_name: impl Into<String>) -> Self {
+        self.session_name = Some(session_name.into());
+        self
+    }
+
+    /// Set how long the returned credentials are valid for
+    ///
+    /// Defaults to the service default of one hour.
+    pub fn session_duration(mut self, session_duration: Duration) -> Self {
+        self.session_duration = Some(session_duration);
+        self
+    }
+
+    /// Set the region of the IAM Roles Anywhere endpoint
+    ///
+    /// Defaults to the region in the trust anchor ARN.
+    pub fn region(mut self, region: Region) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Override the IAM Roles Anywhere endpoint, e.g. to use a VPC endpoint
+    pub fn endpoint_url(mut self, endpoint_url: impl Into<String>) -> Self {
+        self.endpoint_url = Some(endpoint_url.into());
+        self
+    }
+
+    /// Construct a RolesAnywhereCredentialsProvider from the builder
+    ///
+    /// # Panics
+    /// This method will panic if the any of the following required fields are unset:
+    /// - [`trust_anchor_arn`](Self::trust_anchor_arn)
+    /// - [`profile_arn`](Self::profile_arn)
+    /// - [`role_arn`](Self::role_arn)
+    /// - [`certificate`](Self::certificate)
+    /// - [`private_key`](Self::private_key)
+    ///
+    /// It will also panic if no [`region`](Self::region) is set and the trust anchor ARN has no region.
+    pub fn build(self) -> RolesAnywhereCredentialsProvider {
+        let provider_config = self.provider_config.unwrap_or_default();
+        let trust_anchor_arn = self.trust_anchor_arn.expect("trust_anchor_arn must be set");
+        let region = self
+            .region
+            .or_else(|| region_from_arn(&trust_anchor_arn))
+            .expect("region must be set or included in the trust anchor ARN");
+        let config = RolesAnywhereConfig {
+            profile_arn: self.profile_arn.expect("profile_arn must be set"),
+            role_arn: self.role_arn.expect("role_arn must be set"),
+            certificate: self.certificate.expect("certificate must be set"),
+            private_key: self.private_key.expect("private_key must be set"),
+            intermediate_certificates: self.intermediate_certificates,
+            session_name: self.session_name,
+            session_duration: self.session_duration,
+            region,
+            endpoint_url: self.endpoint_url,
+            trust_anchor_arn,
+        };
+        RolesAnywhereCredentialsProvider::new(&provider_config, config)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RolesAnywhereConfig {
+    pub(crate) trust_anchor_arn: String,
+    pub(crate) profile_arn: String,
+    pub(crate) role_arn: String,
+    pub(crate) certificate: PathBuf,
+    pub(crate) private_key: PathBuf,
+    pub(crate) intermediate_certificates: Option<PathBuf>,
+    pub(crate) session_name: Option<String>,
+    pub(crate) session_duration: Option<Duration>,
+    pub(crate) region: Region,
+    pub(crate) endpoint_url: Option<String>,
+}
+
+/// Return the region of an ARN such as `arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/abc`
+pub(crate) fn region_from_arn(arn: &str) -> Option<Region> {
+    arn.split(':')
+        .nth(3)
+        .filter(|region| !region.is_empty())
+        .map(|region| Region::new(region.to_string()))
+}
+
+fn request_body(config: &RolesAnywhereConfig) -> String {
+    let mut body = String::new();
+    let mut writer = JsonObjectWriter::new(&mut body);
+    if let Some(duration) = config.session_duration {
+        writer
+            .key("durationSeconds")
+            .number(Number::PosInt(duration.as_secs()));
+    }
+    writer.key("profileArn").string(&config.profile_arn);
+    writer.key("roleArn").string(&config.role_arn);
+    if let Some(session_name) = &config.session_name {
+        writer.key("roleSessionName").string(session_name);
+    }
+    writer
+        .key("trustAnchorArn")
+        .string(&config.trust_anchor_arn);
+    writer.finish();
+    body
+}
+
+/// Format `time` as a SigV4 timestamp, e.g. `20240101T000000Z`
+fn amz_date(time: SystemTime) -> String {
+    DateTime::from_secs(DateTime::from(time).secs())
+        .fmt(Format::DateTime)
+        .expect("valid timestamp")
+        .replace(['-', ':'], "")
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(digest::digest(&digest::SHA256, data))
+}
+
+fn parse_response(
+    response: &HttpResponse,
+) -> Result<Credentials, OrchestratorError<CredentialsError>> {
+    let body = response.body().bytes().expect("non-streaming deserializer");
+    if !response.status().is_success() {
+        let message = parse_document(body)
+            .ok()
+            .and_then(|document| Some(field(object(&document)?, "message")?.to_string()))
+            .unwrap_or_else(|| "no message".into());
+        return Err(OrchestratorError::operation(
+            CredentialsError::provider_error(format!(
+                "IAM Roles Anywhere returned status {}: {message}",
+                response.status().as_u16()
+            )),
+        ));
+    }
+    parse_credentials(body).map_err(OrchestratorError::operation)
+}
+
+fn parse_document(body: &[u8]) -> Result<Document, CredentialsError> {
+    expect_document(&mut json_token_iter(body).peekable()).map_err(CredentialsError::unhandled)
+}
+
+fn object(document: &Document) -> Option<&HashMap<String, Document>> {
+    document.as_object()
+}
+
+fn field<'a>(object: &'a HashMap<String, Document>, name: &str) -> Option<&'a str> {
+    object.get(name).and_then(Document::as_string)
+}
+
+/// Parse the first credential set from a `CreateSession` response
+///
+/// ```json
+/// {
+///   "credentialSet": [{
+///     "assumedRoleUser": { "arn": "...", "assumedRoleId": "..." },
+///     "credentials": {
+///       "accessKeyId": "...",
+///       "secretAccessKey": "...",
+///       "sessionToken": "...",
+///       "expiration": "2024-01-01T00:00:00Z"
+///     },
+///     ...
+///   }],
+///   "subjectArn": "..."
+/// }
+/// ```
+fn parse_credentials(body: &[u8]) -> Result<Credentials, CredentialsError> {
+    let document = parse_document(body)?;
+    let missing = |name: &str| {
+        CredentialsError::unhandled(format!(
+            "`{name}` was missing from the CreateSession response"
+        ))
+    };
+    let credential_set = object(&document)
+        .and_then(|document| document.get("credentialSet"))
+        .and_then(Document::as_array)
+        .and_then(|sets| sets.first())
+        .and_then(object)
+        .ok_or_else(|| missing("credentialSet"))?;
+    let credentials = credential_set
+        .get("credentials")
+        .and_then(object)
+        .ok_or_else(|| missing("credentials"))?;
+    let required = |name: &'static str| field(credentials, name).ok_or_else(|| missing(name));
+    let expiration = DateTime::from_str(required("expiration")?, Format::DateTime)
+        .map_err(CredentialsError::unhandled)
+        .and_then(|expiration| {
+            SystemTime::try_from(expiration).map_err(|_| {
+                CredentialsError::unhandled(
+                    "credential expiration time cannot be represented by a SystemTime",
+                )
+            })
+        })?;
+    let mut credentials = Credentials::new(
+        required("accessKeyId")?,
+        required("secretAccessKey")?,
+        Some(required("sessionToken")?.to_string()),
+        Some(expiration),
+        PROVIDER_NAME,
+    );
+    if let Some(arn) = credential_set
+        .get("assumedRoleUser")
+        .and_then(object)
+        .and_then(|user| field(user, "arn"))
+    {
+        if let Some(account_id) = crate::sts::util::account_id_from_arn(arn) {
+            credentials = credentials.with_account_id(account_id);
+        }
+        if let Some(step) = credentials.provenance_mut().first_mut() {
+            *step = ProvenanceStep::new(PROVIDER_NAME).with_detail(arn);
+        }
+    }
+    Ok(credentials)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aws_smithy_async::time::StaticTimeSource;
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use std::time::UNIX_EPOCH;
+
+    const TRUST_ANCHOR_ARN: &str = "arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/abc";
+
+    fn provider(http_client: StaticReplayClient, key: &str) -> RolesAnywhereCredentialsProvider {
+        let provider_config = ProviderConfig::no_configuration()
+            .with_fs(Fs::from_test_dir("test-data/rolesanywhere", "/certs"))
+            .with_time_source(StaticTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            ))
+            .with_http_client(http_client);
+        RolesAnywhereCredentialsProvider::builder()
+            .configure(&provider_config)
+            .trust_anchor_arn(TRUST_ANCHOR_ARN)
+            .profile_arn("arn:aws:rolesanywhere:us-east-1:123456789012:profile/def")
+            .role_arn("arn:aws:iam::123456789012:role/MyRole")
+            .certificate(format!("/certs/{key}-cert.pem"))
+            .private_key(format!("/certs/{key}-key.pem"))
+            .intermediate_certificates("/certs/ca.pem")
+            .session_duration(Duration::from_secs(900))
+            .endpoint_url("http://localhost:1234")
+            .build()
+    }
+
+    fn response(status: u16, body: &'static str) -> ReplayEvent {
+        ReplayEvent::new(
+            http::Request::builder()
+                .uri("http://localhost:1234/sessions")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(status)
+                .body(SdkBody::from(body))
+                .unwrap(),
+        )
+    }
+
+    const SUCCESS: &str = r#"{
+        "credentialSet": [{
+            "assumedRoleUser": {
+                "arn": "arn:aws:sts::123456789012:assumed-role/MyRole/session",
+                "assumedRoleId": "AROAEXAMPLE:session"
+            },
+            "credentials": {
+                "accessKeyId": "ASIARTESTID",
+                "secretAccessKey": "TESTSECRETKEY",
+                "sessionToken": "TESTSESSIONTOKEN",
+                "expiration": "2023-11-14T23:28:20Z"
+            },
+            "packedPolicySize": 10,
+            "roleArn": "arn:aws:iam::123456789012:role/MyRole",
+            "sourceIdentity": "CN=rsa-client"
+        }],
+        "subjectArn": "arn:aws:rolesanywhere:us-east-1:123456789012:subject/xyz"
+    }"#;
+
+    #[tokio::test]
+    async fn signs_create_session_with_rsa_and_ecdsa_keys() {
+        for (key, algorithm, serial_number) in [
+            ("rsa", "AWS4-X509-RSA-SHA256", "4759477275222530853130"),
+            ("ec", "AWS4-X509-ECDSA-SHA256", "42"),
+        ] {
+            let http_client = StaticReplayClient::new(vec![response(200, SUCCESS)]);
+            let creds = provider(http_client.clone(), key)
+                .provide_credentials()
+                .await
+                .expect("success");
+            assert_eq!("ASIARTESTID", creds.access_key_id());
+            assert_eq!("TESTSECRETKEY", creds.secret_access_key());
+            assert_eq!(Some("TESTSESSIONTOKEN"), creds.session_token());
+            assert_eq!(
+                Some(UNIX_EPOCH + Duration::from_secs(1_700_004_500)),
+                creds.expiry()
+            );
+            assert_eq!(Some("123456789012"), creds.account_id());
+
+            let requests = http_client.actual_requests().collect::<Vec<_>>();
+            let request = &requests[0];
+            assert_eq!("POST", request.method());
+            assert_eq!("localhost:1234", request.headers().get("host").unwrap());
+            assert_eq!(
+                "20231114T221320Z",
+                request.headers().get("x-amz-date").unwrap()
+            );
+            assert!(request.headers().get("x-amz-x509").is_some());
+            assert!(request.headers().get("x-amz-x509-chain").is_some());
+            let authorization = request.headers().get("authorization").unwrap();
+            assert!(
+                authorization.starts_with(&format!(
+                    "{algorithm} Credential={serial_number}/20231114/us-east-1/rolesanywhere/aws4_request, \
+                    SignedHeaders=content-type;host;x-amz-date;x-amz-x509;x-amz-x509-chain, Signature="
+                )),
+                "{authorization}"
+            );
+            assert_eq!(
+                r#"{"durationSeconds":900,"profileArn":"arn:aws:rolesanywhere:us-east-1:123456789012:profile/def","roleArn":"arn:aws:iam::123456789012:role/MyRole","trustAnchorArn":"arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/abc"}"#,
+                std::str::from_utf8(request.body().bytes().unwrap()).unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn service_errors_are_surfaced() {
+        let http_client = StaticReplayClient::new(vec![response(
+            403,
+            r#"{"message": "Untrusted certificate. Insufficient certificate"}"#,
+        )]);
+        let err = provider(http_client, "rsa")
+            .provide_credentials()
+            .await
+            .expect_err("access denied");
+        assert!(
+            format!(
+                "{}",
+                aws_smithy_types::error::display::DisplayErrorContext(&err)
+            )
+            .contains("Untrusted certificate"),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_certificate_is_a_configuration_error() {
+        let http_client = StaticReplayClient::new(vec![]);
+        let err = provider(http_client, "missing")
+            .provide_credentials()
+            .await
+            .expect_err("no certificate");
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn timestamps_and_regions() {
+        assert_eq!(
+            "20231114T221320Z",
+            amz_date(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))
+        );
+        assert_eq!(
+            Some(Region::new("us-east-1")),
+            region_from_arn(TRUST_ANCHOR_ARN)
+        );
+        assert_eq!(None, region_from_arn("not-an-arn"));
+    }
+}
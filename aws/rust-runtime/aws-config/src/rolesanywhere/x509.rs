@@ -0,0 +1,368 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! X.509 certificate and private key handling for the `AWS4-X509-*` signing algorithms
+//!
+//! Only the small subset of DER needed to find a certificate's serial number and to wrap SEC1
+//! EC keys into PKCS#8 is implemented here. Key parsing and signing is delegated to `ring`.
+
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, RsaKeyPair, ECDSA_P256_SHA256_ASN1_SIGNING, RSA_PKCS1_SHA256};
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt;
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_VERSION: u8 = 0xA0;
+
+/// DER encoding of the `id-ecPublicKey` object identifier (1.2.840.10045.2.1)
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+
+#[derive(Debug)]
+pub(super) enum X509Error {
+    /// The certificate or key contained no PEM block with the expected label
+    MissingPemBlock { expected: &'static str },
+    /// A PEM block or DER structure was malformed
+    Malformed(Cow<'static, str>),
+    /// The private key was rejected by `ring` or uses an unsupported algorithm
+    UnsupportedKey(Cow<'static, str>),
+    /// Signing failed
+    SigningFailed,
+}
+
+impl fmt::Display for X509Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPemBlock { expected } => {
+                write!(f, "no `{expected}` PEM block was found")
+            }
+            Self::Malformed(message) => write!(f, "malformed certificate or key: {message}"),
+            Self::UnsupportedKey(message) => write!(f, "unsupported private key: {message}"),
+            Self::SigningFailed => write!(f, "failed to sign the request"),
+        }
+    }
+}
+
+impl StdError for X509Error {}
+
+/// Private key used to sign `CreateSession` requests
+pub(super) enum SigningKey {
+    Rsa(RsaKeyPair),
+    Ecdsa(EcdsaKeyPair),
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rsa(_) => f.write_str("SigningKey::Rsa(** redacted **)"),
+            Self::Ecdsa(_) => f.write_str("SigningKey::Ecdsa(** redacted **)"),
+        }
+    }
+}
+
+impl SigningKey {
+    /// Load a private key from PEM (`PRIVATE KEY`, `RSA PRIVATE KEY` or `EC PRIVATE KEY`) or from
+    /// DER encoded PKCS#8.
+    pub(super) fn from_pem_or_der(data: &[u8]) -> Result<Self, X509Error> {
+        if !is_pem(data) {
+            return Self::from_pkcs8(data);
+        }
+        let blocks = pem_blocks(data)?;
+        let (label, der) = blocks
+            .into_iter()
+            .find(|(label, _)| label.ends_with("PRIVATE KEY"))
+            .ok_or(X509Error::MissingPemBlock {
+                expected: "PRIVATE KEY",
+            })?;
+        match label.as_str() {
+            "PRIVATE KEY" => Self::from_pkcs8(&der),
+            "RSA PRIVATE KEY" => RsaKeyPair::from_der(&der)
+                .map(Self::Rsa)
+                .map_err(|err| X509Error::UnsupportedKey(err.to_string().into())),
+            "EC PRIVATE KEY" => Self::from_pkcs8(&sec1_to_pkcs8(&der)?),
+            "ENCRYPTED PRIVATE KEY" => Err(X509Error::UnsupportedKey(
+                "encrypted private keys are not supported".into(),
+            )),
+            other => Err(X509Error::UnsupportedKey(
+                format!("unknown PEM label `{other}`").into(),
+            )),
+        }
+    }
+
+    fn from_pkcs8(der: &[u8]) -> Result<Self, X509Error> {
+        if let Ok(key) = RsaKeyPair::from_pkcs8(der) {
+            return Ok(Self::Rsa(key));
+        }
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, der, &SystemRandom::new())
+            .map(Self::Ecdsa)
+            .map_err(|_| {
+                X509Error::UnsupportedKey("expected an RSA or P-256 EC private key".into())
+            })
+    }
+
+    /// The name of the signing algorithm used with this key
+    pub(super) fn algorithm(&self) -> &'static str {
+        match self {
+            Self::Rsa(_) => "AWS4-X509-RSA-SHA256",
+            Self::Ecdsa(_) => "AWS4-X509-ECDSA-SHA256",
+        }
+    }
+
+    /// Sign `message` with SHA-256 and return the DER (ECDSA) or PKCS#1 v1.5 (RSA) signature
+    pub(super) fn sign(&self, message: &[u8]) -> Result<Vec<u8>, X509Error> {
+        let rng = SystemRandom::new();
+        match self {
+            Self::Rsa(key) => {
+                let mut signature = vec![0; key.public().modulus_len()];
+                key.sign(&RSA_PKCS1_SHA256, &rng, message, &mut signature)
+                    .map_err(|_| X509Error::SigningFailed)?;
+                Ok(signature)
+            }
+            Self::Ecdsa(key) => key
+                .sign(&rng, message)
+                .map(|signature| signature.as_ref().to_vec())
+                .map_err(|_| X509Error::SigningFailed),
+        }
+    }
+}
+
+/// Load DER certificates from PEM `CERTIFICATE` blocks, or a single DER certificate
+pub(super) fn load_certificates(data: &[u8]) -> Result<Vec<Vec<u8>>, X509Error> {
+    if !is_pem(data) {
+        return Ok(vec![data.to_vec()]);
+    }
+    let certificates: Vec<_> = pem_blocks(data)?
+        .into_iter()
+        .filter(|(label, _)| label == "CERTIFICATE")
+        .map(|(_, der)| der)
+        .collect();
+    if certificates.is_empty() {
+        return Err(X509Error::MissingPemBlock {
+            expected: "CERTIFICATE",
+        });
+    }
+    Ok(certificates)
+}
+
+/// Return the serial number of a DER certificate as a decimal string
+pub(super) fn serial_number(certificate: &[u8]) -> Result<String, X509Error> {
+    // Certificate ::= SEQUENCE { tbsCertificate TBSCertificate, ... }
+    let (certificate, _) = read_tlv(certificate, TAG_SEQUENCE)?;
+    // TBSCertificate ::= SEQUENCE { version [0] EXPLICIT Version DEFAULT v1, serialNumber INTEGER, ... }
+    let (tbs_certificate, _) = read_tlv(certificate, TAG_SEQUENCE)?;
+    let rest = match read_tlv(tbs_certificate, TAG_VERSION) {
+        Ok((_, rest)) => rest,
+        Err(_) => tbs_certificate,
+    };
+    let (serial, _) = read_tlv(rest, TAG_INTEGER)?;
+    Ok(to_decimal(serial))
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    data.windows(11).any(|window| window == b"-----BEGIN ")
+}
+
+/// Parse all PEM blocks in `data` into `(label, der)` pairs
+fn pem_blocks(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>, X509Error> {
+    let data = std::str::from_utf8(data)
+        .map_err(|_| X509Error::Malformed("PEM data is not valid UTF-8".into()))?;
+    let mut blocks = Vec::new();
+    let mut current: Option<(String, String)> = None;
+    for line in data.lines().map(str::trim) {
+        if let Some(label) = line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|line| line.strip_suffix("-----"))
+        {
+            current = Some((label.to_string(), String::new()));
+        } else if let Some(label) = line
+            .strip_prefix("-----END ")
+            .and_then(|line| line.strip_suffix("-----"))
+        {
+            let (begin_label, base64) = current.take().ok_or_else(|| {
+                X509Error::Malformed(format!("`END {label}` without a matching `BEGIN`").into())
+            })?;
+            if begin_label != label {
+                return Err(X509Error::Malformed(
+                    format!("`BEGIN {begin_label}` was ended by `END {label}`").into(),
+                ));
+            }
+            let der = aws_smithy_types::base64::decode(&base64).map_err(|err| {
+                X509Error::Malformed(format!("invalid base64 in `{label}`: {err}").into())
+            })?;
+            blocks.push((begin_label, der));
+        } else if let Some((_, base64)) = current.as_mut() {
+            // skip RFC 1421 style headers such as `Proc-Type`
+            if !line.contains(':') {
+                base64.push_str(line);
+            }
+        }
+    }
+    if let Some((label, _)) = current {
+        return Err(X509Error::Malformed(
+            format!("`BEGIN {label}` was never ended").into(),
+        ));
+    }
+    Ok(blocks)
+}
+
+/// Read a DER value with the expected `tag`, returning its contents and the remaining input
+fn read_tlv(input: &[u8], tag: u8) -> Result<(&[u8], &[u8]), X509Error> {
+    let malformed = || X509Error::Malformed("truncated DER value".into());
+    match input.first() {
+        Some(actual) if *actual == tag => {}
+        Some(actual) => {
+            return Err(X509Error::Malformed(
+                format!("expected DER tag {tag:#04x} but found {actual:#04x}").into(),
+            ))
+        }
+        None => return Err(malformed()),
+    }
+    let first = *input.get(1).ok_or_else(malformed)?;
+    let (len, header_len) = if first < 0x80 {
+        (first as usize, 2)
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 {
+            return Err(X509Error::Malformed("unsupported DER length".into()));
+        }
+        let bytes = input.get(2..2 + count).ok_or_else(malformed)?;
+        let len = bytes
+            .iter()
+            .fold(0usize, |len, byte| (len << 8) | *byte as usize);
+        (len, 2 + count)
+    };
+    let end = header_len.checked_add(len).ok_or_else(malformed)?;
+    let value = input.get(header_len..end).ok_or_else(malformed)?;
+    Ok((value, &input[end..]))
+}
+
+fn write_tlv(out: &mut Vec<u8>, tag: u8, value: &[u8]) {
+    out.push(tag);
+    let len = value.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(value);
+}
+
+/// Wrap a SEC1 `ECPrivateKey` into a PKCS#8 `PrivateKeyInfo` so that `ring` can load it
+fn sec1_to_pkcs8(sec1: &[u8]) -> Result<Vec<u8>, X509Error> {
+    // ECPrivateKey ::= SEQUENCE { version INTEGER, privateKey OCTET STRING, parameters [0] ECParameters, ... }
+    let (ec_private_key, _) = read_tlv(sec1, TAG_SEQUENCE)?;
+    let (_version, rest) = read_tlv(ec_private_key, TAG_INTEGER)?;
+    let (_private_key, rest) = read_tlv(rest, TAG_OCTET_STRING)?;
+    let (parameters, _) = read_tlv(rest, TAG_VERSION).map_err(|_| {
+        X509Error::UnsupportedKey("EC private keys must specify their named curve".into())
+    })?;
+    let (curve, _) = read_tlv(parameters, TAG_OID)?;
+
+    let mut algorithm = Vec::new();
+    write_tlv(&mut algorithm, TAG_OID, OID_EC_PUBLIC_KEY);
+    write_tlv(&mut algorithm, TAG_OID, curve);
+    let mut private_key_info = Vec::new();
+    write_tlv(&mut private_key_info, TAG_INTEGER, &[0]);
+    write_tlv(&mut private_key_info, TAG_SEQUENCE, &algorithm);
+    write_tlv(&mut private_key_info, TAG_OCTET_STRING, sec1);
+    let mut out = Vec::new();
+    write_tlv(&mut out, TAG_SEQUENCE, &private_key_info);
+    Ok(out)
+}
+
+/// Convert a big-endian unsigned integer to a decimal string
+fn to_decimal(bytes: &[u8]) -> String {
+    let mut bytes: Vec<u8> = bytes.iter().copied().skip_while(|b| *b == 0).collect();
+    let mut digits = Vec::new();
+    while !bytes.is_empty() {
+        let mut remainder = 0u32;
+        for byte in bytes.iter_mut() {
+            let value = (remainder << 8) | *byte as u32;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+        let leading_zeros = bytes.iter().take_while(|b| **b == 0).count();
+        bytes.drain(..leading_zeros);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("digits are ASCII")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_data(name: &str) -> Vec<u8> {
+        std::fs::read(format!("test-data/rolesanywhere/{name}")).expect("test data exists")
+    }
+
+    #[test]
+    fn decimal_serial_numbers() {
+        assert_eq!("0", to_decimal(&[]));
+        assert_eq!("0", to_decimal(&[0]));
+        assert_eq!("42", to_decimal(&[42]));
+        assert_eq!("256", to_decimal(&[0, 1, 0]));
+        assert_eq!(
+            "4759477275222530853130",
+            to_decimal(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+        );
+    }
+
+    #[test]
+    fn load_certificates_from_pem_and_der() {
+        let rsa = load_certificates(&test_data("rsa-cert.pem")).unwrap();
+        assert_eq!(1, rsa.len());
+        assert_eq!("4759477275222530853130", serial_number(&rsa[0]).unwrap());
+
+        let ec_pem = load_certificates(&test_data("ec-cert.pem")).unwrap();
+        let ec_der = load_certificates(&test_data("ec-cert.der")).unwrap();
+        assert_eq!(ec_pem, ec_der);
+        assert_eq!("42", serial_number(&ec_der[0]).unwrap());
+
+        let err = load_certificates(&test_data("rsa-key.pem")).unwrap_err();
+        assert!(matches!(err, X509Error::MissingPemBlock { .. }), "{err}");
+    }
+
+    #[test]
+    fn load_private_keys() {
+        for (name, algorithm) in [
+            ("rsa-key.pem", "AWS4-X509-RSA-SHA256"),
+            ("rsa-key-pkcs8.pem", "AWS4-X509-RSA-SHA256"),
+            ("ec-key.pem", "AWS4-X509-ECDSA-SHA256"),
+            ("ec-key-pkcs8.der", "AWS4-X509-ECDSA-SHA256"),
+        ] {
+            let key = SigningKey::from_pem_or_der(&test_data(name))
+                .unwrap_or_else(|err| panic!("{name}: {err}"));
+            assert_eq!(algorithm, key.algorithm(), "{name}");
+            assert!(!key.sign(b"message").unwrap().is_empty());
+        }
+        let err = SigningKey::from_pem_or_der(&test_data("ca.pem")).unwrap_err();
+        assert!(matches!(err, X509Error::MissingPemBlock { .. }), "{err}");
+    }
+
+    #[test]
+    fn rsa_signatures_verify() {
+        use ring::signature::{KeyPair, UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256};
+        let SigningKey::Rsa(key) = SigningKey::from_pem_or_der(&test_data("rsa-key.pem")).unwrap()
+        else {
+            panic!("expected an RSA key")
+        };
+        let public_key = key.public_key().as_ref().to_vec();
+        let signature = SigningKey::Rsa(key).sign(b"message").unwrap();
+        UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, public_key)
+            .verify(b"message", &signature)
+            .expect("valid signature");
+    }
+}
@@ -287,5 +287,6 @@ async fn load_sso_credentials(
         credentials.session_token,
         Some(expiration),
         "SSO",
-    ))
+    )
+    .with_account_id(&sso_provider_config.account_id))
 }
@@ -8,4 +8,5 @@
 pub use assume_role::{AssumeRoleProvider, AssumeRoleProviderBuilder};
 
 mod assume_role;
+pub mod mfa;
 pub(crate) mod util;
@@ -5,13 +5,15 @@
 
 //! Assume credentials for a role through the AWS Security Token Service (STS).
 
+use super::mfa::{MfaSession, MfaTokenProvider};
 use aws_credential_types::provider::{
     self, error::CredentialsError, future, ProvideCredentials, SharedCredentialsProvider,
 };
 use aws_sdk_sts::operation::assume_role::builders::AssumeRoleFluentBuilder;
 use aws_sdk_sts::operation::assume_role::AssumeRoleError;
-use aws_sdk_sts::types::PolicyDescriptorType;
+use aws_sdk_sts::types::{PolicyDescriptorType, Tag};
 use aws_sdk_sts::Client as StsClient;
+use aws_smithy_async::time::SharedTimeSource;
 use aws_smithy_runtime::client::identity::IdentityCache;
 use aws_smithy_runtime_api::client::result::SdkError;
 use aws_smithy_types::error::display::DisplayErrorContext;
@@ -74,6 +76,8 @@ pub struct AssumeRoleProvider {
 #[derive(Debug)]
 struct Inner {
     fluent_builder: AssumeRoleFluentBuilder,
+    mfa: Option<MfaSession>,
+    time_source: SharedTimeSource,
 }
 
 impl AssumeRoleProvider {
@@ -100,7 +104,12 @@ pub struct AssumeRoleProviderBuilder {
     session_length: Option<Duration>,
     policy: Option<String>,
     policy_arns: Option<Vec<PolicyDescriptorType>>,
+    tags: Option<Vec<Tag>>,
+    transitive_tag_keys: Option<Vec<String>>,
+    source_identity: Option<String>,
     region_override: Option<Region>,
+    mfa_serial: Option<String>,
+    mfa_token_provider: Option<MfaTokenProvider>,
     sdk_config: Option<SdkConfig>,
 }
 
@@ -120,8 +129,13 @@ impl AssumeRoleProviderBuilder {
             session_length: None,
             policy: None,
             policy_arns: None,
+            tags: None,
+            transitive_tag_keys: None,
+            source_identity: None,
             sdk_config: None,
             region_override: None,
+            mfa_serial: None,
+            mfa_token_provider: None,
         }
     }
 
@@ -171,6 +185,49 @@ impl AssumeRoleProviderBuilder {
         self
     }
 
+    /// Add a session tag to the role session.
+    ///
+    /// Session tags are passed as principal tags to the assumed role session, which allows them
+    /// to be used in attribute-based access control (ABAC) policies. Tags with the same key as
+    /// a tag on the role take precedence over it.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [tags](aws_sdk_sts::operation::assume_role::builders::AssumeRoleInputBuilder::tags)
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let tag = Tag::builder()
+            .key(key)
+            .value(value)
+            .build()
+            .expect("key and value are set");
+        self.tags.get_or_insert_with(Vec::new).push(tag);
+        self
+    }
+
+    /// Set the keys of the session tags that persist when this role session is used to assume
+    /// another role.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [transitive_tag_keys](aws_sdk_sts::operation::assume_role::builders::AssumeRoleInputBuilder::transitive_tag_keys)
+    pub fn transitive_tag_keys(mut self, keys: Vec<String>) -> Self {
+        self.transitive_tag_keys = Some(keys);
+        self
+    }
+
+    /// Set the source identity of the role session.
+    ///
+    /// The source identity is recorded in AWS CloudTrail, and persists across role chaining, so
+    /// that actions taken with the role can be attributed to the person who started the session.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [source_identity](aws_sdk_sts::operation::assume_role::builders::AssumeRoleInputBuilder::source_identity)
+    pub fn source_identity(mut self, source_identity: impl Into<String>) -> Self {
+        self.source_identity = Some(source_identity.into());
+        self
+    }
+
     /// Set the expiration time of the role session.
     ///
     /// When unset, this value defaults to 1 hour.
@@ -188,6 +245,25 @@ impl AssumeRoleProviderBuilder {
         self
     }
 
+    /// Set the serial number of the MFA device required to assume the role.
+    ///
+    /// This is either the ARN of a virtual MFA device, like
+    /// `arn:aws:iam::123456789012:mfa/user`, or the serial number of a hardware device. A token
+    /// code for the device is requested from the [`mfa_token_provider`](Self::mfa_token_provider)
+    /// whenever a new session is needed, and the session is cached until it is about to expire.
+    pub fn mfa_serial(mut self, serial_number: impl Into<String>) -> Self {
+        self.mfa_serial = Some(serial_number.into());
+        self
+    }
+
+    /// Set the source of token codes for the MFA device set with [`mfa_serial`](Self::mfa_serial).
+    ///
+    /// Loading credentials fails if an MFA serial number is set without a token provider.
+    pub fn mfa_token_provider(mut self, provider: MfaTokenProvider) -> Self {
+        self.mfa_token_provider = Some(provider);
+        self
+    }
+
     /// Set the region to assume the role in.
     ///
     /// This dictates which STS endpoint the AssumeRole action is invoked on. This will override
@@ -255,10 +331,19 @@ impl AssumeRoleProviderBuilder {
             .set_role_session_name(Some(session_name))
             .set_policy(self.policy)
             .set_policy_arns(self.policy_arns)
+            .set_tags(self.tags)
+            .set_transitive_tag_keys(self.transitive_tag_keys)
+            .set_source_identity(self.source_identity)
             .set_duration_seconds(self.session_length.map(|dur| dur.as_secs() as i32));
 
         AssumeRoleProvider {
-            inner: Inner { fluent_builder },
+            inner: Inner {
+                fluent_builder,
+                mfa: self
+                    .mfa_serial
+                    .map(|serial| MfaSession::new(serial, self.mfa_token_provider)),
+                time_source,
+            },
         }
     }
 
@@ -282,16 +367,37 @@ impl AssumeRoleProviderBuilder {
 
 impl Inner {
     async fn credentials(&self) -> provider::Result {
+        match &self.mfa {
+            Some(mfa) => {
+                mfa.credentials(&self.time_source, |serial_number, token_code| {
+                    self.assume_role(
+                        self.fluent_builder
+                            .clone()
+                            .serial_number(serial_number)
+                            .token_code(token_code),
+                    )
+                })
+                .await
+            }
+            None => self.assume_role(self.fluent_builder.clone()).await,
+        }
+    }
+
+    async fn assume_role(&self, fluent_builder: AssumeRoleFluentBuilder) -> provider::Result {
         tracing::debug!("retrieving assumed credentials");
 
-        let assumed = self.fluent_builder.clone().send().in_current_span().await;
+        let assumed = fluent_builder.send().in_current_span().await;
         match assumed {
             Ok(assumed) => {
                 tracing::debug!(
                     access_key_id = ?assumed.credentials.as_ref().map(|c| &c.access_key_id),
                     "obtained assumed credentials"
                 );
-                super::util::into_credentials(assumed.credentials, "AssumeRoleProvider")
+                super::util::into_credentials(
+                    assumed.credentials,
+                    assumed.assumed_role_user,
+                    "AssumeRoleProvider",
+                )
             }
             Err(SdkError::ServiceError(ref context))
                 if matches!(
@@ -328,6 +434,7 @@ impl ProvideCredentials for AssumeRoleProvider {
 
 #[cfg(test)]
 mod test {
+    use crate::sts::mfa::MfaTokenProvider;
     use crate::sts::AssumeRoleProvider;
     use aws_credential_types::credential_fn::provide_credentials_fn;
     use aws_credential_types::provider::{ProvideCredentials, SharedCredentialsProvider};
@@ -374,6 +481,99 @@ mod test {
         assert_eq!(req.uri(), "https://sts.us-east-1.amazonaws.com/");
     }
 
+    fn assume_role_response() -> http::Response<SdkBody> {
+        http::Response::builder()
+            .status(200)
+            .body(SdkBody::from(
+                "<AssumeRoleResponse xmlns=\"https://sts.amazonaws.com/doc/2011-06-15/\">\n  <AssumeRoleResult>\n    <Credentials>\n      <AccessKeyId>ASIARCORRECT</AccessKeyId>\n      <SecretAccessKey>secretkeycorrect</SecretAccessKey>\n      <SessionToken>tokencorrect</SessionToken>\n      <Expiration>2009-02-13T23:31:30Z</Expiration>\n    </Credentials>\n  </AssumeRoleResult>\n</AssumeRoleResponse>\n",
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn sends_mfa_serial_and_token_code() {
+        let (http_client, request) = capture_request(Some(assume_role_response()));
+        let sdk_config = SdkConfig::builder()
+            .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+            .time_source(StaticTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1234567890 - 120),
+            ))
+            .http_client(http_client)
+            .region(Region::from_static("us-east-1"))
+            .behavior_version(crate::BehaviorVersion::latest())
+            .build();
+        let provider = AssumeRoleProvider::builder("myrole")
+            .configure(&sdk_config)
+            .mfa_serial("arn:aws:iam::123456789012:mfa/user")
+            .mfa_token_provider(MfaTokenProvider::from_fn(|serial_number| async move {
+                assert_eq!("arn:aws:iam::123456789012:mfa/user", serial_number);
+                Ok("123456".to_string())
+            }))
+            .build_from_provider(provide_credentials_fn(|| async {
+                Ok(Credentials::for_tests())
+            }))
+            .await;
+        let creds = provider
+            .provide_credentials()
+            .await
+            .expect("assumed the role");
+        assert_eq!("ASIARCORRECT", creds.access_key_id());
+        let req = request.expect_request();
+        let str_body = std::str::from_utf8(req.body().bytes().unwrap()).unwrap();
+        assert!(
+            str_body.contains("SerialNumber=arn%3Aaws%3Aiam%3A%3A123456789012%3Amfa%2Fuser"),
+            "{}",
+            str_body
+        );
+        assert!(str_body.contains("TokenCode=123456"), "{}", str_body);
+    }
+
+    #[tokio::test]
+    async fn sends_session_tags_and_source_identity() {
+        let (http_client, request) = capture_request(Some(assume_role_response()));
+        let sdk_config = SdkConfig::builder()
+            .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+            .time_source(StaticTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1234567890 - 120),
+            ))
+            .http_client(http_client)
+            .region(Region::from_static("us-east-1"))
+            .behavior_version(crate::BehaviorVersion::latest())
+            .build();
+        let provider = AssumeRoleProvider::builder("myrole")
+            .configure(&sdk_config)
+            .tag("Project", "blue")
+            .tag("CostCenter", "1234")
+            .transitive_tag_keys(vec!["Project".into()])
+            .source_identity("alice")
+            .build_from_provider(provide_credentials_fn(|| async {
+                Ok(Credentials::for_tests())
+            }))
+            .await;
+        let creds = provider
+            .provide_credentials()
+            .await
+            .expect("assumed the role");
+        assert_eq!("ASIARCORRECT", creds.access_key_id());
+        let req = request.expect_request();
+        let str_body = std::str::from_utf8(req.body().bytes().unwrap()).unwrap();
+        for expected in [
+            "Tags.member.1.Key=Project",
+            "Tags.member.1.Value=blue",
+            "Tags.member.2.Key=CostCenter",
+            "Tags.member.2.Value=1234",
+            "TransitiveTagKeys.member.1=Project",
+            "SourceIdentity=alice",
+        ] {
+            assert!(
+                str_body.contains(expected),
+                "{} not in {}",
+                expected,
+                str_body
+            );
+        }
+    }
+
     #[tokio::test]
     async fn loads_region_from_sdk_config() {
         let (http_client, request) = capture_request(None);
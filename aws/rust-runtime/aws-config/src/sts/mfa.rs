@@ -0,0 +1,456 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Multi-factor authentication (MFA) token providers for assuming roles.
+//!
+//! Roles whose trust policy requires MFA can only be assumed by passing the serial number of an
+//! MFA device along with the current token code from that device. An [`MfaTokenProvider`] is asked
+//! for the token code whenever a new role session is needed. Since token codes can't be reused,
+//! sessions assumed with MFA are cached until they are about to expire.
+//!
+//! # Examples
+//!
+//! Assume a role with a token code entered by the user:
+//! ```no_run
+//! use aws_config::sts::mfa::MfaTokenProvider;
+//! use aws_config::sts::AssumeRoleProvider;
+//! # async fn docs() {
+//! let provider = AssumeRoleProvider::builder("arn:aws:iam::123456789012:role/demo")
+//!     .mfa_serial("arn:aws:iam::123456789012:mfa/user")
+//!     .mfa_token_provider(MfaTokenProvider::stdin_prompt())
+//!     .build()
+//!     .await;
+//! # }
+//! ```
+
+use aws_credential_types::provider::{self, error::CredentialsError};
+use aws_credential_types::Credentials;
+use aws_smithy_async::time::SharedTimeSource;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Cached sessions are discarded once they are this close to expiring.
+const SESSION_EXPIRY_BUFFER: Duration = Duration::from_secs(5 * 60);
+
+/// Future types returned by MFA token providers
+pub mod future {
+    use aws_credential_types::provider::error::CredentialsError;
+    use aws_smithy_async::future::now_or_later::NowOrLater;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+    type TokenResult = Result<String, CredentialsError>;
+
+    /// Future new-type that `ProvideMfaToken::provide_mfa_token` must return.
+    #[derive(Debug)]
+    pub struct ProvideMfaToken<'a>(NowOrLater<TokenResult, BoxFuture<'a, TokenResult>>);
+
+    impl<'a> ProvideMfaToken<'a> {
+        /// Creates a `ProvideMfaToken` struct from a future.
+        pub fn new(future: impl Future<Output = TokenResult> + Send + 'a) -> Self {
+            ProvideMfaToken(NowOrLater::new(Box::pin(future)))
+        }
+
+        /// Creates a `ProvideMfaToken` struct from a resolved token code.
+        pub fn ready(token_code: TokenResult) -> Self {
+            ProvideMfaToken(NowOrLater::ready(token_code))
+        }
+    }
+
+    impl Future for ProvideMfaToken<'_> {
+        type Output = TokenResult;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Pin::new(&mut self.0).poll(cx)
+        }
+    }
+}
+
+/// Provides token codes for MFA devices
+pub trait ProvideMfaToken: fmt::Debug + Send + Sync {
+    /// Returns the current token code of the MFA device with the given serial number.
+    ///
+    /// The serial number is either the ARN of a virtual MFA device, or the serial number of a
+    /// hardware device.
+    fn provide_mfa_token<'a>(&'a self, serial_number: &'a str) -> future::ProvideMfaToken<'a>;
+}
+
+/// A pluggable source of MFA token codes
+///
+/// Use [`MfaTokenProvider::new`] for a custom [`ProvideMfaToken`] implementation, or one of the
+/// built-in sources:
+/// - [`from_fn`](MfaTokenProvider::from_fn) calls an async function
+/// - [`stdin_prompt`](MfaTokenProvider::stdin_prompt) prompts the user in the terminal
+/// - [`totp`](MfaTokenProvider::totp) generates codes from a virtual MFA device secret, which is
+///   mostly useful for testing
+#[derive(Clone, Debug)]
+pub struct MfaTokenProvider(Arc<dyn ProvideMfaToken>);
+
+impl MfaTokenProvider {
+    /// Create a new `MfaTokenProvider` from the given implementation.
+    pub fn new(provider: impl ProvideMfaToken + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+
+    /// Create an `MfaTokenProvider` that calls the given function with the serial number of the
+    /// MFA device whenever a token code is needed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_config::sts::mfa::MfaTokenProvider;
+    /// # async fn read_token_from_hardware(_serial: &str) -> String { todo!() }
+    /// let provider = MfaTokenProvider::from_fn(|serial_number| async move {
+    ///     Ok(read_token_from_hardware(&serial_number).await)
+    /// });
+    /// ```
+    pub fn from_fn<F, Fut>(f: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, CredentialsError>> + Send + 'static,
+    {
+        Self::new(FnMfaTokenProvider(f))
+    }
+
+    /// Create an `MfaTokenProvider` that prompts for a token code on `stderr` and reads it from
+    /// `stdin`.
+    #[cfg(feature = "rt-tokio")]
+    pub fn stdin_prompt() -> Self {
+        Self::new(StdinPrompt)
+    }
+
+    /// Create an `MfaTokenProvider` that generates time-based one-time passwords (RFC 6238) from
+    /// the base32-encoded secret of a virtual MFA device.
+    ///
+    /// This is intended for tests and automation. Storing the secret next to the credentials it
+    /// protects defeats the purpose of MFA.
+    ///
+    /// Returns an error if the secret isn't valid base32.
+    #[cfg(feature = "mfa-totp")]
+    pub fn totp(secret: &str) -> Result<Self, CredentialsError> {
+        Self::totp_with_time_source(secret, SharedTimeSource::default())
+    }
+
+    /// Create an `MfaTokenProvider` that generates time-based one-time passwords using the given
+    /// time source.
+    ///
+    /// See [`totp`](Self::totp).
+    #[cfg(feature = "mfa-totp")]
+    pub fn totp_with_time_source(
+        secret: &str,
+        time_source: SharedTimeSource,
+    ) -> Result<Self, CredentialsError> {
+        let key = totp::decode_base32(secret).ok_or_else(|| {
+            CredentialsError::invalid_configuration("the TOTP secret is not valid base32")
+        })?;
+        Ok(Self::new(totp::Totp { key, time_source }))
+    }
+}
+
+impl ProvideMfaToken for MfaTokenProvider {
+    fn provide_mfa_token<'a>(&'a self, serial_number: &'a str) -> future::ProvideMfaToken<'a> {
+        self.0.provide_mfa_token(serial_number)
+    }
+}
+
+struct FnMfaTokenProvider<F>(F);
+
+impl<F> fmt::Debug for FnMfaTokenProvider<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FnMfaTokenProvider")
+    }
+}
+
+impl<F, Fut> ProvideMfaToken for FnMfaTokenProvider<F>
+where
+    F: Fn(String) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<String, CredentialsError>> + Send + 'static,
+{
+    fn provide_mfa_token<'a>(&'a self, serial_number: &'a str) -> future::ProvideMfaToken<'a> {
+        future::ProvideMfaToken::new((self.0)(serial_number.to_string()))
+    }
+}
+
+#[cfg(feature = "rt-tokio")]
+#[derive(Debug)]
+struct StdinPrompt;
+
+#[cfg(feature = "rt-tokio")]
+impl ProvideMfaToken for StdinPrompt {
+    fn provide_mfa_token<'a>(&'a self, serial_number: &'a str) -> future::ProvideMfaToken<'a> {
+        let prompt = format!("Enter MFA code for {}: ", serial_number);
+        future::ProvideMfaToken::new(async move {
+            tokio::task::spawn_blocking(move || {
+                use std::io::{BufRead, Write};
+                let mut stderr = std::io::stderr();
+                stderr.write_all(prompt.as_bytes())?;
+                stderr.flush()?;
+                let mut token_code = String::new();
+                std::io::stdin().lock().read_line(&mut token_code)?;
+                Ok::<_, std::io::Error>(token_code.trim().to_string())
+            })
+            .await
+            .map_err(CredentialsError::unhandled)?
+            .map_err(|err| {
+                CredentialsError::provider_error(format!("failed to read MFA token code: {}", err))
+            })
+        })
+    }
+}
+
+#[cfg(feature = "mfa-totp")]
+mod totp {
+    use super::{future, ProvideMfaToken};
+    use aws_smithy_async::time::SharedTimeSource;
+    use std::fmt;
+    use std::time::UNIX_EPOCH;
+
+    const TIME_STEP_SECS: u64 = 30;
+    const DIGITS: u32 = 6;
+
+    pub(super) struct Totp {
+        pub(super) key: Vec<u8>,
+        pub(super) time_source: SharedTimeSource,
+    }
+
+    impl fmt::Debug for Totp {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Totp")
+                .field("key", &"** redacted **")
+                .finish()
+        }
+    }
+
+    impl ProvideMfaToken for Totp {
+        fn provide_mfa_token<'a>(&'a self, _serial_number: &'a str) -> future::ProvideMfaToken<'a> {
+            let secs = self
+                .time_source
+                .now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            future::ProvideMfaToken::ready(Ok(code(&self.key, secs / TIME_STEP_SECS)))
+        }
+    }
+
+    /// Computes the HOTP value (RFC 4226) for the given counter
+    pub(super) fn code(key: &[u8], counter: u64) -> String {
+        let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key);
+        let hash = ring::hmac::sign(&key, &counter.to_be_bytes());
+        let hash = hash.as_ref();
+        let offset = (hash[hash.len() - 1] & 0xf) as usize;
+        let truncated = u32::from_be_bytes([
+            hash[offset],
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]) & 0x7fff_ffff;
+        format!(
+            "{:0width$}",
+            truncated % 10u32.pow(DIGITS),
+            width = DIGITS as usize
+        )
+    }
+
+    /// Decodes unpadded or padded base32 (RFC 4648), ignoring case and whitespace
+    pub(super) fn decode_base32(input: &str) -> Option<Vec<u8>> {
+        let mut output = Vec::with_capacity(input.len() * 5 / 8);
+        let (mut buffer, mut bits) = (0u32, 0u32);
+        for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+            let value = match c.to_ascii_uppercase() {
+                c @ 'A'..='Z' => c as u32 - 'A' as u32,
+                c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+                _ => return None,
+            };
+            buffer = (buffer << 5) | value;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                output.push((buffer >> bits) as u8);
+                buffer &= (1 << bits) - 1;
+            }
+        }
+        Some(output)
+    }
+}
+
+/// A role session that requires MFA
+///
+/// Since each token code can only be used once, the assumed credentials are cached and reused
+/// until they are about to expire.
+#[derive(Debug)]
+pub(crate) struct MfaSession {
+    serial_number: String,
+    token_provider: Option<MfaTokenProvider>,
+    cached: Mutex<Option<Credentials>>,
+}
+
+impl MfaSession {
+    pub(crate) fn new(serial_number: String, token_provider: Option<MfaTokenProvider>) -> Self {
+        Self {
+            serial_number,
+            token_provider,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached session, or calls `assume_role` with the serial number and a fresh
+    /// token code to start a new one.
+    pub(crate) async fn credentials<F, Fut>(
+        &self,
+        time_source: &SharedTimeSource,
+        assume_role: F,
+    ) -> provider::Result
+    where
+        F: FnOnce(String, String) -> Fut,
+        Fut: Future<Output = provider::Result>,
+    {
+        // Held while the token is requested so that concurrent callers don't prompt again
+        let mut cached = self.cached.lock().await;
+        if let Some(credentials) = cached.as_ref() {
+            let still_valid = credentials.expiry().map_or(false, |expiry| {
+                expiry > time_source.now() + SESSION_EXPIRY_BUFFER
+            });
+            if still_valid {
+                tracing::debug!("using cached MFA session");
+                return Ok(credentials.clone());
+            }
+        }
+
+        let token_provider = self.token_provider.as_ref().ok_or_else(|| {
+            CredentialsError::invalid_configuration(format!(
+                "assuming this role requires a token code for the MFA device `{}`, \
+                but no MFA token provider was configured",
+                self.serial_number
+            ))
+        })?;
+        tracing::debug!(serial_number = %self.serial_number, "requesting MFA token code");
+        let token_code = token_provider
+            .provide_mfa_token(&self.serial_number)
+            .await?;
+        let credentials = assume_role(self.serial_number.clone(), token_code).await?;
+        *cached = Some(credentials.clone());
+        Ok(credentials)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MfaSession, MfaTokenProvider};
+    use aws_credential_types::provider::error::CredentialsError;
+    use aws_credential_types::Credentials;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_async::time::SharedTimeSource;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn session_expiring_at(secs: u64) -> Credentials {
+        Credentials::new(
+            "ASIARTESTID",
+            "TESTSECRETKEY",
+            Some("TESTSESSIONTOKEN".into()),
+            Some(UNIX_EPOCH + Duration::from_secs(secs)),
+            "test",
+        )
+    }
+
+    #[tokio::test]
+    async fn session_is_cached_until_it_is_about_to_expire() {
+        let time_source = ManualTimeSource::new(UNIX_EPOCH + Duration::from_secs(1000));
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let session = MfaSession::new(
+            "arn:aws:iam::123456789012:mfa/user".into(),
+            Some(MfaTokenProvider::from_fn({
+                let requested = requested.clone();
+                move |serial_number| {
+                    requested.lock().unwrap().push(serial_number);
+                    async { Ok("123456".to_string()) }
+                }
+            })),
+        );
+        let assumed = AtomicUsize::new(0);
+        let assume_role = |serial_number: String, token_code: String| {
+            assert_eq!("arn:aws:iam::123456789012:mfa/user", serial_number);
+            assert_eq!("123456", token_code);
+            assumed.fetch_add(1, Ordering::SeqCst);
+            async { Ok::<_, CredentialsError>(session_expiring_at(1000 + 3600)) }
+        };
+
+        let time = SharedTimeSource::new(time_source.clone());
+        session.credentials(&time, assume_role).await.unwrap();
+        time_source.advance(Duration::from_secs(3000));
+        session.credentials(&time, assume_role).await.unwrap();
+        assert_eq!(1, assumed.load(Ordering::SeqCst));
+
+        // within five minutes of expiring, a new session is started
+        time_source.advance(Duration::from_secs(400));
+        session.credentials(&time, assume_role).await.unwrap();
+        assert_eq!(2, assumed.load(Ordering::SeqCst));
+        assert_eq!(2, requested.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn missing_token_provider_is_a_configuration_error() {
+        let session = MfaSession::new("arn:aws:iam::123456789012:mfa/user".into(), None);
+        let err = session
+            .credentials(&SharedTimeSource::default(), |_, _| async {
+                Err(CredentialsError::unhandled("the role must not be assumed"))
+            })
+            .await
+            .expect_err("no token provider");
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[cfg(feature = "mfa-totp")]
+    #[test]
+    fn totp_matches_rfc_6238_test_vectors() {
+        use super::totp::{code, decode_base32};
+
+        // The RFC's SHA-1 secret is the ASCII string "12345678901234567890"
+        let key = decode_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap();
+        assert_eq!(b"12345678901234567890".to_vec(), key);
+        // The RFC lists 8 digit codes; 6 digit codes are their last six digits
+        for (time, expected) in [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            assert_eq!(expected, code(&key, time / 30), "time = {}", time);
+        }
+        assert_eq!(None, decode_base32("not base32!"));
+    }
+
+    #[cfg(feature = "mfa-totp")]
+    #[tokio::test]
+    async fn totp_provider_uses_the_time_source() {
+        use super::ProvideMfaToken;
+
+        let provider = MfaTokenProvider::totp_with_time_source(
+            "gezd gnbv gy3t qojq gezd gnbv gy3t qojq",
+            SharedTimeSource::new(ManualTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1234567890),
+            )),
+        )
+        .unwrap();
+        assert_eq!(
+            "005924",
+            provider
+                .provide_mfa_token("arn:aws:iam::123456789012:mfa/user")
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -4,14 +4,18 @@
  */
 
 use aws_credential_types::provider::{self, error::CredentialsError};
-use aws_credential_types::Credentials as AwsCredentials;
-use aws_sdk_sts::types::Credentials as StsCredentials;
+use aws_credential_types::{Credentials as AwsCredentials, ProvenanceStep};
+use aws_sdk_sts::types::{AssumedRoleUser, Credentials as StsCredentials};
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Convert STS credentials to aws_auth::Credentials
+///
+/// When STS also returns the assumed role user, its ARN is recorded on the provenance of the
+/// credentials and the account ID is taken from it.
 pub(crate) fn into_credentials(
     sts_credentials: Option<StsCredentials>,
+    assumed_role_user: Option<AssumedRoleUser>,
     provider_name: &'static str,
 ) -> provider::Result {
     let sts_credentials = sts_credentials
@@ -21,13 +25,33 @@ pub(crate) fn into_credentials(
             "credential expiration time cannot be represented by a SystemTime",
         )
     })?;
-    Ok(AwsCredentials::new(
+    let mut credentials = AwsCredentials::new(
         sts_credentials.access_key_id,
         sts_credentials.secret_access_key,
         Some(sts_credentials.session_token),
         Some(expiration),
         provider_name,
-    ))
+    );
+    if let Some(assumed_role_user) = assumed_role_user {
+        if let Some(account_id) = account_id_from_arn(&assumed_role_user.arn) {
+            credentials = credentials.with_account_id(account_id);
+        }
+        if let Some(step) = credentials.provenance_mut().first_mut() {
+            *step = ProvenanceStep::new(provider_name).with_detail(assumed_role_user.arn);
+        }
+    }
+    Ok(credentials)
+}
+
+/// Extract the account ID from an ARN such as `arn:aws:sts::123456789012:assumed-role/role/session`
+pub(crate) fn account_id_from_arn(arn: &str) -> Option<&str> {
+    let mut parts = arn.splitn(6, ':');
+    if parts.next() != Some("arn") {
+        return None;
+    }
+    parts.nth(3).filter(|account_id| {
+        !account_id.is_empty() && account_id.bytes().all(|b| b.is_ascii_digit())
+    })
 }
 
 /// Create a default STS session name
@@ -39,3 +63,26 @@ pub(crate) fn default_session_name(base: &str, ts: SystemTime) -> String {
     let now = ts.duration_since(UNIX_EPOCH).expect("post epoch");
     format!("{}-{}", base, now.as_millis())
 }
+
+#[cfg(test)]
+mod test {
+    use super::account_id_from_arn;
+
+    #[test]
+    fn account_id_is_parsed_from_arn() {
+        assert_eq!(
+            Some("123456789012"),
+            account_id_from_arn("arn:aws:sts::123456789012:assumed-role/role/session")
+        );
+        assert_eq!(
+            Some("123456789012"),
+            account_id_from_arn("arn:aws-cn:sts::123456789012:assumed-role/a:b/session")
+        );
+        assert_eq!(None, account_id_from_arn("arn:aws:s3:::bucket"));
+        assert_eq!(None, account_id_from_arn("not-an-arn"));
+        assert_eq!(
+            None,
+            account_id_from_arn("arn:aws:sts::abc:assumed-role/role")
+        );
+    }
+}
@@ -273,7 +273,7 @@ async fn load_credentials(
             tracing::warn!(error = %DisplayErrorContext(&sdk_error), "STS returned an error assuming web identity role");
             CredentialsError::provider_error(sdk_error)
         })?;
-    sts::util::into_credentials(resp.credentials, "WebIdentityToken")
+    sts::util::into_credentials(resp.credentials, resp.assumed_role_user, "WebIdentityToken")
 }
 
 #[cfg(test)]
@@ -4,6 +4,7 @@
  */
 
 use aws_smithy_types::date_time::Format;
+use std::borrow::Cow;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
@@ -22,7 +23,7 @@ use aws_smithy_runtime_api::client::identity::Identity;
 #[derive(Clone, Eq, PartialEq)]
 pub struct Credentials(Arc<Inner>);
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq)]
 struct Inner {
     access_key_id: Zeroizing<String>,
     secret_access_key: Zeroizing<String>,
@@ -37,7 +38,71 @@ struct Inner {
     /// If these credentials never expire, this value will be set to `None`
     expires_after: Option<SystemTime>,
 
+    /// The ID of the AWS account the credentials belong to, if known
+    account_id: Option<String>,
+
     provider_name: &'static str,
+
+    provenance: Vec<ProvenanceStep>,
+}
+
+// Credentials resolved through different providers are still the same credentials, so
+// provenance doesn't take part in comparisons.
+impl PartialEq for Inner {
+    fn eq(&self, other: &Self) -> bool {
+        self.access_key_id == other.access_key_id
+            && self.secret_access_key == other.secret_access_key
+            && self.session_token == other.session_token
+            && self.expires_after == other.expires_after
+            && self.account_id == other.account_id
+            && self.provider_name == other.provider_name
+    }
+}
+
+/// A step in the resolution of [`Credentials`].
+///
+/// See [`Credentials::provenance`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvenanceStep {
+    provider_name: Cow<'static, str>,
+    detail: Option<String>,
+}
+
+impl ProvenanceStep {
+    /// Creates a step for the provider, or the step of a provider chain, with the given name.
+    pub fn new(provider_name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            provider_name: provider_name.into(),
+            detail: None,
+        }
+    }
+
+    /// Adds a human-readable detail to this step, such as the profile or role it used.
+    ///
+    /// The detail must never contain secrets, since it's meant to be logged.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Returns the name of the provider, or of the provider chain step.
+    pub fn provider_name(&self) -> &str {
+        &self.provider_name
+    }
+
+    /// Returns the detail of this step, if any.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+}
+
+impl fmt::Display for ProvenanceStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{} ({})", self.provider_name, detail),
+            None => write!(f, "{}", self.provider_name),
+        }
+    }
 }
 
 impl Debug for Credentials {
@@ -47,6 +112,9 @@ impl Debug for Credentials {
             .field("provider_name", &self.0.provider_name)
             .field("access_key_id", &self.0.access_key_id.as_str())
             .field("secret_access_key", &"** redacted **");
+        if let Some(account_id) = self.account_id() {
+            creds.field("account_id", &account_id);
+        }
         if let Some(expiry) = self.expiry() {
             if let Some(formatted) = expiry.duration_since(UNIX_EPOCH).ok().and_then(|dur| {
                 aws_smithy_types::DateTime::from_secs(dur.as_secs() as _)
@@ -84,7 +152,9 @@ impl Credentials {
             secret_access_key: Zeroizing::new(secret_access_key.into()),
             session_token: Zeroizing::new(session_token),
             expires_after,
+            account_id: None,
             provider_name,
+            provenance: vec![ProvenanceStep::new(provider_name)],
         }))
     }
 
@@ -168,6 +238,38 @@ impl Credentials {
     pub fn session_token(&self) -> Option<&str> {
         self.0.session_token.as_deref()
     }
+
+    /// Returns the ID of the AWS account the credentials belong to, if known.
+    pub fn account_id(&self) -> Option<&str> {
+        self.0.account_id.as_deref()
+    }
+
+    /// Sets the ID of the AWS account the credentials belong to.
+    pub fn with_account_id(mut self, account_id: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.0).account_id = Some(account_id.into());
+        self
+    }
+
+    /// Returns the steps that were taken to resolve these credentials.
+    ///
+    /// The steps start with the outermost one, such as the step of the default credentials
+    /// provider chain that produced the credentials, and end with the provider that
+    /// originally created them. For example, credentials resolved by the default credentials
+    /// provider chain from a profile that assumes a role with SSO credentials have the steps
+    /// `Profile`, `AssumeRoleProvider (arn:aws:sts::...)` and `SSO`.
+    ///
+    /// Credentials start out with a single step for the provider that created them.
+    pub fn provenance(&self) -> &[ProvenanceStep] {
+        &self.0.provenance
+    }
+
+    /// Returns a mutable reference to the steps that were taken to resolve these credentials.
+    ///
+    /// Credentials providers that return credentials resolved by other providers insert their
+    /// own step at the start. See [`Credentials::provenance`].
+    pub fn provenance_mut(&mut self) -> &mut Vec<ProvenanceStep> {
+        &mut Arc::make_mut(&mut self.0).provenance
+    }
 }
 
 #[cfg(feature = "test-util")]
@@ -198,13 +300,14 @@ impl Credentials {
 impl From<Credentials> for Identity {
     fn from(val: Credentials) -> Self {
         let expiry = val.expiry();
-        Identity::new(val, expiry)
+        let provider_name = val.0.provider_name;
+        Identity::new(val, expiry).with_provider_name(provider_name)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Credentials;
+    use crate::{Credentials, ProvenanceStep};
     use std::time::{Duration, UNIX_EPOCH};
 
     #[test]
@@ -221,4 +324,38 @@ mod test {
             r#"Credentials { provider_name: "debug tester", access_key_id: "akid", secret_access_key: "** redacted **", expires_after: "2009-02-13T23:31:30Z" }"#
         );
     }
+
+    #[test]
+    fn account_id_and_provenance() {
+        let mut creds = Credentials::new("akid", "secret", None, None, "AssumeRoleProvider")
+            .with_account_id("123456789012");
+        creds.provenance_mut().insert(
+            0,
+            ProvenanceStep::new("Profile").with_detail("profile: dev"),
+        );
+        assert_eq!(Some("123456789012"), creds.account_id());
+        assert_eq!(
+            vec!["Profile (profile: dev)", "AssumeRoleProvider"],
+            creds
+                .provenance()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            format!("{:?}", creds),
+            r#"Credentials { provider_name: "AssumeRoleProvider", access_key_id: "akid", secret_access_key: "** redacted **", account_id: "123456789012", expires_after: "never" }"#
+        );
+    }
+
+    #[test]
+    fn provenance_is_ignored_by_eq() {
+        let creds = Credentials::new("akid", "secret", None, None, "test");
+        let mut resolved = creds.clone();
+        resolved
+            .provenance_mut()
+            .insert(0, ProvenanceStep::new("Profile"));
+        assert_eq!(creds, resolved);
+        assert_ne!(creds, creds.clone().with_account_id("123456789012"));
+    }
 }
@@ -25,7 +25,7 @@ mod credentials_impl;
 pub mod provider;
 pub mod token_fn;
 
-pub use credentials_impl::Credentials;
+pub use credentials_impl::{Credentials, ProvenanceStep};
 
 /// AWS Access Token
 ///
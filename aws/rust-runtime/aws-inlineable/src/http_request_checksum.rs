@@ -7,9 +7,12 @@
 
 //! Interceptor for handling Smithy `@httpChecksum` request checksumming with AWS SigV4
 
-use aws_runtime::content_encoding::{AwsChunkedBody, AwsChunkedBodyOptions};
+use aws_runtime::content_encoding::{
+    AwsChunkedBody, AwsChunkedBodyOptions, DeferredChunkSigner, SignChunkedPayloads,
+};
 use aws_runtime::{auth::SigV4OperationSigningConfig, content_encoding::header_value::AWS_CHUNKED};
 use aws_sigv4::http_request::SignableBody;
+use aws_sigv4::SignatureVersion;
 use aws_smithy_checksums::ChecksumAlgorithm;
 use aws_smithy_checksums::{body::calculate, http::HttpChecksum};
 use aws_smithy_runtime_api::box_error::BoxError;
@@ -21,9 +24,12 @@ use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 use aws_smithy_types::body::SdkBody;
 use aws_smithy_types::config_bag::{ConfigBag, Layer, Storable, StoreReplace};
+use aws_smithy_types::endpoint::Endpoint;
 use aws_smithy_types::error::operation::BuildError;
+use aws_smithy_types::Document;
 use http::HeaderValue;
 use http_body::Body;
+use std::sync::Mutex;
 use std::{fmt, mem};
 
 /// Errors related to constructing checksum-validated HTTP requests
@@ -190,20 +196,57 @@ fn add_checksum_for_request_body(
         // Body is streaming: wrap the body so it will emit a checksum as a trailer.
         None => {
             tracing::debug!("applying {checksum_algorithm:?} of the request body as a trailer");
+            let mut chunk_signer = None;
             if let Some(mut signing_config) = cfg.load::<SigV4OperationSigningConfig>().cloned() {
-                signing_config.signing_options.payload_override =
-                    Some(SignableBody::StreamingUnsignedPayloadTrailer);
+                let sign_chunks = cfg.load::<SignChunkedPayloads>().map_or(false, |it| it.0);
+                signing_config.signing_options.payload_override = Some(if sign_chunks {
+                    SignableBody::StreamingSignedPayloadTrailer
+                } else {
+                    SignableBody::StreamingUnsignedPayloadTrailer
+                });
+                if sign_chunks {
+                    // This runs for every attempt, so each attempt's body gets a signer seeded
+                    // with that attempt's request signature.
+                    let (signer, sender) = DeferredChunkSigner::new(chunk_signature_version(cfg));
+                    cfg.interceptor_state().store_put(sender);
+                    chunk_signer = Some(signer);
+                }
                 cfg.interceptor_state().store_put(signing_config);
             }
-            wrap_streaming_request_body_in_checksum_calculating_body(request, checksum_algorithm)?;
+            wrap_streaming_request_body_in_checksum_calculating_body(
+                request,
+                checksum_algorithm,
+                chunk_signer,
+            )?;
         }
     }
     Ok(())
 }
 
+/// The signature version the request will be signed with, which the chunk signatures must match.
+///
+/// Auth schemes are selected after the body is encoded, so this follows the endpoint's preferred
+/// auth scheme. If the request ends up signed differently, signing fails.
+fn chunk_signature_version(cfg: &ConfigBag) -> SignatureVersion {
+    let preferred_scheme = cfg
+        .load::<Endpoint>()
+        .and_then(|endpoint| match endpoint.properties().get("authSchemes") {
+            Some(Document::Array(schemes)) => schemes.first(),
+            _ => None,
+        })
+        .and_then(Document::as_object)
+        .and_then(|scheme| scheme.get("name"))
+        .and_then(Document::as_string);
+    match preferred_scheme {
+        Some("sigv4a") => SignatureVersion::V4a,
+        _ => SignatureVersion::V4,
+    }
+}
+
 fn wrap_streaming_request_body_in_checksum_calculating_body(
     request: &mut HttpRequest,
     checksum_algorithm: ChecksumAlgorithm,
+    chunk_signer: Option<DeferredChunkSigner>,
 ) -> Result<(), BuildError> {
     let original_body_size = request
         .body()
@@ -213,13 +256,27 @@ fn wrap_streaming_request_body_in_checksum_calculating_body(
 
     let mut body = {
         let body = mem::replace(request.body_mut(), SdkBody::taken());
+        let signature_version = chunk_signer
+            .as_ref()
+            .map(DeferredChunkSigner::signature_version);
+        let chunk_signer = Mutex::new(chunk_signer);
 
         body.map(move |body| {
             let checksum = checksum_algorithm.into_impl();
             let trailer_len = HttpChecksum::size(checksum.as_ref());
             let body = calculate::ChecksumBody::new(body, checksum);
-            let aws_chunked_body_options =
+            let mut aws_chunked_body_options =
                 AwsChunkedBodyOptions::new(original_body_size, vec![trailer_len]);
+            if let Some(signature_version) = signature_version {
+                // Only the first body is signed for this attempt. Bodies rebuilt from it can't be
+                // sent without being signed again, so they get a signer that is never wired up.
+                let signer = chunk_signer
+                    .lock()
+                    .unwrap()
+                    .take()
+                    .unwrap_or_else(|| DeferredChunkSigner::new(signature_version).0);
+                aws_chunked_body_options = aws_chunked_body_options.with_chunk_signer(signer);
+            }
 
             let body = AwsChunkedBody::new(body, aws_chunked_body_options);
 
@@ -285,8 +342,12 @@ mod tests {
         assert!(request.body().try_clone().is_some());
 
         let checksum_algorithm: ChecksumAlgorithm = "crc32".parse().unwrap();
-        wrap_streaming_request_body_in_checksum_calculating_body(&mut request, checksum_algorithm)
-            .unwrap();
+        wrap_streaming_request_body_in_checksum_calculating_body(
+            &mut request,
+            checksum_algorithm,
+            None,
+        )
+        .unwrap();
 
         // ensure wrapped SdkBody is retryable
         let mut body = request.body().try_clone().expect("body is retryable");
@@ -331,8 +392,12 @@ mod tests {
         // ensure original SdkBody is retryable
         assert!(request.body().try_clone().is_some());
 
-        wrap_streaming_request_body_in_checksum_calculating_body(&mut request, checksum_algorithm)
-            .unwrap();
+        wrap_streaming_request_body_in_checksum_calculating_body(
+            &mut request,
+            checksum_algorithm,
+            None,
+        )
+        .unwrap();
 
         // ensure wrapped SdkBody is retryable
         let mut body = request.body().try_clone().expect("body is retryable");
@@ -349,4 +414,102 @@ mod tests {
             "expected {body} to end with '{expected}'"
         );
     }
+
+    #[tokio::test]
+    async fn test_signed_chunks_are_verifiable_for_every_attempt() {
+        use crate::http_request_checksum::add_checksum_for_request_body;
+        use aws_credential_types::Credentials;
+        use aws_runtime::auth::sigv4::SigV4Signer;
+        use aws_runtime::auth::{SigV4OperationSigningConfig, SigningOptions};
+        use aws_runtime::content_encoding::{
+            AwsChunkedDecodingBody, ChunkSignatureVerifier, SignChunkedPayloads,
+        };
+        use aws_sigv4::http_request::{
+            verify, SignableBody, SignableRequest, VerificationSettings,
+        };
+        use aws_smithy_async::time::StaticTimeSource;
+        use aws_smithy_runtime_api::client::auth::{AuthSchemeEndpointConfig, Sign};
+        use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+        use aws_smithy_types::config_bag::{ConfigBag, Layer};
+        use aws_types::region::SigningRegion;
+        use aws_types::SigningName;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let mut signing_options = SigningOptions::default();
+        signing_options.content_sha256_header = true;
+        let mut layer = Layer::new("test");
+        layer.store_put(SigV4OperationSigningConfig {
+            region: Some(SigningRegion::from_static("us-east-1")),
+            name: Some(SigningName::from_static("s3")),
+            signing_options,
+            ..Default::default()
+        });
+        layer.store_put(SignChunkedPayloads(true));
+        let mut cfg = ConfigBag::of_layers(vec![layer]);
+
+        let original: HttpRequest = http::Request::builder()
+            .method("PUT")
+            .uri("https://bucket.s3.us-east-1.amazonaws.com/key")
+            .body(SdkBody::retryable(|| {
+                SdkBody::from_body_0_4(SdkBody::from("Hello world"))
+            }))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let identity = Credentials::for_tests().into();
+
+        // Every attempt starts over from the original request, and is signed at a different time
+        let mut seed_signatures = Vec::new();
+        for attempt in 0..2 {
+            let now = UNIX_EPOCH + Duration::from_secs(1369353600 + attempt);
+            let mut request = original.try_clone().unwrap();
+            add_checksum_for_request_body(&mut request, ChecksumAlgorithm::Crc32, &mut cfg)
+                .unwrap();
+            let runtime_components = RuntimeComponentsBuilder::for_tests()
+                .with_time_source(Some(StaticTimeSource::new(now)))
+                .build()
+                .unwrap();
+            SigV4Signer::new()
+                .sign_http_request(
+                    &mut request,
+                    &identity,
+                    AuthSchemeEndpointConfig::empty(),
+                    &runtime_components,
+                    &cfg,
+                )
+                .unwrap();
+            assert_eq!(
+                Some("STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER"),
+                request.headers().get("x-amz-content-sha256")
+            );
+
+            let signable = SignableRequest::new(
+                request.method(),
+                request.uri(),
+                request.headers().iter(),
+                SignableBody::StreamingSignedPayloadTrailer,
+            )
+            .unwrap();
+            let verified = verify(
+                &signable,
+                &VerificationSettings::default(),
+                |_| Some(Credentials::for_tests()),
+                now,
+            )
+            .unwrap();
+            seed_signatures.push(verified.signature().to_string());
+
+            let mut body = AwsChunkedDecodingBody::new(request.take_body()).with_chunk_verifier(
+                ChunkSignatureVerifier::from_verified_request(&verified, identity.clone()),
+            );
+            let mut body_data = BytesMut::new();
+            while let Some(data) = body.data().await {
+                body_data.extend_from_slice(&data.unwrap())
+            }
+            let trailers = body.trailers().await.unwrap().unwrap();
+            assert_eq!("Hello world", std::str::from_utf8(&body_data).unwrap());
+            assert_eq!("i9aeUg==", trailers["x-amz-checksum-crc32"]);
+        }
+        assert_ne!(seed_signatures[0], seed_signatures[1]);
+    }
 }
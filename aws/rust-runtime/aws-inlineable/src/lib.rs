@@ -36,6 +36,10 @@ pub mod presigning_interceptors;
 // fail to compile.
 // pub mod s3_express;
 
+// Like `s3_express`, this module refers to the target crate's `crate::Config` and generated
+// endpoint parameters, so it can't be included into `aws-inlineable`.
+// pub mod s3_presigned_post;
+
 /// Special logic for extracting request IDs from S3's responses.
 #[allow(dead_code)]
 pub mod s3_request_id;
@@ -0,0 +1,425 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Presigned POST policies, used to upload objects to S3 directly from a browser form.
+
+use crate::presigning::PresigningConfig;
+use aws_credential_types::Credentials;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::endpoint::{EndpointResolverParams, ResolveEndpoint};
+use aws_smithy_types::endpoint::Endpoint;
+use aws_smithy_types::Document;
+use std::fmt;
+use std::time::SystemTime;
+
+/// A condition on a form field in a [`PostPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PostCondition {
+    /// The form field must exactly match the given value.
+    Eq(String, String),
+    /// The form field must start with the given prefix.
+    StartsWith(String, String),
+    /// The uploaded content must have a length (in bytes) within the given range.
+    ContentLengthRange(u64, u64),
+}
+
+/// A policy for an S3 presigned POST, used to upload objects directly from a browser form.
+///
+/// The policy restricts what the form is allowed to upload. It is signed with SigV4 by
+/// [`PostPolicy::presign`], which produces the URL and form fields to embed in the HTML form.
+///
+/// # Example
+///
+/// ```no_run
+/// use aws_sdk_s3::presigned_post::PostPolicy;
+/// use aws_sdk_s3::presigning::PresigningConfig;
+/// use std::time::Duration;
+///
+/// # async fn example(
+/// #     client: &aws_sdk_s3::Client,
+/// #     credentials: &aws_sdk_s3::config::Credentials,
+/// # ) -> Result<(), Box<dyn std::error::Error>> {
+/// let policy = PostPolicy::builder()
+///     .bucket("my-bucket")
+///     .key_starts_with("uploads/")
+///     .content_length_range(1, 10 * 1024 * 1024)
+///     .build()?;
+/// let presigned = policy
+///     .presign(
+///         client.config(),
+///         credentials,
+///         &PresigningConfig::expires_in(Duration::from_secs(3600))?,
+///     )
+///     .await?;
+/// println!("POST to {}", presigned.url());
+/// for (name, value) in presigned.fields() {
+///     println!("<input type=\"hidden\" name=\"{name}\" value=\"{value}\" />");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct PostPolicy {
+    bucket: String,
+    conditions: Vec<PostCondition>,
+}
+
+impl PostPolicy {
+    /// Creates a new builder for creating a `PostPolicy`.
+    pub fn builder() -> PostPolicyBuilder {
+        PostPolicyBuilder::default()
+    }
+
+    /// Returns the bucket that uploads are restricted to.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Signs this policy with the given credentials, producing the URL and form fields for a
+    /// presigned POST.
+    ///
+    /// The URL is resolved with the endpoint resolver of the given client config, so it honors
+    /// settings such as `endpoint_url` and `force_path_style`. The policy expires after the
+    /// duration given by the `PresigningConfig`, measured from its start time.
+    pub async fn presign(
+        &self,
+        config: &crate::Config,
+        credentials: &Credentials,
+        presigning_config: &PresigningConfig,
+    ) -> Result<PresignedPost, PostPolicyError> {
+        let region = config.region().ok_or(PostPolicyErrorKind::RegionRequired)?;
+
+        // `endpoint_params` is generated, since it depends on the service's endpoint parameters
+        let params = self
+            .endpoint_params(config)
+            .map_err(PostPolicyErrorKind::EndpointUnresolvable)?;
+        let endpoint = config
+            .endpoint_resolver()
+            .resolve_endpoint(&EndpointResolverParams::new(params))
+            .await
+            .map_err(PostPolicyErrorKind::EndpointUnresolvable)?;
+        let (signing_region, signing_name) = signing_scope(&endpoint);
+        let signing_region = signing_region.unwrap_or_else(|| region.as_ref());
+        let signing_name = signing_name.unwrap_or("s3");
+
+        let start_time = presigning_config.start_time();
+        let expiration = format_timestamp(start_time + presigning_config.expires())?;
+        let amz_date = format_timestamp(start_time)?.replace(['-', ':'], "");
+        let credential = format!(
+            "{}/{}/{}/{}/aws4_request",
+            credentials.access_key_id(),
+            &amz_date[..8],
+            signing_region,
+            signing_name,
+        );
+
+        let mut signing_fields: Vec<(String, String)> = vec![
+            ("x-amz-algorithm".into(), POST_ALGORITHM.into()),
+            ("x-amz-credential".into(), credential),
+            ("x-amz-date".into(), amz_date),
+        ];
+        if let Some(token) = credentials.session_token() {
+            signing_fields.push(("x-amz-security-token".into(), token.into()));
+        }
+
+        let mut document = String::new();
+        document.push_str("{\"expiration\":");
+        push_json_string(&mut document, &expiration);
+        document.push_str(",\"conditions\":[");
+        push_eq_condition(&mut document, "bucket", &self.bucket);
+        for condition in &self.conditions {
+            document.push(',');
+            match condition {
+                PostCondition::Eq(name, value) => push_eq_condition(&mut document, name, value),
+                PostCondition::StartsWith(name, prefix) => {
+                    document.push_str("[\"starts-with\",");
+                    push_json_string(&mut document, &format!("${name}"));
+                    document.push(',');
+                    push_json_string(&mut document, prefix);
+                    document.push(']');
+                }
+                PostCondition::ContentLengthRange(min, max) => {
+                    document.push_str(&format!("[\"content-length-range\",{min},{max}]"));
+                }
+            }
+        }
+        // The signing fields are part of the form, so the policy must permit them too
+        for (name, value) in &signing_fields {
+            document.push(',');
+            push_eq_condition(&mut document, name, value);
+        }
+        document.push_str("]}");
+
+        let policy = aws_smithy_types::base64::encode(&document);
+        let signing_key = aws_sigv4::sign::v4::generate_signing_key(
+            credentials.secret_access_key(),
+            start_time,
+            signing_region,
+            signing_name,
+        );
+        let signature = aws_sigv4::sign::v4::calculate_signature(signing_key, policy.as_bytes());
+
+        let mut fields: Vec<(String, String)> = self
+            .conditions
+            .iter()
+            .filter_map(|condition| match condition {
+                PostCondition::Eq(name, value) => Some((name.clone(), value.clone())),
+                _ => None,
+            })
+            .collect();
+        fields.extend(signing_fields);
+        fields.push(("policy".into(), policy));
+        fields.push(("x-amz-signature".into(), signature));
+
+        // The form is posted to the root of the bucket
+        let url = format!("{}/", endpoint.url().trim_end_matches('/'));
+        Ok(PresignedPost { url, fields })
+    }
+}
+
+/// Returns the signing region and name of the endpoint's SigV4 auth scheme, if it overrides them.
+fn signing_scope(endpoint: &Endpoint) -> (Option<&str>, Option<&str>) {
+    let scheme = match endpoint.properties().get("authSchemes") {
+        Some(Document::Array(schemes)) => schemes
+            .iter()
+            .filter_map(Document::as_object)
+            .find(|scheme| scheme.get("name").and_then(Document::as_string) == Some("sigv4")),
+        _ => None,
+    };
+    let property = |name| {
+        scheme
+            .and_then(|scheme| scheme.get(name))
+            .and_then(Document::as_string)
+    };
+    (property("signingRegion"), property("signingName"))
+}
+
+const POST_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+fn format_timestamp(time: SystemTime) -> Result<String, PostPolicyError> {
+    // Drop any sub-second precision so the timestamp doesn't include fractional seconds
+    let time = aws_smithy_types::DateTime::from(time);
+    aws_smithy_types::DateTime::from_secs(time.secs())
+        .fmt(aws_smithy_types::date_time::Format::DateTime)
+        .map_err(|_| PostPolicyErrorKind::TimestampOutOfRange.into())
+}
+
+fn push_eq_condition(document: &mut String, name: &str, value: &str) {
+    document.push('{');
+    push_json_string(document, name);
+    document.push(':');
+    push_json_string(document, value);
+    document.push('}');
+}
+
+fn push_json_string(document: &mut String, value: &str) {
+    document.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => document.push_str("\\\""),
+            '\\' => document.push_str("\\\\"),
+            '\n' => document.push_str("\\n"),
+            '\r' => document.push_str("\\r"),
+            '\t' => document.push_str("\\t"),
+            c if c.is_control() => document.push_str(&format!("\\u{:04x}", c as u32)),
+            c => document.push(c),
+        }
+    }
+    document.push('"');
+}
+
+#[derive(Debug)]
+enum PostPolicyErrorKind {
+    /// The `PostPolicy` builder requires a value for `bucket`.
+    BucketRequired,
+
+    /// The minimum of a `content-length-range` condition is greater than its maximum.
+    InvalidContentLengthRange { min: u64, max: u64 },
+
+    /// The policy's start or expiration time can't be formatted as a timestamp.
+    TimestampOutOfRange,
+
+    /// The client config has no region.
+    RegionRequired,
+
+    /// The URL to post the form to couldn't be resolved.
+    EndpointUnresolvable(BoxError),
+}
+
+/// `PostPolicy` build and presigning errors.
+#[derive(Debug)]
+pub struct PostPolicyError {
+    kind: PostPolicyErrorKind,
+}
+
+impl std::error::Error for PostPolicyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            PostPolicyErrorKind::EndpointUnresolvable(source) => Some(source.as_ref() as _),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PostPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PostPolicyErrorKind::BucketRequired => write!(f, "`bucket` is required"),
+            PostPolicyErrorKind::InvalidContentLengthRange { min, max } => write!(
+                f,
+                "`content_length_range` minimum ({min}) must not be greater than its maximum ({max})"
+            ),
+            PostPolicyErrorKind::TimestampOutOfRange => {
+                write!(f, "the policy's start or expiration time is out of range")
+            }
+            PostPolicyErrorKind::RegionRequired => write!(f, "the client config has no region"),
+            PostPolicyErrorKind::EndpointUnresolvable(_) => {
+                write!(f, "failed to resolve the URL to post the form to")
+            }
+        }
+    }
+}
+
+impl From<PostPolicyErrorKind> for PostPolicyError {
+    fn from(kind: PostPolicyErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// Builder used to create `PostPolicy`.
+#[non_exhaustive]
+#[derive(Default, Debug)]
+pub struct PostPolicyBuilder {
+    bucket: Option<String>,
+    conditions: Vec<PostCondition>,
+}
+
+impl PostPolicyBuilder {
+    /// Sets the bucket that uploads are restricted to.
+    ///
+    /// Required.
+    pub fn bucket(mut self, bucket: impl Into<String>) -> Self {
+        self.bucket = Some(bucket.into());
+        self
+    }
+
+    /// Requires the uploaded object to have exactly the given key.
+    pub fn key(self, key: impl Into<String>) -> Self {
+        self.condition("key", key)
+    }
+
+    /// Requires the uploaded object's key to start with the given prefix.
+    ///
+    /// The `key` field isn't included in the presigned form fields, so the form must provide it.
+    /// S3 replaces `${filename}` in the key with the name of the uploaded file.
+    pub fn key_starts_with(self, prefix: impl Into<String>) -> Self {
+        self.condition_starts_with("key", prefix)
+    }
+
+    /// Requires the uploaded object to have the given canned ACL, such as `private` or `public-read`.
+    pub fn acl(self, acl: impl Into<String>) -> Self {
+        self.condition("acl", acl)
+    }
+
+    /// Requires the uploaded object to have exactly the given `Content-Type`.
+    pub fn content_type(self, content_type: impl Into<String>) -> Self {
+        self.condition("Content-Type", content_type)
+    }
+
+    /// Requires the uploaded object's `Content-Type` to start with the given prefix, such as `image/`.
+    pub fn content_type_starts_with(self, prefix: impl Into<String>) -> Self {
+        self.condition_starts_with("Content-Type", prefix)
+    }
+
+    /// Requires the uploaded content to be between `min` and `max` bytes long, inclusive.
+    pub fn content_length_range(mut self, min: u64, max: u64) -> Self {
+        self.conditions
+            .push(PostCondition::ContentLengthRange(min, max));
+        self
+    }
+
+    /// Requires the uploaded object to have the given user-defined metadata.
+    ///
+    /// The `name` is prefixed with `x-amz-meta-` to produce the form field name.
+    pub fn metadata(self, name: impl AsRef<str>, value: impl Into<String>) -> Self {
+        self.condition(format!("x-amz-meta-{}", name.as_ref()), value)
+    }
+
+    /// Sets the status code S3 responds with after a successful upload, such as `201`.
+    pub fn success_action_status(self, status: u16) -> Self {
+        self.condition("success_action_status", status.to_string())
+    }
+
+    /// Sets the URL that the browser is redirected to after a successful upload.
+    pub fn success_action_redirect(self, url: impl Into<String>) -> Self {
+        self.condition("success_action_redirect", url)
+    }
+
+    /// Requires the form field `field` to exactly match `value`.
+    ///
+    /// The field is included in the presigned form fields with this value.
+    pub fn condition(mut self, field: impl Into<String>, value: impl Into<String>) -> Self {
+        self.conditions
+            .push(PostCondition::Eq(field.into(), value.into()));
+        self
+    }
+
+    /// Requires the form field `field` to start with `prefix`. An empty prefix allows any value.
+    ///
+    /// The field isn't included in the presigned form fields, so the form must provide it.
+    pub fn condition_starts_with(
+        mut self,
+        field: impl Into<String>,
+        prefix: impl Into<String>,
+    ) -> Self {
+        self.conditions
+            .push(PostCondition::StartsWith(field.into(), prefix.into()));
+        self
+    }
+
+    /// Builds the `PostPolicy`. This will error if `bucket` is not given, or if a
+    /// content length range has a minimum greater than its maximum.
+    pub fn build(self) -> Result<PostPolicy, PostPolicyError> {
+        let bucket = self.bucket.ok_or(PostPolicyErrorKind::BucketRequired)?;
+        for condition in &self.conditions {
+            if let PostCondition::ContentLengthRange(min, max) = *condition {
+                if min > max {
+                    return Err(PostPolicyErrorKind::InvalidContentLengthRange { min, max }.into());
+                }
+            }
+        }
+        Ok(PostPolicy {
+            bucket,
+            conditions: self.conditions,
+        })
+    }
+}
+
+/// Represents a presigned POST: the URL to post a form to, and the fields to include in it.
+///
+/// The fields should be added to the form as hidden inputs, in order. Any fields restricted by a
+/// `starts-with` condition must also be provided, and the `file` field containing the object's
+/// contents must come last, since S3 ignores any fields after it.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct PresignedPost {
+    url: String,
+    fields: Vec<(String, String)>,
+}
+
+impl PresignedPost {
+    /// Returns the URL that the form should be posted to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the form fields, including the encoded `policy` and its `x-amz-signature`.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
@@ -181,6 +181,8 @@ enum SigV4SigningError {
     MissingSigningRegion,
     #[cfg(feature = "sigv4a")]
     MissingSigningRegionSet,
+    #[cfg(feature = "sigv4a")]
+    InvalidSigningRegionSet(aws_types::region::InvalidSigningRegionSet),
     MissingSigningName,
     WrongIdentityType(Identity),
     BadTypeInEndpointAuthSchemeConfig(&'static str),
@@ -195,6 +197,8 @@ impl fmt::Display for SigV4SigningError {
             MissingSigningRegion => w("missing signing region"),
             #[cfg(feature = "sigv4a")]
             MissingSigningRegionSet => w("missing signing region set"),
+            #[cfg(feature = "sigv4a")]
+            InvalidSigningRegionSet(err) => write!(f, "{err}"),
             MissingSigningName => w("missing signing name"),
             WrongIdentityType(identity) => {
                 write!(f, "wrong identity type for SigV4/sigV4a. Expected AWS credentials but got `{identity:?}`")
@@ -265,3 +269,131 @@ fn apply_signing_instructions(
     }
     Ok(())
 }
+
+#[cfg(feature = "http-02x")]
+/// Sends a chunk signer seeded with the request signature if the request body is an
+/// [`AwsChunkedBody`](crate::content_encoding::AwsChunkedBody) with signed chunks.
+///
+/// Fails if the request was signed with a different signature version than the one the body was
+/// sized for, because the chunk signatures wouldn't fit in it.
+pub(crate) fn send_chunk_signer(
+    config_bag: &ConfigBag,
+    seed_signature: &str,
+    identity: &Identity,
+    signing_params: &aws_sigv4::http_request::SigningParams<'_>,
+) -> Result<(), BoxError> {
+    use crate::content_encoding::DeferredChunkSignerSender;
+
+    if let Some(signer_sender) = config_bag.load::<DeferredChunkSignerSender>() {
+        let signature_version = signing_params.signature_version();
+        if signature_version != signer_sender.signature_version() {
+            return Err(format!(
+                "the request body was encoded for {} chunk signatures, but the request was signed with {}",
+                signer_sender.signature_version(),
+                signature_version
+            )
+            .into());
+        }
+        let scope = signing_params.region();
+        #[cfg(feature = "sigv4a")]
+        let scope = scope.or_else(|| signing_params.region_set());
+        let context = ChunkSigningContext {
+            identity: identity.clone(),
+            scope: scope.unwrap_or_default().to_string(),
+            name: signing_params.name().to_string(),
+            time: *signing_params.time(),
+            signature_version,
+        };
+        signer_sender
+            .send(Box::new(ChunkSigner {
+                last_signature: seed_signature.to_string(),
+                context,
+            }) as _)
+            .map_err(|_| "the body the chunk signer was sent to no longer exists")?;
+    }
+    Ok(())
+}
+
+/// Everything but the previous signature that goes into signing, or verifying, a chunk of an
+/// `aws-chunked` body. This must match the signing params of the request that carries the body.
+#[cfg(feature = "http-02x")]
+#[derive(Debug)]
+pub(crate) struct ChunkSigningContext {
+    pub(crate) identity: Identity,
+    /// The signing region for SigV4, or the signing region set for SigV4a
+    pub(crate) scope: String,
+    pub(crate) name: String,
+    pub(crate) time: std::time::SystemTime,
+    pub(crate) signature_version: aws_sigv4::SignatureVersion,
+}
+
+#[cfg(feature = "http-02x")]
+impl ChunkSigningContext {
+    pub(crate) fn signing_params(
+        &self,
+    ) -> Result<aws_sigv4::http_request::SigningParams<'_>, BoxError> {
+        Ok(match self.signature_version {
+            #[cfg(feature = "sigv4a")]
+            aws_sigv4::SignatureVersion::V4a => aws_sigv4::sign::v4a::SigningParams::builder()
+                .identity(&self.identity)
+                .region_set(&self.scope)
+                .name(&self.name)
+                .time(self.time)
+                .settings(SigningSettings::default())
+                .build()?
+                .into(),
+            _ => aws_sigv4::sign::v4::SigningParams::builder()
+                .identity(&self.identity)
+                .region(&self.scope)
+                .name(&self.name)
+                .time(self.time)
+                .settings(SigningSettings::default())
+                .build()?
+                .into(),
+        })
+    }
+}
+
+/// Converts trailers to the name/value pairs that are signed
+#[cfg(feature = "http-02x")]
+pub(crate) fn trailer_pairs(
+    trailers: &http_02x::HeaderMap,
+) -> Result<Vec<(&str, &str)>, http_02x::header::ToStrError> {
+    trailers
+        .iter()
+        .map(|(name, value)| Ok((name.as_str(), value.to_str()?)))
+        .collect()
+}
+
+/// Signs the chunks of an `aws-chunked` body, chaining each signature to the previous one.
+#[cfg(feature = "http-02x")]
+#[derive(Debug)]
+struct ChunkSigner {
+    last_signature: String,
+    context: ChunkSigningContext,
+}
+
+#[cfg(feature = "http-02x")]
+impl crate::content_encoding::SignChunk for ChunkSigner {
+    fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+        let signature = aws_sigv4::chunked::sign_chunk(
+            chunk,
+            &self.last_signature,
+            &self.context.signing_params()?,
+        )?;
+        let padded = aws_sigv4::chunked::pad_signature(&signature, self.context.signature_version);
+        self.last_signature = signature;
+        Ok(padded)
+    }
+
+    fn sign_trailers(&mut self, trailers: &http_02x::HeaderMap) -> Result<String, BoxError> {
+        let signature = aws_sigv4::chunked::sign_trailers(
+            trailer_pairs(trailers)?,
+            &self.last_signature,
+            &self.context.signing_params()?,
+        )?;
+        let padded = aws_sigv4::chunked::pad_signature(&signature, self.context.signature_version);
+        self.last_signature = signature;
+        Ok(padded)
+    }
+}
@@ -123,8 +123,15 @@ impl SigV4aSigner {
             .or(config_bag.load::<SigningName>().cloned());
 
         let region_set =
-            extract_endpoint_auth_scheme_signing_region_set(&auth_scheme_endpoint_config)?
-                .or(config_bag.load::<SigningRegionSet>().cloned());
+            match extract_endpoint_auth_scheme_signing_region_set(&auth_scheme_endpoint_config)? {
+                Some(region_set) => Some(region_set),
+                // A configured region set may have been created without being parsed, so validate it here
+                None => config_bag
+                    .load::<SigningRegionSet>()
+                    .map(|region_set| SigningRegionSet::parse(region_set.as_ref()))
+                    .transpose()
+                    .map_err(SigV4SigningError::InvalidSigningRegionSet)?,
+            };
 
         match (region_set, name) {
             (None, None) => Ok(Cow::Borrowed(operation_config)),
@@ -147,10 +154,15 @@ fn extract_endpoint_auth_scheme_signing_region_set(
     match super::extract_field_from_endpoint_config("signingRegionSet", endpoint_config) {
         Some(Array(docs)) => {
             // The service defines the region set as a string array. Here, we convert it to a comma separated list.
-            let region_set: SigningRegionSet =
-                docs.iter().filter_map(|doc| doc.as_string()).collect();
-
-            Ok(Some(region_set))
+            let region_set = docs
+                .iter()
+                .filter_map(|doc| doc.as_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            SigningRegionSet::parse(&region_set)
+                .map(Some)
+                .map_err(SigV4SigningError::InvalidSigningRegionSet)
         }
         None => Ok(None),
         _it => Err(UnexpectedType("signingRegionSet")),
@@ -178,6 +190,7 @@ impl Sign for SigV4aSigner {
         let signing_params =
             Self::signing_params(settings, identity, &operation_config, request_time)?;
 
+        let signing_params = signing_params.into();
         let (signing_instructions, _signature) = {
             // A body that is already in memory can be signed directly. A body that is not in memory
             // (any sort of streaming body or presigned request) will be signed via UNSIGNED-PAYLOAD.
@@ -202,10 +215,14 @@ impl Sign for SigV4aSigner {
                 request.headers().iter(),
                 signable_body,
             )?;
-            sign(signable_request, &signing_params.into())?
+            sign(signable_request, &signing_params)?
         }
         .into_parts();
 
+        // If the body is sent in signed chunks, set up the chunk signer
+        #[cfg(feature = "http-02x")]
+        crate::auth::send_chunk_signer(config_bag, &_signature, identity, &signing_params)?;
+
         apply_signing_instructions(signing_instructions, request)?;
         Ok(())
     }
@@ -214,12 +231,13 @@ impl Sign for SigV4aSigner {
 #[cfg(test)]
 mod tests {
     use super::{SigV4OperationSigningConfig, SigV4aSigner, EXPIRATION_WARNING};
-    use crate::auth::{HttpSignatureType, SigningOptions};
+    use crate::auth::{HttpSignatureType, SigV4SigningError, SigningOptions};
     use aws_credential_types::Credentials;
     use aws_sigv4::http_request::SigningSettings;
     use aws_smithy_runtime_api::client::auth::AuthSchemeEndpointConfig;
     use aws_smithy_types::config_bag::{ConfigBag, Layer};
     use aws_smithy_types::Document;
+    use aws_types::region::SigningRegionSet;
     use aws_types::SigningName;
     use std::borrow::Cow;
     use std::collections::HashMap;
@@ -296,6 +314,75 @@ mod tests {
         assert!(matches!(result, Cow::Owned(_)));
     }
 
+    #[test]
+    fn endpoint_config_region_sets_are_validated() {
+        let mut layer = Layer::new("test");
+        layer.store_put(SigV4OperationSigningConfig {
+            region_set: Some("test".into()),
+            name: Some(SigningName::from_static("qldb")),
+            ..Default::default()
+        });
+        let cfg = ConfigBag::of_layers(vec![layer]);
+        let config_with_region_set = |regions: Vec<&str>| {
+            Document::Object({
+                let mut out = HashMap::new();
+                out.insert("name".to_owned(), "sigv4a".to_owned().into());
+                out.insert(
+                    "signingRegionSet".to_string(),
+                    Document::Array(regions.into_iter().map(|r| r.to_owned().into()).collect()),
+                );
+                out
+            })
+        };
+
+        let config = config_with_region_set(vec!["us-*", "eu-west-1"]);
+        let result = SigV4aSigner::extract_operation_config(
+            AuthSchemeEndpointConfig::from(Some(&config)),
+            &cfg,
+        )
+        .expect("success");
+        assert_eq!(result.region_set, Some("us-*,eu-west-1".into()));
+
+        let config = config_with_region_set(vec!["us-east-1", ""]);
+        let err = SigV4aSigner::extract_operation_config(
+            AuthSchemeEndpointConfig::from(Some(&config)),
+            &cfg,
+        )
+        .expect_err("empty region");
+        assert!(
+            matches!(err, SigV4SigningError::InvalidSigningRegionSet(_)),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn configured_region_sets_are_validated() {
+        let config_with_region_set = |region_set: SigningRegionSet| {
+            let mut layer = Layer::new("test");
+            layer.store_put(SigV4OperationSigningConfig {
+                region_set: Some("test".into()),
+                name: Some(SigningName::from_static("qldb")),
+                ..Default::default()
+            });
+            layer.store_put(region_set);
+            ConfigBag::of_layers(vec![layer])
+        };
+
+        let cfg = config_with_region_set(SigningRegionSet::from(" us-* , eu-west-1"));
+        let result =
+            SigV4aSigner::extract_operation_config(AuthSchemeEndpointConfig::empty(), &cfg)
+                .expect("success");
+        assert_eq!(result.region_set, Some("us-*,eu-west-1".into()));
+
+        let cfg = config_with_region_set(["us-east-1", "US-WEST-2"].into_iter().collect());
+        let err = SigV4aSigner::extract_operation_config(AuthSchemeEndpointConfig::empty(), &cfg)
+            .expect_err("uppercase region");
+        assert!(
+            matches!(err, SigV4SigningError::InvalidSigningRegionSet(_)),
+            "{err:?}"
+        );
+    }
+
     #[test]
     fn endpoint_config_supports_fallback_when_region_or_service_are_unset() {
         let mut layer = Layer::new("test");
@@ -3,17 +3,45 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_sigv4::SignatureVersion;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
 use bytes::{Bytes, BytesMut};
 use http_02x::{HeaderMap, HeaderValue};
 use http_body_04x::{Body, SizeHint};
 use pin_project_lite::pin_project;
 
+use std::fmt;
 use std::pin::Pin;
+use std::sync::{mpsc, Mutex};
 use std::task::{Context, Poll};
 
 const CRLF: &str = "\r\n";
 const CHUNK_TERMINATOR: &str = "0\r\n";
 const TRAILER_SEPARATOR: &[u8] = b":";
+const CHUNK_SIGNATURE_PREFIX: &str = ";chunk-signature=";
+const TRAILER_SIGNATURE_PREFIX: &str = "x-amz-trailer-signature:";
+
+/// The default size of the chunks written by an [`AwsChunkedBody`] with signed chunks.
+pub const DEFAULT_SIGNED_CHUNK_SIZE: usize = 64 * 1024;
+
+mod decode;
+
+pub use decode::{
+    AwsChunkedDecodingBody, AwsChunkedDecodingError, ChunkSignatureVerifier, VerifyChunk,
+    DEFAULT_MAX_CHUNK_SIZE,
+};
+
+/// Whether streaming request bodies are sent as `aws-chunked` bodies with signed chunks.
+///
+/// By default, the chunks of streaming request bodies are unsigned, and only the request itself
+/// is signed. When this is enabled, every chunk, and the trailers, are signed as well.
+#[derive(Clone, Debug, Default)]
+pub struct SignChunkedPayloads(pub bool);
+
+impl Storable for SignChunkedPayloads {
+    type Storer = StoreReplace<Self>;
+}
 
 /// Content encoding header value constants
 pub mod header_value {
@@ -25,13 +53,21 @@ pub mod header_value {
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct AwsChunkedBodyOptions {
-    /// The total size of the stream. Because we only support unsigned encoding
-    /// this implies that there will only be a single chunk containing the
-    /// underlying payload.
+    /// The total size of the stream. Unless chunks are signed, there will only be
+    /// a single chunk containing the underlying payload.
     stream_length: u64,
     /// The length of each trailer sent within an `AwsChunkedBody`. Necessary in
     /// order to correctly calculate the total size of the body accurately.
     trailer_lengths: Vec<u64>,
+    /// Signs each chunk, and the trailers, when set.
+    chunk_signer: Option<ChunkSigning>,
+}
+
+#[derive(Debug)]
+struct ChunkSigning {
+    signer: DeferredChunkSigner,
+    signature_len: usize,
+    chunk_size: usize,
 }
 
 impl AwsChunkedBodyOptions {
@@ -40,7 +76,33 @@ impl AwsChunkedBodyOptions {
         Self {
             stream_length,
             trailer_lengths,
+            chunk_signer: None,
+        }
+    }
+
+    /// Signs every chunk, and the trailers, with the given signer.
+    ///
+    /// The request carrying the body must be signed with one of the streaming signed payload
+    /// markers, using the signature version the signer was created for.
+    pub fn with_chunk_signer(mut self, signer: DeferredChunkSigner) -> Self {
+        self.chunk_signer = Some(ChunkSigning {
+            signature_len: aws_sigv4::chunked::signature_len(signer.signature_version),
+            signer,
+            chunk_size: DEFAULT_SIGNED_CHUNK_SIZE,
+        });
+        self
+    }
+
+    /// Sets the size of the chunks written when chunks are signed.
+    ///
+    /// This has no effect unless a [chunk signer](Self::with_chunk_signer) is set, because
+    /// unsigned bodies are written as a single chunk.
+    pub fn with_signed_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+        if let Some(chunk_signer) = self.chunk_signer.as_mut() {
+            chunk_signer.chunk_size = chunk_size;
         }
+        self
     }
 
     fn total_trailer_length(&self) -> u64 {
@@ -64,6 +126,9 @@ enum AwsChunkedBodyState {
     /// Write out the next chunk of data. Multiple polls of the inner body may need to occur before
     /// all data is written out. Once there is no more data to write, transition into the
     /// `WritingTrailers` state.
+    ///
+    /// When chunks are signed, data is buffered until a whole chunk can be signed and written out
+    /// along with its size and signature.
     WritingChunk,
     /// Write out all trailers associated with this `AwsChunkedBody` and then transition into the
     /// `Closed` state.
@@ -73,8 +138,10 @@ enum AwsChunkedBodyState {
 }
 
 pin_project! {
-    /// A request body compatible with `Content-Encoding: aws-chunked`. This implementation is only
-    /// capable of writing a single chunk and does not support signed chunks.
+    /// A request body compatible with `Content-Encoding: aws-chunked`. Unsigned bodies are written
+    /// as a single chunk. When a [chunk signer](AwsChunkedBodyOptions::with_chunk_signer) is set,
+    /// the body is split into chunks of a fixed size, each carrying a signature chained to the
+    /// signature of the previous chunk, and the trailers are followed by their signature.
     ///
     /// Chunked-Body grammar is defined in [ABNF] as:
     ///
@@ -84,10 +151,12 @@ pin_project! {
     ///                   chunked-trailer
     ///                   CRLF
     ///
-    /// chunk           = chunk-size CRLF chunk-data CRLF
+    /// chunk           = chunk-size [ chunk-ext ] CRLF chunk-data CRLF
     /// chunk-size      = 1*HEXDIG
-    /// last-chunk      = 1*("0") CRLF
+    /// chunk-ext       = ";chunk-signature=" signature
+    /// last-chunk      = 1*("0") [ chunk-ext ] CRLF
     /// chunked-trailer = *( entity-header CRLF )
+    ///                   [ "x-amz-trailer-signature:" signature CRLF ]
     /// entity-header   = field-name ":" OWS field-value OWS
     /// ```
     /// For more info on what the abbreviations mean, see https://datatracker.ietf.org/doc/html/rfc7230#section-1.2
@@ -101,21 +170,32 @@ pin_project! {
         state: AwsChunkedBodyState,
         options: AwsChunkedBodyOptions,
         inner_body_bytes_read_so_far: usize,
+        buffer: BytesMut,
     }
 }
 
 impl<Inner> AwsChunkedBody<Inner> {
     /// Wrap the given body in an outer body compatible with `Content-Encoding: aws-chunked`
     pub fn new(body: Inner, options: AwsChunkedBodyOptions) -> Self {
+        // Signed chunks carry their own size, so there is no separate size to write
+        let state = if options.chunk_signer.is_some() {
+            AwsChunkedBodyState::WritingChunk
+        } else {
+            AwsChunkedBodyState::WritingChunkSize
+        };
         Self {
             inner: body,
-            state: AwsChunkedBodyState::WritingChunkSize,
+            state,
             options,
             inner_body_bytes_read_so_far: 0,
+            buffer: BytesMut::new(),
         }
     }
 
     fn encoded_length(&self) -> u64 {
+        if let Some(chunk_signer) = &self.options.chunk_signer {
+            return self.signed_encoded_length(chunk_signer);
+        }
         let mut length = 0;
         if self.options.stream_length != 0 {
             length += get_unsigned_chunk_bytes_length(self.options.stream_length);
@@ -136,6 +216,57 @@ impl<Inner> AwsChunkedBody<Inner> {
     }
 }
 
+impl<Inner> AwsChunkedBody<Inner> {
+    fn signed_encoded_length(&self, chunk_signer: &ChunkSigning) -> u64 {
+        let chunk_size = chunk_signer.chunk_size as u64;
+        let signature_len = chunk_signer.signature_len as u64;
+        let full_chunks = self.options.stream_length / chunk_size;
+        let remainder = self.options.stream_length % chunk_size;
+
+        let mut length = full_chunks * get_signed_chunk_bytes_length(chunk_size, signature_len);
+        if remainder != 0 {
+            length += get_signed_chunk_bytes_length(remainder, signature_len);
+        }
+
+        // End chunk, which has a signature but no data
+        length += 1 + (CHUNK_SIGNATURE_PREFIX.len() + CRLF.len()) as u64 + signature_len;
+
+        // Trailers, followed by their signature
+        if !self.options.trailer_lengths.is_empty() {
+            length += self.options.total_trailer_length();
+            length += (TRAILER_SIGNATURE_PREFIX.len() + CRLF.len()) as u64 + signature_len;
+        }
+
+        // Encoding terminator
+        length += CRLF.len() as u64;
+
+        length
+    }
+}
+
+fn get_signed_chunk_bytes_length(payload_length: u64, signature_len: u64) -> u64 {
+    get_unsigned_chunk_bytes_length(payload_length)
+        + CHUNK_SIGNATURE_PREFIX.len() as u64
+        + signature_len
+}
+
+/// Writes a chunk, prefixed by its size and signature.
+fn signed_chunk(
+    chunk_signer: &mut ChunkSigning,
+    chunk: &[u8],
+    out: &mut BytesMut,
+) -> Result<(), BoxError> {
+    let signature = chunk_signer.signer.sign_chunk(chunk)?;
+    out.extend_from_slice(
+        format!("{:X}{CHUNK_SIGNATURE_PREFIX}{signature}{CRLF}", chunk.len()).as_bytes(),
+    );
+    if !chunk.is_empty() {
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(CRLF.as_bytes());
+    }
+    Ok(())
+}
+
 fn get_unsigned_chunk_bytes_length(payload_length: u64) -> u64 {
     let hex_repr_len = int_log16(payload_length);
     hex_repr_len + CRLF.len() as u64 + payload_length + CRLF.len() as u64
@@ -227,6 +358,53 @@ where
                     Poll::Ready(Some(Ok(chunk_size)))
                 }
             }
+            AwsChunkedBodyState::WritingChunk if this.options.chunk_signer.is_some() => {
+                let chunk_signer = this.options.chunk_signer.as_mut().expect("checked above");
+                loop {
+                    if this.buffer.len() >= chunk_signer.chunk_size {
+                        let chunk = this.buffer.split_to(chunk_signer.chunk_size);
+                        let mut out = BytesMut::new();
+                        if let Err(err) = signed_chunk(chunk_signer, &chunk, &mut out) {
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                        tracing::trace!(len = chunk.len(), "writing signed chunk");
+                        return Poll::Ready(Some(Ok(out.freeze())));
+                    }
+                    match this.inner.as_mut().poll_data(cx) {
+                        Poll::Ready(Some(Ok(data))) => {
+                            *this.inner_body_bytes_read_so_far += data.len();
+                            this.buffer.extend_from_slice(&data);
+                        }
+                        Poll::Ready(None) => {
+                            let actual_stream_length = *this.inner_body_bytes_read_so_far as u64;
+                            let expected_stream_length = this.options.stream_length;
+                            if actual_stream_length != expected_stream_length {
+                                let err = Box::new(AwsChunkedBodyError::StreamLengthMismatch {
+                                    actual: actual_stream_length,
+                                    expected: expected_stream_length,
+                                });
+                                return Poll::Ready(Some(Err(err)));
+                            };
+
+                            tracing::trace!("no more chunk data, writing final signed chunks");
+                            *this.state = AwsChunkedBodyState::WritingTrailers;
+                            let mut out = BytesMut::new();
+                            let remainder = this.buffer.split();
+                            if !remainder.is_empty() {
+                                if let Err(err) = signed_chunk(chunk_signer, &remainder, &mut out) {
+                                    return Poll::Ready(Some(Err(err)));
+                                }
+                            }
+                            if let Err(err) = signed_chunk(chunk_signer, &[], &mut out) {
+                                return Poll::Ready(Some(Err(err)));
+                            }
+                            return Poll::Ready(Some(Ok(out.freeze())));
+                        }
+                        Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+            }
             AwsChunkedBodyState::WritingChunk => match this.inner.poll_data(cx) {
                 Poll::Ready(Some(Ok(data))) => {
                     tracing::trace!(len = data.len(), "writing chunk data");
@@ -269,8 +447,26 @@ where
                             return Poll::Ready(Some(Err(err)));
                         }
 
+                        let trailer_signature = match this.options.chunk_signer.as_mut() {
+                            Some(chunk_signer) if !this.options.trailer_lengths.is_empty() => {
+                                match chunk_signer
+                                    .signer
+                                    .sign_trailers(trailers.as_ref().unwrap_or(&HeaderMap::new()))
+                                {
+                                    Ok(signature) => Some(signature),
+                                    Err(err) => return Poll::Ready(Some(Err(err))),
+                                }
+                            }
+                            _ => None,
+                        };
+
                         let mut trailers =
                             trailers_as_aws_chunked_bytes(trailers, actual_length + 1);
+                        if let Some(signature) = trailer_signature {
+                            trailers.extend_from_slice(
+                                format!("{TRAILER_SIGNATURE_PREFIX}{signature}{CRLF}").as_bytes(),
+                            );
+                        }
                         // Insert the final CRLF to close the body
                         trailers.extend_from_slice(CRLF.as_bytes());
 
@@ -330,6 +526,106 @@ impl std::fmt::Display for AwsChunkedBodyError {
 
 impl std::error::Error for AwsChunkedBodyError {}
 
+/// Signs the chunks and trailers of an [`AwsChunkedBody`].
+///
+/// Each signature is chained to the previous one, so implementations keep track of the last
+/// signature they produced, starting with the signature of the request that carries the body.
+pub trait SignChunk: fmt::Debug {
+    /// Signs the next chunk. The last chunk is always empty.
+    fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError>;
+
+    /// Signs the trailers, after the last chunk has been signed.
+    fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, BoxError>;
+}
+
+/// A sender that gets placed in the request config to wire up a chunk signer after signing.
+#[derive(Debug)]
+pub struct DeferredChunkSignerSender {
+    tx: Mutex<mpsc::Sender<Box<dyn SignChunk + Send + Sync>>>,
+    signature_version: SignatureVersion,
+}
+
+impl DeferredChunkSignerSender {
+    /// Sends a signer on the channel
+    pub fn send(
+        &self,
+        signer: Box<dyn SignChunk + Send + Sync>,
+    ) -> Result<(), mpsc::SendError<Box<dyn SignChunk + Send + Sync>>> {
+        self.tx.lock().unwrap().send(signer)
+    }
+
+    /// The signature version the body was sized for. The request must be signed with it.
+    pub fn signature_version(&self) -> SignatureVersion {
+        self.signature_version
+    }
+}
+
+impl Storable for DeferredChunkSignerSender {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Deferred chunk signer to allow a signer to be wired up later.
+///
+/// The body of a request is established before the request is signed, but chunk signatures are
+/// chained to the request signature. Like the event stream `DeferredSigner`, this establishes a
+/// channel whose sender is placed in the request config, so that the HTTP signer can send it a
+/// [`SignChunk`] implementation seeded with the request signature.
+#[derive(Debug)]
+pub struct DeferredChunkSigner {
+    rx: Option<Mutex<mpsc::Receiver<Box<dyn SignChunk + Send + Sync>>>>,
+    signer: Option<Box<dyn SignChunk + Send + Sync>>,
+    signature_version: SignatureVersion,
+}
+
+impl DeferredChunkSigner {
+    /// Creates a new `DeferredChunkSigner` and the sender that wires up its signer.
+    ///
+    /// The chunks are signed with the given signature version, which determines the length of
+    /// their signatures, and so the length of the body.
+    pub fn new(signature_version: SignatureVersion) -> (Self, DeferredChunkSignerSender) {
+        let (tx, rx) = mpsc::channel();
+        (
+            Self {
+                rx: Some(Mutex::new(rx)),
+                signer: None,
+                signature_version,
+            },
+            DeferredChunkSignerSender {
+                tx: Mutex::new(tx),
+                signature_version,
+            },
+        )
+    }
+
+    /// The signature version the chunks are signed with.
+    pub fn signature_version(&self) -> SignatureVersion {
+        self.signature_version
+    }
+
+    fn acquire(&mut self) -> Result<&mut (dyn SignChunk + Send + Sync), BoxError> {
+        if self.signer.is_none() {
+            let signer = self
+                .rx
+                .take()
+                .and_then(|rx| rx.into_inner().ok())
+                .and_then(|rx| rx.try_recv().ok())
+                .ok_or("no chunk signer was sent; was the request signed with a streaming signed payload?")?;
+            self.signer = Some(signer);
+        }
+        Ok(self.signer.as_mut().expect("set above").as_mut())
+    }
+}
+
+impl SignChunk for DeferredChunkSigner {
+    fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+        self.acquire()?.sign_chunk(chunk)
+    }
+
+    fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, BoxError> {
+        self.acquire()?.sign_trailers(trailers)
+    }
+}
+
 // Used for finding how many hexadecimal digits it takes to represent a base 10 integer
 fn int_log16<T>(mut i: T) -> u64
 where
@@ -351,8 +647,10 @@ where
 mod tests {
     use super::{
         total_rendered_length_of_trailers, trailers_as_aws_chunked_bytes, AwsChunkedBody,
-        AwsChunkedBodyOptions, CHUNK_TERMINATOR, CRLF,
+        AwsChunkedBodyOptions, DeferredChunkSigner, SignChunk, CHUNK_TERMINATOR, CRLF,
     };
+    use aws_sigv4::SignatureVersion;
+    use aws_smithy_runtime_api::box_error::BoxError;
 
     use aws_smithy_types::body::SdkBody;
     use bytes::{Buf, Bytes};
@@ -580,6 +878,111 @@ mod tests {
         );
     }
 
+    struct TrailerBody {
+        data: Option<Bytes>,
+        trailers: Option<HeaderMap>,
+    }
+
+    impl Body for TrailerBody {
+        type Data = Bytes;
+        type Error = aws_smithy_types::body::Error;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            Poll::Ready(self.data.take().map(Ok))
+        }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
+            Poll::Ready(Ok(self.trailers.take()))
+        }
+    }
+
+    #[derive(Debug)]
+    struct TestChunkSigner {
+        signed: usize,
+    }
+
+    // Signatures are padded to the length of SigV4 signatures, which the body is sized for
+    fn test_signature(signature: &str) -> String {
+        format!("{signature:0>64}")
+    }
+
+    impl SignChunk for TestChunkSigner {
+        fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+            self.signed += 1;
+            Ok(test_signature(&format!("{}-{}", self.signed, chunk.len())))
+        }
+
+        fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, BoxError> {
+            self.signed += 1;
+            Ok(test_signature(&format!(
+                "{}-{}",
+                self.signed,
+                trailers.len()
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_chunks() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-amz-checksum-crc32", HeaderValue::from_static("AAAAAA=="));
+        let trailer_len = total_rendered_length_of_trailers(Some(&trailers)) - CRLF.len() as u64;
+        let input = TrailerBody {
+            data: Some(Bytes::from_static(b"Hello world")),
+            trailers: Some(trailers),
+        };
+
+        let (signer, sender) = DeferredChunkSigner::new(SignatureVersion::V4);
+        sender
+            .send(Box::new(TestChunkSigner { signed: 0 }))
+            .unwrap();
+        let opts = AwsChunkedBodyOptions::new(11, vec![trailer_len])
+            .with_chunk_signer(signer)
+            .with_signed_chunk_size(4);
+        let mut body = AwsChunkedBody::new(input, opts);
+        let size_hint = body.size_hint().exact();
+
+        let mut output = Vec::new();
+        while let Some(buf) = body.data().await {
+            output.extend_from_slice(&buf.unwrap());
+        }
+        let expected_output = format!(
+            "4;chunk-signature={}\r\nHell\r\n\
+            4;chunk-signature={}\r\no wo\r\n\
+            3;chunk-signature={}\r\nrld\r\n\
+            0;chunk-signature={}\r\n\
+            x-amz-checksum-crc32:AAAAAA==\r\n\
+            x-amz-trailer-signature:{}\r\n\
+            \r\n",
+            test_signature("1-4"),
+            test_signature("2-4"),
+            test_signature("3-3"),
+            test_signature("4-0"),
+            test_signature("5-1"),
+        );
+        assert_eq!(expected_output, String::from_utf8(output).unwrap());
+        assert_eq!(Some(expected_output.len() as u64), size_hint);
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_chunks_without_signer_fails() {
+        let (signer, _sender) = DeferredChunkSigner::new(SignatureVersion::V4);
+        let opts = AwsChunkedBodyOptions::new(5, Vec::new()).with_chunk_signer(signer);
+        let mut body = AwsChunkedBody::new(SdkBody::from("hello"), opts);
+
+        let err = body.data().await.unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("no chunk signer was sent"),
+            "{err}"
+        );
+    }
+
     #[tokio::test]
     async fn test_total_rendered_length_of_trailers() {
         let mut trailers = HeaderMap::new();
@@ -0,0 +1,1009 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Decoding of `aws-chunked` encoded bodies, for servers and tests.
+
+use super::{CHUNK_SIGNATURE_PREFIX, CRLF};
+use crate::auth::{trailer_pairs, ChunkSigningContext};
+use aws_sigv4::http_request::VerifiedRequest;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::identity::Identity;
+use bytes::{Bytes, BytesMut};
+use http_02x::header::{HeaderName, HeaderValue};
+use http_02x::HeaderMap;
+use http_body_04x::{Body, SizeHint};
+use pin_project_lite::pin_project;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The name of the trailer that carries the signature of the other trailers
+const TRAILER_SIGNATURE: &str = "x-amz-trailer-signature";
+
+/// The longest chunk header or trailer line that is accepted, so that a body without line breaks
+/// can't make the decoder buffer it in full.
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// The default size of the largest chunk that is accepted when chunk signatures are verified.
+///
+/// Verified chunks are buffered in full, so this bounds how much of the body is held in memory.
+/// It comfortably fits the [chunks written by `AwsChunkedBody`](super::DEFAULT_SIGNED_CHUNK_SIZE).
+pub const DEFAULT_MAX_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Verifies the signatures of the chunks and trailers of an [`AwsChunkedDecodingBody`].
+///
+/// This is the counterpart of [`SignChunk`](super::SignChunk). Each signature is chained to the
+/// previous one, so implementations keep track of the last signature they verified, starting
+/// with the signature of the request that carried the body.
+pub trait VerifyChunk: fmt::Debug {
+    /// Returns whether the signature of the next chunk is valid. The last chunk is always empty.
+    fn verify_chunk(&mut self, chunk: &[u8], signature: &str) -> Result<bool, BoxError>;
+
+    /// Returns whether the signature of the trailers is valid.
+    fn verify_trailers(&mut self, trailers: &HeaderMap, signature: &str) -> Result<bool, BoxError>;
+}
+
+/// Verifies SigV4 and SigV4a chunk signatures, chained to the signature of a verified request.
+#[derive(Debug)]
+pub struct ChunkSignatureVerifier {
+    last_signature: String,
+    context: ChunkSigningContext,
+}
+
+impl ChunkSignatureVerifier {
+    /// Creates a verifier for the chunks of the body of `request`.
+    ///
+    /// `identity` must be the credentials the request was verified with.
+    pub fn from_verified_request(request: &VerifiedRequest, identity: Identity) -> Self {
+        Self {
+            last_signature: request.signature().to_string(),
+            context: ChunkSigningContext {
+                identity,
+                scope: request.region().to_string(),
+                name: request.signing_name().to_string(),
+                time: request.time(),
+                signature_version: request.signature_version(),
+            },
+        }
+    }
+
+    fn chain(&mut self, valid: bool, signature: &str) -> bool {
+        if valid {
+            self.last_signature = aws_sigv4::chunked::unpad_signature(signature).to_string();
+        }
+        valid
+    }
+}
+
+impl VerifyChunk for ChunkSignatureVerifier {
+    fn verify_chunk(&mut self, chunk: &[u8], signature: &str) -> Result<bool, BoxError> {
+        let valid = aws_sigv4::chunked::verify_chunk(
+            chunk,
+            &self.last_signature,
+            signature,
+            &self.context.signing_params()?,
+        )?;
+        Ok(self.chain(valid, signature))
+    }
+
+    fn verify_trailers(&mut self, trailers: &HeaderMap, signature: &str) -> Result<bool, BoxError> {
+        let valid = aws_sigv4::chunked::verify_trailers(
+            trailer_pairs(trailers)?,
+            &self.last_signature,
+            signature,
+            &self.context.signing_params()?,
+        )?;
+        Ok(self.chain(valid, signature))
+    }
+}
+
+/// Errors returned by [`AwsChunkedDecodingBody`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AwsChunkedDecodingError {
+    /// The body isn't valid `aws-chunked` encoding.
+    Malformed(&'static str),
+    /// The body ended before the final chunk and trailers were read.
+    UnexpectedEof,
+    /// The length of the decoded body doesn't match the expected length, usually taken from the
+    /// `x-amz-decoded-content-length` header.
+    DecodedLengthMismatch {
+        /// The expected length
+        expected: u64,
+        /// The length of the decoded body, or of the part that was decoded when it grew longer
+        /// than expected
+        actual: u64,
+    },
+    /// A chunk has no signature, but signatures are verified.
+    MissingChunkSignature,
+    /// A chunk signature is invalid.
+    InvalidChunkSignature,
+    /// The body has trailers, but they aren't signed even though signatures are verified.
+    MissingTrailerSignature,
+    /// The trailer signature is invalid.
+    InvalidTrailerSignature,
+    /// A chunk is larger than the largest chunk that is accepted when signatures are verified.
+    ChunkTooLarge {
+        /// The size of the chunk
+        size: usize,
+        /// The largest size that is accepted
+        max_size: usize,
+    },
+    /// A chunk or trailer signature couldn't be verified, for example because the credentials
+    /// of the request couldn't be used.
+    VerificationFailed(BoxError),
+    /// The checksum trailer that is verified is missing.
+    MissingChecksum {
+        /// The name of the missing trailer
+        trailer_name: &'static str,
+    },
+    /// The checksum of the decoded body doesn't match the checksum sent in its trailers.
+    ChecksumMismatch {
+        /// The checksum sent in the trailers
+        expected: String,
+        /// The checksum of the decoded body
+        actual: String,
+    },
+}
+
+impl fmt::Display for AwsChunkedDecodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(message) => write!(f, "malformed aws-chunked body: {message}"),
+            Self::UnexpectedEof => write!(f, "the aws-chunked body ended before its final chunk"),
+            Self::DecodedLengthMismatch { expected, actual } => write!(
+                f,
+                "the decoded aws-chunked body is {actual} bytes long, but {expected} bytes were expected"
+            ),
+            Self::MissingChunkSignature => write!(f, "a chunk of the aws-chunked body isn't signed"),
+            Self::InvalidChunkSignature => {
+                write!(f, "a chunk signature of the aws-chunked body is invalid")
+            }
+            Self::MissingTrailerSignature => {
+                write!(f, "the trailers of the aws-chunked body aren't signed")
+            }
+            Self::InvalidTrailerSignature => {
+                write!(f, "the trailer signature of the aws-chunked body is invalid")
+            }
+            Self::ChunkTooLarge { size, max_size } => write!(
+                f,
+                "a chunk of the aws-chunked body is {size} bytes long, but at most {max_size} bytes are accepted"
+            ),
+            Self::VerificationFailed(_) => {
+                write!(f, "failed to verify a signature of the aws-chunked body")
+            }
+            Self::MissingChecksum { trailer_name } => write!(
+                f,
+                "the aws-chunked body has no `{trailer_name}` trailer to check its checksum against"
+            ),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "the checksum of the aws-chunked body is {actual}, but its trailers claim {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AwsChunkedDecodingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::VerificationFailed(err) => Some(err.as_ref() as _),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "checksums")]
+type BodyChecksum = Box<dyn aws_smithy_checksums::http::HttpChecksum>;
+// `pin_project!` doesn't support `cfg` attributes on fields
+#[cfg(not(feature = "checksums"))]
+type BodyChecksum = std::convert::Infallible;
+
+#[derive(Debug, PartialEq, Eq)]
+enum DecodingState {
+    /// Read the size, and signature, of the next chunk.
+    ReadingChunkHeader,
+    /// Read the data of the current chunk. Unless chunks are verified, data is passed on as it
+    /// arrives. Verified chunks are passed on once they have been read, and verified, in full.
+    ReadingChunk {
+        remaining: usize,
+        signature: Option<String>,
+    },
+    /// Read the CRLF that ends the data of a chunk.
+    ReadingChunkEnd,
+    /// Read trailers until the empty line that ends the body.
+    ReadingTrailers,
+    /// The body was decoded and checked.
+    Done,
+}
+
+pin_project! {
+    /// A body that decodes an `aws-chunked` encoded body, the counterpart of
+    /// [`AwsChunkedBody`](super::AwsChunkedBody).
+    ///
+    /// The chunk framing is stripped from the data, and the trailers that follow the final chunk
+    /// are returned from [`Body::poll_trailers`]. Optionally, the body also checks
+    ///
+    /// - its decoded length, usually sent in the `x-amz-decoded-content-length` header,
+    /// - the signatures of its chunks and trailers, and
+    /// - its checksum, sent in a trailer (this requires the `checksums` feature).
+    ///
+    /// These checks fail with an [`AwsChunkedDecodingError`] once the body has been read.
+    /// Unverified chunk data is passed on as it arrives, but chunks whose signatures are verified
+    /// are only passed on once they have been read in full and verified.
+    pub struct AwsChunkedDecodingBody<InnerBody> {
+        #[pin]
+        inner: InnerBody,
+        inner_done: bool,
+        buffer: BytesMut,
+        state: DecodingState,
+        decoded_length: u64,
+        expected_decoded_length: Option<u64>,
+        verifier: Option<Box<dyn VerifyChunk + Send + Sync>>,
+        max_chunk_size: usize,
+        checksum: Option<BodyChecksum>,
+        trailers: Option<HeaderMap>,
+    }
+}
+
+impl<Inner> fmt::Debug for AwsChunkedDecodingBody<Inner> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsChunkedDecodingBody")
+            .field("state", &self.state)
+            .field("decoded_length", &self.decoded_length)
+            .field("expected_decoded_length", &self.expected_decoded_length)
+            .field("verifier", &self.verifier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Inner> AwsChunkedDecodingBody<Inner> {
+    /// Wraps the given `aws-chunked` encoded body in a body that decodes it.
+    pub fn new(body: Inner) -> Self {
+        Self {
+            inner: body,
+            inner_done: false,
+            buffer: BytesMut::new(),
+            state: DecodingState::ReadingChunkHeader,
+            decoded_length: 0,
+            expected_decoded_length: None,
+            verifier: None,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+            checksum: None,
+            trailers: None,
+        }
+    }
+
+    /// Checks that the decoded body is `decoded_length` bytes long.
+    ///
+    /// This is usually the value of the `x-amz-decoded-content-length` header.
+    pub fn with_decoded_content_length(mut self, decoded_length: u64) -> Self {
+        self.expected_decoded_length = Some(decoded_length);
+        self
+    }
+
+    /// Verifies the signature of every chunk, and of the trailers, with the given verifier.
+    ///
+    /// Chunks and trailers without signatures are rejected, as are chunks larger than the
+    /// [maximum chunk size](Self::with_max_chunk_size).
+    pub fn with_chunk_verifier(
+        mut self,
+        verifier: impl VerifyChunk + Send + Sync + 'static,
+    ) -> Self {
+        self.verifier = Some(Box::new(verifier));
+        self
+    }
+
+    /// Sets the size of the largest chunk that is accepted when chunk signatures are verified.
+    /// Defaults to [`DEFAULT_MAX_CHUNK_SIZE`].
+    ///
+    /// Verified chunks are buffered in full before they are passed on, so larger chunks are
+    /// rejected as soon as their header is read. Unverified chunks are passed on as they arrive,
+    /// and aren't limited.
+    pub fn with_max_chunk_size(mut self, max_chunk_size: usize) -> Self {
+        self.max_chunk_size = max_chunk_size;
+        self
+    }
+
+    /// Checks the checksum of the decoded body against the checksum trailer of the given
+    /// algorithm, e.g. `x-amz-checksum-crc32` for CRC32.
+    ///
+    /// The algorithm is usually found in the `x-amz-sdk-checksum-algorithm` or `x-amz-trailer`
+    /// header.
+    #[cfg(feature = "checksums")]
+    pub fn with_checksum(mut self, algorithm: aws_smithy_checksums::ChecksumAlgorithm) -> Self {
+        self.checksum = Some(algorithm.into_impl());
+        self
+    }
+}
+
+/// Removes the next CRLF terminated line from `buffer`, without the CRLF.
+fn take_line(buffer: &mut BytesMut) -> Result<Option<BytesMut>, AwsChunkedDecodingError> {
+    match buffer
+        .windows(CRLF.len())
+        .position(|w| w == CRLF.as_bytes())
+    {
+        Some(end) => {
+            let mut line = buffer.split_to(end + CRLF.len());
+            line.truncate(end);
+            Ok(Some(line))
+        }
+        None if buffer.len() > MAX_LINE_LENGTH => Err(AwsChunkedDecodingError::Malformed(
+            "chunk header or trailer is too long",
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Parses a chunk header of the form `size[;chunk-signature=signature]`.
+fn parse_chunk_header(line: &[u8]) -> Result<(usize, Option<String>), AwsChunkedDecodingError> {
+    let line = std::str::from_utf8(line)
+        .map_err(|_| AwsChunkedDecodingError::Malformed("chunk header isn't valid UTF-8"))?;
+    let (size, signature) = match line.find(';') {
+        Some(index) => {
+            let (size, extension) = line.split_at(index);
+            let signature = extension.strip_prefix(CHUNK_SIGNATURE_PREFIX).ok_or(
+                AwsChunkedDecodingError::Malformed("unknown chunk extension"),
+            )?;
+            (size, Some(signature.to_string()))
+        }
+        None => (line, None),
+    };
+    let size = usize::from_str_radix(size, 16)
+        .map_err(|_| AwsChunkedDecodingError::Malformed("invalid chunk size"))?;
+    Ok((size, signature))
+}
+
+/// Parses a trailer of the form `name:value`.
+fn parse_trailer(line: &[u8]) -> Result<(HeaderName, HeaderValue), AwsChunkedDecodingError> {
+    let separator = line
+        .iter()
+        .position(|&b| b == b':')
+        .ok_or(AwsChunkedDecodingError::Malformed("trailer has no value"))?;
+    let name = HeaderName::from_bytes(&line[..separator])
+        .map_err(|_| AwsChunkedDecodingError::Malformed("invalid trailer name"))?;
+    let value = HeaderValue::from_str(
+        std::str::from_utf8(&line[separator + 1..])
+            .map_err(|_| AwsChunkedDecodingError::Malformed("invalid trailer value"))?
+            .trim(),
+    )
+    .map_err(|_| AwsChunkedDecodingError::Malformed("invalid trailer value"))?;
+    Ok((name, value))
+}
+
+impl<Inner> AwsChunkedDecodingBody<Inner> {
+    /// Decodes as much of the buffered body as possible. Returns `None` if more of the body must be
+    /// read first.
+    fn decode(self: Pin<&mut Self>) -> Result<Option<Option<Bytes>>, AwsChunkedDecodingError> {
+        let this = self.project();
+        loop {
+            match this.state {
+                DecodingState::ReadingChunkHeader => {
+                    let Some(line) = take_line(this.buffer)? else {
+                        return Ok(None);
+                    };
+                    let (size, signature) = parse_chunk_header(&line)?;
+                    if this.verifier.is_some() && size > *this.max_chunk_size {
+                        return Err(AwsChunkedDecodingError::ChunkTooLarge {
+                            size,
+                            max_size: *this.max_chunk_size,
+                        });
+                    }
+                    if size == 0 {
+                        if let Some(verifier) = this.verifier.as_mut() {
+                            let signature =
+                                signature.ok_or(AwsChunkedDecodingError::MissingChunkSignature)?;
+                            if !verifier
+                                .verify_chunk(&[], &signature)
+                                .map_err(AwsChunkedDecodingError::VerificationFailed)?
+                            {
+                                return Err(AwsChunkedDecodingError::InvalidChunkSignature);
+                            }
+                        }
+                        if let Some(expected) = *this.expected_decoded_length {
+                            if expected != *this.decoded_length {
+                                return Err(AwsChunkedDecodingError::DecodedLengthMismatch {
+                                    expected,
+                                    actual: *this.decoded_length,
+                                });
+                            }
+                        }
+                        *this.state = DecodingState::ReadingTrailers;
+                    } else {
+                        *this.state = DecodingState::ReadingChunk {
+                            remaining: size,
+                            signature,
+                        };
+                    }
+                }
+                DecodingState::ReadingChunk {
+                    remaining,
+                    signature,
+                } => {
+                    let data = if let Some(verifier) = this.verifier.as_mut() {
+                        if this.buffer.len() < *remaining {
+                            return Ok(None);
+                        }
+                        let data = this.buffer.split_to(*remaining).freeze();
+                        let signature = signature
+                            .as_deref()
+                            .ok_or(AwsChunkedDecodingError::MissingChunkSignature)?;
+                        if !verifier
+                            .verify_chunk(&data, signature)
+                            .map_err(AwsChunkedDecodingError::VerificationFailed)?
+                        {
+                            return Err(AwsChunkedDecodingError::InvalidChunkSignature);
+                        }
+                        *this.state = DecodingState::ReadingChunkEnd;
+                        data
+                    } else {
+                        if this.buffer.is_empty() {
+                            return Ok(None);
+                        }
+                        let len = (*remaining).min(this.buffer.len());
+                        let data = this.buffer.split_to(len).freeze();
+                        *remaining -= len;
+                        if *remaining == 0 {
+                            *this.state = DecodingState::ReadingChunkEnd;
+                        }
+                        data
+                    };
+                    *this.decoded_length += data.len() as u64;
+                    if let Some(expected) = *this.expected_decoded_length {
+                        if *this.decoded_length > expected {
+                            return Err(AwsChunkedDecodingError::DecodedLengthMismatch {
+                                expected,
+                                actual: *this.decoded_length,
+                            });
+                        }
+                    }
+                    #[cfg(feature = "checksums")]
+                    if let Some(checksum) = this.checksum.as_mut() {
+                        checksum.update(&data);
+                    }
+                    return Ok(Some(Some(data)));
+                }
+                DecodingState::ReadingChunkEnd => {
+                    if this.buffer.len() < CRLF.len() {
+                        return Ok(None);
+                    }
+                    if this.buffer.split_to(CRLF.len()) != CRLF.as_bytes() {
+                        return Err(AwsChunkedDecodingError::Malformed(
+                            "chunk data isn't followed by CRLF",
+                        ));
+                    }
+                    *this.state = DecodingState::ReadingChunkHeader;
+                }
+                DecodingState::ReadingTrailers => {
+                    let Some(line) = take_line(this.buffer)? else {
+                        return Ok(None);
+                    };
+                    if !line.is_empty() {
+                        let (name, value) = parse_trailer(&line)?;
+                        this.trailers
+                            .get_or_insert_with(HeaderMap::new)
+                            .append(name, value);
+                        continue;
+                    }
+
+                    let mut trailers = this.trailers.take().unwrap_or_default();
+                    let signature = trailers.remove(TRAILER_SIGNATURE);
+                    if let Some(verifier) = this.verifier.as_mut() {
+                        if !trailers.is_empty() {
+                            let signature = signature
+                                .as_ref()
+                                .and_then(|signature| signature.to_str().ok())
+                                .ok_or(AwsChunkedDecodingError::MissingTrailerSignature)?;
+                            if !verifier
+                                .verify_trailers(&trailers, signature)
+                                .map_err(AwsChunkedDecodingError::VerificationFailed)?
+                            {
+                                return Err(AwsChunkedDecodingError::InvalidTrailerSignature);
+                            }
+                        }
+                    }
+                    #[cfg(feature = "checksums")]
+                    if let Some(checksum) = this.checksum.take() {
+                        let trailer_name = checksum.header_name();
+                        let expected = trailers
+                            .get(trailer_name)
+                            .ok_or(AwsChunkedDecodingError::MissingChecksum { trailer_name })?;
+                        let actual = checksum.header_value();
+                        if expected != actual {
+                            return Err(AwsChunkedDecodingError::ChecksumMismatch {
+                                expected: String::from_utf8_lossy(expected.as_bytes()).into(),
+                                actual: String::from_utf8_lossy(actual.as_bytes()).into(),
+                            });
+                        }
+                    }
+                    if !trailers.is_empty() {
+                        *this.trailers = Some(trailers);
+                    }
+                    *this.state = DecodingState::Done;
+                    return Ok(Some(None));
+                }
+                DecodingState::Done => return Ok(Some(None)),
+            }
+        }
+    }
+}
+
+impl<Inner> Body for AwsChunkedDecodingBody<Inner>
+where
+    Inner: Body<Data = Bytes, Error = aws_smithy_types::body::Error>,
+{
+    type Data = Bytes;
+    type Error = aws_smithy_types::body::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        loop {
+            match self.as_mut().decode() {
+                Ok(Some(data)) => return Poll::Ready(data.map(Ok)),
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::debug!(error = %err, "failed to decode aws-chunked body");
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+            }
+
+            let this = self.as_mut().project();
+            if *this.inner_done {
+                return Poll::Ready(Some(Err(AwsChunkedDecodingError::UnexpectedEof.into())));
+            }
+            match this.inner.poll_data(cx) {
+                Poll::Ready(Some(Ok(data))) => this.buffer.extend_from_slice(&data),
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => *this.inner_done = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
+        // Trailers are part of the encoded body, so they are only known once it has been decoded
+        Poll::Ready(Ok(self.project().trailers.take()))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.state == DecodingState::Done && self.trailers.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match self.expected_decoded_length {
+            Some(length) => SizeHint::with_exact(length),
+            None => SizeHint::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_encoding::{
+        AwsChunkedBody, AwsChunkedBodyOptions, DeferredChunkSigner, SignChunk,
+    };
+    use aws_sigv4::SignatureVersion;
+    use aws_smithy_types::body::SdkBody;
+
+    async fn decode(
+        mut body: AwsChunkedDecodingBody<SdkBody>,
+    ) -> Result<(String, Option<HeaderMap>), BoxError> {
+        let mut output = Vec::new();
+        while let Some(data) = body.data().await {
+            output.extend_from_slice(&data?);
+        }
+        let trailers = body.trailers().await?;
+        Ok((String::from_utf8(output).unwrap(), trailers))
+    }
+
+    fn decoding_error(err: BoxError) -> AwsChunkedDecodingError {
+        *err.downcast().expect("an AwsChunkedDecodingError")
+    }
+
+    #[tokio::test]
+    async fn decodes_unsigned_chunks_and_trailers() {
+        let encoded = "5\r\nHello\r\n6\r\n world\r\n0\r\n\
+            x-amz-checksum-crc32:i9aeUg==\r\n\
+            x-amz-meta-a: b \r\n\
+            \r\n";
+        let body =
+            AwsChunkedDecodingBody::new(SdkBody::from(encoded)).with_decoded_content_length(11);
+        assert_eq!(Some(11), body.size_hint().exact());
+
+        let (data, trailers) = decode(body).await.unwrap();
+        assert_eq!("Hello world", data);
+        let trailers = trailers.unwrap();
+        assert_eq!("i9aeUg==", trailers["x-amz-checksum-crc32"]);
+        assert_eq!("b", trailers["x-amz-meta-a"]);
+    }
+
+    #[tokio::test]
+    async fn decodes_what_aws_chunked_body_encodes() {
+        let body = AwsChunkedBody::new(
+            SdkBody::from("Hello world"),
+            AwsChunkedBodyOptions::new(11, Vec::new()),
+        );
+        let body = AwsChunkedDecodingBody::new(SdkBody::from_body_0_4(body));
+        let (data, trailers) = decode(body).await.unwrap();
+        assert_eq!("Hello world", data);
+        assert!(trailers.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_bodies() {
+        for (encoded, expected) in [
+            ("5\r\nHello", "UnexpectedEof"),
+            ("5\r\nHello\r\n", "UnexpectedEof"),
+            ("5\r\nHello!\r\n0\r\n\r\n", "Malformed"),
+            ("x\r\n", "Malformed"),
+            ("5;foo=bar\r\nHello\r\n0\r\n\r\n", "Malformed"),
+        ] {
+            let body = AwsChunkedDecodingBody::new(SdkBody::from(encoded));
+            let err = decoding_error(decode(body).await.unwrap_err());
+            assert!(
+                format!("{err:?}").starts_with(expected),
+                "{encoded:?}: {err:?}"
+            );
+        }
+
+        let body = AwsChunkedDecodingBody::new(SdkBody::from(vec![b'a'; 10_000]));
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::Malformed(_)),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn checks_decoded_length() {
+        let body = AwsChunkedDecodingBody::new(SdkBody::from("5\r\nHello\r\n0\r\n\r\n"))
+            .with_decoded_content_length(6);
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(
+                err,
+                AwsChunkedDecodingError::DecodedLengthMismatch {
+                    expected: 6,
+                    actual: 5
+                }
+            ),
+            "{err:?}"
+        );
+
+        let body = AwsChunkedDecodingBody::new(SdkBody::from("5\r\nHello\r\n0\r\n\r\n"))
+            .with_decoded_content_length(4);
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(
+                err,
+                AwsChunkedDecodingError::DecodedLengthMismatch {
+                    expected: 4,
+                    actual: 5
+                }
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[cfg(feature = "checksums")]
+    #[tokio::test]
+    async fn checks_checksums() {
+        use aws_smithy_checksums::ChecksumAlgorithm;
+
+        let encoded = "B\r\nHello world\r\n0\r\nx-amz-checksum-crc32:i9aeUg==\r\n\r\n";
+        let body = AwsChunkedDecodingBody::new(SdkBody::from(encoded))
+            .with_checksum(ChecksumAlgorithm::Crc32);
+        decode(body).await.unwrap();
+
+        let encoded = "B\r\nHello World\r\n0\r\nx-amz-checksum-crc32:i9aeUg==\r\n\r\n";
+        let body = AwsChunkedDecodingBody::new(SdkBody::from(encoded))
+            .with_checksum(ChecksumAlgorithm::Crc32);
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::ChecksumMismatch { .. }),
+            "{err:?}"
+        );
+
+        let body = AwsChunkedDecodingBody::new(SdkBody::from(encoded))
+            .with_checksum(ChecksumAlgorithm::Sha256);
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(
+                err,
+                AwsChunkedDecodingError::MissingChecksum {
+                    trailer_name: "x-amz-checksum-sha256"
+                }
+            ),
+            "{err:?}"
+        );
+    }
+
+    /// Signs and verifies chunks with signatures that are easy to predict
+    #[derive(Debug, Default)]
+    struct TestChunkSigner {
+        signed: usize,
+    }
+
+    impl SignChunk for TestChunkSigner {
+        fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+            self.signed += 1;
+            Ok(format!("{}-{}", self.signed, chunk.len()))
+        }
+
+        fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, BoxError> {
+            self.signed += 1;
+            Ok(format!("{}-{}", self.signed, trailers.len()))
+        }
+    }
+
+    impl VerifyChunk for TestChunkSigner {
+        fn verify_chunk(&mut self, chunk: &[u8], signature: &str) -> Result<bool, BoxError> {
+            Ok(self.sign_chunk(chunk)? == signature)
+        }
+
+        fn verify_trailers(
+            &mut self,
+            trailers: &HeaderMap,
+            signature: &str,
+        ) -> Result<bool, BoxError> {
+            Ok(self.sign_trailers(trailers)? == signature)
+        }
+    }
+
+    fn signed_body(trailers: &str) -> SdkBody {
+        SdkBody::from(format!(
+            "4;chunk-signature=1-4\r\nHell\r\n\
+            7;chunk-signature=2-7\r\no world\r\n\
+            0;chunk-signature=3-0\r\n{trailers}\r\n"
+        ))
+    }
+
+    #[tokio::test]
+    async fn verifies_chunk_signatures() {
+        let body = AwsChunkedDecodingBody::new(signed_body(""))
+            .with_chunk_verifier(TestChunkSigner::default());
+        assert_eq!("Hello world", decode(body).await.unwrap().0);
+
+        let body = AwsChunkedDecodingBody::new(signed_body(
+            "x-amz-checksum-crc32:i9aeUg==\r\nx-amz-trailer-signature:4-1\r\n",
+        ))
+        .with_chunk_verifier(TestChunkSigner::default());
+        let (_, trailers) = decode(body).await.unwrap();
+        let trailers = trailers.unwrap();
+        assert_eq!(1, trailers.len(), "the trailer signature is removed");
+
+        let body = AwsChunkedDecodingBody::new(signed_body(
+            "x-amz-checksum-crc32:i9aeUg==\r\nx-amz-trailer-signature:5-1\r\n",
+        ))
+        .with_chunk_verifier(TestChunkSigner::default());
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::InvalidTrailerSignature),
+            "{err:?}"
+        );
+
+        let body = AwsChunkedDecodingBody::new(signed_body("x-amz-checksum-crc32:i9aeUg==\r\n"))
+            .with_chunk_verifier(TestChunkSigner::default());
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::MissingTrailerSignature),
+            "{err:?}"
+        );
+
+        let body = AwsChunkedDecodingBody::new(SdkBody::from(
+            "4;chunk-signature=1-5\r\nHell\r\n0;chunk-signature=2-0\r\n\r\n",
+        ))
+        .with_chunk_verifier(TestChunkSigner::default());
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::InvalidChunkSignature),
+            "{err:?}"
+        );
+
+        let body = AwsChunkedDecodingBody::new(SdkBody::from("4\r\nHell\r\n0\r\n\r\n"))
+            .with_chunk_verifier(TestChunkSigner::default());
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::MissingChunkSignature),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_verified_chunks_larger_than_the_max_chunk_size() {
+        // Only the header of the chunk is sent; it is rejected before its data is buffered
+        let body = AwsChunkedDecodingBody::new(SdkBody::from("ffffffff;chunk-signature=1-0\r\n"))
+            .with_chunk_verifier(TestChunkSigner::default());
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(
+                err,
+                AwsChunkedDecodingError::ChunkTooLarge {
+                    size: 0xffffffff,
+                    max_size: DEFAULT_MAX_CHUNK_SIZE
+                }
+            ),
+            "{err:?}"
+        );
+
+        let body = AwsChunkedDecodingBody::new(signed_body(""))
+            .with_chunk_verifier(TestChunkSigner::default())
+            .with_max_chunk_size(4);
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(
+                err,
+                AwsChunkedDecodingError::ChunkTooLarge {
+                    size: 7,
+                    max_size: 4
+                }
+            ),
+            "{err:?}"
+        );
+
+        // Unverified chunks are passed on as they arrive, so they aren't limited
+        let body = AwsChunkedDecodingBody::new(SdkBody::from("7\r\nHello w\r\n0\r\n\r\n"))
+            .with_max_chunk_size(4);
+        assert_eq!("Hello w", decode(body).await.unwrap().0);
+    }
+
+    #[tokio::test]
+    async fn returns_verifier_errors() {
+        #[derive(Debug)]
+        struct FailingVerifier;
+
+        impl VerifyChunk for FailingVerifier {
+            fn verify_chunk(&mut self, _: &[u8], _: &str) -> Result<bool, BoxError> {
+                Err("no credentials".into())
+            }
+
+            fn verify_trailers(&mut self, _: &HeaderMap, _: &str) -> Result<bool, BoxError> {
+                Err("no credentials".into())
+            }
+        }
+
+        let body =
+            AwsChunkedDecodingBody::new(signed_body("")).with_chunk_verifier(FailingVerifier);
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::VerificationFailed(_)),
+            "{err:?}"
+        );
+        assert_eq!(
+            "no credentials",
+            std::error::Error::source(&err).unwrap().to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn verifies_sigv4_chunk_signatures() {
+        use aws_credential_types::Credentials;
+        use aws_sigv4::http_request::{
+            sign, verify, PayloadChecksumKind, SignableBody, SignableRequest, SigningSettings,
+            VerificationSettings,
+        };
+        use aws_sigv4::sign::v4;
+        use aws_smithy_types::config_bag::{ConfigBag, Layer};
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let identity: Identity = Credentials::for_tests().into();
+        let mut settings = SigningSettings::default();
+        settings.payload_checksum_kind = PayloadChecksumKind::XAmzSha256;
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region("us-east-1")
+            .name("s3")
+            .time(now)
+            .settings(settings)
+            .build()
+            .unwrap()
+            .into();
+
+        // Sign the request on the client, and verify it on the server
+        let mut request = http_02x::Request::builder()
+            .method("PUT")
+            .uri("/bucket/key")
+            .header("host", "example.com")
+            .header("x-amz-decoded-content-length", "11")
+            .body(())
+            .unwrap();
+        let signable = SignableRequest::new(
+            "PUT",
+            "/bucket/key",
+            request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.to_str().unwrap())),
+            SignableBody::StreamingSignedPayload,
+        )
+        .unwrap();
+        let (instructions, seed_signature) = sign(signable, &params).unwrap().into_parts();
+        instructions.apply_to_request_http0x(&mut request);
+        let signable = SignableRequest::new(
+            "PUT",
+            "/bucket/key",
+            request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.to_str().unwrap())),
+            SignableBody::StreamingSignedPayload,
+        )
+        .unwrap();
+        let verified = verify(
+            &signable,
+            &VerificationSettings::default(),
+            |_| Some(Credentials::for_tests()),
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            "STREAMING-AWS4-HMAC-SHA256-PAYLOAD",
+            verified.payload_hash()
+        );
+
+        // Encode the body with signed chunks, as the client's signer does
+        let (signer, sender) = DeferredChunkSigner::new(SignatureVersion::V4);
+        let mut layer = Layer::new("test");
+        layer.store_put(sender);
+        crate::auth::send_chunk_signer(
+            &ConfigBag::of_layers(vec![layer]),
+            &seed_signature,
+            &identity,
+            &params,
+        )
+        .unwrap();
+        let mut encoded = AwsChunkedBody::new(
+            SdkBody::from("Hello world"),
+            AwsChunkedBodyOptions::new(11, Vec::new())
+                .with_chunk_signer(signer)
+                .with_signed_chunk_size(4),
+        );
+        let mut encoded_bytes = Vec::new();
+        while let Some(data) = encoded.data().await {
+            encoded_bytes.extend_from_slice(&data.unwrap());
+        }
+
+        let body =
+            AwsChunkedDecodingBody::new(SdkBody::from(encoded_bytes.clone())).with_chunk_verifier(
+                ChunkSignatureVerifier::from_verified_request(&verified, identity.clone()),
+            );
+        assert_eq!("Hello world", decode(body).await.unwrap().0);
+
+        let tampered = String::from_utf8(encoded_bytes)
+            .unwrap()
+            .replace("o wo", "o Wo");
+        let body = AwsChunkedDecodingBody::new(SdkBody::from(tampered)).with_chunk_verifier(
+            ChunkSignatureVerifier::from_verified_request(&verified, identity),
+        );
+        let err = decoding_error(decode(body).await.unwrap_err());
+        assert!(
+            matches!(err, AwsChunkedDecodingError::InvalidChunkSignature),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn decodes_signed_aws_chunked_body() {
+        let (signer, sender) = DeferredChunkSigner::new(SignatureVersion::V4);
+        sender.send(Box::<TestChunkSigner>::default()).unwrap();
+        let body = AwsChunkedBody::new(
+            SdkBody::from("Hello world"),
+            AwsChunkedBodyOptions::new(11, Vec::new())
+                .with_chunk_signer(signer)
+                .with_signed_chunk_size(4),
+        );
+        let body = AwsChunkedDecodingBody::new(SdkBody::from_body_0_4(body))
+            .with_chunk_verifier(TestChunkSigner::default())
+            .with_decoded_content_length(11);
+        assert_eq!("Hello world", decode(body).await.unwrap().0);
+    }
+}
@@ -12,6 +12,7 @@ use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 
+pub mod document;
 pub mod error;
 pub mod file;
 mod normalize;
@@ -74,7 +75,7 @@ impl<'a> fmt::Display for Scope<'a> {
 /// - Whether some config came from a config file or an env var.
 /// - The key used to identify the config value.
 ///
-/// Only used when displaying config-extraction errors.
+/// Used when displaying config-extraction errors and when explaining where config came from.
 #[derive(Debug)]
 pub struct EnvConfigSource<'a> {
     key: Cow<'a, str>,
@@ -132,6 +133,27 @@ impl<'a> EnvConfigSource<'a> {
             scope: Scope::Service { service_id },
         }
     }
+
+    /// The environment variable or profile key that was read
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The profile that was read, or `None` if the value came from an environment variable
+    pub fn profile_name(&self) -> Option<&str> {
+        match &self.location {
+            Location::Environment => None,
+            Location::Profile { name } => Some(name),
+        }
+    }
+
+    /// The service that the value was specific to, or `None` if the value applies to all services
+    pub fn service_id(&self) -> Option<&str> {
+        match &self.scope {
+            Scope::Global => None,
+            Scope::Service { service_id } => Some(service_id),
+        }
+    }
 }
 
 impl<'a> fmt::Display for EnvConfigSource<'a> {
@@ -249,103 +271,95 @@ impl<'a> EnvConfigValue<'a> {
         env: &'a Env,
         profiles: Option<&'a EnvConfigSections>,
     ) -> Option<(Cow<'a, str>, EnvConfigSource<'a>)> {
-        let env_value = self.environment_variable.as_ref().and_then(|env_var| {
-            // Check for a service-specific env var first
-            let service_config =
-                get_service_config_from_env(env, self.service_id.clone(), env_var.clone());
-            // Then check for a global env var
-            let global_config = env.get(env_var).ok().map(|value| {
-                (
-                    Cow::Owned(value),
-                    EnvConfigSource::global_from_env(env_var.clone()),
-                )
-            });
-
-            if let Some(v) = service_config {
-                tracing::trace!("(service env) {env_var} = {v:?}");
-                Some(v)
-            } else if let Some(v) = global_config {
-                tracing::trace!("(global env) {env_var} = {v:?}");
-                Some(v)
-            } else {
-                tracing::trace!("(env) no value set for {env_var}");
-                None
-            }
-        });
+        self.candidates(env, profiles)
+            .into_iter()
+            .find_map(|(source, value)| value.map(|value| (value, source)))
+    }
 
-        let profile_value = match (profiles, self.profile_key.as_ref()) {
-            (Some(profiles), Some(profile_key)) => {
-                // Check for a service-specific profile key first
-                let service_config = get_service_config_from_profile(
+    /// Return every location this value may be loaded from, in order of precedence, along with
+    /// the value set there, if any
+    ///
+    /// [`load`](Self::load) returns the first of these that is set. Service-specific locations are
+    /// only included when a [service ID](Self::service_id) is set, and profile locations are only
+    /// included when `profiles` are given.
+    pub fn candidates(
+        &self,
+        env: &'a Env,
+        profiles: Option<&'a EnvConfigSections>,
+    ) -> Vec<(EnvConfigSource<'a>, Option<Cow<'a, str>>)> {
+        let mut candidates = Vec::new();
+        if let Some(env_var) = &self.environment_variable {
+            if let Some(service_id) = &self.service_id {
+                candidates.push(get_service_config_from_env(
+                    env,
+                    service_id.clone(),
+                    env_var.clone(),
+                ));
+            }
+            let value = env.get(env_var).ok().map(Cow::Owned);
+            candidates.push((EnvConfigSource::global_from_env(env_var.clone()), value));
+        }
+        if let (Some(profiles), Some(profile_key)) = (profiles, &self.profile_key) {
+            let profile_name = || Cow::Owned(profiles.selected_profile().to_owned());
+            if let Some(service_id) = &self.service_id {
+                candidates.push(get_service_config_from_profile(
                     profiles,
-                    self.service_id.clone(),
+                    service_id.clone(),
                     profile_key.clone(),
-                );
-                let global_config = profiles.get(profile_key.as_ref()).map(|value| {
-                    (
-                        Cow::Borrowed(value),
-                        EnvConfigSource::global_from_profile(
-                            profile_key.clone(),
-                            Cow::Owned(profiles.selected_profile().to_owned()),
-                        ),
-                    )
-                });
-
-                if let Some(v) = service_config {
-                    tracing::trace!("(service profile) {profile_key} = {v:?}");
-                    Some(v)
-                } else if let Some(v) = global_config {
-                    tracing::trace!("(global profile) {profile_key} = {v:?}");
-                    Some(v)
-                } else {
-                    tracing::trace!("(service profile) no value set for {profile_key}");
-                    None
-                }
+                ));
             }
-            _ => None,
-        };
-
-        env_value.or(profile_value)
+            let value = profiles.get(profile_key.as_ref()).map(Cow::Borrowed);
+            candidates.push((
+                EnvConfigSource::global_from_profile(profile_key.clone(), profile_name()),
+                value,
+            ));
+        }
+        for (source, value) in &candidates {
+            match value {
+                Some(value) => tracing::trace!("({source}) = {value:?}"),
+                None => tracing::trace!("({source}) no value set"),
+            }
+        }
+        candidates
     }
 }
 
 fn get_service_config_from_env<'a>(
     env: &'a Env,
-    service_id: Option<Cow<'a, str>>,
+    service_id: Cow<'a, str>,
     env_var: Cow<'a, str>,
-) -> Option<(Cow<'a, str>, EnvConfigSource<'a>)> {
-    let service_id = service_id?;
+) -> (EnvConfigSource<'a>, Option<Cow<'a, str>>) {
     let env_case_service_id = format_service_id_for_env(service_id.clone());
     let service_specific_env_key = format!("{env_var}_{env_case_service_id}");
-    let env_var = env.get(&service_specific_env_key).ok()?;
-    let env_var: Cow<'_, str> = Cow::Owned(env_var);
-    let source = EnvConfigSource::service_from_env(env_var.clone(), service_id);
+    let value = env.get(&service_specific_env_key).ok().map(Cow::Owned);
+    let source =
+        EnvConfigSource::service_from_env(Cow::Owned(service_specific_env_key), service_id);
 
-    Some((env_var, source))
+    (source, value)
 }
 
 const SERVICES: &str = "services";
 
 fn get_service_config_from_profile<'a>(
     profile: &EnvConfigSections,
-    service_id: Option<Cow<'a, str>>,
+    service_id: Cow<'a, str>,
     profile_key: Cow<'a, str>,
-) -> Option<(Cow<'a, str>, EnvConfigSource<'a>)> {
-    let service_id = service_id?.clone();
+) -> (EnvConfigSource<'a>, Option<Cow<'a, str>>) {
     let profile_case_service_id = format_service_id_for_profile(service_id.clone());
-    let services_section_name = profile.get(SERVICES)?;
-    let properties_key = PropertiesKey::builder()
-        .section_key(SERVICES)
-        .section_name(services_section_name)
-        .property_name(profile_case_service_id)
-        .sub_property_name(profile_key.clone())
-        .build()
-        .ok()?;
-    let value = profile.other_sections().get(&properties_key)?;
+    let value = profile.get(SERVICES).and_then(|services_section_name| {
+        let properties_key = PropertiesKey::builder()
+            .section_key(SERVICES)
+            .section_name(services_section_name)
+            .property_name(profile_case_service_id)
+            .sub_property_name(profile_key.clone())
+            .build()
+            .ok()?;
+        profile.other_sections().get(&properties_key)
+    });
     let profile_name = Cow::Owned(profile.selected_profile().to_owned());
     let source = EnvConfigSource::service_from_profile(profile_key, profile_name, service_id);
 
-    Some((Cow::Owned(value.to_owned()), source))
+    (source, value.map(|value| Cow::Owned(value.to_owned())))
 }
 
 fn format_service_id_for_env(service_id: impl AsRef<str>) -> String {
@@ -516,6 +530,52 @@ mod test {
         assert_eq!(Some(4), service_from_profile);
     }
 
+    #[test]
+    fn candidates_in_order_of_precedence() {
+        let env = Env::from_slice(&[("AWS_SOME_KEY", "1"), ("AWS_SOME_KEY_S3", "2")]);
+        let profiles = EnvConfigSections::new(
+            HashMap::from([(
+                "dev".to_owned(),
+                HashMap::from([("services".to_owned(), "dev".to_owned())]),
+            )]),
+            Cow::Borrowed("dev"),
+            HashMap::new(),
+            Properties::new_from_slice(&[(
+                new_prop_key("services", "dev", "s3", Some("some_key")),
+                "4".to_string(),
+            )]),
+        );
+        let value = EnvConfigValue::new()
+            .env("AWS_SOME_KEY")
+            .profile("some_key")
+            .service_id("s3");
+        let candidates = value.candidates(&env, Some(&profiles));
+        let candidates: Vec<_> = candidates
+            .iter()
+            .map(|(source, value)| {
+                (
+                    source.key(),
+                    source.profile_name(),
+                    source.service_id(),
+                    value.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("AWS_SOME_KEY_S3", None, Some("s3"), Some("2")),
+                ("AWS_SOME_KEY", None, None, Some("1")),
+                ("some_key", Some("dev"), Some("s3"), Some("4")),
+                ("some_key", Some("dev"), None, None),
+            ],
+            candidates
+        );
+        assert_eq!(
+            Some(Cow::Borrowed("2")),
+            value.load(&env, Some(&profiles)).map(|(value, _)| value)
+        );
+    }
+
     #[tokio::test]
     async fn test_multiple_services() {
         let env = Env::from_slice(&[
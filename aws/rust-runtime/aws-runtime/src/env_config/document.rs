@@ -0,0 +1,1057 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Editable representation of config and credentials files
+//!
+//! [`EnvConfigSections`](crate::env_config::section::EnvConfigSections) is the SDK's view of the
+//! merged config: comments, ordering and anything the SDK ignores are discarded. An
+//! [`EnvConfigDocument`] keeps every line of a single file so that it can be modified and written
+//! back without disturbing the rest of the file.
+//!
+//! # Example: Adding a profile
+//!
+//! ```no_run
+//! use aws_runtime::env_config::document::{EnvConfigDocument, SectionId};
+//! use aws_runtime::env_config::file::EnvConfigFileKind;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let path = "/home/user/.aws/config";
+//! let mut config = EnvConfigDocument::load(EnvConfigFileKind::Config, path)?;
+//! let dev = SectionId::profile("dev");
+//! config.set(&dev, "region", "us-west-2")?;
+//! config.set(&dev, "sso_session", "my-sso")?;
+//! config.set(&SectionId::sso_session("my-sso"), "sso_region", "us-east-1")?;
+//! config.set_sub_property(&SectionId::services("dev"), "s3", "endpoint_url", "http://localhost:4566")?;
+//! config.write_to(path)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::env_config::error::{CouldNotReadConfigFile, EnvConfigFileLoadError};
+use crate::env_config::file::EnvConfigFileKind;
+use crate::env_config::normalize::validate_identifier;
+use crate::env_config::parse::{
+    is_comment_line, is_empty_line, parse_profile_file, parse_property_line, prepare_line,
+    EnvConfigParseError, WHITESPACE,
+};
+use crate::env_config::source::File;
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const DEFAULT: &str = "default";
+const PROFILE_PREFIX: &str = "profile";
+const SSO_SESSION_PREFIX: &str = "sso-session";
+const SERVICES_PREFIX: &str = "services";
+const SUB_PROPERTY_INDENT: &str = "  ";
+
+/// Identifies a section of a config or credentials file
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SectionId {
+    /// A profile, like `[profile dev]` in the config file or `[dev]` in the credentials file
+    Profile(String),
+    /// An SSO session, like `[sso-session my-sso]`. Only valid in the config file.
+    SsoSession(String),
+    /// Service-specific settings, like `[services dev]`. Only valid in the config file.
+    Services(String),
+}
+
+impl SectionId {
+    /// The profile with the given name
+    pub fn profile(name: impl Into<String>) -> Self {
+        Self::Profile(name.into())
+    }
+
+    /// The SSO session with the given name
+    pub fn sso_session(name: impl Into<String>) -> Self {
+        Self::SsoSession(name.into())
+    }
+
+    /// The services section with the given name
+    pub fn services(name: impl Into<String>) -> Self {
+        Self::Services(name.into())
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Profile(name) | Self::SsoSession(name) | Self::Services(name) => name,
+        }
+    }
+
+    /// Identifies the section named by a header, following the same rules as the SDK.
+    ///
+    /// Returns `None` for sections that the SDK ignores or doesn't know about.
+    fn from_header(header: &str, kind: EnvConfigFileKind) -> Option<Self> {
+        let header = prepare_line(header, false)
+            .strip_prefix('[')?
+            .strip_suffix(']')?
+            .trim_matches(WHITESPACE);
+        let (prefix, name) = match header.split_once(WHITESPACE) {
+            Some((prefix, name)) => (Some(prefix), name.trim_matches(WHITESPACE)),
+            None => (None, header),
+        };
+        validate_identifier(name).ok()?;
+        match (kind, prefix) {
+            (EnvConfigFileKind::Config, None) if name == DEFAULT => Some(Self::profile(name)),
+            (EnvConfigFileKind::Config, Some(PROFILE_PREFIX)) => Some(Self::profile(name)),
+            (EnvConfigFileKind::Config, Some(SSO_SESSION_PREFIX)) => Some(Self::sso_session(name)),
+            (EnvConfigFileKind::Config, Some(SERVICES_PREFIX)) => Some(Self::services(name)),
+            (EnvConfigFileKind::Credentials, None) => Some(Self::profile(name)),
+            _ => None,
+        }
+    }
+
+    /// The header for this section in the given kind of file
+    fn header(&self, kind: EnvConfigFileKind) -> Result<String, EnvConfigEditError> {
+        match (kind, self) {
+            (EnvConfigFileKind::Config, Self::Profile(name)) if name == DEFAULT => {
+                Ok(format!("[{DEFAULT}]"))
+            }
+            (EnvConfigFileKind::Config, Self::Profile(name)) => {
+                Ok(format!("[{PROFILE_PREFIX} {name}]"))
+            }
+            (EnvConfigFileKind::Config, Self::SsoSession(name)) => {
+                Ok(format!("[{SSO_SESSION_PREFIX} {name}]"))
+            }
+            (EnvConfigFileKind::Config, Self::Services(name)) => {
+                Ok(format!("[{SERVICES_PREFIX} {name}]"))
+            }
+            (EnvConfigFileKind::Credentials, Self::Profile(name)) => Ok(format!("[{name}]")),
+            (EnvConfigFileKind::Credentials, _) => Err(EditErrorKind::SectionNotAllowed {
+                section: self.clone(),
+            }
+            .into()),
+        }
+    }
+}
+
+impl fmt::Display for SectionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Profile(name) => write!(f, "profile `{name}`"),
+            Self::SsoSession(name) => write!(f, "sso-session `{name}`"),
+            Self::Services(name) => write!(f, "services `{name}`"),
+        }
+    }
+}
+
+/// An error returned when a change can't be made to an [`EnvConfigDocument`]
+#[derive(Debug)]
+pub struct EnvConfigEditError {
+    kind: EditErrorKind,
+}
+
+#[derive(Debug)]
+enum EditErrorKind {
+    SectionNotAllowed { section: SectionId },
+    InvalidIdentifier { identifier: String },
+    InvalidValue { key: String, value: String },
+}
+
+impl From<EditErrorKind> for EnvConfigEditError {
+    fn from(kind: EditErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl fmt::Display for EnvConfigEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            EditErrorKind::SectionNotAllowed { section } => write!(
+                f,
+                "{section} can't be written to the credentials file; it belongs in the config file"
+            ),
+            EditErrorKind::InvalidIdentifier { identifier } => write!(
+                f,
+                "`{identifier}` is not a valid name; names may only contain letters, numbers and `_-/.%@:+`"
+            ),
+            EditErrorKind::InvalidValue { key, value } => write!(
+                f,
+                "the value `{value}` for `{key}` would not be read back as written; \
+                values can't span lines or contain comments"
+            ),
+        }
+    }
+}
+
+impl Error for EnvConfigEditError {}
+
+/// A line in a section other than a property
+fn is_trivia(line: &str) -> bool {
+    is_empty_line(line) || is_comment_line(line)
+}
+
+#[derive(Clone, Debug)]
+enum Entry {
+    /// A blank line or comment
+    Trivia(String),
+    /// A property, including its continuation lines and any trivia between them
+    Property { key: String, lines: Vec<String> },
+}
+
+impl Entry {
+    fn property(key: &str, value: &str) -> Self {
+        Entry::Property {
+            key: key.to_ascii_lowercase(),
+            lines: vec![format!("{key} = {value}")],
+        }
+    }
+
+    fn is_property(&self, name: &str) -> bool {
+        matches!(self, Entry::Property { key, .. } if key.eq_ignore_ascii_case(name))
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &String> {
+        match self {
+            Entry::Trivia(line) => std::slice::from_ref(line).iter(),
+            Entry::Property { lines, .. } => lines.iter(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SectionBlock {
+    header: String,
+    id: Option<SectionId>,
+    entries: Vec<Entry>,
+}
+
+/// A config or credentials file that can be modified and written back
+///
+/// Comments, blank lines, the order of sections and properties, and sections or properties that
+/// the SDK doesn't know about are all preserved. Only the lines that are changed are rewritten.
+///
+/// Like the SDK, a document treats sections that appear more than once in a file as a single
+/// section, and properties that appear more than once in a section as a single property whose
+/// last value wins. Changes are made to the last occurrence.
+#[derive(Clone)]
+pub struct EnvConfigDocument {
+    kind: EnvConfigFileKind,
+    /// Lines before the first section
+    preamble: Vec<String>,
+    sections: Vec<SectionBlock>,
+    trailing_newline: bool,
+}
+
+impl fmt::Debug for EnvConfigDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Security: Redact the contents since they may have credentials in them
+        f.debug_struct("EnvConfigDocument")
+            .field("kind", &self.kind)
+            .field(
+                "sections",
+                &self
+                    .sections
+                    .iter()
+                    .map(|section| &section.header)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl EnvConfigDocument {
+    /// Create an empty document for the given kind of file
+    pub fn new(kind: EnvConfigFileKind) -> Self {
+        Self {
+            kind,
+            preamble: Vec::new(),
+            sections: Vec::new(),
+            trailing_newline: true,
+        }
+    }
+
+    /// Parse the contents of a config or credentials file
+    ///
+    /// Returns the same errors as the SDK does when loading the file.
+    pub fn parse(kind: EnvConfigFileKind, contents: &str) -> Result<Self, EnvConfigParseError> {
+        // Validate with the SDK's parser so that the document only accepts files the SDK accepts
+        parse_profile_file(&File {
+            kind,
+            path: None,
+            contents: contents.to_string(),
+        })?;
+
+        let mut document = Self::new(kind);
+        document.trailing_newline = contents.is_empty() || contents.ends_with('\n');
+        for line in contents.lines() {
+            let Some(section) = document.sections.last_mut() else {
+                if line.starts_with('[') {
+                    document.push_section(line);
+                } else {
+                    // only trivia is valid before the first section
+                    document.preamble.push(line.to_string());
+                }
+                continue;
+            };
+            if is_trivia(line) {
+                section.entries.push(Entry::Trivia(line.to_string()));
+            } else if line.starts_with('[') {
+                document.push_section(line);
+            } else if line.starts_with(WHITESPACE) {
+                // A continuation of the last property. Any trivia since then is kept with it so
+                // that the lines stay in order.
+                let start = section
+                    .entries
+                    .iter()
+                    .rposition(|entry| matches!(entry, Entry::Property { .. }))
+                    .expect("the parser rejects continuations without a property");
+                let trivia = section.entries.split_off(start + 1);
+                let Some(Entry::Property { lines, .. }) = section.entries.last_mut() else {
+                    unreachable!("found above")
+                };
+                lines.extend(trivia.iter().flat_map(Entry::lines).cloned());
+                lines.push(line.to_string());
+            } else {
+                let (key, _value) =
+                    parse_property_line(line).expect("the parser rejects invalid properties");
+                section.entries.push(Entry::Property {
+                    key: key.into_owned(),
+                    lines: vec![line.to_string()],
+                });
+            }
+        }
+        Ok(document)
+    }
+
+    /// Load a config or credentials file from the given path
+    ///
+    /// If the file doesn't exist, an empty document is returned so that it can be created with
+    /// [`write_to`](Self::write_to).
+    pub fn load(
+        kind: EnvConfigFileKind,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, EnvConfigFileLoadError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(Self::parse(kind, &contents)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::new(kind)),
+            Err(err) => Err(EnvConfigFileLoadError::CouldNotReadFile(
+                CouldNotReadConfigFile {
+                    path: path.to_path_buf(),
+                    cause: Arc::new(err),
+                },
+            )),
+        }
+    }
+
+    /// The kind of file this document represents
+    pub fn kind(&self) -> EnvConfigFileKind {
+        self.kind
+    }
+
+    /// Returns the sections in this document that the SDK recognizes, in order of appearance
+    pub fn sections(&self) -> Vec<&SectionId> {
+        let mut sections = Vec::new();
+        for id in self.sections.iter().filter_map(|s| s.id.as_ref()) {
+            if !sections.contains(&id) {
+                sections.push(id);
+            }
+        }
+        sections
+    }
+
+    /// Returns the value of a property, the same way the SDK would read it
+    ///
+    /// For properties with sub-properties, the value contains one `key = value` line per
+    /// sub-property.
+    pub fn get(&self, section: &SectionId, key: &str) -> Option<String> {
+        let lines = self.find_property(section, key).map(|(s, e)| {
+            let Entry::Property { lines, .. } = &self.sections[s].entries[e] else {
+                unreachable!("find_property only returns properties")
+            };
+            lines
+        })?;
+        let (_key, first) = parse_property_line(&lines[0]).ok()?;
+        let mut value = first.to_string();
+        for line in lines[1..].iter().filter(|line| !is_trivia(line)) {
+            value.push('\n');
+            value.push_str(line.trim_matches(WHITESPACE));
+        }
+        Some(value)
+    }
+
+    /// Set a property, adding the section if it doesn't exist yet
+    ///
+    /// An existing property is rewritten in place. Otherwise, the property is added after the
+    /// last property of the section.
+    pub fn set(
+        &mut self,
+        section: &SectionId,
+        key: &str,
+        value: &str,
+    ) -> Result<(), EnvConfigEditError> {
+        self.validate(section, key)?;
+        validate_value(key, value)?;
+        match self.find_property(section, key) {
+            Some((s, e)) => self.sections[s].entries[e] = Entry::property(key, value),
+            None => self.insert_property(section, Entry::property(key, value))?,
+        }
+        Ok(())
+    }
+
+    /// Set a sub-property, like the `endpoint_url` of `s3` in a services section:
+    ///
+    /// ```ini
+    /// [services dev]
+    /// s3 =
+    ///   endpoint_url = http://localhost:4566
+    /// ```
+    ///
+    /// If the property has a plain value, it is replaced.
+    pub fn set_sub_property(
+        &mut self,
+        section: &SectionId,
+        key: &str,
+        sub_key: &str,
+        value: &str,
+    ) -> Result<(), EnvConfigEditError> {
+        self.validate(section, key)?;
+        validate(sub_key)?;
+        validate_value(&format!("{key}.{sub_key}"), value)?;
+        let line = format!("{SUB_PROPERTY_INDENT}{sub_key} = {value}");
+
+        let Some((s, e)) = self.find_property(section, key) else {
+            let entry = Entry::Property {
+                key: key.to_ascii_lowercase(),
+                lines: vec![format!("{key} ="), line],
+            };
+            return self.insert_property(section, entry);
+        };
+        let Entry::Property { lines, .. } = &mut self.sections[s].entries[e] else {
+            unreachable!("find_property only returns properties")
+        };
+        if !matches!(parse_property_line(&lines[0]), Ok((_, value)) if value.is_empty()) {
+            // replace the plain value
+            *lines = vec![format!("{key} =")];
+        }
+        match sub_property_index(lines, sub_key) {
+            Some(index) => {
+                let indent = &lines[index]
+                    [..lines[index].len() - lines[index].trim_start_matches(WHITESPACE).len()];
+                lines[index] = format!("{indent}{sub_key} = {value}");
+            }
+            None => {
+                let last = lines
+                    .iter()
+                    .rposition(|line| !is_trivia(line))
+                    .expect("the first line is the property");
+                lines.insert(last + 1, line);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a property from every occurrence of a section
+    ///
+    /// Returns `true` if the property was found.
+    pub fn remove(&mut self, section: &SectionId, key: &str) -> bool {
+        let mut removed = false;
+        for block in self.blocks_mut(section) {
+            block.entries.retain(|entry| {
+                let matches = entry.is_property(key);
+                removed |= matches;
+                !matches
+            });
+        }
+        removed
+    }
+
+    /// Remove a sub-property, and the property itself if it has no other sub-properties
+    ///
+    /// Returns `true` if the sub-property was found.
+    pub fn remove_sub_property(&mut self, section: &SectionId, key: &str, sub_key: &str) -> bool {
+        let Some((s, e)) = self.find_property(section, key) else {
+            return false;
+        };
+        let Entry::Property { lines, .. } = &mut self.sections[s].entries[e] else {
+            unreachable!("find_property only returns properties")
+        };
+        let Some(index) = sub_property_index(lines, sub_key) else {
+            return false;
+        };
+        lines.remove(index);
+        if lines[1..].iter().all(|line| is_trivia(line)) {
+            self.sections[s].entries.remove(e);
+        }
+        true
+    }
+
+    /// Remove every occurrence of a section, along with its properties and comments
+    ///
+    /// Returns `true` if the section was found.
+    pub fn remove_section(&mut self, section: &SectionId) -> bool {
+        let before = self.sections.len();
+        self.sections
+            .retain(|block| block.id.as_ref() != Some(section));
+        before != self.sections.len()
+    }
+
+    /// Atomically replace the file at `path` with the contents of this document
+    ///
+    /// The contents are written to a temporary file in the same directory, which is then renamed
+    /// over `path`, so that readers never see a partially written file. The permissions of an
+    /// existing file are kept. New files are only readable by their owner on Unix, since they
+    /// may contain credentials. Missing parent directories are created.
+    ///
+    /// If `path` is a symbolic link, the file it points to is replaced and the link is kept.
+    pub fn write_to(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        // Replacing the link itself would disconnect it from the file it points to
+        let path = &resolve_symlinks(path.as_ref())?;
+        let file_name = path.file_name().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("`{}` is not a file path", path.display()),
+            )
+        })?;
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        std::fs::create_dir_all(directory)?;
+        // Unique per call, so that concurrent writes from threads of this process don't share it
+        static WRITE_COUNT: AtomicU64 = AtomicU64::new(0);
+        let temp_path = directory.join(format!(
+            ".{}.{}.{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id(),
+            WRITE_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let write = || {
+            // a file left over from a process that crashed while writing
+            let _ = std::fs::remove_file(&temp_path);
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let mut file = options.open(&temp_path)?;
+            if let Ok(metadata) = std::fs::metadata(path) {
+                file.set_permissions(metadata.permissions())?;
+            }
+            file.write_all(self.to_string().as_bytes())?;
+            file.sync_all()?;
+            drop(file);
+            std::fs::rename(&temp_path, path)
+        };
+        write().map_err(|err| {
+            let _ = std::fs::remove_file(&temp_path);
+            err
+        })
+    }
+
+    fn validate(&self, section: &SectionId, key: &str) -> Result<(), EnvConfigEditError> {
+        validate(section.name())?;
+        validate(key)?;
+        section.header(self.kind).map(|_| ())
+    }
+
+    fn blocks_mut<'a>(
+        &'a mut self,
+        section: &'a SectionId,
+    ) -> impl Iterator<Item = &'a mut SectionBlock> {
+        self.sections
+            .iter_mut()
+            .filter(move |block| block.id.as_ref() == Some(section))
+    }
+
+    /// Returns the section and entry index of the last occurrence of a property
+    fn find_property(&self, section: &SectionId, key: &str) -> Option<(usize, usize)> {
+        self.sections
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, block)| block.id.as_ref() == Some(section))
+            .find_map(|(s, block)| {
+                block
+                    .entries
+                    .iter()
+                    .rposition(|entry| entry.is_property(key))
+                    .map(|e| (s, e))
+            })
+    }
+
+    fn insert_property(
+        &mut self,
+        section: &SectionId,
+        entry: Entry,
+    ) -> Result<(), EnvConfigEditError> {
+        if let Some(block) = self.blocks_mut(section).last() {
+            // after the last property, so that comments preceding the next section stay with it
+            let index = block
+                .entries
+                .iter()
+                .rposition(|entry| matches!(entry, Entry::Property { .. }))
+                .or_else(|| {
+                    block.entries.iter().rposition(
+                        |entry| !matches!(entry, Entry::Trivia(line) if is_empty_line(line)),
+                    )
+                })
+                .map_or(0, |index| index + 1);
+            block.entries.insert(index, entry);
+            return Ok(());
+        }
+
+        let header = section.header(self.kind)?;
+        let last_line = match self.sections.last() {
+            Some(block) => block.entries.last().and_then(|e| e.lines().last()),
+            None => self.preamble.last(),
+        };
+        // separate the new section from the previous one
+        if last_line.map_or(false, |line| !is_empty_line(line))
+            || self.sections.last().map_or(false, |b| b.entries.is_empty())
+        {
+            match self.sections.last_mut() {
+                Some(block) => block.entries.push(Entry::Trivia(String::new())),
+                None => self.preamble.push(String::new()),
+            }
+        }
+        self.sections.push(SectionBlock {
+            header,
+            id: Some(section.clone()),
+            entries: vec![entry],
+        });
+        Ok(())
+    }
+
+    fn push_section(&mut self, header: &str) {
+        self.sections.push(SectionBlock {
+            header: header.to_string(),
+            id: SectionId::from_header(header, self.kind),
+            entries: Vec::new(),
+        });
+    }
+}
+
+impl fmt::Display for EnvConfigDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .preamble
+            .iter()
+            .chain(self.sections.iter().flat_map(|section| {
+                std::iter::once(&section.header)
+                    .chain(section.entries.iter().flat_map(Entry::lines))
+            }));
+        for (index, line) in lines.enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(line)?;
+        }
+        if self.trailing_newline && !(self.preamble.is_empty() && self.sections.is_empty()) {
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+fn validate(identifier: &str) -> Result<(), EnvConfigEditError> {
+    match validate_identifier(identifier) {
+        Ok(_) if !identifier.is_empty() => Ok(()),
+        _ => Err(EditErrorKind::InvalidIdentifier {
+            identifier: identifier.into(),
+        }
+        .into()),
+    }
+}
+
+/// Checks that `value` will be read back exactly as written
+fn validate_value(key: &str, value: &str) -> Result<(), EnvConfigEditError> {
+    let line = format!("key = {value}");
+    match parse_property_line(&line) {
+        Ok((_, parsed)) if parsed == value && !value.contains(['\n', '\r']) => Ok(()),
+        _ => Err(EditErrorKind::InvalidValue {
+            key: key.into(),
+            value: value.into(),
+        }
+        .into()),
+    }
+}
+
+/// Returns the index of the line that sets `sub_key`, if there is one
+fn sub_property_index(lines: &[String], sub_key: &str) -> Option<usize> {
+    lines.iter().skip(1).position(|line| {
+        !is_trivia(line)
+            && matches!(parse_property_line(line), Ok((key, _)) if key.eq_ignore_ascii_case(sub_key))
+    })
+    .map(|index| index + 1)
+}
+
+/// Follows `path` through any symbolic links to the file they point to, which may not exist yet
+fn resolve_symlinks(path: &Path) -> std::io::Result<PathBuf> {
+    // The same limit on the number of links followed as Linux's `ELOOP`
+    const MAX_LINKS: usize = 40;
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_LINKS {
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let target = std::fs::read_link(&path)?;
+                // Relative targets are relative to the directory containing the link
+                path = match path.parent() {
+                    Some(parent) => parent.join(target),
+                    None => target,
+                };
+            }
+            _ => return Ok(path),
+        }
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("too many levels of symbolic links at `{}`", path.display()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EnvConfigDocument, SectionId};
+    use crate::env_config::file::EnvConfigFileKind;
+    use crate::env_config::normalize::merge_in;
+    use crate::env_config::parse::parse_profile_file;
+    use crate::env_config::property::PropertiesKey;
+    use crate::env_config::section::EnvConfigSections;
+    use crate::env_config::source::File;
+
+    const CONFIG: &str = "\
+# shared settings for the team
+[default]
+region = us-east-1 # the default region
+output = json
+
+; the development account
+[profile dev]
+sso_session = my-sso
+Unknown_Key = kept
+s3 =
+  addressing_style = path
+
+[sso-session my-sso]
+sso_start_url = https://example.awsapps.com/start
+sso_region = us-east-1
+
+[unknown section]
+whatever = 1
+";
+
+    fn config() -> EnvConfigDocument {
+        EnvConfigDocument::parse(EnvConfigFileKind::Config, CONFIG).expect("valid config")
+    }
+
+    /// Parses the document the way the SDK would
+    fn sdk_view(document: &EnvConfigDocument) -> EnvConfigSections {
+        let file = File {
+            kind: document.kind(),
+            path: None,
+            contents: document.to_string(),
+        };
+        let mut sections = EnvConfigSections::default();
+        merge_in(
+            &mut sections,
+            parse_profile_file(&file).unwrap(),
+            document.kind(),
+        );
+        sections
+    }
+
+    #[test]
+    fn unmodified_documents_round_trip() {
+        assert_eq!(CONFIG, config().to_string());
+        for contents in ["", "\n", "[default]\nregion = us-east-1", "# comment only"] {
+            let document = EnvConfigDocument::parse(EnvConfigFileKind::Config, contents).unwrap();
+            assert_eq!(contents, document.to_string());
+        }
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        let err = EnvConfigDocument::parse(EnvConfigFileKind::Config, "[default\nk=v")
+            .expect_err("unterminated section header");
+        assert!(format!("{}", err).contains("Profile definition must end with ']'"));
+    }
+
+    #[test]
+    fn get_reads_values_like_the_sdk() {
+        let config = config();
+        let default = SectionId::profile("default");
+        assert_eq!(Some("us-east-1".into()), config.get(&default, "region"));
+        assert_eq!(
+            Some("kept".into()),
+            config.get(&SectionId::profile("dev"), "unknown_key")
+        );
+        assert_eq!(
+            Some("\naddressing_style = path".into()),
+            config.get(&SectionId::profile("dev"), "s3")
+        );
+        assert_eq!(None, config.get(&default, "missing"));
+        assert_eq!(
+            vec![
+                &SectionId::profile("default"),
+                &SectionId::profile("dev"),
+                &SectionId::sso_session("my-sso")
+            ],
+            config.sections()
+        );
+    }
+
+    #[test]
+    fn set_updates_properties_in_place() {
+        let mut config = config();
+        config
+            .set(&SectionId::profile("default"), "region", "us-west-2")
+            .unwrap();
+        assert_eq!(
+            CONFIG.replace(
+                "region = us-east-1 # the default region",
+                "region = us-west-2"
+            ),
+            config.to_string()
+        );
+    }
+
+    #[test]
+    fn set_adds_properties_after_the_last_property_of_a_section() {
+        let mut config = config();
+        config
+            .set(&SectionId::profile("default"), "retry_mode", "adaptive")
+            .unwrap();
+        assert_eq!(
+            CONFIG.replace("output = json\n", "output = json\nretry_mode = adaptive\n"),
+            config.to_string()
+        );
+    }
+
+    #[test]
+    fn set_adds_missing_sections_to_the_end() {
+        let mut config = config();
+        config
+            .set(&SectionId::profile("prod"), "region", "eu-west-1")
+            .unwrap();
+        config
+            .set(&SectionId::sso_session("other"), "sso_region", "eu-west-1")
+            .unwrap();
+        assert_eq!(
+            format!(
+                "{CONFIG}\n[profile prod]\nregion = eu-west-1\n\n[sso-session other]\nsso_region = eu-west-1\n"
+            ),
+            config.to_string()
+        );
+
+        let sdk = sdk_view(&config);
+        assert_eq!(
+            Some("eu-west-1"),
+            sdk.get_profile("prod").unwrap().get("region")
+        );
+        assert_eq!(
+            Some("eu-west-1"),
+            sdk.sso_session("other").unwrap().get("sso_region")
+        );
+    }
+
+    #[test]
+    fn new_documents() {
+        let mut credentials = EnvConfigDocument::new(EnvConfigFileKind::Credentials);
+        credentials
+            .set(&SectionId::profile("dev"), "aws_access_key_id", "AKID")
+            .unwrap();
+        credentials
+            .set(&SectionId::profile("prod"), "aws_access_key_id", "AKID2")
+            .unwrap();
+        assert_eq!(
+            "[dev]\naws_access_key_id = AKID\n\n[prod]\naws_access_key_id = AKID2\n",
+            credentials.to_string()
+        );
+        let err = credentials
+            .set(&SectionId::sso_session("my-sso"), "sso_region", "us-east-1")
+            .expect_err("sso sessions belong in the config file");
+        assert!(
+            format!("{}", err).contains("can't be written to the credentials file"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn values_must_round_trip() {
+        let mut config = config();
+        let default = SectionId::profile("default");
+        for value in ["two\nlines", "value # with a comment"] {
+            config
+                .set(&default, "region", value)
+                .expect_err("value would be read differently");
+        }
+        config
+            .set(&default, "not a key", "value")
+            .expect_err("invalid key");
+        config
+            .set(&SectionId::profile("has space"), "region", "us-east-1")
+            .expect_err("invalid profile name");
+        assert_eq!(CONFIG, config.to_string());
+    }
+
+    #[test]
+    fn sub_properties() {
+        let mut config = config();
+        let services = SectionId::services("dev");
+        config
+            .set_sub_property(&services, "s3", "endpoint_url", "http://localhost:4566")
+            .unwrap();
+        config
+            .set_sub_property(&services, "sts", "endpoint_url", "http://localhost:4567")
+            .unwrap();
+        config
+            .set_sub_property(&services, "s3", "endpoint_url", "http://localhost:4568")
+            .unwrap();
+        config
+            .set_sub_property(
+                &SectionId::profile("dev"),
+                "s3",
+                "use_accelerate_endpoint",
+                "true",
+            )
+            .unwrap();
+        assert!(config.to_string().ends_with(
+            "[services dev]\ns3 =\n  endpoint_url = http://localhost:4568\nsts =\n  endpoint_url = http://localhost:4567\n"
+        ), "{}", config);
+        assert!(config
+            .to_string()
+            .contains("s3 =\n  addressing_style = path\n  use_accelerate_endpoint = true\n"));
+
+        let sdk = sdk_view(&config);
+        let key = PropertiesKey::builder()
+            .section_key("services")
+            .section_name("dev")
+            .property_name("s3")
+            .sub_property_name("endpoint_url")
+            .build()
+            .unwrap();
+        assert_eq!(
+            Some("http://localhost:4568"),
+            sdk.other_sections().get(&key).map(String::as_str)
+        );
+
+        assert!(config.remove_sub_property(&services, "sts", "endpoint_url"));
+        assert!(!config.remove_sub_property(&services, "sts", "endpoint_url"));
+        assert_eq!(None, config.get(&services, "sts"));
+    }
+
+    #[test]
+    fn remove() {
+        let mut config = config();
+        assert!(config.remove(&SectionId::profile("default"), "output"));
+        assert!(!config.remove(&SectionId::profile("default"), "output"));
+        assert!(config.remove_section(&SectionId::sso_session("my-sso")));
+        assert!(!config.remove_section(&SectionId::sso_session("my-sso")));
+        let expected = CONFIG.replace("output = json\n", "").replace(
+            "[sso-session my-sso]\nsso_start_url = https://example.awsapps.com/start\nsso_region = us-east-1\n\n",
+            "",
+        );
+        assert_eq!(expected, config.to_string());
+    }
+
+    #[test]
+    fn write_to_replaces_the_file() {
+        let directory =
+            std::env::temp_dir().join(format!("env-config-document-test-{}", std::process::id()));
+        let path = directory.join("nested").join("config");
+        let _ = std::fs::remove_dir_all(&directory);
+
+        let mut config = EnvConfigDocument::load(EnvConfigFileKind::Config, &path)
+            .expect("missing files are empty documents");
+        config
+            .set(&SectionId::profile("default"), "region", "us-east-1")
+            .unwrap();
+        config.write_to(&path).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+
+        let mut config = EnvConfigDocument::load(EnvConfigFileKind::Config, &path).unwrap();
+        config
+            .set(&SectionId::profile("default"), "region", "us-west-2")
+            .unwrap();
+        config.write_to(&path).unwrap();
+        assert_eq!(
+            "[default]\nregion = us-west-2\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+        // no temporary files are left behind
+        assert_eq!(
+            1,
+            std::fs::read_dir(directory.join("nested")).unwrap().count()
+        );
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn concurrent_writes() {
+        let directory =
+            std::env::temp_dir().join(format!("env-config-concurrent-test-{}", std::process::id()));
+        let path = directory.join("config");
+        let _ = std::fs::remove_dir_all(&directory);
+
+        std::thread::scope(|scope| {
+            for region in ["us-east-1", "us-west-2", "eu-west-1", "ap-south-1"] {
+                let path = &path;
+                scope.spawn(move || {
+                    let mut config =
+                        EnvConfigDocument::parse(EnvConfigFileKind::Config, "").unwrap();
+                    config
+                        .set(&SectionId::profile("default"), "region", region)
+                        .unwrap();
+                    for _ in 0..20 {
+                        config.write_to(path).unwrap();
+                    }
+                });
+            }
+        });
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("[default]\nregion = "), "{contents}");
+        assert_eq!(1, std::fs::read_dir(&directory).unwrap().count());
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_to_follows_symlinks() {
+        let directory =
+            std::env::temp_dir().join(format!("env-config-symlink-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("dotfiles")).unwrap();
+        let target = directory.join("dotfiles").join("config");
+        std::fs::write(&target, "[default]\nregion = us-east-1\n").unwrap();
+        let link = directory.join("config");
+        std::os::unix::fs::symlink("dotfiles/config", &link).unwrap();
+
+        let mut config = EnvConfigDocument::load(EnvConfigFileKind::Config, &link).unwrap();
+        config
+            .set(&SectionId::profile("default"), "region", "us-west-2")
+            .unwrap();
+        config.write_to(&link).unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            "[default]\nregion = us-west-2\n",
+            std::fs::read_to_string(&target).unwrap()
+        );
+
+        // A link to a file that doesn't exist yet creates the file
+        let dangling = directory.join("credentials");
+        std::os::unix::fs::symlink("dotfiles/credentials", &dangling).unwrap();
+        config.write_to(&dangling).unwrap();
+        assert!(directory.join("dotfiles").join("credentials").is_file());
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
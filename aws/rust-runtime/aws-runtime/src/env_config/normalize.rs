@@ -199,7 +199,7 @@ fn merge_into_base(target: &mut dyn Section, profile: HashMap<Cow<'_, str>, Cow<
 /// Validate that a string is a valid identifier
 ///
 /// Identifiers must match `[A-Za-z0-9_\-/.%@:\+]+`
-fn validate_identifier(input: &str) -> Result<&str, ()> {
+pub(super) fn validate_identifier(input: &str) -> Result<&str, ()> {
     input
         .chars()
         .all(|ch| {
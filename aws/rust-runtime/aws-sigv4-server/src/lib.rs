@@ -0,0 +1,519 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+/* Automatically managed default lints */
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+/* End of automatically managed default lints */
+
+//! A plugin for `aws-smithy-http-server` services that accept requests signed with SigV4.
+//!
+//! Requests that fail verification are rejected with the protocol's `AccessDeniedException`
+//! (403), or its `UnauthorizedException` (401) if they aren't signed at all, before they reach the
+//! operation. For requests that pass, the [`VerifiedRequest`] is added to the request extensions,
+//! so operation handlers can find out who signed the request with
+//! [`Extension<VerifiedRequest>`](aws_smithy_http_server::Extension).
+//!
+//! Requests that sign a hash of their body in the `x-amz-content-sha256` header have their
+//! signature checked before the body is read. The body is then buffered, up to
+//! [`SigV4VerificationPlugin::with_max_body_size`] bytes, and checked against the signed hash.
+//! Requests without that header sign their body directly, so it is buffered, under the same limit,
+//! before the signature can be checked.
+//!
+//! ```no_run
+//! use aws_credential_types::Credentials;
+//! use aws_sigv4::http_request::VerificationSettings;
+//! use aws_sigv4_server::{SigV4VerificationExt, SigV4VerificationPlugin};
+//! use aws_smithy_http_server::plugin::HttpPlugins;
+//!
+//! let mut settings = VerificationSettings::default();
+//! settings.signing_name = Some("pokemonservice".into());
+//! let verification = SigV4VerificationPlugin::new(settings, |access_key_id| {
+//!     // Look up the secret for the access key, e.g. from a secrets store
+//!     # let _ = access_key_id;
+//!     Some(Credentials::new("AKIDEXAMPLE", "secret", None, None, "lookup"))
+//! });
+//! let http_plugins = HttpPlugins::new().verify_sigv4(verification);
+//! ```
+
+#![warn(
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    missing_debug_implementations,
+    rust_2018_idioms,
+    unreachable_pub
+)]
+
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{
+    verify, SignableBody, SignableRequest, VerificationError, VerificationErrorKind,
+    VerificationSettings, VerifiedRequest,
+};
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_http_server::auth::{AccessDeniedException, UnauthorizedException};
+use aws_smithy_http_server::body::BoxBody;
+use aws_smithy_http_server::plugin::{HttpMarker, HttpPlugins, Plugin, PluginStack};
+use aws_smithy_http_server::response::IntoResponse;
+use aws_smithy_http_server::service::ServiceShape;
+use bytes::Bytes;
+use http::{Request, Response};
+use http_body::{LengthLimitError, Limited};
+use hyper::Body;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::Service;
+
+/// The default for [`SigV4VerificationPlugin::with_max_body_size`]
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+const X_AMZ_CONTENT_SHA_256: &str = "x-amz-content-sha256";
+
+type LookupCredentials = dyn Fn(&str) -> Option<Credentials> + Send + Sync;
+
+#[derive(Clone)]
+struct Verifier {
+    settings: VerificationSettings,
+    lookup_credentials: Arc<LookupCredentials>,
+    time_source: SharedTimeSource,
+    max_body_size: usize,
+}
+
+impl Verifier {
+    fn verify(
+        &self,
+        parts: &http::request::Parts,
+        body: SignableBody<'_>,
+    ) -> Result<VerifiedRequest, VerificationError> {
+        let malformed = |reason: String| -> VerificationError {
+            VerificationErrorKind::MalformedSignature { reason }.into()
+        };
+        let headers = parts
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                value
+                    .to_str()
+                    .map(|value| (name.as_str(), value))
+                    .map_err(|_| {
+                        malformed(format!("the `{name}` header contains non-ASCII characters"))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let request = SignableRequest::new(
+            parts.method.as_str(),
+            parts.uri.to_string(),
+            headers.into_iter(),
+            body,
+        )
+        .map_err(|err| malformed(err.to_string()))?;
+        verify(
+            &request,
+            &self.settings,
+            &*self.lookup_credentials,
+            self.time_source.now(),
+        )
+    }
+
+    /// Reads the body, failing if it's larger than `max_body_size`
+    async fn read_body(
+        &self,
+        parts: &http::request::Parts,
+        body: Body,
+    ) -> Result<Bytes, Rejection> {
+        let too_large = || {
+            Rejection::AccessDenied(format!(
+                "the request body is larger than the limit of {} bytes",
+                self.max_body_size
+            ))
+        };
+        let content_length = parts
+            .headers
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        if content_length.map_or(false, |length| length > self.max_body_size as u64) {
+            return Err(too_large());
+        }
+        hyper::body::to_bytes(Limited::new(body, self.max_body_size))
+            .await
+            .map_err(|err| match err.downcast_ref::<LengthLimitError>() {
+                Some(_) => too_large(),
+                None => Rejection::AccessDenied(format!("failed to read the request body: {err}")),
+            })
+    }
+
+    async fn verify_request(&self, request: Request<Body>) -> Result<Request<Body>, Rejection> {
+        let (mut parts, body) = request.into_parts();
+        let signed_hash = parts
+            .headers
+            .get(X_AMZ_CONTENT_SHA_256)
+            .map(|value| value.as_bytes())
+            .filter(|value| value.len() == 64 && value.iter().all(u8::is_ascii_hexdigit))
+            .map(|value| value.to_vec());
+        let (verified, body) = match (
+            signed_hash,
+            parts.headers.contains_key(X_AMZ_CONTENT_SHA_256),
+        ) {
+            // The signature covers the claimed hash, so check it before reading the body
+            (Some(signed_hash), _) => {
+                let verified = self.verify(&parts, SignableBody::UnsignedPayload)?;
+                let bytes = self.read_body(&parts, body).await?;
+                let body_hash = hex::encode(Sha256::digest(&bytes));
+                if !body_hash.as_bytes().eq_ignore_ascii_case(&signed_hash) {
+                    return Err(VerificationError::from(
+                        VerificationErrorKind::PayloadHashMismatch,
+                    )
+                    .into());
+                }
+                (verified, Body::from(bytes))
+            }
+            // Unsigned and streaming payloads are passed on as they are
+            (None, true) => (self.verify(&parts, SignableBody::UnsignedPayload)?, body),
+            (None, false) => {
+                let bytes = self.read_body(&parts, body).await?;
+                (
+                    self.verify(&parts, SignableBody::Bytes(&bytes))?,
+                    Body::from(bytes),
+                )
+            }
+        };
+        parts.extensions.insert(verified);
+        Ok(Request::from_parts(parts, body))
+    }
+}
+
+/// Why a request was rejected
+enum Rejection {
+    Unauthorized(String),
+    AccessDenied(String),
+}
+
+impl From<VerificationError> for Rejection {
+    fn from(err: VerificationError) -> Self {
+        match err.kind() {
+            VerificationErrorKind::MissingSignature => Rejection::Unauthorized(err.to_string()),
+            _ => Rejection::AccessDenied(err.to_string()),
+        }
+    }
+}
+
+impl Rejection {
+    fn into_response<P>(self) -> Response<BoxBody>
+    where
+        UnauthorizedException: IntoResponse<P>,
+        AccessDeniedException: IntoResponse<P>,
+    {
+        match self {
+            Rejection::Unauthorized(reason) => {
+                tracing::debug!(%reason, "rejecting request that isn't signed with SigV4");
+                UnauthorizedException.into_response()
+            }
+            Rejection::AccessDenied(reason) => {
+                tracing::debug!(%reason, "rejecting request that failed SigV4 verification");
+                AccessDeniedException.into_response()
+            }
+        }
+    }
+}
+
+/// A [`Plugin`] that verifies the SigV4 signature of every request before passing it on.
+///
+/// See the [crate documentation](crate) for more information.
+#[derive(Clone)]
+pub struct SigV4VerificationPlugin {
+    verifier: Arc<Verifier>,
+}
+
+impl fmt::Debug for SigV4VerificationPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigV4VerificationPlugin")
+            .field("settings", &self.verifier.settings)
+            .field("max_body_size", &self.verifier.max_body_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SigV4VerificationPlugin {
+    /// Creates a new `SigV4VerificationPlugin`.
+    ///
+    /// `lookup_credentials` is called with the access key ID a request was signed with, and must
+    /// return the credentials for it, or `None` if the access key is unknown.
+    pub fn new(
+        settings: VerificationSettings,
+        lookup_credentials: impl Fn(&str) -> Option<Credentials> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            verifier: Arc::new(Verifier {
+                settings,
+                lookup_credentials: Arc::new(lookup_credentials),
+                time_source: SharedTimeSource::default(),
+                max_body_size: DEFAULT_MAX_BODY_SIZE,
+            }),
+        }
+    }
+
+    /// Sets the time source used to check the request time.
+    ///
+    /// This defaults to the system clock, and should only be overridden in tests.
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        Arc::make_mut(&mut self.verifier).time_source = SharedTimeSource::new(time_source);
+        self
+    }
+
+    /// Sets the largest request body, in bytes, that is buffered to check it against its signature.
+    ///
+    /// Requests with larger bodies are rejected. This defaults to [`DEFAULT_MAX_BODY_SIZE`].
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        Arc::make_mut(&mut self.verifier).max_body_size = max_body_size;
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for SigV4VerificationPlugin
+where
+    Ser: ServiceShape,
+{
+    type Output = SigV4VerificationService<T, Ser::Protocol>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        SigV4VerificationService {
+            inner,
+            verifier: self.verifier.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl HttpMarker for SigV4VerificationPlugin {}
+
+/// An extension trait for applying [`SigV4VerificationPlugin`].
+pub trait SigV4VerificationExt<CurrentPlugin> {
+    /// Verifies the SigV4 signature of every request. See [`SigV4VerificationPlugin`] for more information.
+    fn verify_sigv4(
+        self,
+        plugin: SigV4VerificationPlugin,
+    ) -> HttpPlugins<PluginStack<SigV4VerificationPlugin, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> SigV4VerificationExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn verify_sigv4(
+        self,
+        plugin: SigV4VerificationPlugin,
+    ) -> HttpPlugins<PluginStack<SigV4VerificationPlugin, CurrentPlugin>> {
+        self.push(plugin)
+    }
+}
+
+/// A [`Service`] that verifies the SigV4 signature of requests before calling the inner service.
+///
+/// Created by [`SigV4VerificationPlugin`].
+pub struct SigV4VerificationService<S, P> {
+    inner: S,
+    verifier: Arc<Verifier>,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<S: Clone, P> Clone for SigV4VerificationService<S, P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            verifier: self.verifier.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S: fmt::Debug, P> fmt::Debug for SigV4VerificationService<S, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigV4VerificationService")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, P> Service<Request<Body>> for SigV4VerificationService<S, P>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    UnauthorizedException: IntoResponse<P>,
+    AccessDeniedException: IntoResponse<P>,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The inner service was driven to readiness, so use it and leave the clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let verifier = self.verifier.clone();
+        Box::pin(async move {
+            match verifier.verify_request(request).await {
+                Ok(request) => inner.call(request).await,
+                Err(rejection) => Ok(rejection.into_response::<P>()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sigv4::http_request::{sign, SigningSettings};
+    use aws_sigv4::sign::v4;
+    use aws_smithy_async::time::StaticTimeSource;
+    use aws_smithy_http_server::body::boxed;
+    use aws_smithy_http_server::protocol::rest_json_1::RestJson1;
+    use aws_smithy_http_server::shape_id::ShapeId;
+    use http::StatusCode;
+    use std::convert::Infallible;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use tower::ServiceExt;
+
+    struct TestService;
+
+    impl ServiceShape for TestService {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = RestJson1;
+        type Operations = ();
+    }
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    fn plugin() -> SigV4VerificationPlugin {
+        let mut settings = VerificationSettings::default();
+        settings.signing_name = Some("service".into());
+        SigV4VerificationPlugin::new(settings, |access_key_id| {
+            Some(Credentials::for_tests()).filter(|c| c.access_key_id() == access_key_id)
+        })
+        .with_time_source(StaticTimeSource::new(now()))
+        .with_max_body_size(16)
+    }
+
+    fn signed_request(body: &'static [u8], headers: &[(&'static str, &str)]) -> Request<Body> {
+        let identity = Credentials::for_tests().into();
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region("us-east-1")
+            .name("service")
+            .time(now())
+            .settings(SigningSettings::default())
+            .build()
+            .unwrap()
+            .into();
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/operation?a=b")
+            .header("host", "example.com");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let mut request = request.body(body).unwrap();
+        let signable = SignableRequest::new(
+            "POST",
+            "/operation?a=b",
+            request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.to_str().unwrap())),
+            SignableBody::Bytes(body),
+        )
+        .unwrap();
+        let (instructions, _signature) = sign(signable, &params).unwrap().into_parts();
+        instructions.apply_to_request_http0x(&mut request);
+        request.map(Body::from)
+    }
+
+    async fn call(request: Request<Body>) -> Response<BoxBody> {
+        let inner = tower::service_fn(|request: Request<Body>| async move {
+            let verified = request
+                .extensions()
+                .get::<VerifiedRequest>()
+                .expect("verified requests are marked")
+                .clone();
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            assert_eq!(b"hello", body.as_ref());
+            Ok::<_, Infallible>(Response::new(boxed(Body::from(
+                verified.access_key_id().to_string(),
+            ))))
+        });
+        Plugin::<TestService, (), _>::apply(&plugin(), inner)
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    async fn body(response: Response<BoxBody>) -> String {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    fn error_type(response: &Response<BoxBody>) -> &str {
+        response.headers()["x-amzn-errortype"].to_str().unwrap()
+    }
+
+    #[tokio::test]
+    async fn passes_verified_requests_on() {
+        let response = call(signed_request(b"hello", &[])).await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("ANOTREAL", body(response).await);
+
+        let hash = hex::encode(Sha256::digest(b"hello"));
+        let response = call(signed_request(b"hello", &[(X_AMZ_CONTENT_SHA_256, &hash)])).await;
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_that_fail_verification() {
+        let response = call(signed_request(b"goodbye", &[]).map(|_| Body::from("hello"))).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("AccessDeniedException", error_type(&response));
+
+        // the signature is valid, but the body doesn't match the hash it signed
+        let hash = hex::encode(Sha256::digest(b"goodbye"));
+        let request = signed_request(b"goodbye", &[(X_AMZ_CONTENT_SHA_256, &hash)])
+            .map(|_| Body::from("hello"));
+        let response = call(request).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("AccessDeniedException", error_type(&response));
+
+        let unsigned = Request::builder()
+            .uri("/operation")
+            .body(Body::from("hello"))
+            .unwrap();
+        let response = call(unsigned).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        assert_eq!("UnauthorizedException", error_type(&response));
+    }
+
+    #[tokio::test]
+    async fn rejects_bodies_over_the_limit() {
+        let body = b"this body is too large";
+        let response = call(signed_request(body, &[("content-length", "22")])).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+        // the limit also applies to bodies without a `content-length`
+        let hash = hex::encode(Sha256::digest(body));
+        let request = signed_request(body, &[(X_AMZ_CONTENT_SHA_256, &hash)]).map(|_| {
+            Body::wrap_stream(futures_util::stream::iter(
+                body.chunks(4).map(Ok::<_, Infallible>),
+            ))
+        });
+        let response = call(request).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("AccessDeniedException", error_type(&response));
+    }
+}
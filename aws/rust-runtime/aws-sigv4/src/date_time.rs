@@ -7,7 +7,7 @@
 #![allow(dead_code)]
 
 use std::time::SystemTime;
-use time::{OffsetDateTime, Time};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
 
 /// Truncates the subseconds from the given `SystemTime` to zero.
 pub(crate) fn truncate_subsecs(time: SystemTime) -> SystemTime {
@@ -45,6 +45,30 @@ pub(crate) fn format_date_time(time: SystemTime) -> String {
     )
 }
 
+/// Parses a `YYYYMMDD'T'HHMMSS'Z'` formatted date, returning `None` if it isn't well-formed.
+pub(crate) fn parse_date_time(date_time: &str) -> Option<SystemTime> {
+    let bytes = date_time.as_bytes();
+    if bytes.len() != 16
+        || bytes[8] != b'T'
+        || bytes[15] != b'Z'
+        || !bytes[..8]
+            .iter()
+            .chain(&bytes[9..15])
+            .all(u8::is_ascii_digit)
+    {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| date_time[range].parse::<u8>().ok();
+    let date = Date::from_calendar_date(
+        date_time[0..4].parse().ok()?,
+        Month::try_from(num(4..6)?).ok()?,
+        num(6..8)?,
+    )
+    .ok()?;
+    let time = Time::from_hms(num(9..11)?, num(11..13)?, num(13..15)?).ok()?;
+    Some(PrimitiveDateTime::new(date, time).assume_utc().into())
+}
+
 /// Parse functions that are only needed for unit tests.
 #[cfg(test)]
 pub(crate) mod test_parsers {
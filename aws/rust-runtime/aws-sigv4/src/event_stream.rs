@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! Utilities to sign Event Stream messages.
+//! Utilities to sign Event Stream messages, and to verify the signatures of received ones.
 //!
 //! # Example: Signing an event stream message
 //!
@@ -51,9 +51,14 @@ use crate::http_request::SigningError;
 use crate::sign::v4::{calculate_signature, generate_signing_key, sha256_hex_string};
 use crate::SigningOutput;
 use aws_credential_types::Credentials;
-use aws_smithy_eventstream::frame::{write_headers_to, write_message_to};
+use aws_smithy_eventstream::frame::{
+    read_message_from, write_headers_to, write_message_to, VerifyMessage, VerifyMessageError,
+};
 use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
+use aws_smithy_types::DateTime;
 use bytes::Bytes;
+use std::error::Error as StdError;
+use std::fmt;
 use std::io::Write;
 use std::time::SystemTime;
 
@@ -65,7 +70,8 @@ fn calculate_string_to_sign(
     message_payload: &[u8],
     last_signature: &str,
     time: SystemTime,
-    params: &SigningParams<'_>,
+    region: &str,
+    name: &str,
 ) -> Vec<u8> {
     // Event Stream string to sign format is documented here:
     // https://docs.aws.amazon.com/transcribe/latest/dg/how-streaming.html
@@ -75,12 +81,7 @@ fn calculate_string_to_sign(
     let mut sts: Vec<u8> = Vec::new();
     writeln!(sts, "AWS4-HMAC-SHA256-PAYLOAD").unwrap();
     writeln!(sts, "{}", date_time_str).unwrap();
-    writeln!(
-        sts,
-        "{}/{}/{}/aws4_request",
-        date_str, params.region, params.name
-    )
-    .unwrap();
+    writeln!(sts, "{}/{}/{}/aws4_request", date_str, region, name).unwrap();
     writeln!(sts, "{}", last_signature).unwrap();
 
     let date_header = Header::new(":date", HeaderValue::Timestamp(time.into()));
@@ -140,7 +141,8 @@ fn sign_payload<'a>(
         message_payload.as_ref().map(|v| &v[..]).unwrap_or(&[]),
         last_signature,
         time,
-        params,
+        params.region,
+        params.name,
     );
     let signature = calculate_signature(signing_key, &string_to_sign);
     tracing::trace!(canonical_request = ?message_payload, string_to_sign = ?string_to_sign, "calculated signing parameters");
@@ -157,12 +159,256 @@ fn sign_payload<'a>(
     ))
 }
 
+/// Verifies a signed Event Stream message received from a client.
+///
+/// This recomputes the signature of `message` from the previous message's signature
+/// (`last_signature`) and the message's `:date` header, and compares it to the message's
+/// `:chunk-signature` header. On success, the returned [`SigningOutput`] holds the message
+/// wrapped by the signed message, or `None` if this was the signed empty message that ends the
+/// stream, along with the signature to verify the next message against.
+pub fn verify_message(
+    message: &Message,
+    last_signature: &str,
+    credentials: &Credentials,
+    region: &str,
+    name: &str,
+) -> Result<SigningOutput<Option<Message>>, VerificationError> {
+    let mut chunk_signature = None;
+    let mut date = None;
+    for header in message.headers() {
+        match (header.name().as_str(), header.value()) {
+            (":chunk-signature", HeaderValue::ByteArray(value)) => chunk_signature = Some(value),
+            (":date", HeaderValue::Timestamp(value)) => date = Some(*value),
+            (":chunk-signature", _) | (":date", _) => {
+                return Err(VerificationErrorKind::InvalidHeader {
+                    name: header.name().as_str().to_string(),
+                }
+                .into())
+            }
+            _ => {}
+        }
+    }
+    let chunk_signature = chunk_signature.ok_or(VerificationErrorKind::MissingHeader {
+        name: ":chunk-signature",
+    })?;
+    let time = date
+        .ok_or(VerificationErrorKind::MissingHeader { name: ":date" })
+        .and_then(|date: DateTime| {
+            SystemTime::try_from(date).map_err(|_| VerificationErrorKind::InvalidHeader {
+                name: ":date".into(),
+            })
+        })?;
+
+    let signing_key = generate_signing_key(credentials.secret_access_key(), time, region, name);
+    let string_to_sign =
+        calculate_string_to_sign(message.payload(), last_signature, time, region, name);
+    let signature = calculate_signature(signing_key, &string_to_sign);
+    let expected = hex::decode(&signature).expect("signature is valid hex");
+    if !constant_time_eq(&expected, chunk_signature) {
+        return Err(VerificationErrorKind::SignatureMismatch.into());
+    }
+
+    let inner = if message.payload().is_empty() {
+        None
+    } else {
+        Some(
+            read_message_from(message.payload().clone())
+                .map_err(|source| VerificationErrorKind::InvalidPayload { source })?,
+        )
+    };
+    Ok(SigningOutput::new(inner, signature))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Verifies the signature chain of the messages of an incoming event stream.
+///
+/// The chain is seeded with the signature of the HTTP request (or WebSocket handshake) that
+/// opened the stream. Verifying that request's own signature is out of scope for this type,
+/// so it must be done separately before trusting the stream.
+#[derive(Debug)]
+pub struct MessageVerifier {
+    last_signature: String,
+    credentials: Credentials,
+    region: String,
+    name: String,
+    ended: bool,
+}
+
+impl MessageVerifier {
+    /// Creates a verifier for a stream opened by a request with the given `seed_signature`,
+    /// signed with `credentials` for `region` and the service signing `name`.
+    pub fn new(
+        seed_signature: impl Into<String>,
+        credentials: Credentials,
+        region: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            last_signature: seed_signature.into(),
+            credentials,
+            region: region.into(),
+            name: name.into(),
+            ended: false,
+        }
+    }
+
+    /// Creates a verifier from the `Authorization` header of the request that opened the stream.
+    ///
+    /// `credentials_lookup` is given the access key ID from the header's credential scope and
+    /// returns the credentials it belongs to, or `None` if it's unknown.
+    pub fn from_authorization_header(
+        authorization: &str,
+        credentials_lookup: impl FnOnce(&str) -> Option<Credentials>,
+    ) -> Result<Self, VerificationError> {
+        let params = authorization
+            .strip_prefix("AWS4-HMAC-SHA256 ")
+            .ok_or(VerificationErrorKind::MalformedAuthorization)?;
+        let mut credential = None;
+        let mut signature = None;
+        for param in params.split(',') {
+            match param.trim().split_once('=') {
+                Some(("Credential", value)) => credential = Some(value),
+                Some(("Signature", value)) => signature = Some(value),
+                _ => {}
+            }
+        }
+        Self::from_scope(credential, signature, credentials_lookup)
+    }
+
+    /// Creates a verifier from the query string of a presigned request that opened the stream,
+    /// such as a WebSocket handshake.
+    ///
+    /// `credentials_lookup` is given the access key ID from the `X-Amz-Credential` parameter and
+    /// returns the credentials it belongs to, or `None` if it's unknown.
+    pub fn from_presigned_query(
+        query: &str,
+        credentials_lookup: impl FnOnce(&str) -> Option<Credentials>,
+    ) -> Result<Self, VerificationError> {
+        let mut credential = None;
+        let mut signature = None;
+        for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+            match name.as_ref() {
+                "X-Amz-Credential" => credential = Some(value),
+                "X-Amz-Signature" => signature = Some(value),
+                _ => {}
+            }
+        }
+        Self::from_scope(
+            credential.as_deref(),
+            signature.as_deref(),
+            credentials_lookup,
+        )
+    }
+
+    fn from_scope(
+        credential: Option<&str>,
+        signature: Option<&str>,
+        credentials_lookup: impl FnOnce(&str) -> Option<Credentials>,
+    ) -> Result<Self, VerificationError> {
+        let (credential, signature) = credential
+            .zip(signature)
+            .ok_or(VerificationErrorKind::MalformedAuthorization)?;
+        // The credential scope is `<access key ID>/<date>/<region>/<service>/aws4_request`
+        let scope: Vec<&str> = credential.split('/').collect();
+        let [access_key_id, _date, region, name, "aws4_request"] = scope[..] else {
+            return Err(VerificationErrorKind::MalformedAuthorization.into());
+        };
+        let credentials = credentials_lookup(access_key_id).ok_or_else(|| {
+            VerificationErrorKind::UnknownAccessKey {
+                access_key_id: access_key_id.to_string(),
+            }
+        })?;
+        Ok(Self::new(signature, credentials, region, name))
+    }
+}
+
+impl VerifyMessage for MessageVerifier {
+    fn verify(&mut self, message: Message) -> Result<Option<Message>, VerifyMessageError> {
+        if self.ended {
+            return Err(VerificationError::from(VerificationErrorKind::MessageAfterEnd).into());
+        }
+        let (inner, signature) = verify_message(
+            &message,
+            &self.last_signature,
+            &self.credentials,
+            &self.region,
+            &self.name,
+        )?
+        .into_parts();
+        self.last_signature = signature;
+        self.ended = inner.is_none();
+        Ok(inner)
+    }
+}
+
+#[derive(Debug)]
+enum VerificationErrorKind {
+    MissingHeader {
+        name: &'static str,
+    },
+    InvalidHeader {
+        name: String,
+    },
+    SignatureMismatch,
+    InvalidPayload {
+        source: aws_smithy_eventstream::error::Error,
+    },
+    MessageAfterEnd,
+    MalformedAuthorization,
+    UnknownAccessKey {
+        access_key_id: String,
+    },
+}
+
+/// Error verifying the signature of an Event Stream message
+#[derive(Debug)]
+pub struct VerificationError {
+    kind: VerificationErrorKind,
+}
+
+impl From<VerificationErrorKind> for VerificationError {
+    fn from(kind: VerificationErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VerificationErrorKind::*;
+        match &self.kind {
+            MissingHeader { name } => write!(f, "signed message is missing the `{name}` header"),
+            InvalidHeader { name } => write!(f, "signed message has an invalid `{name}` header"),
+            SignatureMismatch => write!(f, "message signature does not match"),
+            InvalidPayload { .. } => write!(f, "signed message payload is not a valid message"),
+            MessageAfterEnd => write!(f, "received a message after the end of the stream"),
+            MalformedAuthorization => write!(f, "the request's SigV4 authorization is malformed"),
+            UnknownAccessKey { access_key_id } => {
+                write!(f, "no credentials found for access key `{access_key_id}`")
+            }
+        }
+    }
+}
+
+impl StdError for VerificationError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            VerificationErrorKind::InvalidPayload { source } => Some(source),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::event_stream::{calculate_string_to_sign, sign_message, SigningParams};
+    use crate::event_stream::{
+        calculate_string_to_sign, sign_empty_message, sign_message, MessageVerifier, SigningParams,
+    };
     use crate::sign::v4::sha256_hex_string;
     use aws_credential_types::Credentials;
-    use aws_smithy_eventstream::frame::write_message_to;
+    use aws_smithy_eventstream::frame::{write_message_to, VerifyMessage};
     use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
     use std::time::{Duration, UNIX_EPOCH};
 
@@ -199,7 +445,8 @@ mod tests {
                 &message_payload,
                 &last_signature,
                 params.time,
-                &params
+                params.region,
+                params.name,
             ))
             .unwrap()
         );
@@ -238,4 +485,118 @@ mod tests {
             panic!("expected timestamp for :date header");
         }
     }
+
+    fn signed_stream(seed: &str, messages: &[&'static [u8]]) -> Vec<Message> {
+        let identity = Credentials::for_tests().into();
+        let params = SigningParams {
+            identity: &identity,
+            region: "us-east-1",
+            name: "testservice",
+            time: (UNIX_EPOCH + Duration::new(123_456_789_u64, 1234u32)),
+            settings: (),
+        };
+        let mut last_signature = seed.to_string();
+        let mut signed = Vec::new();
+        for payload in messages {
+            let (message, signature) =
+                sign_message(&Message::new(*payload), &last_signature, &params)
+                    .unwrap()
+                    .into_parts();
+            signed.push(message);
+            last_signature = signature;
+        }
+        signed.push(
+            sign_empty_message(&last_signature, &params)
+                .unwrap()
+                .into_parts()
+                .0,
+        );
+        signed
+    }
+
+    fn verifier(seed: &str) -> MessageVerifier {
+        MessageVerifier::new(seed, Credentials::for_tests(), "us-east-1", "testservice")
+    }
+
+    #[test]
+    fn verify_signature_chain() {
+        let seed = sha256_hex_string(b"seed");
+        let mut verifier = verifier(&seed);
+        let mut messages = signed_stream(&seed, &[b"one", b"two"]).into_iter();
+        let first = verifier.verify(messages.next().unwrap()).unwrap().unwrap();
+        assert_eq!(&b"one"[..], &first.payload()[..]);
+        let second = verifier.verify(messages.next().unwrap()).unwrap().unwrap();
+        assert_eq!(&b"two"[..], &second.payload()[..]);
+        assert!(verifier.verify(messages.next().unwrap()).unwrap().is_none());
+
+        let after_end = signed_stream(&seed, &[b"one"]).remove(0);
+        let err = verifier.verify(after_end).unwrap_err();
+        assert_eq!(
+            "received a message after the end of the stream",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn reject_tampered_and_reordered_messages() {
+        let seed = sha256_hex_string(b"seed");
+        let messages = signed_stream(&seed, &[b"one", b"two"]);
+
+        let err = verifier(&sha256_hex_string(b"other seed"))
+            .verify(messages[0].clone())
+            .unwrap_err();
+        assert_eq!("message signature does not match", err.to_string());
+
+        let err = verifier(&seed).verify(messages[1].clone()).unwrap_err();
+        assert_eq!("message signature does not match", err.to_string());
+
+        let mut tampered_payload = Vec::new();
+        write_message_to(&Message::new(&b"uno"[..]), &mut tampered_payload).unwrap();
+        let tampered = Message::new(tampered_payload)
+            .add_header(messages[0].headers()[0].clone())
+            .add_header(messages[0].headers()[1].clone());
+        let err = verifier(&seed).verify(tampered).unwrap_err();
+        assert_eq!("message signature does not match", err.to_string());
+
+        let unsigned = Message::new(&b"one"[..]);
+        let err = verifier(&seed).verify(unsigned).unwrap_err();
+        assert_eq!(
+            "signed message is missing the `:chunk-signature` header",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn verifier_from_request() {
+        let seed = sha256_hex_string(b"seed");
+        let lookup =
+            |access_key_id: &str| (access_key_id == "ANOTREAL").then(Credentials::for_tests);
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential=ANOTREAL/19731129/us-east-1/testservice/aws4_request, \
+             SignedHeaders=host;x-amz-date, Signature={seed}"
+        );
+        let query = format!(
+            "X-Amz-Algorithm=AWS4-HMAC-SHA256\
+             &X-Amz-Credential=ANOTREAL%2F19731129%2Fus-east-1%2Ftestservice%2Faws4_request\
+             &X-Amz-Signature={seed}"
+        );
+        for mut verifier in [
+            MessageVerifier::from_authorization_header(&authorization, lookup).unwrap(),
+            MessageVerifier::from_presigned_query(&query, lookup).unwrap(),
+        ] {
+            let message = signed_stream(&seed, &[b"one"]).remove(0);
+            assert!(verifier.verify(message).unwrap().is_some());
+        }
+
+        let err = MessageVerifier::from_authorization_header(&authorization, |_| None).unwrap_err();
+        assert_eq!(
+            "no credentials found for access key `ANOTREAL`",
+            err.to_string()
+        );
+        let err = MessageVerifier::from_authorization_header("Bearer token", lookup).unwrap_err();
+        assert_eq!(
+            "the request's SigV4 authorization is malformed",
+            err.to_string()
+        );
+    }
 }
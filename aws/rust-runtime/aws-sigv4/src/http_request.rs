@@ -57,10 +57,12 @@
 
 mod canonical_request;
 mod error;
+mod presigned_url;
 mod settings;
 mod sign;
 mod uri_path_normalization;
 mod url_escape;
+pub(crate) mod verify;
 
 #[cfg(test)]
 pub(crate) mod test;
@@ -70,13 +72,15 @@ use crate::sign::v4;
 use crate::sign::v4a;
 use crate::SignatureVersion;
 use aws_credential_types::Credentials;
-pub use error::SigningError;
+pub use error::{SigningError, VerificationError, VerificationErrorKind};
+pub use presigned_url::PresignedUrl;
 pub use settings::{
     PayloadChecksumKind, PercentEncodingMode, SessionTokenMode, SignatureLocation, SigningSettings,
     UriPathNormalizationMode,
 };
 pub use sign::{sign, SignableBody, SignableRequest, SigningInstructions};
 use std::time::SystemTime;
+pub use verify::{verify, VerificationSettings, VerifiedRequest};
 
 // Individual Debug impls are responsible for redacting sensitive fields.
 #[derive(Debug)]
@@ -45,8 +45,14 @@ pub(crate) mod param {
 
 pub(crate) const HMAC_256: &str = "AWS4-HMAC-SHA256";
 
-const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
-const STREAMING_UNSIGNED_PAYLOAD_TRAILER: &str = "STREAMING-UNSIGNED-PAYLOAD-TRAILER";
+pub(crate) const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+pub(crate) const STREAMING_UNSIGNED_PAYLOAD_TRAILER: &str = "STREAMING-UNSIGNED-PAYLOAD-TRAILER";
+pub(crate) const STREAMING_HMAC_256_PAYLOAD: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD";
+pub(crate) const STREAMING_HMAC_256_PAYLOAD_TRAILER: &str =
+    "STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER";
+pub(crate) const STREAMING_ECDSA_256_PAYLOAD: &str = "STREAMING-AWS4-ECDSA-P256-SHA256-PAYLOAD";
+pub(crate) const STREAMING_ECDSA_256_PAYLOAD_TRAILER: &str =
+    "STREAMING-AWS4-ECDSA-P256-SHA256-PAYLOAD-TRAILER";
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct HeaderValues<'a> {
@@ -152,7 +158,7 @@ impl<'a> CanonicalRequest<'a> {
             PercentEncodingMode::Double => Cow::Owned(percent_encode_path(&path)),
             PercentEncodingMode::Single => path,
         };
-        let payload_hash = Self::payload_hash(req.body());
+        let payload_hash = Self::payload_hash(req.body(), params.signature_version());
 
         let date_time = format_date_time(*params.time());
         let (signed_headers, canonical_headers) =
@@ -224,6 +230,76 @@ impl<'a> CanonicalRequest<'a> {
         Ok(creq)
     }
 
+    /// Reconstruct the CanonicalRequest that was signed to produce an incoming signed request.
+    ///
+    /// Unlike [`CanonicalRequest::from`], nothing is added to the request: only the headers listed
+    /// in `signed_headers` are included, as they were received, and for presigned requests, all
+    /// query params except for the signature itself are included.
+    pub(crate) fn from_signed<'b>(
+        req: &'b SignableRequest<'b>,
+        signed_headers: &str,
+        content_sha256: Cow<'b, str>,
+        signature_location: SignatureLocation,
+        percent_encoding_mode: PercentEncodingMode,
+        uri_path_normalization_mode: UriPathNormalizationMode,
+    ) -> Result<CanonicalRequest<'b>, CanonicalRequestError> {
+        let path = req.uri().path();
+        let path = match uri_path_normalization_mode {
+            UriPathNormalizationMode::Enabled => normalize_uri_path(path),
+            UriPathNormalizationMode::Disabled => Cow::Borrowed(path),
+        };
+        let path = match percent_encoding_mode {
+            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(&path)),
+            PercentEncodingMode::Single => path,
+        };
+
+        let mut signed = Vec::new();
+        let mut canonical_headers = HeaderMap::new();
+        for name in signed_headers.split(';') {
+            let name = HeaderName::from_str(name)?;
+            for (_, value) in req
+                .headers()
+                .iter()
+                .filter(|(key, _)| key.eq_ignore_ascii_case(name.as_str()))
+            {
+                canonical_headers.append(name.clone(), normalize_header_value(value)?);
+            }
+            if name == HOST && !canonical_headers.contains_key(&HOST) {
+                if let Some(authority) = req.uri().authority() {
+                    canonical_headers.insert(HOST, HeaderValue::try_from(authority.as_str())?);
+                }
+            }
+            signed.push(CanonicalHeaderName(name));
+        }
+
+        let params = match signature_location {
+            SignatureLocation::Headers => {
+                form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes()).collect()
+            }
+            SignatureLocation::QueryParams => {
+                form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+                    .filter(|(key, _)| key != param::X_AMZ_SIGNATURE)
+                    .collect()
+            }
+        };
+
+        Ok(CanonicalRequest {
+            method: req.method(),
+            path,
+            params: Self::canonical_query_string(req.uri(), params),
+            headers: canonical_headers,
+            values: SignatureValues::Headers(HeaderValues {
+                content_sha256,
+                // Only the signed headers and the payload hash are part of the canonical request
+                date_time: String::new(),
+                security_token: None,
+                signed_headers: SignedHeaders::new(signed),
+                #[cfg(feature = "sigv4a")]
+                region_set: None,
+            }),
+        })
+    }
+
     fn headers(
         req: &SignableRequest<'_>,
         params: &SigningParams<'_>,
@@ -304,7 +380,10 @@ impl<'a> CanonicalRequest<'a> {
         Ok((signed_headers, canonical_headers))
     }
 
-    fn payload_hash<'b>(body: &'b SignableBody<'b>) -> Cow<'b, str> {
+    fn payload_hash<'b>(
+        body: &'b SignableBody<'b>,
+        signature_version: SignatureVersion,
+    ) -> Cow<'b, str> {
         // Payload hash computation
         //
         // Based on the input body, set the payload_hash of the canonical request:
@@ -314,6 +393,7 @@ impl<'a> CanonicalRequest<'a> {
         // - use `UnsignedPayload`
         // - use `UnsignedPayload` for streaming requests
         // - use `StreamingUnsignedPayloadTrailer` for streaming requests with trailers
+        // - use the streaming marker of the signature version for streaming requests with signed chunks
         match body {
             SignableBody::Bytes(data) => Cow::Owned(sha256_hex_string(data)),
             SignableBody::Precomputed(digest) => Cow::Borrowed(digest.as_str()),
@@ -321,6 +401,14 @@ impl<'a> CanonicalRequest<'a> {
             SignableBody::StreamingUnsignedPayloadTrailer => {
                 Cow::Borrowed(STREAMING_UNSIGNED_PAYLOAD_TRAILER)
             }
+            SignableBody::StreamingSignedPayload => Cow::Borrowed(match signature_version {
+                SignatureVersion::V4 => STREAMING_HMAC_256_PAYLOAD,
+                SignatureVersion::V4a => STREAMING_ECDSA_256_PAYLOAD,
+            }),
+            SignableBody::StreamingSignedPayloadTrailer => Cow::Borrowed(match signature_version {
+                SignatureVersion::V4 => STREAMING_HMAC_256_PAYLOAD_TRAILER,
+                SignatureVersion::V4a => STREAMING_ECDSA_256_PAYLOAD_TRAILER,
+            }),
         }
     }
 
@@ -362,6 +450,13 @@ impl<'a> CanonicalRequest<'a> {
                 );
             }
         }
+        Self::canonical_query_string(uri, params)
+    }
+
+    fn canonical_query_string(
+        uri: &Uri,
+        mut params: Vec<(Cow<'_, str>, Cow<'_, str>)>,
+    ) -> Option<String> {
         // Sort by param name, and then by param value
         params.sort();
 
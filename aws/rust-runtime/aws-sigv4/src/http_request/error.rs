@@ -128,3 +128,152 @@ impl From<InvalidUri> for CanonicalRequestError {
         }
     }
 }
+
+/// The reason a signed request failed verification
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum VerificationErrorKind {
+    /// The request has neither an `Authorization` header nor a presigned query string
+    MissingSignature,
+    /// The signature components of the request couldn't be parsed
+    MalformedSignature {
+        /// A description of what was malformed
+        reason: String,
+    },
+    /// The request was signed with an algorithm other than SigV4 or SigV4a
+    UnsupportedAlgorithm {
+        /// The algorithm named in the request
+        algorithm: String,
+    },
+    /// The credentials lookup didn't know the access key the request was signed with
+    UnknownAccessKey {
+        /// The access key ID the request was signed with
+        access_key_id: String,
+    },
+    /// The credential scope of the request doesn't match the date, region, or service expected
+    CredentialScopeMismatch {
+        /// A description of the mismatch
+        reason: String,
+    },
+    /// The request time is too far from the current time
+    RequestTimeTooSkewed,
+    /// The presigned request has expired
+    Expired,
+    /// The session token in the request doesn't match the credentials it was signed with
+    InvalidSecurityToken,
+    /// The request signed a payload hash that can't be computed without the request body
+    MissingPayloadHash,
+    /// The request body doesn't match the payload hash that was signed
+    PayloadHashMismatch,
+    /// The request didn't sign its payload, and the verifier requires it
+    UnsignedPayloadNotAllowed,
+    /// The signature doesn't match the request
+    SignatureMismatch,
+}
+
+impl fmt::Display for VerificationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VerificationErrorKind::*;
+        match self {
+            MissingSignature => write!(f, "the request is not signed"),
+            MalformedSignature { reason } => {
+                write!(f, "the request signature is malformed: {reason}")
+            }
+            UnsupportedAlgorithm { algorithm } => {
+                write!(f, "unsupported signing algorithm `{algorithm}`")
+            }
+            UnknownAccessKey { access_key_id } => {
+                write!(f, "unknown access key ID `{access_key_id}`")
+            }
+            CredentialScopeMismatch { reason } => {
+                write!(f, "the credential scope is invalid: {reason}")
+            }
+            RequestTimeTooSkewed => write!(
+                f,
+                "the difference between the request time and the current time is too large"
+            ),
+            Expired => write!(f, "the presigned request has expired"),
+            InvalidSecurityToken => {
+                write!(f, "the security token included in the request is invalid")
+            }
+            MissingPayloadHash => write!(
+                f,
+                "the request doesn't include a payload hash and its body wasn't provided"
+            ),
+            PayloadHashMismatch => write!(
+                f,
+                "the request body doesn't match the payload hash that was signed"
+            ),
+            UnsignedPayloadNotAllowed => write!(f, "unsigned payloads are not allowed"),
+            SignatureMismatch => write!(
+                f,
+                "the request signature doesn't match the signature calculated for it"
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum VerificationErrorSource {
+    CanonicalRequest(CanonicalRequestError),
+}
+
+/// Error verifying a signed request
+#[derive(Debug)]
+pub struct VerificationError {
+    kind: VerificationErrorKind,
+    source: Option<VerificationErrorSource>,
+}
+
+impl VerificationError {
+    /// Returns the reason verification failed
+    pub fn kind(&self) -> &VerificationErrorKind {
+        &self.kind
+    }
+
+    pub(crate) fn malformed(reason: impl Into<String>) -> Self {
+        VerificationErrorKind::MalformedSignature {
+            reason: reason.into(),
+        }
+        .into()
+    }
+
+    pub(crate) fn scope_mismatch(reason: impl Into<String>) -> Self {
+        VerificationErrorKind::CredentialScopeMismatch {
+            reason: reason.into(),
+        }
+        .into()
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to verify request: {}", self.kind)
+    }
+}
+
+impl Error for VerificationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.source {
+            Some(VerificationErrorSource::CanonicalRequest(source)) => Some(source),
+            None => None,
+        }
+    }
+}
+
+impl From<VerificationErrorKind> for VerificationError {
+    fn from(kind: VerificationErrorKind) -> Self {
+        Self { kind, source: None }
+    }
+}
+
+impl From<CanonicalRequestError> for VerificationError {
+    fn from(source: CanonicalRequestError) -> Self {
+        Self {
+            kind: VerificationErrorKind::MalformedSignature {
+                reason: source.to_string(),
+            },
+            source: Some(VerificationErrorSource::CanonicalRequest(source)),
+        }
+    }
+}
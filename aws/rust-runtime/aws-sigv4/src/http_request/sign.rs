@@ -91,6 +91,16 @@ pub enum SignableBody<'a> {
 
     /// Set when a streaming body has checksum trailers.
     StreamingUnsignedPayloadTrailer,
+
+    /// Set when a streaming body is sent in `aws-chunked` encoded chunks that are each signed.
+    ///
+    /// The signature of the request is the seed signature the chunk signatures are chained to.
+    /// See [`chunked`](crate::chunked) for how to sign the chunks.
+    StreamingSignedPayload,
+
+    /// Set when a streaming body is sent in signed `aws-chunked` encoded chunks, followed by
+    /// signed trailers.
+    StreamingSignedPayloadTrailer,
 }
 
 impl SignableBody<'_> {
@@ -272,7 +282,7 @@ fn calculate_signing_params<'a>(
                     .to_string();
 
             let secret_key =
-                v4a::generate_signing_key(creds.access_key_id(), creds.secret_access_key());
+                v4a::cached_signing_key(creds.access_key_id(), creds.secret_access_key());
             let signature = v4a::calculate_signature(&secret_key, string_to_sign.as_bytes());
             (signature, string_to_sign)
         }
@@ -398,7 +408,7 @@ fn calculate_signing_headers<'a>(
             );
 
             let signing_key =
-                v4a::generate_signing_key(creds.access_key_id(), creds.secret_access_key());
+                v4a::cached_signing_key(creds.access_key_id(), creds.secret_access_key());
             let signature = v4a::calculate_signature(&signing_key, sts.to_string().as_bytes());
 
             let values = creq.values.as_headers().expect("signing with headers");
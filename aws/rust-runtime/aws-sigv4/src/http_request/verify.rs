@@ -0,0 +1,926 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::date_time::{format_date, parse_date_time};
+use crate::http_request::canonical_request::{
+    header, param, CanonicalRequest, StringToSign, HMAC_256, STREAMING_UNSIGNED_PAYLOAD_TRAILER,
+    UNSIGNED_PAYLOAD,
+};
+use crate::http_request::error::{VerificationError, VerificationErrorKind};
+use crate::http_request::{
+    PercentEncodingMode, SignableBody, SignableRequest, SignatureLocation, UriPathNormalizationMode,
+};
+use crate::sign::v4;
+#[cfg(feature = "sigv4a")]
+use crate::sign::v4a;
+use crate::SignatureVersion;
+use aws_credential_types::Credentials;
+use aws_types::region::SigningRegionSet;
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime};
+
+/// The longest a presigned request can be valid for
+const MAX_PRESIGNED_EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Settings for verifying signed HTTP requests
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct VerificationSettings {
+    /// The signing name requests must be signed for. When unset, any signing name is accepted.
+    pub signing_name: Option<Cow<'static, str>>,
+
+    /// The region requests must be signed for. When unset, any region is accepted.
+    ///
+    /// For SigV4a, the region must be matched by the region set the request was signed for.
+    pub region: Option<Cow<'static, str>>,
+
+    /// How the request URL was encoded when it was signed. This must match the
+    /// [`SigningSettings`](crate::http_request::SigningSettings) that clients sign with.
+    pub percent_encoding_mode: PercentEncodingMode,
+
+    /// Whether the URI path was normalized when it was signed. This must match the
+    /// [`SigningSettings`](crate::http_request::SigningSettings) that clients sign with.
+    pub uri_path_normalization_mode: UriPathNormalizationMode,
+
+    /// How far the time a request was signed at may be from the current time
+    pub max_clock_skew: Duration,
+
+    /// Whether requests may be signed with `UNSIGNED-PAYLOAD` instead of a hash of their body
+    pub allow_unsigned_payload: bool,
+}
+
+impl Default for VerificationSettings {
+    fn default() -> Self {
+        Self {
+            signing_name: None,
+            region: None,
+            percent_encoding_mode: PercentEncodingMode::Double,
+            uri_path_normalization_mode: UriPathNormalizationMode::Enabled,
+            max_clock_skew: Duration::from_secs(15 * 60),
+            allow_unsigned_payload: true,
+        }
+    }
+}
+
+/// Details of a request whose signature was successfully verified
+#[derive(Clone, Debug)]
+pub struct VerifiedRequest {
+    access_key_id: String,
+    signature_version: SignatureVersion,
+    signature_location: SignatureLocation,
+    region: String,
+    signing_name: String,
+    time: SystemTime,
+    payload_hash: String,
+    signature: String,
+}
+
+impl VerifiedRequest {
+    /// Returns the access key ID the request was signed with
+    pub fn access_key_id(&self) -> &str {
+        &self.access_key_id
+    }
+
+    /// Returns the algorithm the request was signed with
+    pub fn signature_version(&self) -> SignatureVersion {
+        self.signature_version
+    }
+
+    /// Returns whether the request was signed with headers or presigned with query params
+    pub fn signature_location(&self) -> SignatureLocation {
+        self.signature_location
+    }
+
+    /// Returns the region the request was signed for. For SigV4a, this is the region set.
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Returns the signing name the request was signed for
+    pub fn signing_name(&self) -> &str {
+        &self.signing_name
+    }
+
+    /// Returns the time the request was signed at
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Returns the payload hash that was signed
+    ///
+    /// This is either the SHA-256 of the body, which was checked if the body was provided, or a
+    /// marker such as `UNSIGNED-PAYLOAD` or `STREAMING-AWS4-HMAC-SHA256-PAYLOAD`. For streaming
+    /// payloads, the chunk signatures are chained from the [`signature`](Self::signature) and
+    /// must be verified separately.
+    pub fn payload_hash(&self) -> &str {
+        &self.payload_hash
+    }
+
+    /// Returns the verified signature as a lowercase hex string
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+}
+
+/// The values a client added to a request when signing it
+#[derive(Debug)]
+pub(super) struct SignedValues {
+    pub(super) location: SignatureLocation,
+    pub(super) algorithm: String,
+    pub(super) credential: String,
+    pub(super) signed_headers: String,
+    pub(super) signature: String,
+    pub(super) date_time: String,
+    pub(super) expires: Option<String>,
+    pub(super) security_token: Option<String>,
+    pub(super) region_set: Option<String>,
+}
+
+impl SignedValues {
+    fn from_request(request: &SignableRequest<'_>) -> Result<Self, VerificationError> {
+        if let Some(authorization) = find_header(request, "authorization") {
+            Self::from_authorization_header(request, authorization)
+        } else {
+            Self::from_query_params(request)
+        }
+    }
+
+    fn from_authorization_header(
+        request: &SignableRequest<'_>,
+        authorization: &str,
+    ) -> Result<Self, VerificationError> {
+        let (algorithm, components) = authorization.trim().split_once(' ').ok_or_else(|| {
+            VerificationError::malformed("the authorization header is incomplete")
+        })?;
+        let (mut credential, mut signed_headers, mut signature) = (None, None, None);
+        for component in components.split(',') {
+            let (key, value) = component.trim().split_once('=').ok_or_else(|| {
+                VerificationError::malformed("the authorization header is malformed")
+            })?;
+            match key {
+                "Credential" => credential = Some(value.to_string()),
+                "SignedHeaders" => signed_headers = Some(value.to_string()),
+                "Signature" => signature = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        let missing = |name: &str| {
+            VerificationError::malformed(format!("the authorization header has no `{name}`"))
+        };
+        Ok(Self {
+            location: SignatureLocation::Headers,
+            algorithm: algorithm.to_string(),
+            credential: credential.ok_or_else(|| missing("Credential"))?,
+            signed_headers: signed_headers.ok_or_else(|| missing("SignedHeaders"))?,
+            signature: signature.ok_or_else(|| missing("Signature"))?,
+            date_time: find_header(request, header::X_AMZ_DATE)
+                .ok_or_else(|| {
+                    VerificationError::malformed(format!(
+                        "the `{}` header is required",
+                        header::X_AMZ_DATE
+                    ))
+                })?
+                .to_string(),
+            expires: None,
+            security_token: find_header(request, header::X_AMZ_SECURITY_TOKEN).map(Into::into),
+            region_set: find_header(request, "x-amz-region-set").map(Into::into),
+        })
+    }
+
+    fn from_query_params(request: &SignableRequest<'_>) -> Result<Self, VerificationError> {
+        Self::from_query(request.uri().query().unwrap_or_default())
+    }
+
+    /// Parses the values of a presigned request from its query string
+    pub(super) fn from_query(query: &str) -> Result<Self, VerificationError> {
+        let params: Vec<(Cow<'_, str>, Cow<'_, str>)> =
+            form_urlencoded::parse(query.as_bytes()).collect();
+        let find = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        let algorithm = find(param::X_AMZ_ALGORITHM)
+            .ok_or_else(|| VerificationError::from(VerificationErrorKind::MissingSignature))?;
+        let required = |name: &str| {
+            find(name).ok_or_else(|| {
+                VerificationError::malformed(format!("the `{name}` query param is required"))
+            })
+        };
+        Ok(Self {
+            location: SignatureLocation::QueryParams,
+            algorithm,
+            credential: required(param::X_AMZ_CREDENTIAL)?,
+            signed_headers: required(param::X_AMZ_SIGNED_HEADERS)?,
+            signature: required(param::X_AMZ_SIGNATURE)?,
+            date_time: required(param::X_AMZ_DATE)?,
+            expires: Some(required(param::X_AMZ_EXPIRES)?),
+            security_token: find(param::X_AMZ_SECURITY_TOKEN),
+            region_set: find("X-Amz-Region-Set"),
+        })
+    }
+}
+
+/// The credential scope and time a request was signed with
+pub(super) struct SignedScope<'a> {
+    pub(super) access_key_id: &'a str,
+    pub(super) region: Cow<'a, str>,
+    pub(super) signing_name: &'a str,
+    pub(super) time: SystemTime,
+}
+
+impl SignedValues {
+    /// Returns the signature version named by the signing algorithm
+    pub(super) fn signature_version(&self) -> Result<SignatureVersion, VerificationError> {
+        match self.algorithm.as_str() {
+            HMAC_256 => Ok(SignatureVersion::V4),
+            #[cfg(feature = "sigv4a")]
+            v4a::ECDSA_256 => Ok(SignatureVersion::V4a),
+            algorithm => Err(VerificationErrorKind::UnsupportedAlgorithm {
+                algorithm: algorithm.into(),
+            }
+            .into()),
+        }
+    }
+
+    /// Parses the credential scope, checking that its date matches the signing time
+    pub(super) fn scope(
+        &self,
+        signature_version: SignatureVersion,
+    ) -> Result<SignedScope<'_>, VerificationError> {
+        // Credential scope: `<access key>/<date>/<region>/<service>/aws4_request` for SigV4,
+        // and `<access key>/<date>/<service>/aws4_request` for SigV4a
+        let scope: Vec<&str> = self.credential.split('/').collect();
+        let (access_key_id, scope_date, region, signing_name) =
+            match (signature_version, scope.as_slice()) {
+                (SignatureVersion::V4, [key, date, region, name, "aws4_request"]) => {
+                    (*key, *date, Cow::Borrowed(*region), *name)
+                }
+                (SignatureVersion::V4a, [key, date, name, "aws4_request"]) => {
+                    let region_set = self.region_set.as_deref().ok_or_else(|| {
+                        VerificationError::malformed("SigV4a requests must include a region set")
+                    })?;
+                    (*key, *date, Cow::Borrowed(region_set), *name)
+                }
+                _ => {
+                    return Err(VerificationError::malformed(
+                        "the credential scope is malformed",
+                    ))
+                }
+            };
+
+        let time = parse_date_time(&self.date_time)
+            .ok_or_else(|| VerificationError::malformed("the request date is malformed"))?;
+        if format_date(time) != scope_date {
+            return Err(VerificationError::scope_mismatch(
+                "the credential scope date doesn't match the request date",
+            ));
+        }
+        Ok(SignedScope {
+            access_key_id,
+            region,
+            signing_name,
+            time,
+        })
+    }
+}
+
+fn find_header<'a>(request: &'a SignableRequest<'_>, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+/// Verifies the signature of a request signed with SigV4 or SigV4a.
+///
+/// The canonical request is rebuilt from the incoming request, and the signature is recalculated
+/// with the credentials that `lookup_credentials` returns for the access key the request was
+/// signed with. Both requests signed with an `Authorization` header and presigned requests are
+/// supported.
+///
+/// If the request body is available, it should be provided as [`SignableBody::Bytes`] so that it
+/// can be checked against the payload hash that was signed. Otherwise, use
+/// [`SignableBody::UnsignedPayload`], in which case the request must include an
+/// `x-amz-content-sha256` header, and the body must be checked by the caller.
+pub fn verify(
+    request: &SignableRequest<'_>,
+    settings: &VerificationSettings,
+    lookup_credentials: impl Fn(&str) -> Option<Credentials>,
+    now: SystemTime,
+) -> Result<VerifiedRequest, VerificationError> {
+    let values = SignedValues::from_request(request)?;
+    let signature_version = values.signature_version()?;
+    let SignedScope {
+        access_key_id,
+        region,
+        signing_name,
+        time,
+    } = values.scope(signature_version)?;
+    if let Some(expected) = &settings.signing_name {
+        if signing_name != expected {
+            return Err(VerificationError::scope_mismatch(format!(
+                "expected signing name `{expected}` but the request was signed for `{signing_name}`"
+            )));
+        }
+    }
+    if let Some(expected) = &settings.region {
+        let matches = match signature_version {
+            SignatureVersion::V4 => region == expected.as_ref(),
+            SignatureVersion::V4a => SigningRegionSet::parse(&region)
+                .map_err(|err| VerificationError::malformed(err.to_string()))?
+                .contains(expected),
+        };
+        if !matches {
+            return Err(VerificationError::scope_mismatch(format!(
+                "expected region `{expected}` but the request was signed for `{region}`"
+            )));
+        }
+    }
+    check_time(&values, time, settings.max_clock_skew, now)?;
+
+    let payload_hash = payload_hash(request, &values, settings)?;
+    if !values.signed_headers.split(';').any(|name| name == "host") {
+        return Err(VerificationError::malformed(
+            "the `host` header must be signed",
+        ));
+    }
+    for name in values.signed_headers.split(';') {
+        if name != "host" && find_header(request, name).is_none() {
+            return Err(VerificationError::malformed(format!(
+                "the signed header `{name}` is missing"
+            )));
+        }
+    }
+
+    let credentials = lookup_credentials(access_key_id).ok_or_else(|| {
+        VerificationError::from(VerificationErrorKind::UnknownAccessKey {
+            access_key_id: access_key_id.into(),
+        })
+    })?;
+    match (
+        credentials.session_token(),
+        values.security_token.as_deref(),
+    ) {
+        (None, None) => {}
+        (Some(expected), Some(actual)) if constant_time_eq(expected, actual) => {}
+        _ => return Err(VerificationErrorKind::InvalidSecurityToken.into()),
+    }
+
+    let creq = CanonicalRequest::from_signed(
+        request,
+        &values.signed_headers,
+        Cow::Borrowed(payload_hash.as_str()),
+        values.location,
+        settings.percent_encoding_mode,
+        settings.uri_path_normalization_mode,
+    )?;
+    let encoded_creq = v4::sha256_hex_string(creq.to_string().as_bytes());
+    tracing::trace!(canonical_request = %creq, "rebuilt canonical request for verification");
+
+    let valid = match signature_version {
+        SignatureVersion::V4 => {
+            let string_to_sign =
+                StringToSign::new_v4(time, &region, signing_name, &encoded_creq).to_string();
+            let signing_key = v4::generate_signing_key(
+                credentials.secret_access_key(),
+                time,
+                &region,
+                signing_name,
+            );
+            let signature = v4::calculate_signature(signing_key, string_to_sign.as_bytes());
+            constant_time_eq(&signature, &values.signature)
+        }
+        #[cfg(feature = "sigv4a")]
+        SignatureVersion::V4a => {
+            let string_to_sign =
+                StringToSign::new_v4a(time, &region, signing_name, &encoded_creq).to_string();
+            let signing_key =
+                v4a::cached_signing_key(access_key_id, credentials.secret_access_key());
+            v4a::verify_signature(signing_key, string_to_sign.as_bytes(), &values.signature)
+        }
+        #[cfg(not(feature = "sigv4a"))]
+        SignatureVersion::V4a => unreachable!("SigV4a is rejected as an unsupported algorithm"),
+    };
+    if !valid {
+        return Err(VerificationErrorKind::SignatureMismatch.into());
+    }
+
+    Ok(VerifiedRequest {
+        access_key_id: access_key_id.into(),
+        signature_version,
+        signature_location: values.location,
+        region: region.into_owned(),
+        signing_name: signing_name.into(),
+        time,
+        payload_hash,
+        signature: values.signature,
+    })
+}
+
+fn check_time(
+    values: &SignedValues,
+    time: SystemTime,
+    max_clock_skew: Duration,
+    now: SystemTime,
+) -> Result<(), VerificationError> {
+    let too_skewed = || VerificationError::from(VerificationErrorKind::RequestTimeTooSkewed);
+    match &values.expires {
+        None => {
+            let skew = match now.duration_since(time) {
+                Ok(elapsed) => elapsed,
+                Err(err) => err.duration(),
+            };
+            if skew > max_clock_skew {
+                return Err(too_skewed());
+            }
+        }
+        Some(expires) => {
+            let expires = expires
+                .parse()
+                .ok()
+                .map(Duration::from_secs)
+                .filter(|expires| !expires.is_zero() && *expires <= MAX_PRESIGNED_EXPIRY)
+                .ok_or_else(|| {
+                    VerificationError::malformed(format!(
+                        "`{}` must be between 1 and {} seconds",
+                        param::X_AMZ_EXPIRES,
+                        MAX_PRESIGNED_EXPIRY.as_secs()
+                    ))
+                })?;
+            if time > now + max_clock_skew {
+                return Err(too_skewed());
+            }
+            if now > time + expires {
+                return Err(VerificationErrorKind::Expired.into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Determines the payload hash the request was signed with, checking it against the body if possible
+fn payload_hash(
+    request: &SignableRequest<'_>,
+    values: &SignedValues,
+    settings: &VerificationSettings,
+) -> Result<String, VerificationError> {
+    let body_hash = match request.body() {
+        SignableBody::Bytes(data) => Some(v4::sha256_hex_string(data)),
+        SignableBody::Precomputed(digest) => Some(digest.clone()),
+        _ => None,
+    };
+    let payload_hash = match find_header(request, header::X_AMZ_CONTENT_SHA_256) {
+        Some(claimed) => {
+            let is_hash = claimed.len() == 64 && claimed.bytes().all(|b| b.is_ascii_hexdigit());
+            if let (true, Some(body_hash)) = (is_hash, &body_hash) {
+                if !body_hash.eq_ignore_ascii_case(claimed) {
+                    return Err(VerificationErrorKind::PayloadHashMismatch.into());
+                }
+            }
+            claimed.to_string()
+        }
+        // Presigned requests don't sign their payload unless `x-amz-content-sha256` was set
+        None if values.location == SignatureLocation::QueryParams => UNSIGNED_PAYLOAD.into(),
+        None => body_hash
+            .ok_or_else(|| VerificationError::from(VerificationErrorKind::MissingPayloadHash))?,
+    };
+    if !settings.allow_unsigned_payload
+        && (payload_hash == UNSIGNED_PAYLOAD || payload_hash == STREAMING_UNSIGNED_PAYLOAD_TRAILER)
+    {
+        return Err(VerificationErrorKind::UnsignedPayloadNotAllowed.into());
+    }
+    Ok(payload_hash)
+}
+
+/// Compares two strings in constant time so that signatures can't be guessed from timing
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_time::test_parsers::parse_date_time;
+    use crate::http_request::test;
+    use crate::http_request::{sign, PayloadChecksumKind, SigningSettings};
+    use http0::Request;
+
+    fn now() -> SystemTime {
+        parse_date_time("20150830T123600Z").unwrap()
+    }
+
+    fn lookup(access_key_id: &str) -> Option<Credentials> {
+        // The test suite is signed with its own example credentials, or with the test credentials
+        let test_suite = Credentials::new(
+            "AKIDEXAMPLE",
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            None,
+            None,
+            "test",
+        );
+        [test_suite, Credentials::for_tests()]
+            .into_iter()
+            .find(|c| c.access_key_id() == access_key_id)
+    }
+
+    fn verify_test(request: &test::TestRequest) -> Result<VerifiedRequest, VerificationError> {
+        verify(
+            &SignableRequest::from(request),
+            &VerificationSettings::default(),
+            lookup,
+            now(),
+        )
+    }
+
+    #[test]
+    fn verifies_the_test_suite() {
+        for name in [
+            "get-header-value-trim",
+            "get-unreserved",
+            "get-vanilla",
+            "get-vanilla-query-order-key-case",
+            "get-vanilla-query-unreserved",
+            "post-header-key-sort",
+            "post-vanilla",
+            "post-vanilla-query",
+        ] {
+            let verified = verify_test(&test::v4::test_signed_request(name))
+                .unwrap_or_else(|err| panic!("{name}: {err}"));
+            assert_eq!("us-east-1", verified.region());
+            assert_eq!("service", verified.signing_name());
+            assert_eq!(SignatureLocation::Headers, verified.signature_location());
+            assert_eq!(now(), verified.time());
+        }
+    }
+
+    fn signed_request(
+        settings: SigningSettings,
+        body: &'static [u8],
+        credentials: Credentials,
+    ) -> Request<&'static [u8]> {
+        let identity = credentials.into();
+        let params = v4::SigningParams {
+            identity: &identity,
+            region: "us-west-2",
+            name: "lambda",
+            time: now(),
+            settings,
+        }
+        .into();
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("https://lambda.us-west-2.amazonaws.com/functions/a%20b?x=1&a=2")
+            .header("content-type", "application/json")
+            .body(body)
+            .unwrap();
+        let signable = SignableRequest::new(
+            "POST",
+            request.uri().to_string(),
+            request
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.to_str().unwrap())),
+            SignableBody::Bytes(body),
+        )
+        .unwrap();
+        let out = sign(signable, &params).unwrap();
+        out.output.apply_to_request_http0x(&mut request);
+        request
+    }
+
+    fn verify_request(
+        request: &Request<&'static [u8]>,
+        body: SignableBody<'_>,
+        settings: &VerificationSettings,
+        now: SystemTime,
+    ) -> Result<VerifiedRequest, VerificationError> {
+        let uri = request.uri().to_string();
+        let signable = SignableRequest::new(
+            request.method().as_str(),
+            uri,
+            request
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.to_str().unwrap())),
+            body,
+        )
+        .unwrap();
+        verify(&signable, settings, lookup, now)
+    }
+
+    fn kind(result: Result<VerifiedRequest, VerificationError>) -> VerificationErrorKind {
+        result.expect_err("verification should fail").kind().clone()
+    }
+
+    #[test]
+    fn verifies_signed_headers_and_body() {
+        let settings = SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        let request = signed_request(settings, b"{}", Credentials::for_tests());
+        let verification = VerificationSettings {
+            signing_name: Some("lambda".into()),
+            region: Some("us-west-2".into()),
+            ..Default::default()
+        };
+        let verified = verify_request(
+            &request,
+            SignableBody::Bytes(b"{}"),
+            &verification,
+            now() + Duration::from_secs(60),
+        )
+        .unwrap();
+        assert_eq!(v4::sha256_hex_string(b"{}"), verified.payload_hash());
+        assert_eq!(SignatureVersion::V4, verified.signature_version());
+
+        // the body can't be checked if it isn't available, but the signed hash still is
+        verify_request(
+            &request,
+            SignableBody::UnsignedPayload,
+            &verification,
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            VerificationErrorKind::PayloadHashMismatch,
+            kind(verify_request(
+                &request,
+                SignableBody::Bytes(b"{\"a\":1}"),
+                &verification,
+                now()
+            ))
+        );
+        assert_eq!(
+            VerificationErrorKind::RequestTimeTooSkewed,
+            kind(verify_request(
+                &request,
+                SignableBody::Bytes(b"{}"),
+                &verification,
+                now() - Duration::from_secs(16 * 60)
+            ))
+        );
+        let wrong_service = VerificationSettings {
+            signing_name: Some("s3".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            kind(verify_request(
+                &request,
+                SignableBody::Bytes(b"{}"),
+                &wrong_service,
+                now()
+            )),
+            VerificationErrorKind::CredentialScopeMismatch { .. }
+        ));
+
+        let mut tampered = request;
+        tampered
+            .headers_mut()
+            .insert("content-type", "text/plain".parse().unwrap());
+        assert_eq!(
+            VerificationErrorKind::SignatureMismatch,
+            kind(verify_request(
+                &tampered,
+                SignableBody::Bytes(b"{}"),
+                &verification,
+                now()
+            ))
+        );
+    }
+
+    #[test]
+    fn body_hash_is_required_without_a_content_sha256_header() {
+        let request = signed_request(Default::default(), b"{}", Credentials::for_tests());
+        let settings = VerificationSettings::default();
+        verify_request(&request, SignableBody::Bytes(b"{}"), &settings, now()).unwrap();
+        assert_eq!(
+            VerificationErrorKind::MissingPayloadHash,
+            kind(verify_request(
+                &request,
+                SignableBody::UnsignedPayload,
+                &settings,
+                now()
+            ))
+        );
+        assert_eq!(
+            VerificationErrorKind::SignatureMismatch,
+            kind(verify_request(
+                &request,
+                SignableBody::Bytes(b"[]"),
+                &settings,
+                now()
+            ))
+        );
+    }
+
+    #[test]
+    fn verifies_presigned_requests() {
+        let settings = SigningSettings {
+            signature_location: SignatureLocation::QueryParams,
+            expires_in: Some(Duration::from_secs(300)),
+            ..Default::default()
+        };
+        let identity = Credentials::for_tests().into();
+        let params = v4::SigningParams {
+            identity: &identity,
+            region: "us-west-2",
+            name: "s3",
+            time: now(),
+            settings,
+        }
+        .into();
+        let mut request = Request::builder()
+            .uri("https://bucket.s3.us-west-2.amazonaws.com/key")
+            .body(&b""[..])
+            .unwrap();
+        let signable = SignableRequest::new(
+            "GET",
+            request.uri().to_string(),
+            std::iter::empty(),
+            SignableBody::UnsignedPayload,
+        )
+        .unwrap();
+        sign(signable, &params)
+            .unwrap()
+            .output
+            .apply_to_request_http0x(&mut request);
+
+        let settings = VerificationSettings::default();
+        let verified = verify_request(
+            &request,
+            SignableBody::UnsignedPayload,
+            &settings,
+            now() + Duration::from_secs(299),
+        )
+        .unwrap();
+        assert_eq!(
+            SignatureLocation::QueryParams,
+            verified.signature_location()
+        );
+        assert_eq!(UNSIGNED_PAYLOAD, verified.payload_hash());
+
+        assert_eq!(
+            VerificationErrorKind::Expired,
+            kind(verify_request(
+                &request,
+                SignableBody::UnsignedPayload,
+                &settings,
+                now() + Duration::from_secs(301)
+            ))
+        );
+        let signed_payloads_only = VerificationSettings {
+            allow_unsigned_payload: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            VerificationErrorKind::UnsignedPayloadNotAllowed,
+            kind(verify_request(
+                &request,
+                SignableBody::UnsignedPayload,
+                &signed_payloads_only,
+                now()
+            ))
+        );
+    }
+
+    #[test]
+    fn checks_access_keys_and_session_tokens() {
+        let credentials =
+            Credentials::new("AKIDEXAMPLE", "secret", Some("token".into()), None, "t");
+        let request = signed_request(Default::default(), b"", credentials.clone());
+        let settings = VerificationSettings::default();
+        let signable = |request: &Request<&'static [u8]>| {
+            let headers: Vec<(String, String)> = request
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_str().unwrap().to_string()))
+                .collect();
+            (request.uri().to_string(), headers)
+        };
+        let (uri, headers) = signable(&request);
+        let verify_with = |credentials: Option<Credentials>| {
+            let request = SignableRequest::new(
+                "POST",
+                uri.as_str(),
+                headers.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+                SignableBody::Bytes(b""),
+            )
+            .unwrap();
+            verify(&request, &settings, |_| credentials.clone(), now())
+        };
+        verify_with(Some(credentials)).unwrap();
+        assert_eq!(
+            VerificationErrorKind::UnknownAccessKey {
+                access_key_id: "AKIDEXAMPLE".into()
+            },
+            kind(verify_with(None))
+        );
+        assert_eq!(
+            VerificationErrorKind::InvalidSecurityToken,
+            kind(verify_with(Some(Credentials::new(
+                "AKIDEXAMPLE",
+                "secret",
+                Some("other".into()),
+                None,
+                "t"
+            ))))
+        );
+    }
+
+    #[test]
+    fn rejects_unsigned_and_malformed_requests() {
+        let request = test::v4::test_request("get-vanilla");
+        assert_eq!(
+            VerificationErrorKind::MissingSignature,
+            kind(verify_test(&request))
+        );
+
+        let mut request = test::v4::test_signed_request("get-vanilla");
+        for (name, value) in request.headers.iter_mut() {
+            if name.eq_ignore_ascii_case("authorization") {
+                *value = value.replace("AWS4-HMAC-SHA256", "AWS4-HMAC-SHA1");
+            }
+        }
+        assert_eq!(
+            VerificationErrorKind::UnsupportedAlgorithm {
+                algorithm: "AWS4-HMAC-SHA1".into()
+            },
+            kind(verify_test(&request))
+        );
+    }
+
+    #[cfg(feature = "sigv4a")]
+    #[test]
+    fn verifies_sigv4a_requests() {
+        let identity = Credentials::for_tests().into();
+        let params = v4a::SigningParams {
+            identity: &identity,
+            region_set: "us-*",
+            name: "service",
+            time: now(),
+            settings: SigningSettings::default(),
+        }
+        .into();
+        let mut request = Request::builder()
+            .uri("https://example.amazonaws.com/path?a=b")
+            .body(&b""[..])
+            .unwrap();
+        let signable = SignableRequest::new(
+            "GET",
+            request.uri().to_string(),
+            std::iter::empty(),
+            SignableBody::Bytes(b""),
+        )
+        .unwrap();
+        sign(signable, &params)
+            .unwrap()
+            .output
+            .apply_to_request_http0x(&mut request);
+
+        let settings = VerificationSettings {
+            region: Some("us-east-1".into()),
+            ..Default::default()
+        };
+        let verified =
+            verify_request(&request, SignableBody::Bytes(b""), &settings, now()).unwrap();
+        assert_eq!(SignatureVersion::V4a, verified.signature_version());
+        assert_eq!("us-*", verified.region());
+
+        let settings = VerificationSettings {
+            region: Some("eu-west-1".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            kind(verify_request(
+                &request,
+                SignableBody::Bytes(b""),
+                &settings,
+                now()
+            )),
+            VerificationErrorKind::CredentialScopeMismatch { .. }
+        ));
+
+        let mut tampered = request;
+        *tampered.uri_mut() = "https://example.amazonaws.com/path?a=c".parse().unwrap();
+        assert_eq!(
+            VerificationErrorKind::SignatureMismatch,
+            kind(verify_request(
+                &tampered,
+                SignableBody::Bytes(b""),
+                &VerificationSettings::default(),
+                now()
+            ))
+        );
+    }
+}
@@ -184,6 +184,139 @@ impl Fs {
         }
         Ok(())
     }
+
+    /// Create a new file with the given contents, failing if the file already exists.
+    ///
+    /// Checking for existence and creating the file is a single atomic operation, so this can be used
+    /// to create lock files. On Unix, the file is created readable and writable by its owner only.
+    pub async fn create_new(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> std::io::Result<()> {
+        use fs::Inner;
+        match &self.0 {
+            Inner::Real => create_new_real(path.as_ref(), contents.as_ref()),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(fs) => {
+                    let mut fs = fs.lock().unwrap();
+                    let key = path.as_ref().as_os_str();
+                    if fs.contains_key(key) {
+                        return Err(std::io::ErrorKind::AlreadyExists.into());
+                    }
+                    fs.insert(key.into(), contents.as_ref().to_vec());
+                    Ok(())
+                }
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => create_new_real(
+                    &namespaced_path(real_path, namespaced_to, path.as_ref())?,
+                    contents.as_ref(),
+                ),
+            },
+        }
+    }
+
+    /// Rename a file, replacing the destination if it already exists.
+    ///
+    /// This is equivalent to `std::fs::rename`.
+    pub async fn rename(
+        &self,
+        from: impl AsRef<Path>,
+        to: impl AsRef<Path>,
+    ) -> std::io::Result<()> {
+        use fs::Inner;
+        match &self.0 {
+            Inner::Real => std::fs::rename(from, to),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(fs) => {
+                    let mut fs = fs.lock().unwrap();
+                    let contents = fs
+                        .remove(from.as_ref().as_os_str())
+                        .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+                    fs.insert(to.as_ref().as_os_str().into(), contents);
+                    Ok(())
+                }
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => std::fs::rename(
+                    namespaced_path(real_path, namespaced_to, from.as_ref())?,
+                    namespaced_path(real_path, namespaced_to, to.as_ref())?,
+                ),
+            },
+        }
+    }
+
+    /// Remove a file.
+    ///
+    /// This is equivalent to `std::fs::remove_file`.
+    pub async fn remove_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        use fs::Inner;
+        match &self.0 {
+            Inner::Real => std::fs::remove_file(path),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(fs) => fs
+                    .lock()
+                    .unwrap()
+                    .remove(path.as_ref().as_os_str())
+                    .map(|_| ())
+                    .ok_or_else(|| std::io::ErrorKind::NotFound.into()),
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => {
+                    std::fs::remove_file(namespaced_path(real_path, namespaced_to, path.as_ref())?)
+                }
+            },
+        }
+    }
+
+    /// Recursively create a directory and all of its missing parents.
+    ///
+    /// This is equivalent to `std::fs::create_dir_all`. In-memory file systems have no directories,
+    /// so this always succeeds for them.
+    pub async fn create_dir_all(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        use fs::Inner;
+        match &self.0 {
+            Inner::Real => std::fs::create_dir_all(path),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(_) => Ok(()),
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => std::fs::create_dir_all(namespaced_path(
+                    real_path,
+                    namespaced_to,
+                    path.as_ref(),
+                )?),
+            },
+        }
+    }
+}
+
+fn namespaced_path(
+    real_path: &Path,
+    namespaced_to: &Path,
+    path: &Path,
+) -> std::io::Result<PathBuf> {
+    let actual_path = path
+        .strip_prefix(namespaced_to)
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+    Ok(real_path.join(actual_path))
+}
+
+fn create_new_real(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
 }
 
 mod fs {
@@ -325,4 +458,40 @@ mod test {
         let result = fs.read_to_end(&path).await.expect("success");
         assert_eq!(b"test", &result[..]);
     }
+
+    #[tokio::test]
+    async fn fs_create_new_and_rename_with_real() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("nested/dir");
+        let lock = dir.join("lock");
+        let renamed = dir.join("renamed");
+
+        let fs = Fs::real();
+        fs.create_dir_all(&dir).await.expect("success");
+        fs.create_new(&lock, b"one").await.expect("success");
+        let err = fs.create_new(&lock, b"two").await.expect_err("exists");
+        assert_eq!(std::io::ErrorKind::AlreadyExists, err.kind());
+
+        fs.rename(&lock, &renamed).await.expect("success");
+        assert_eq!(b"one", &fs.read_to_end(&renamed).await.unwrap()[..]);
+        fs.read_to_end(&lock).await.expect_err("renamed away");
+
+        fs.remove_file(&renamed).await.expect("success");
+        fs.read_to_end(&renamed).await.expect_err("removed");
+    }
+
+    #[tokio::test]
+    async fn fs_create_new_and_rename_with_map() {
+        let fs = Fs::from_slice(&[("/a", "a")]);
+        let err = fs.create_new("/a", b"other").await.expect_err("exists");
+        assert_eq!(std::io::ErrorKind::AlreadyExists, err.kind());
+        fs.create_new("/b", b"b").await.expect("success");
+
+        fs.rename("/b", "/a").await.expect("success");
+        assert_eq!(b"b", &fs.read_to_end("/a").await.unwrap()[..]);
+        fs.read_to_end("/b").await.expect_err("renamed away");
+
+        fs.remove_file("/a").await.expect("success");
+        fs.remove_file("/a").await.expect_err("already removed");
+    }
 }
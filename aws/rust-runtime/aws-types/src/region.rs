@@ -7,7 +7,9 @@
 
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
 use std::borrow::Cow;
+use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 /// The region to send requests to.
 ///
@@ -99,12 +101,15 @@ impl From<Region> for SigningRegionSet {
     }
 }
 
+/// Creates a region set without validating it. Use [`SigningRegionSet::parse`] to validate the region set.
 impl From<&'static str> for SigningRegionSet {
     fn from(region: &'static str) -> Self {
         SigningRegionSet(Cow::Borrowed(region))
     }
 }
 
+/// Creates a region set from its regions without validating them. Use [`SigningRegionSet::parse`]
+/// to validate the region set.
 impl<'a> FromIterator<&'a str> for SigningRegionSet {
     fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
         let mut s = String::new();
@@ -133,3 +138,129 @@ impl AsRef<str> for SigningRegionSet {
         self.0.as_ref()
     }
 }
+
+impl SigningRegionSet {
+    /// Parses a comma-separated list of regions, such as `us-east-1,us-west-2`.
+    ///
+    /// Whitespace around each region is ignored. A region may contain a single `*` wildcard,
+    /// such as `us-*`, and `*` on its own matches every region.
+    ///
+    /// This will return an `InvalidSigningRegionSet` error if the list or any of its regions
+    /// is empty, or if a region contains anything other than lowercase letters, digits, `-`,
+    /// and at most one `*`.
+    pub fn parse(region_set: &str) -> Result<Self, InvalidSigningRegionSet> {
+        let regions = region_set
+            .split(',')
+            .map(|region| {
+                let region = region.trim();
+                if region.is_empty() {
+                    return Err(InvalidSigningRegionSet::new(
+                        "the region set must not contain empty regions",
+                    ));
+                }
+                let valid_character = |c: char| matches!(c, 'a'..='z' | '0'..='9' | '-' | '*');
+                if !region.chars().all(valid_character) {
+                    return Err(InvalidSigningRegionSet::new(format!(
+                        "`{region}` may only contain lowercase letters, digits, `-`, and `*`"
+                    )));
+                }
+                if region.matches('*').count() > 1 {
+                    return Err(InvalidSigningRegionSet::new(format!(
+                        "`{region}` may contain at most one `*` wildcard"
+                    )));
+                }
+                Ok(region)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(regions.into_iter().collect())
+    }
+
+    /// Returns the regions in this set.
+    pub fn regions(&self) -> impl Iterator<Item = &str> {
+        self.0.split(',')
+    }
+
+    /// Returns true if the given region is in this set, taking wildcards into account.
+    pub fn contains(&self, region: &str) -> bool {
+        self.regions().any(|pattern| match pattern.split_once('*') {
+            None => pattern == region,
+            Some((prefix, suffix)) => {
+                region.len() >= prefix.len() + suffix.len()
+                    && region.starts_with(prefix)
+                    && region.ends_with(suffix)
+            }
+        })
+    }
+}
+
+impl FromStr for SigningRegionSet {
+    type Err = InvalidSigningRegionSet;
+
+    fn from_str(region_set: &str) -> Result<Self, Self::Err> {
+        Self::parse(region_set)
+    }
+}
+
+impl Display for SigningRegionSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Error for when a region set can't be parsed.
+///
+/// See [`SigningRegionSet::parse`] for details on the format.
+#[derive(Debug)]
+pub struct InvalidSigningRegionSet {
+    reason: Cow<'static, str>,
+}
+
+impl InvalidSigningRegionSet {
+    fn new(reason: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for InvalidSigningRegionSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid signing region set: {}", self.reason)
+    }
+}
+
+impl Error for InvalidSigningRegionSet {}
+
+#[cfg(test)]
+mod tests {
+    use super::SigningRegionSet;
+
+    #[test]
+    fn parse_region_sets() {
+        let region_set = SigningRegionSet::parse(" us-east-1 ,us-west-2").unwrap();
+        assert_eq!("us-east-1,us-west-2", region_set.as_ref());
+        assert_eq!(
+            vec!["us-east-1", "us-west-2"],
+            region_set.regions().collect::<Vec<_>>()
+        );
+        assert_eq!("*", "*".parse::<SigningRegionSet>().unwrap().as_ref());
+        assert_eq!("us-*", SigningRegionSet::parse("us-*").unwrap().as_ref());
+
+        assert!(SigningRegionSet::parse("").is_err());
+        assert!(SigningRegionSet::parse("us-east-1,").is_err());
+        assert!(SigningRegionSet::parse("US-EAST-1").is_err());
+        assert!(SigningRegionSet::parse("us east 1").is_err());
+        assert!(SigningRegionSet::parse("*-*").is_err());
+    }
+
+    #[test]
+    fn region_set_contains() {
+        let region_set = SigningRegionSet::parse("us-*,eu-west-1,*-2").unwrap();
+        assert!(region_set.contains("us-east-1"));
+        assert!(region_set.contains("eu-west-1"));
+        assert!(region_set.contains("ap-southeast-2"));
+        assert!(!region_set.contains("eu-west-3"));
+        assert!(!region_set.contains("us"));
+        assert!(SigningRegionSet::from("*").contains("cn-north-1"));
+    }
+}
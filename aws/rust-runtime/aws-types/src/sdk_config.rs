@@ -12,7 +12,7 @@
 use crate::app_name::AppName;
 use crate::docs_for;
 use crate::origin::Origin;
-use crate::region::Region;
+use crate::region::{Region, SigningRegionSet};
 use crate::service_config::LoadServiceConfig;
 use aws_credential_types::provider::token::SharedTokenProvider;
 pub use aws_credential_types::provider::SharedCredentialsProvider;
@@ -66,6 +66,14 @@ This is useful for request bodies because, for small request bodies, compression
 
 **Only some services support request compression.** For services
 that don't support request compression, this setting does nothing.
+" };
+        (sigv4a_signing_region_set) => {
+"The set of regions that SigV4a signatures are valid in, such as `us-east-1,us-west-2` or `*`.
+
+This is used when the endpoint doesn't specify a region set. If neither sets one, requests are signed
+for the client's region.
+
+**Only some services support SigV4a.** For services that don't, this setting does nothing.
 " };
     }
 }
@@ -92,6 +100,7 @@ pub struct SdkConfig {
     config_origins: HashMap<&'static str, Origin>,
     disable_request_compression: Option<bool>,
     request_min_compression_size_bytes: Option<u32>,
+    sigv4a_signing_region_set: Option<SigningRegionSet>,
 }
 
 /// Builder for AWS Shared Configuration
@@ -120,6 +129,7 @@ pub struct Builder {
     config_origins: HashMap<&'static str, Origin>,
     disable_request_compression: Option<bool>,
     request_min_compression_size_bytes: Option<u32>,
+    sigv4a_signing_region_set: Option<SigningRegionSet>,
 }
 
 impl Builder {
@@ -655,6 +665,24 @@ impl Builder {
         self
     }
 
+    #[doc = docs_for!(sigv4a_signing_region_set)]
+    pub fn sigv4a_signing_region_set(
+        mut self,
+        sigv4a_signing_region_set: SigningRegionSet,
+    ) -> Self {
+        self.set_sigv4a_signing_region_set(Some(sigv4a_signing_region_set));
+        self
+    }
+
+    #[doc = docs_for!(sigv4a_signing_region_set)]
+    pub fn set_sigv4a_signing_region_set(
+        &mut self,
+        sigv4a_signing_region_set: Option<SigningRegionSet>,
+    ) -> &mut Self {
+        self.sigv4a_signing_region_set = sigv4a_signing_region_set;
+        self
+    }
+
     /// Sets the [`BehaviorVersion`] for the [`SdkConfig`]
     pub fn behavior_version(mut self, behavior_version: BehaviorVersion) -> Self {
         self.set_behavior_version(Some(behavior_version));
@@ -720,6 +748,7 @@ impl Builder {
             config_origins: self.config_origins,
             disable_request_compression: self.disable_request_compression,
             request_min_compression_size_bytes: self.request_min_compression_size_bytes,
+            sigv4a_signing_region_set: self.sigv4a_signing_region_set,
         }
     }
 }
@@ -871,6 +900,11 @@ impl SdkConfig {
         self.request_min_compression_size_bytes
     }
 
+    /// Configured SigV4a signing region set.
+    pub fn sigv4a_signing_region_set(&self) -> Option<&SigningRegionSet> {
+        self.sigv4a_signing_region_set.as_ref()
+    }
+
     /// Configured stalled stream protection
     pub fn stalled_stream_protection(&self) -> Option<StalledStreamProtectionConfig> {
         self.stalled_stream_protection_config.clone()
@@ -933,6 +967,7 @@ impl SdkConfig {
             config_origins: self.config_origins,
             disable_request_compression: self.disable_request_compression,
             request_min_compression_size_bytes: self.request_min_compression_size_bytes,
+            sigv4a_signing_region_set: self.sigv4a_signing_region_set,
         }
     }
 }
@@ -6,11 +6,14 @@
 package software.amazon.smithy.rustsdk
 
 import software.amazon.smithy.aws.traits.HttpChecksumTrait
+import software.amazon.smithy.model.knowledge.TopDownIndex
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
 import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.Visibility
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
@@ -25,6 +28,7 @@ import software.amazon.smithy.rust.codegen.core.util.expectMember
 import software.amazon.smithy.rust.codegen.core.util.getTrait
 import software.amazon.smithy.rust.codegen.core.util.inputShape
 import software.amazon.smithy.rust.codegen.core.util.orNull
+import software.amazon.smithy.rust.codegen.core.util.thenSingletonListOf
 
 internal fun RuntimeConfig.awsInlineableHttpRequestChecksum() =
     RuntimeType.forInlineDependency(
@@ -35,6 +39,7 @@ internal fun RuntimeConfig.awsInlineableHttpRequestChecksum() =
             CargoDependency.HttpBody,
             CargoDependency.Tracing,
             AwsCargoDependency.awsRuntime(this).withFeature("http-02x"),
+            AwsCargoDependency.awsSigv4(this),
             CargoDependency.smithyChecksums(this),
             CargoDependency.smithyHttp(this),
             CargoDependency.smithyRuntimeApiClient(this),
@@ -51,6 +56,86 @@ class HttpRequestChecksumDecorator : ClientCodegenDecorator {
         operation: OperationShape,
         baseCustomizations: List<OperationCustomization>,
     ): List<OperationCustomization> = baseCustomizations + HttpRequestChecksumCustomization(codegenContext, operation)
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> =
+        baseCustomizations +
+            usesRequestChecksums(codegenContext).thenSingletonListOf {
+                HttpRequestChecksumConfigCustomization(codegenContext)
+            }
+
+    private fun usesRequestChecksums(codegenContext: ClientCodegenContext): Boolean {
+        val index = TopDownIndex.of(codegenContext.model)
+        val ops = index.getContainedOperations(codegenContext.serviceShape.id)
+        return ops.any { it.getTrait<HttpChecksumTrait>()?.requestAlgorithmMember?.isPresent == true }
+    }
+}
+
+class HttpRequestChecksumConfigCustomization(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenScope =
+        arrayOf(
+            "SignChunkedPayloads" to
+                AwsCargoDependency.awsRuntime(runtimeConfig).withFeature("http-02x").toType()
+                    .resolve("content_encoding::SignChunkedPayloads"),
+            *preludeScope,
+        )
+
+    override fun section(section: ServiceConfig) =
+        writable {
+            when (section) {
+                ServiceConfig.ConfigImpl -> {
+                    rustTemplate(
+                        """
+                        /// Returns whether the chunks of streaming request bodies are signed, if it was set.
+                        pub fn sign_chunked_payloads(&self) -> #{Option}<bool> {
+                            self.config.load::<#{SignChunkedPayloads}>().map(|it| it.0)
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                ServiceConfig.BuilderImpl -> {
+                    rustTemplate(
+                        """
+                        /// Sets whether the chunks of streaming request bodies are signed.
+                        ///
+                        /// Streaming request bodies that are checksummed are sent `aws-chunked` encoded. By default,
+                        /// only the request is signed. When this is enabled, every chunk, and the trailing checksum,
+                        /// are signed as well, for endpoints that don't accept unsigned payloads.
+                        pub fn sign_chunked_payloads(mut self, sign_chunked_payloads: impl #{Into}<#{Option}<bool>>) -> Self {
+                            self.set_sign_chunked_payloads(sign_chunked_payloads.into());
+                            self
+                        }
+
+                        /// Sets whether the chunks of streaming request bodies are signed.
+                        ///
+                        /// See [`sign_chunked_payloads`](Self::sign_chunked_payloads).
+                        pub fn set_sign_chunked_payloads(&mut self, sign_chunked_payloads: #{Option}<bool>) -> &mut Self {
+                            self.config.store_or_unset(sign_chunked_payloads.map(#{SignChunkedPayloads}));
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                is ServiceConfig.BuilderFromConfigBag -> {
+                    rustTemplate(
+                        """
+                        ${section.builder}.set_sign_chunked_payloads(
+                            ${section.configBag}.load::<#{SignChunkedPayloads}>().map(|it| it.0));
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                else -> emptySection
+            }
+        }
 }
 
 private fun HttpChecksumTrait.requestAlgorithmMember(
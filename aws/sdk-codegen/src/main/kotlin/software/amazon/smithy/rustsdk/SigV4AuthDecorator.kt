@@ -14,6 +14,7 @@ import software.amazon.smithy.model.shapes.ShapeId
 import software.amazon.smithy.rulesengine.language.EndpointRuleSet
 import software.amazon.smithy.rulesengine.traits.EndpointRuleSetTrait
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.WebSocketTransportFeature
 import software.amazon.smithy.rust.codegen.client.smithy.customize.AuthSchemeOption
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ConditionalDecorator
@@ -32,7 +33,10 @@ import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
 import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.smithy.customize.AdHocCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.adhocCustomization
 import software.amazon.smithy.rust.codegen.core.util.dq
 import software.amazon.smithy.rust.codegen.core.util.getTrait
 import software.amazon.smithy.rust.codegen.core.util.hasEventStreamOperations
@@ -109,7 +113,19 @@ class SigV4AuthDecorator : ConditionalDecorator(
                 codegenContext: ClientCodegenContext,
                 baseCustomizations: List<ConfigCustomization>,
             ): List<ConfigCustomization> =
-                baseCustomizations + SigV4SigningConfig(codegenContext.runtimeConfig, codegenContext.serviceShape.getTrait())
+                baseCustomizations + SigV4SigningConfig(codegenContext.runtimeConfig, codegenContext.serviceShape.getTrait()) +
+                    codegenContext.usesSigV4a().thenSingletonListOf {
+                        SigV4aSigningRegionSetConfig(codegenContext.runtimeConfig)
+                    }
+
+            override fun extraSections(codegenContext: ClientCodegenContext): List<AdHocCustomization> =
+                codegenContext.usesSigV4a().thenSingletonListOf {
+                    adhocCustomization<SdkConfigSection.CopySdkConfigToClientConfig> { section ->
+                        rust(
+                            "${section.serviceConfigBuilder}.set_sigv4a_signing_region_set(${section.sdkConfig}.sigv4a_signing_region_set().cloned());",
+                        )
+                    }
+                }
 
             override fun extras(
                 codegenContext: ClientCodegenContext,
@@ -119,6 +135,10 @@ class SigV4AuthDecorator : ConditionalDecorator(
                     // Add optional feature for SigV4a support
                     rustCrate.mergeFeature(Feature("sigv4a", true, listOf("aws-runtime/sigv4a")))
                 }
+                if (codegenContext.serviceShape.hasEventStreamOperations(codegenContext.model)) {
+                    // Sign the handshakes of event streams sent over a WebSocket
+                    rustCrate.mergeFeature(WebSocketTransportFeature.copy(deps = listOf("aws-runtime/event-stream-websocket")))
+                }
             }
         },
 ) {
@@ -184,6 +204,65 @@ private class SigV4SigningConfig(
         }
 }
 
+private class SigV4aSigningRegionSetConfig(runtimeConfig: RuntimeConfig) : ConfigCustomization() {
+    private val codegenScope =
+        arrayOf(
+            *preludeScope,
+            "SigningRegionSet" to AwsRuntimeType.awsTypes(runtimeConfig).resolve("region::SigningRegionSet"),
+        )
+
+    override fun section(section: ServiceConfig): Writable =
+        writable {
+            when (section) {
+                ServiceConfig.ConfigImpl -> {
+                    rustTemplate(
+                        """
+                        /// Returns the SigV4a signing region set, if it was provided.
+                        pub fn sigv4a_signing_region_set(&self) -> #{Option}<&#{SigningRegionSet}> {
+                            self.config.load::<#{SigningRegionSet}>()
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                ServiceConfig.BuilderImpl -> {
+                    rustTemplate(
+                        """
+                        /// Sets the set of regions that SigV4a signatures are valid in, such as `us-east-1,us-west-2` or `*`.
+                        ///
+                        /// This is used when the endpoint doesn't specify a region set. If neither sets one, requests
+                        /// are signed for the client's region.
+                        pub fn sigv4a_signing_region_set(mut self, sigv4a_signing_region_set: impl #{Into}<#{Option}<#{SigningRegionSet}>>) -> Self {
+                            self.set_sigv4a_signing_region_set(sigv4a_signing_region_set.into());
+                            self
+                        }
+
+                        /// Sets the set of regions that SigV4a signatures are valid in, such as `us-east-1,us-west-2` or `*`.
+                        ///
+                        /// This is used when the endpoint doesn't specify a region set. If neither sets one, requests
+                        /// are signed for the client's region.
+                        pub fn set_sigv4a_signing_region_set(&mut self, sigv4a_signing_region_set: #{Option}<#{SigningRegionSet}>) -> &mut Self {
+                            self.config.store_or_unset(sigv4a_signing_region_set);
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                is ServiceConfig.BuilderFromConfigBag -> {
+                    rustTemplate(
+                        "${section.builder}.set_sigv4a_signing_region_set(${section.configBag}.load::<#{SigningRegionSet}>().cloned());",
+                        *codegenScope,
+                    )
+                }
+
+                else -> emptySection
+            }
+        }
+}
+
 private class AuthServiceRuntimePluginCustomization(private val codegenContext: ClientCodegenContext) :
     ServiceRuntimePluginCustomization() {
     private val runtimeConfig = codegenContext.runtimeConfig
@@ -0,0 +1,101 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rustsdk.customize.s3
+
+import software.amazon.smithy.rulesengine.traits.ContextIndex
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.endpoint.ClientContextConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.endpoint.EndpointTypesGenerator
+import software.amazon.smithy.rust.codegen.client.smithy.endpoint.generators.EndpointParamsGenerator
+import software.amazon.smithy.rust.codegen.client.smithy.endpoint.rustName
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.configParamNewtype
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.loadFromConfigBag
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.Visibility
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.orNull
+import software.amazon.smithy.rust.codegen.core.util.toPascalCase
+import software.amazon.smithy.rustsdk.AwsCargoDependency
+import software.amazon.smithy.rustsdk.InlineAwsDependency
+
+/**
+ * Adds the `presigned_post` module, used to create presigned POST policies for uploading objects from a browser form.
+ *
+ * The policy is presigned with a client config rather than sent as a request, so this also generates how to
+ * load the endpoint parameters for the policy's bucket from the config, to resolve the URL the form is posted to.
+ */
+class S3PresignedPostDecorator : ClientCodegenDecorator {
+    override val name: String = "S3PresignedPostDecorator"
+    override val order: Byte = 0
+
+    override fun extras(
+        codegenContext: ClientCodegenContext,
+        rustCrate: RustCrate,
+    ) {
+        val runtimeConfig = codegenContext.runtimeConfig
+        val endpointTypesGenerator = EndpointTypesGenerator.fromContext(codegenContext)
+        rustCrate.withModule(ClientRustModule.Config.endpoint) {
+            rustTemplate(
+                """
+                impl #{PostPolicy} {
+                    pub(crate) fn endpoint_params(&self, config: &crate::Config) -> #{Result}<#{Params}, #{BoxError}> {
+                        let cfg = &config.config;
+                        #{Params}::builder()
+                            #{param_setters}
+                            .set_bucket(#{Some}(self.bucket().to_owned()))
+                            .build()
+                            .map_err(#{Into}::into)
+                    }
+                }
+                """,
+                *preludeScope,
+                "BoxError" to RuntimeType.boxError(runtimeConfig),
+                "Params" to endpointTypesGenerator.paramsStruct(),
+                "PostPolicy" to presignedPostModule(runtimeConfig).resolve("PostPolicy"),
+                "param_setters" to
+                    writable {
+                        endpointTypesGenerator.params.toList().filter { it.isBuiltIn }.forEach { param ->
+                            endpointTypesGenerator.builtInFor(param, "cfg")?.also { defaultValue ->
+                                rust(".set_${param.name.rustName()}(#W)", defaultValue)
+                            }
+                        }
+
+                        val idx = ContextIndex.of(codegenContext.model)
+                        idx.getClientContextParams(codegenContext.serviceShape).orNull()?.parameters?.forEach { (name, param) ->
+                            val setterName = EndpointParamsGenerator.setterName(name)
+                            val inner = ClientContextConfigCustomization.toSymbol(param.type, codegenContext.symbolProvider)
+                            val newtype = configParamNewtype(name.toPascalCase(), inner, runtimeConfig)
+                            rustTemplate(
+                                ".$setterName(cfg.#{load_from_service_config_layer})",
+                                "load_from_service_config_layer" to loadFromConfigBag(inner.name, newtype),
+                            )
+                        }
+                    },
+            )
+        }
+    }
+}
+
+private fun presignedPostModule(runtimeConfig: RuntimeConfig) =
+    RuntimeType.forInlineDependency(
+        InlineAwsDependency.forRustFileAs(
+            "s3_presigned_post",
+            "presigned_post",
+            Visibility.PUBLIC,
+            AwsCargoDependency.awsCredentialTypes(runtimeConfig),
+            AwsCargoDependency.awsSigv4(runtimeConfig),
+            CargoDependency.smithyRuntimeApiClient(runtimeConfig),
+            CargoDependency.smithyTypes(runtimeConfig),
+        ),
+    )
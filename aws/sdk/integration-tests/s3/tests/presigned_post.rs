@@ -0,0 +1,189 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_sdk_s3 as s3;
+use s3::config::{Credentials, Region};
+use s3::presigned_post::PostPolicy;
+use s3::presigning::PresigningConfig;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn config(region: &'static str) -> s3::config::Builder {
+    s3::Config::builder().region(Region::new(region))
+}
+
+fn presigning_config() -> PresigningConfig {
+    PresigningConfig::builder()
+        // 2015-12-29T00:00:00Z
+        .start_time(UNIX_EPOCH + Duration::from_secs(1451347200))
+        .expires_in(Duration::from_secs(36 * 3600))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn presigned_post() {
+    let policy = PostPolicy::builder()
+        .bucket("sigv4examplebucket")
+        .key_starts_with("user/user1/")
+        .acl("public-read")
+        .success_action_redirect(
+            "http://sigv4examplebucket.s3.amazonaws.com/successful_upload.html",
+        )
+        .content_type_starts_with("image/")
+        .metadata("uuid", "14365123651274")
+        .content_length_range(1, 1024)
+        .build()
+        .unwrap();
+    let presigned = policy
+        .presign(
+            &config("us-east-1").build(),
+            &Credentials::for_tests_with_session_token(),
+            &presigning_config(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        "https://sigv4examplebucket.s3.us-east-1.amazonaws.com/",
+        presigned.url()
+    );
+    let fields: Vec<_> = presigned.fields().collect();
+    let names: Vec<_> = fields.iter().map(|(name, _)| *name).collect();
+    assert_eq!(
+        vec![
+            "acl",
+            "success_action_redirect",
+            "x-amz-meta-uuid",
+            "x-amz-algorithm",
+            "x-amz-credential",
+            "x-amz-date",
+            "x-amz-security-token",
+            "policy",
+            "x-amz-signature",
+        ],
+        names
+    );
+    let field = |name: &str| fields.iter().find(|(n, _)| *n == name).unwrap().1;
+    assert_eq!("AWS4-HMAC-SHA256", field("x-amz-algorithm"));
+    assert_eq!(
+        "ANOTREAL/20151229/us-east-1/s3/aws4_request",
+        field("x-amz-credential")
+    );
+    assert_eq!("20151229T000000Z", field("x-amz-date"));
+
+    let document = aws_smithy_types::base64::decode(field("policy")).unwrap();
+    assert_eq!(
+        concat!(
+            r#"{"expiration":"2015-12-30T12:00:00Z","conditions":["#,
+            r#"{"bucket":"sigv4examplebucket"},"#,
+            r#"["starts-with","$key","user/user1/"],"#,
+            r#"{"acl":"public-read"},"#,
+            r#"{"success_action_redirect":"http://sigv4examplebucket.s3.amazonaws.com/successful_upload.html"},"#,
+            r#"["starts-with","$Content-Type","image/"],"#,
+            r#"{"x-amz-meta-uuid":"14365123651274"},"#,
+            r#"["content-length-range",1,1024],"#,
+            r#"{"x-amz-algorithm":"AWS4-HMAC-SHA256"},"#,
+            r#"{"x-amz-credential":"ANOTREAL/20151229/us-east-1/s3/aws4_request"},"#,
+            r#"{"x-amz-date":"20151229T000000Z"},"#,
+            r#"{"x-amz-security-token":"notarealsessiontoken"}]}"#,
+        ),
+        std::str::from_utf8(&document).unwrap()
+    );
+
+    let signing_key = aws_sigv4::sign::v4::generate_signing_key(
+        "notrealrnrELgWzOk3IfjzDKtFBhDby",
+        presigning_config().start_time(),
+        "us-east-1",
+        "s3",
+    );
+    assert_eq!(
+        aws_sigv4::sign::v4::calculate_signature(signing_key, field("policy").as_bytes()),
+        field("x-amz-signature")
+    );
+}
+
+#[tokio::test]
+async fn presigned_post_escapes_values_and_uses_path_style_for_dotted_buckets() {
+    let policy = PostPolicy::builder()
+        .bucket("my.bucket")
+        .key("uploads/\"quoted\"\\\n.txt")
+        .build()
+        .unwrap();
+    let presigned = policy
+        .presign(
+            &config("us-west-2").build(),
+            &Credentials::for_tests(),
+            &presigning_config(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        "https://s3.us-west-2.amazonaws.com/my.bucket/",
+        presigned.url()
+    );
+    let fields: Vec<_> = presigned.fields().collect();
+    assert_eq!(("key", "uploads/\"quoted\"\\\n.txt"), fields[0]);
+    assert!(!fields
+        .iter()
+        .any(|(name, _)| *name == "x-amz-security-token"));
+    let policy = fields.iter().find(|(name, _)| *name == "policy").unwrap().1;
+    let document = String::from_utf8(aws_smithy_types::base64::decode(policy).unwrap()).unwrap();
+    assert!(
+        document.contains(r#"{"key":"uploads/\"quoted\"\\\n.txt"}"#),
+        "{document}"
+    );
+}
+
+#[tokio::test]
+async fn presigned_post_honors_endpoint_url() {
+    let policy = PostPolicy::builder().bucket("uploads").build().unwrap();
+    let config = config("us-west-2")
+        .endpoint_url("http://localhost:9000")
+        .force_path_style(true)
+        .build();
+    let presigned = policy
+        .presign(&config, &Credentials::for_tests(), &presigning_config())
+        .await
+        .unwrap();
+
+    assert_eq!("http://localhost:9000/uploads/", presigned.url());
+    let credential = presigned
+        .fields()
+        .find(|(name, _)| *name == "x-amz-credential")
+        .unwrap()
+        .1;
+    assert_eq!("ANOTREAL/20151229/us-west-2/s3/aws4_request", credential);
+}
+
+#[tokio::test]
+async fn presigned_post_requires_a_region() {
+    let policy = PostPolicy::builder().bucket("bucket").build().unwrap();
+    let err = policy
+        .presign(
+            &s3::Config::builder().build(),
+            &Credentials::for_tests(),
+            &presigning_config(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!("the client config has no region", err.to_string());
+}
+
+#[test]
+fn post_policy_build_errors() {
+    let err = PostPolicy::builder().key("key").build().unwrap_err();
+    assert_eq!("`bucket` is required", err.to_string());
+
+    let err = PostPolicy::builder()
+        .bucket("bucket")
+        .content_length_range(10, 1)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        "`content_length_range` minimum (10) must not be greater than its maximum (1)",
+        err.to_string()
+    );
+}
@@ -23,6 +23,7 @@ import software.amazon.smithy.rust.codegen.client.smithy.generators.config.Confi
 import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
 import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
@@ -200,7 +201,12 @@ private class HttpAuthServiceRuntimePluginCustomization(
                         registerNamedAuthScheme("BearerAuthScheme")
                     }
                     if (authSchemes.digest) {
-                        registerNamedAuthScheme("DigestAuthScheme")
+                        // Digest auth learns its challenge from responses, so the scheme shares
+                        // its state with an interceptor and a retry classifier
+                        rustTemplate("let digest_auth_scheme = #{DigestAuthScheme}::new();", *codegenScope)
+                        section.registerInterceptor(this) { rust("digest_auth_scheme.interceptor()") }
+                        section.registerRetryClassifier(this) { rust("digest_auth_scheme.retry_classifier()") }
+                        registerAuthScheme { rust("digest_auth_scheme") }
                     }
                 }
 
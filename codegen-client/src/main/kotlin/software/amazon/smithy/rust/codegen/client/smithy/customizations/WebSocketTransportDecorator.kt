@@ -0,0 +1,180 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.Feature
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.smithy.customize.NamedCustomization
+import software.amazon.smithy.rust.codegen.core.util.extendIf
+import software.amazon.smithy.rust.codegen.core.util.hasEventStreamOperations
+import software.amazon.smithy.rust.codegen.core.util.isInputEventStream
+
+/** Crate feature that enables sending event streams over a WebSocket */
+val WebSocketTransportFeature = Feature("event-stream-websocket", false, listOf("aws-smithy-http/event-stream-websocket"))
+
+/**
+ * Lets the clients of services with event stream operations send their input event streams over a WebSocket
+ * transport set on the client config, behind the `event-stream-websocket` crate feature.
+ */
+class WebSocketTransportDecorator : ClientCodegenDecorator {
+    override val name: String = "WebSocketTransport"
+    override val order: Byte = 0
+
+    private fun enabled(ctx: ClientCodegenContext) = ctx.serviceShape.hasEventStreamOperations(ctx.model)
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> =
+        baseCustomizations.extendIf(enabled(codegenContext)) {
+            WebSocketTransportConfigCustomization(codegenContext)
+        }
+
+    override fun operationCustomizations(
+        codegenContext: ClientCodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>,
+    ): List<OperationCustomization> =
+        baseCustomizations.extendIf(operation.isInputEventStream(codegenContext.model)) {
+            WebSocketTransportOperationCustomization()
+        }
+
+    override fun extras(
+        codegenContext: ClientCodegenContext,
+        rustCrate: RustCrate,
+    ) {
+        if (enabled(codegenContext)) {
+            rustCrate.mergeFeature(WebSocketTransportFeature)
+        }
+    }
+}
+
+/**
+ * Add a `websocket_transport` field to Service config.
+ */
+class WebSocketTransportConfigCustomization(codegenContext: ClientCodegenContext) : NamedCustomization<ServiceConfig>() {
+    private val rc = codegenContext.runtimeConfig
+    private val websocket = RuntimeType.smithyHttp(rc).resolve("event_stream::websocket")
+    private val codegenScope =
+        arrayOf(
+            *preludeScope,
+            "SharedRuntimePlugin" to RuntimeType.sharedRuntimePlugin(rc),
+            "WebSocketRuntimePlugin" to websocket.resolve("WebSocketRuntimePlugin"),
+            "WebSocketTransport" to websocket.resolve("WebSocketTransport"),
+        )
+    private val feature = WebSocketTransportFeature.name
+
+    override fun section(section: ServiceConfig): Writable {
+        return when (section) {
+            ServiceConfig.ConfigImpl ->
+                writable {
+                    rustTemplate(
+                        """
+                        /// Returns the WebSocket transport that event streams are sent over, if any.
+                        ##[cfg(feature = "$feature")]
+                        pub fn websocket_transport(&self) -> #{Option}<&#{WebSocketTransport}> {
+                            self.config.load::<#{WebSocketTransport}>()
+                        }
+
+                        /// Returns the runtime plugin that sends an operation's event stream over the WebSocket
+                        /// transport set on `config_override`, or else on this config.
+                        ##[cfg(feature = "$feature")]
+                        pub(crate) fn websocket_runtime_plugin(
+                            &self,
+                            config_override: #{Option}<&Builder>,
+                        ) -> #{Option}<#{SharedRuntimePlugin}> {
+                            let transport = config_override
+                                .and_then(|config_override| config_override.config.load::<#{WebSocketTransport}>())
+                                .or_else(|| self.websocket_transport())
+                                .cloned();
+                            #{Some}(#{SharedRuntimePlugin}::new(#{WebSocketRuntimePlugin}::new(transport)))
+                        }
+
+                        ##[cfg(not(feature = "$feature"))]
+                        pub(crate) fn websocket_runtime_plugin(
+                            &self,
+                            _config_override: #{Option}<&Builder>,
+                        ) -> #{Option}<#{SharedRuntimePlugin}> {
+                            #{None}
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+            ServiceConfig.BuilderImpl ->
+                writable {
+                    rustTemplate(
+                        """
+                        /// Sets the WebSocket transport that event streams are sent over.
+                        ///
+                        /// When set, operations with an event stream input open a WebSocket connection with it instead
+                        /// of sending an HTTP request, and receive their output stream over the same connection.
+                        ##[cfg(feature = "$feature")]
+                        pub fn websocket_transport(mut self, websocket_transport: #{WebSocketTransport}) -> Self {
+                            self.set_websocket_transport(#{Some}(websocket_transport));
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+
+                    rustTemplate(
+                        """
+                        /// Sets the WebSocket transport that event streams are sent over.
+                        ///
+                        /// When set, operations with an event stream input open a WebSocket connection with it instead
+                        /// of sending an HTTP request, and receive their output stream over the same connection.
+                        ##[cfg(feature = "$feature")]
+                        pub fn set_websocket_transport(
+                            &mut self,
+                            websocket_transport: #{Option}<#{WebSocketTransport}>,
+                        ) -> &mut Self {
+                            self.config.store_or_unset(websocket_transport);
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+            is ServiceConfig.BuilderFromConfigBag ->
+                writable {
+                    rustTemplate(
+                        """
+                        ##[cfg(feature = "$feature")]
+                        ${section.builder}.set_websocket_transport(${section.configBag}.load::<#{WebSocketTransport}>().cloned());
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+            else -> emptySection
+        }
+    }
+}
+
+private class WebSocketTransportOperationCustomization : OperationCustomization() {
+    override fun section(section: OperationSection): Writable =
+        writable {
+            if (section is OperationSection.AdditionalRuntimePlugins) {
+                rust(".with_operation_plugins(client_config.websocket_runtime_plugin(config_override.as_ref()))")
+            }
+        }
+}
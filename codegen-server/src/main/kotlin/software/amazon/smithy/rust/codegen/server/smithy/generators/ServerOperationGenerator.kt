@@ -5,15 +5,25 @@
 
 package software.amazon.smithy.rust.codegen.server.smithy.generators
 
+import software.amazon.smithy.aws.traits.auth.SigV4Trait
+import software.amazon.smithy.model.knowledge.ServiceIndex
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ShapeId
+import software.amazon.smithy.model.traits.HttpApiKeyAuthTrait
+import software.amazon.smithy.model.traits.HttpBasicAuthTrait
+import software.amazon.smithy.model.traits.HttpBearerAuthTrait
+import software.amazon.smithy.model.traits.HttpDigestAuthTrait
+import software.amazon.smithy.model.traits.OptionalAuthTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.documentShape
+import software.amazon.smithy.rust.codegen.core.rustlang.join
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
 import software.amazon.smithy.rust.codegen.core.util.toPascalCase
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
 
@@ -29,6 +39,7 @@ class ServerOperationGenerator(
         )
     private val symbolProvider = codegenContext.symbolProvider
     private val model = codegenContext.model
+    private val serviceShape = codegenContext.serviceShape
 
     private val operationName = symbolProvider.toSymbol(operation).name.toPascalCase()
     private val operationId = operation.id
@@ -44,6 +55,26 @@ class ServerOperationGenerator(
             }
         }
 
+    /** The IDs of the operation's effective auth schemes, in priority order. */
+    private fun authSchemes(): Writable {
+        val authSchemes = ServiceIndex.of(model).getEffectiveAuthSchemes(serviceShape, operation).keys
+        val schemeIds =
+            authSchemes.map { schemeShapeId ->
+                val schemeId =
+                    when (schemeShapeId) {
+                        HttpApiKeyAuthTrait.ID -> "auth::http::HTTP_API_KEY_AUTH_SCHEME_ID"
+                        HttpBasicAuthTrait.ID -> "auth::http::HTTP_BASIC_AUTH_SCHEME_ID"
+                        HttpBearerAuthTrait.ID -> "auth::http::HTTP_BEARER_AUTH_SCHEME_ID"
+                        HttpDigestAuthTrait.ID -> "auth::http::HTTP_DIGEST_AUTH_SCHEME_ID"
+                        SigV4Trait.ID -> "auth::SIGV4_SCHEME_ID"
+                        SIGV4A_TRAIT_ID -> "auth::SIGV4A_SCHEME_ID"
+                        else -> "auth::AuthSchemeId::new(${schemeShapeId.toString().dq()})"
+                    }
+                writable { rustTemplate("#{SmithyHttpServer}::$schemeId", *codegenScope) }
+            }
+        return writable { rustTemplate("&[#{SchemeIds:W}]", "SchemeIds" to schemeIds.join(", ")) }
+    }
+
     fun render(writer: RustWriter) {
         writer.documentShape(operation, model)
 
@@ -64,6 +95,11 @@ class ServerOperationGenerator(
                 type Error = #{Error:W};
             }
 
+            impl #{SmithyHttpServer}::auth::OperationAuth for $operationName {
+                const AUTH_SCHEMES: &'static [#{SmithyHttpServer}::auth::AuthSchemeId] = #{AuthSchemes:W};
+                const OPTIONAL_AUTH: bool = ${operation.hasTrait<OptionalAuthTrait>()};
+            }
+
             impl #{SmithyHttpServer}::instrumentation::sensitivity::Sensitivity for $operationName {
                 type RequestFmt = #{RequestType:W};
                 type ResponseFmt = #{ResponseType:W};
@@ -78,6 +114,7 @@ class ServerOperationGenerator(
             }
             """,
             "Error" to operationError(),
+            "AuthSchemes" to authSchemes(),
             "RequestValue" to requestFmt.value,
             "RequestType" to requestFmt.type,
             "ResponseValue" to responseFmt.value,
@@ -88,3 +125,5 @@ class ServerOperationGenerator(
         writer.rust("")
     }
 }
+
+private val SIGV4A_TRAIT_ID = ShapeId.from("aws.auth#sigv4a")
@@ -91,6 +91,13 @@ interface ServerProtocol : Protocol {
      */
     fun serverContentTypeCheckNoModeledInput(): Boolean = false
 
+    /**
+     * Returns the function that a non-empty request body is checked with before it is deserialized, or `null` if the
+     * protocol doesn't check request bodies. It's called as `check(&extensions, &bytes)` with the request's
+     * `http::Extensions`, so that services can configure the check with a layer.
+     */
+    fun requestBodyCheck(runtimeConfig: RuntimeConfig): RuntimeType? = null
+
     /** The protocol-specific `RequestRejection` type. **/
     fun requestRejection(runtimeConfig: RuntimeConfig): RuntimeType =
         ServerCargoDependency.smithyHttpServer(runtimeConfig)
@@ -317,6 +324,10 @@ class ServerRpcV2CborProtocol(
 
     override val protocolModulePath = "rpc_v2_cbor"
 
+    override fun requestBodyCheck(runtimeConfig: RuntimeConfig): RuntimeType =
+        ServerCargoDependency.smithyHttpServer(runtimeConfig)
+            .toType().resolve("protocol::$protocolModulePath::check_request_body")
+
     override fun structuredDataParser(): StructuredDataParserGenerator =
         CborParserGenerator(
             serverCodegenContext, httpBindingResolver, returnSymbolToParseFn(serverCodegenContext),
@@ -703,6 +703,19 @@ class ServerHttpBoundProtocolTraitImplGenerator(
             "let mut input = #T::default();",
             inputShape.serverBuilderSymbol(codegenContext),
         )
+        val parser = structuredDataParser.serverInputParser(operationShape)
+        val requestBodyCheck = parser?.let { protocol.requestBodyCheck(runtimeConfig) }
+        if (requestBodyCheck != null) {
+            // `RequestParts` doesn't hold the extensions, so take them out of the request before it's converted.
+            rustTemplate(
+                """
+                let (mut parts, body) = request.into_parts();
+                let extensions = std::mem::take(&mut parts.extensions);
+                let request = #{http}::Request::from_parts(parts, body);
+                """,
+                *codegenScope,
+            )
+        }
         Attribute.AllowUnusedVariables.render(this)
         rustTemplate(
             """
@@ -713,7 +726,6 @@ class ServerHttpBoundProtocolTraitImplGenerator(
             "Request" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("http::Request"),
             "RequestParts" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("http::RequestParts"),
         )
-        val parser = structuredDataParser.serverInputParser(operationShape)
 
         if (parser != null) {
             // `null` is only returned by Smithy when there are no members, but we know there's at least one, since
@@ -749,10 +761,15 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                         &headers,
                         Some("$expectedRequestContentType"),
                     )?;
+                    #{RequestBodyCheck:W}
                     input = #{parser}(bytes.as_ref(), input)?;
                     """,
                     *codegenScope,
                     "parser" to parser,
+                    "RequestBodyCheck" to
+                        writable {
+                            requestBodyCheck?.also { rust("#T(&extensions, bytes.as_ref())?;", it) }
+                        },
                 )
             }
         }
@@ -9,6 +9,7 @@ use aws_smithy_types::{Blob, DateTime};
 use minicbor::decode::Error;
 
 use crate::data::Type;
+use crate::validate::DecoderConfig;
 
 /// Provides functions for decoding a CBOR object with a known schema.
 ///
@@ -129,6 +130,15 @@ impl<'b> Decoder<'b> {
         }
     }
 
+    /// Creates a decoder for untrusted input.
+    ///
+    /// The whole input is checked against `config` before the decoder is returned; see the
+    /// [`validate`](crate::validate) module.
+    pub fn with_config(bytes: &'b [u8], config: &DecoderConfig) -> Result<Self, DeserializeError> {
+        crate::validate::validate(bytes, config)?;
+        Ok(Self::new(bytes))
+    }
+
     pub fn datatype(&self) -> Result<Type, DeserializeError> {
         self.decoder
             .datatype()
@@ -155,12 +165,50 @@ impl<'b> Decoder<'b> {
         double => f64(f64);
         /// Reads a null CBOR element at the current position.
         null => null(());
+        /// Reads an undefined CBOR element at the current position.
+        undefined => undefined(());
+        /// Reads a simple value at the current position.
+        simple => simple(u8);
         /// Returns the number of elements in a definite list. For indefinite lists it returns a `None`.
         list => array(Option<u64>);
         /// Returns the number of elements in a definite map. For indefinite map it returns a `None`.
         map => map(Option<u64>);
     }
 
+    /// Reads an integer of any width at the current position.
+    ///
+    /// CBOR integers range from `-2^64` to `2^64 - 1`, so they are widened to an `i128`.
+    pub fn int(&mut self) -> Result<i128, DeserializeError> {
+        self.decoder
+            .int()
+            .map(i128::from)
+            .map_err(DeserializeError::new)
+    }
+
+    /// Reads a tag at the current position and returns its number. The tagged item follows it.
+    pub fn tag(&mut self) -> Result<u64, DeserializeError> {
+        self.decoder
+            .tag()
+            .map(|tag| tag.as_u64())
+            .map_err(DeserializeError::new)
+    }
+
+    /// Reads the `break` stop code that terminates an indefinite-length array or map.
+    pub fn end(&mut self) -> Result<(), DeserializeError> {
+        let position = self.decoder.position();
+        match self.datatype()? {
+            Type::Break => {
+                self.decoder.set_position(position + 1);
+                Ok(())
+            }
+            ty => Err(DeserializeError::new(
+                Error::type_mismatch(ty.into_minicbor_type())
+                    .with_message("expected break")
+                    .at(position),
+            )),
+        }
+    }
+
     /// Returns the current position of the buffer, which will be decoded when any of the methods is called.
     pub fn position(&self) -> usize {
         self.decoder.position()
@@ -69,6 +69,10 @@ impl Encoder {
         double => f64(x: f64);
         /// Writes a null tag.
         null => null();
+        /// Writes an undefined tag.
+        undefined => undefined();
+        /// Writes a simple value.
+        simple => simple(x: u8);
         /// Writes an end tag.
         end => end();
     }
@@ -78,6 +82,32 @@ impl Encoder {
         self
     }
 
+    /// Writes a definite length byte string.
+    pub fn bytes(&mut self, x: &[u8]) -> &mut Self {
+        self.encoder.bytes(x).expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Writes an integer in the CBOR range `-2^64..=2^64 - 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` falls outside of the range representable by a CBOR integer.
+    pub fn int(&mut self, x: i128) -> &mut Self {
+        self.encoder
+            .int(x.try_into().expect("integer out of CBOR range"))
+            .expect(INFALLIBLE_WRITE);
+        self
+    }
+
+    /// Writes a tag. The caller must write the tagged item immediately afterwards.
+    pub fn tag(&mut self, tag: u64) -> &mut Self {
+        self.encoder
+            .tag(minicbor::data::Tag::new(tag))
+            .expect(INFALLIBLE_WRITE);
+        self
+    }
+
     /// Writes a fixed length array of given length.
     pub fn array(&mut self, len: usize) -> &mut Self {
         self.encoder
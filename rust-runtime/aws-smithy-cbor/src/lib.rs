@@ -12,6 +12,9 @@
 pub mod data;
 pub mod decode;
 pub mod encode;
+pub mod validate;
+pub mod value;
 
 pub use decode::Decoder;
 pub use encode::Encoder;
+pub use value::CborValue;
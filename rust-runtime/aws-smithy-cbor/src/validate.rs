@@ -0,0 +1,698 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Resource limits and well-formedness validation for untrusted CBOR input.
+//!
+//! [`Decoder::new`](crate::Decoder::new) accepts whatever `minicbor` can read. When the input
+//! comes from an untrusted peer (e.g. a server receiving rpcv2Cbor requests), use
+//! [`Decoder::with_config`](crate::Decoder::with_config) instead: it scans the whole input once,
+//! without recursion, before any data is handed out, and rejects it if it exceeds the configured
+//! limits or fails the configured [`Validation`].
+
+use std::collections::HashSet;
+
+use crate::decode::DeserializeError;
+use crate::value::CborValue;
+
+/// How strictly the encoding of the input is checked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Validation {
+    /// Only the configured limits are enforced.
+    #[default]
+    Lenient,
+    /// The input must be well-formed and maps must not contain duplicate keys.
+    Strict,
+    /// In addition to the [`Strict`](Validation::Strict) checks, integers, lengths and tags must
+    /// use their shortest encoding, indefinite-length items are rejected and map keys must be
+    /// sorted in the bytewise lexicographic order of their encodings, as required by the
+    /// [core deterministic encoding requirements] of RFC 8949.
+    ///
+    /// Floating-point values are not required to use their shortest encoding.
+    ///
+    /// [core deterministic encoding requirements]: https://www.rfc-editor.org/rfc/rfc8949.html#name-core-deterministic-encoding
+    Canonical,
+}
+
+/// Limits and validation rules enforced by [`Decoder::with_config`](crate::Decoder::with_config).
+///
+/// All limits are unset by default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecoderConfig {
+    max_depth: Option<usize>,
+    max_collection_len: Option<u64>,
+    max_input_len: Option<usize>,
+    validation: Validation,
+}
+
+impl DecoderConfig {
+    /// Creates a config with no limits and [`Validation::Lenient`].
+    pub fn lenient() -> Self {
+        Self::default()
+    }
+
+    /// Creates a config with no limits and [`Validation::Strict`].
+    pub fn strict() -> Self {
+        Self::default().with_validation(Validation::Strict)
+    }
+
+    /// Creates a config with no limits and [`Validation::Canonical`].
+    pub fn canonical() -> Self {
+        Self::default().with_validation(Validation::Canonical)
+    }
+
+    /// Sets the validation applied to the input.
+    pub fn with_validation(mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Sets the maximum nesting depth of arrays, maps and tags. A top-level scalar has a depth
+    /// of 0 and a top-level array containing scalars has a depth of 1.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Sets the maximum number of items in an array or of entries in a map.
+    pub fn with_max_collection_len(mut self, max_collection_len: u64) -> Self {
+        self.max_collection_len = Some(max_collection_len);
+        self
+    }
+
+    /// Sets the maximum size of the input, in bytes.
+    pub fn with_max_input_len(mut self, max_input_len: usize) -> Self {
+        self.max_input_len = Some(max_input_len);
+        self
+    }
+
+    /// Returns the validation applied to the input.
+    pub fn validation(&self) -> Validation {
+        self.validation
+    }
+
+    /// Returns the maximum nesting depth, if set.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Returns the maximum number of items in an array or of entries in a map, if set.
+    pub fn max_collection_len(&self) -> Option<u64> {
+        self.max_collection_len
+    }
+
+    /// Returns the maximum size of the input in bytes, if set.
+    pub fn max_input_len(&self) -> Option<usize> {
+        self.max_input_len
+    }
+}
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+/// The argument of a data item header.
+#[derive(Clone, Copy)]
+enum Argument {
+    Value(u64),
+    Indefinite,
+}
+
+enum Frame {
+    Array {
+        remaining: Option<u64>,
+        count: u64,
+    },
+    Map {
+        remaining: Option<u64>,
+        count: u64,
+        key_start: Option<usize>,
+        keys: Option<HashSet<Vec<u8>>>,
+        /// The encoding of the previous key, when key order is checked.
+        last_key: Option<Vec<u8>>,
+    },
+    Tag,
+}
+
+/// Scans every top-level data item in `bytes` and checks it against `config`.
+pub(crate) fn validate(bytes: &[u8], config: &DecoderConfig) -> Result<(), DeserializeError> {
+    if let Some(max) = config.max_input_len {
+        if bytes.len() > max {
+            return Err(DeserializeError::custom(
+                format!(
+                    "input of {} bytes exceeds the maximum of {max} bytes",
+                    bytes.len()
+                ),
+                0,
+            ));
+        }
+    }
+    Scanner {
+        bytes,
+        pos: 0,
+        config,
+        stack: Vec::new(),
+    }
+    .scan()
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    config: &'a DecoderConfig,
+    stack: Vec<Frame>,
+}
+
+impl<'a> Scanner<'a> {
+    fn strict(&self) -> bool {
+        self.config.validation != Validation::Lenient
+    }
+
+    fn canonical(&self) -> bool {
+        self.config.validation == Validation::Canonical
+    }
+
+    fn scan(&mut self) -> Result<(), DeserializeError> {
+        while self.pos < self.bytes.len() || !self.stack.is_empty() {
+            self.item()?;
+        }
+        Ok(())
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8], DeserializeError> {
+        if self.remaining() < len {
+            return Err(DeserializeError::custom(
+                "encountered unexpected end of input",
+                self.pos,
+            ));
+        }
+        let slice = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    /// Reads a data item header, returning its major type and argument.
+    fn header(&mut self) -> Result<(u8, Argument), DeserializeError> {
+        let start = self.pos;
+        let initial = self.read_slice(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let (argument, min) = match info {
+            0..=23 => return Ok((major, Argument::Value(u64::from(info)))),
+            24 => (u64::from(self.read_slice(1)?[0]), 24),
+            // Floats are the only items whose argument is not a number.
+            25..=27 if major == MAJOR_SIMPLE => {
+                self.read_slice(1 << (info - 24))?;
+                return Ok((major, Argument::Value(0)));
+            }
+            25..=27 => {
+                let width = 1 << (info - 24);
+                let argument = self
+                    .read_slice(width)?
+                    .iter()
+                    .fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+                (argument, 1u64 << (8 * (width / 2)))
+            }
+            31 if matches!(major, MAJOR_BYTES..=MAJOR_MAP | MAJOR_SIMPLE) => {
+                if self.canonical() && major != MAJOR_SIMPLE {
+                    return Err(DeserializeError::custom(
+                        "encountered indefinite-length item; canonical encoding requires definite lengths",
+                        start,
+                    ));
+                }
+                return Ok((major, Argument::Indefinite));
+            }
+            _ => {
+                return Err(DeserializeError::custom(
+                    format!("encountered malformed initial byte 0x{initial:02x}"),
+                    start,
+                ))
+            }
+        };
+        if major == MAJOR_SIMPLE && argument < 32 {
+            // RFC 8949 section 3.3: simple values below 32 must use the one-byte encoding.
+            return Err(DeserializeError::custom(
+                format!("encountered simple value {argument} in two-byte encoding"),
+                start,
+            ));
+        }
+        if self.canonical() && argument < min {
+            return Err(DeserializeError::custom(
+                format!("encountered non-canonical encoding of argument {argument}"),
+                start,
+            ));
+        }
+        Ok((major, Argument::Value(argument)))
+    }
+
+    fn push(&mut self, frame: Frame, start: usize) -> Result<(), DeserializeError> {
+        if let Some(max) = self.config.max_depth {
+            if self.stack.len() >= max {
+                return Err(DeserializeError::custom(
+                    format!("nesting depth exceeds the maximum of {max}"),
+                    start,
+                ));
+            }
+        }
+        self.stack.push(frame);
+        Ok(())
+    }
+
+    /// Scans the next data item, or a `break` if the innermost open collection is
+    /// indefinite-length.
+    fn item(&mut self) -> Result<(), DeserializeError> {
+        let start = self.pos;
+        // Remember where keys start so that duplicates can be detected once they're scanned.
+        if let Some(Frame::Map {
+            key_start,
+            keys: Some(_),
+            count,
+            ..
+        }) = self.stack.last_mut()
+        {
+            if *count % 2 == 0 && key_start.is_none() {
+                *key_start = Some(start);
+            }
+        }
+        let (major, argument) = self.header()?;
+        match (major, argument) {
+            (MAJOR_UNSIGNED | MAJOR_NEGATIVE | MAJOR_SIMPLE, Argument::Value(_)) => {
+                self.complete(start)
+            }
+            (MAJOR_BYTES | MAJOR_TEXT, Argument::Value(len)) => {
+                self.string_body(major, len, start)?;
+                self.complete(start)
+            }
+            (MAJOR_BYTES | MAJOR_TEXT, Argument::Indefinite) => {
+                loop {
+                    let chunk_start = self.pos;
+                    match self.header()? {
+                        (MAJOR_SIMPLE, Argument::Indefinite) => break,
+                        (chunk_major, Argument::Value(len)) if chunk_major == major => {
+                            self.string_body(major, len, chunk_start)?
+                        }
+                        _ => {
+                            return Err(DeserializeError::custom(
+                                "encountered invalid chunk in indefinite-length string",
+                                chunk_start,
+                            ))
+                        }
+                    }
+                }
+                self.complete(start)
+            }
+            (MAJOR_ARRAY | MAJOR_MAP, Argument::Value(len)) => {
+                check_len(self.config.max_collection_len, len, start)?;
+                let items = if major == MAJOR_MAP {
+                    len.saturating_mul(2)
+                } else {
+                    len
+                };
+                // Every item takes at least one byte.
+                if items > self.remaining() as u64 {
+                    return Err(DeserializeError::custom(
+                        format!("collection of {len} items is longer than the remaining input"),
+                        start,
+                    ));
+                }
+                if items == 0 {
+                    return self.complete(start);
+                }
+                self.push_collection(major, Some(items), start)
+            }
+            (MAJOR_ARRAY | MAJOR_MAP, Argument::Indefinite) => {
+                self.push_collection(major, None, start)
+            }
+            (MAJOR_TAG, Argument::Value(_)) => self.push(Frame::Tag, start),
+            (MAJOR_SIMPLE, Argument::Indefinite) => self.end_collection(start),
+            _ => unreachable!("header only returns indefinite arguments for major types 2-5 and 7"),
+        }
+    }
+
+    fn push_collection(
+        &mut self,
+        major: u8,
+        remaining: Option<u64>,
+        start: usize,
+    ) -> Result<(), DeserializeError> {
+        let frame = if major == MAJOR_MAP {
+            Frame::Map {
+                remaining,
+                count: 0,
+                key_start: None,
+                keys: self.strict().then(HashSet::new),
+                last_key: None,
+            }
+        } else {
+            Frame::Array {
+                remaining,
+                count: 0,
+            }
+        };
+        self.push(frame, start)
+    }
+
+    fn string_body(&mut self, major: u8, len: u64, start: usize) -> Result<(), DeserializeError> {
+        if len > self.remaining() as u64 {
+            return Err(DeserializeError::custom(
+                format!("string of {len} bytes is longer than the remaining input"),
+                start,
+            ));
+        }
+        let body = self.read_slice(len as usize)?;
+        if major == MAJOR_TEXT && self.strict() && std::str::from_utf8(body).is_err() {
+            return Err(DeserializeError::custom(
+                "encountered text string that is not valid UTF-8",
+                start,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Handles a `break` stop code.
+    fn end_collection(&mut self, start: usize) -> Result<(), DeserializeError> {
+        match self.stack.last() {
+            Some(Frame::Array {
+                remaining: None, ..
+            }) => {}
+            Some(Frame::Map {
+                remaining: None,
+                count,
+                ..
+            }) if count % 2 == 0 => {}
+            _ => {
+                return Err(DeserializeError::custom(
+                    "encountered unexpected break",
+                    start,
+                ))
+            }
+        }
+        self.stack.pop();
+        self.complete(start)
+    }
+
+    /// Records that the data item that began at `start` has been fully scanned, closing any
+    /// collections and tags that it completes.
+    fn complete(&mut self, start: usize) -> Result<(), DeserializeError> {
+        let strict = self.strict();
+        let canonical = self.canonical();
+        let max_len = self.config.max_collection_len;
+        let mut start = start;
+        loop {
+            let done = match self.stack.last_mut() {
+                None => return Ok(()),
+                Some(Frame::Tag) => true,
+                Some(Frame::Array { remaining, count }) => {
+                    *count += 1;
+                    if remaining.is_none() {
+                        let count = *count;
+                        check_len(max_len, count, start)?;
+                    }
+                    decrement(remaining)
+                }
+                Some(Frame::Map {
+                    remaining,
+                    count,
+                    key_start,
+                    keys,
+                    last_key,
+                }) => {
+                    let is_key = *count % 2 == 0;
+                    *count += 1;
+                    if is_key {
+                        if let (Some(keys), Some(key_start)) = (keys.as_mut(), key_start.take()) {
+                            let key = &self.bytes[key_start..self.pos];
+                            if !keys.insert(normalize_key(key, strict)) {
+                                return Err(DeserializeError::custom(
+                                    "encountered duplicate map key",
+                                    key_start,
+                                ));
+                            }
+                            if canonical {
+                                // Keys are already known to be distinct and shortest-form, so
+                                // comparing their encodings is enough.
+                                if matches!(last_key, Some(last) if last.as_slice() >= key) {
+                                    return Err(DeserializeError::custom(
+                                        "encountered map key out of canonical order",
+                                        key_start,
+                                    ));
+                                }
+                                *last_key = Some(key.to_vec());
+                            }
+                        }
+                    } else if remaining.is_none() {
+                        let entries = *count / 2;
+                        check_len(max_len, entries, start)?;
+                    }
+                    decrement(remaining)
+                }
+            };
+            if !done {
+                return Ok(());
+            }
+            // The innermost collection or tag is done, which completes an item of its parent.
+            self.stack.pop();
+            start = self.pos;
+        }
+    }
+}
+
+fn check_len(max: Option<u64>, len: u64, start: usize) -> Result<(), DeserializeError> {
+    match max {
+        Some(max) if len > max => Err(DeserializeError::custom(
+            format!("collection of {len} items exceeds the maximum of {max} items"),
+            start,
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Decrements the number of items left in a definite-length collection, returning whether it is
+/// done.
+fn decrement(remaining: &mut Option<u64>) -> bool {
+    match remaining {
+        Some(remaining) => {
+            *remaining -= 1;
+            *remaining == 0
+        }
+        None => false,
+    }
+}
+
+/// Returns a representation of a map key such that keys with the same value compare equal even
+/// when they are encoded differently.
+fn normalize_key(key: &[u8], strict: bool) -> Vec<u8> {
+    let major = key[0] >> 5;
+    // Containers and tags are compared byte for byte, to avoid unbounded recursion.
+    if strict && !matches!(major, MAJOR_ARRAY | MAJOR_MAP | MAJOR_TAG) {
+        if let Ok(Ok(encoded)) = CborValue::decode(key).map(|value| value.encode()) {
+            return encoded;
+        }
+    }
+    key.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate, DecoderConfig, Validation};
+    use crate::Decoder;
+
+    fn check(bytes: &[u8], config: DecoderConfig) -> Result<(), String> {
+        validate(bytes, &config).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn lenient_accepts_everything_well_formed() {
+        let inputs: &[&[u8]] = &[
+            &[0x01],
+            &[0x18, 0x01],
+            // [_ 1, [2, 3], [_ 4, 5]]
+            &[0x9f, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0x05, 0xff, 0xff],
+            // {_ "a": 1, "a": 2}
+            &[0xbf, 0x61, 0x61, 0x01, 0x61, 0x61, 0x02, 0xff],
+            // (_ "ab", "c")
+            &[0x7f, 0x62, 0x61, 0x62, 0x61, 0x63, 0xff],
+            // 1(1(1(0)))
+            &[0xc1, 0xc1, 0xc1, 0x00],
+            // [1(2.5), {}]
+            &[0x82, 0xc1, 0xf9, 0x41, 0x00, 0xa0],
+            &[],
+        ];
+        for input in inputs {
+            check(input, DecoderConfig::lenient()).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_and_malformed_input() {
+        assert!(check(&[0x82, 0x01], DecoderConfig::lenient()).is_err());
+        assert!(check(&[0x9f, 0x01], DecoderConfig::lenient()).is_err());
+        assert!(check(&[0x1c], DecoderConfig::lenient()).is_err());
+        assert!(check(&[0xff], DecoderConfig::lenient()).is_err());
+        // Break inside a definite-length array.
+        assert!(check(&[0x82, 0x01, 0xff], DecoderConfig::lenient()).is_err());
+        // Break after a key with no value.
+        assert!(check(&[0xbf, 0x01, 0xff], DecoderConfig::lenient()).is_err());
+        // Text chunk inside an indefinite-length byte string.
+        assert!(check(&[0x5f, 0x61, 0x61, 0xff], DecoderConfig::lenient()).is_err());
+        // Claims a billion-entry array in a handful of bytes.
+        let err = check(
+            &[0x9a, 0x3b, 0x9a, 0xca, 0x00, 0x01],
+            DecoderConfig::lenient(),
+        )
+        .unwrap_err();
+        assert!(err.contains("longer than the remaining input"), "{err}");
+    }
+
+    #[test]
+    fn max_input_len() {
+        let config = DecoderConfig::lenient().with_max_input_len(2);
+        check(&[0x18, 0x64], config.clone()).unwrap();
+        assert!(check(&[0x19, 0x01, 0x00], config).is_err());
+    }
+
+    #[test]
+    fn max_depth() {
+        let config = DecoderConfig::lenient().with_max_depth(2);
+        check(&[0x81, 0x81, 0x01], config.clone()).unwrap();
+        check(&[0x81, 0xc1, 0x01], config.clone()).unwrap();
+        let err = check(&[0x81, 0x81, 0x81, 0x01], config.clone()).unwrap_err();
+        assert!(err.contains("position 2"), "{err}");
+        assert!(check(&[0x9f, 0x9f, 0x9f, 0xff, 0xff, 0xff], config).is_err());
+
+        // Deep nesting does not overflow the stack when there is no limit.
+        let mut deep = vec![0x81; 100_000];
+        deep.push(0x00);
+        check(&deep, DecoderConfig::lenient()).unwrap();
+    }
+
+    #[test]
+    fn max_collection_len() {
+        let config = DecoderConfig::lenient().with_max_collection_len(2);
+        check(&[0x82, 0x01, 0x02], config.clone()).unwrap();
+        assert!(check(&[0x83, 0x01, 0x02, 0x03], config.clone()).is_err());
+        check(&[0x9f, 0x01, 0x02, 0xff], config.clone()).unwrap();
+        assert!(check(&[0x9f, 0x01, 0x02, 0x03, 0xff], config.clone()).is_err());
+        check(&[0xa2, 0x01, 0x02, 0x03, 0x04], config.clone()).unwrap();
+        assert!(check(&[0xbf, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0xff], config).is_err());
+    }
+
+    #[test]
+    fn strict_rejects_duplicate_keys_and_invalid_utf8() {
+        // {"a": 1, "b": 2}
+        check(
+            &[0xa2, 0x61, 0x61, 0x01, 0x61, 0x62, 0x02],
+            DecoderConfig::strict(),
+        )
+        .unwrap();
+        // {_ "a": 1, (_ "a"): 2}
+        let err = check(
+            &[0xbf, 0x61, 0x61, 0x01, 0x7f, 0x61, 0x61, 0xff, 0x02, 0xff],
+            DecoderConfig::strict(),
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("position 4: encountered duplicate map key"),
+            "{err}"
+        );
+        // {1: 1, 1: 2}, with the second key in a longer encoding.
+        assert!(check(
+            &[0xa2, 0x01, 0x01, 0x18, 0x01, 0x02],
+            DecoderConfig::strict()
+        )
+        .is_err());
+        // Same keys in different maps are fine.
+        check(
+            &[0x82, 0xa1, 0x61, 0x61, 0x01, 0xa1, 0x61, 0x61, 0x01],
+            DecoderConfig::strict(),
+        )
+        .unwrap();
+        assert!(check(&[0x61, 0xff], DecoderConfig::strict()).is_err());
+        check(&[0x61, 0xff], DecoderConfig::lenient()).unwrap();
+    }
+
+    #[test]
+    fn canonical_rejects_long_encodings_and_indefinite_lengths() {
+        check(&[0x18, 0x18], DecoderConfig::canonical()).unwrap();
+        assert!(check(&[0x18, 0x17], DecoderConfig::canonical()).is_err());
+        assert!(check(&[0x19, 0x00, 0xff], DecoderConfig::canonical()).is_err());
+        assert!(check(&[0x1a, 0x00, 0x00, 0xff, 0xff], DecoderConfig::canonical()).is_err());
+        assert!(check(
+            &[0x1b, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff],
+            DecoderConfig::canonical()
+        )
+        .is_err());
+        // Array length and tag number.
+        assert!(check(&[0x98, 0x01, 0x01], DecoderConfig::canonical()).is_err());
+        assert!(check(&[0xd8, 0x01, 0x00], DecoderConfig::canonical()).is_err());
+        assert!(check(&[0x9f, 0xff], DecoderConfig::canonical()).is_err());
+        check(&[0x9f, 0xff], DecoderConfig::strict()).unwrap();
+        // Floats are not required to be shortest.
+        check(
+            &[0xfb, 0x3f, 0xf8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            DecoderConfig::canonical(),
+        )
+        .unwrap();
+        assert_eq!(
+            DecoderConfig::canonical().validation(),
+            Validation::Canonical
+        );
+    }
+
+    #[test]
+    fn canonical_requires_sorted_map_keys() {
+        // {"a": 1, "b": 2}
+        let sorted = [0xa2, 0x61, 0x61, 0x01, 0x61, 0x62, 0x02];
+        check(&sorted, DecoderConfig::canonical()).unwrap();
+        // {"b": 2, "a": 1}
+        let unsorted = [0xa2, 0x61, 0x62, 0x02, 0x61, 0x61, 0x01];
+        check(&unsorted, DecoderConfig::strict()).unwrap();
+        let err = check(&unsorted, DecoderConfig::canonical()).unwrap_err();
+        assert!(
+            err.contains("position 4: encountered map key out of canonical order"),
+            "{err}"
+        );
+        // {10: 0, 100: 0, -1: 0, "z": 0, "aa": 0}: shorter encodings sort first.
+        check(
+            &[
+                0xa5, 0x0a, 0x00, 0x18, 0x64, 0x00, 0x20, 0x00, 0x61, 0x7a, 0x00, 0x62, 0x61, 0x61,
+                0x00,
+            ],
+            DecoderConfig::canonical(),
+        )
+        .unwrap();
+        // {"aa": 0, "z": 0}
+        assert!(check(
+            &[0xa2, 0x62, 0x61, 0x61, 0x00, 0x61, 0x7a, 0x00],
+            DecoderConfig::canonical()
+        )
+        .is_err());
+        // Order is checked per map: {"b": {"a": 0, "b": 0}, "c": {"a": 0}}
+        check(
+            &[
+                0xa2, 0x61, 0x62, 0xa2, 0x61, 0x61, 0x00, 0x61, 0x62, 0x00, 0x61, 0x63, 0xa1, 0x61,
+                0x61, 0x00,
+            ],
+            DecoderConfig::canonical(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn decoder_with_config() {
+        let bytes = [0x82, 0x01, 0x02];
+        let mut decoder = Decoder::with_config(&bytes, &DecoderConfig::canonical()).unwrap();
+        assert_eq!(decoder.list().unwrap(), Some(2));
+        assert!(Decoder::with_config(&bytes, &DecoderConfig::lenient().with_max_depth(0)).is_err());
+    }
+}
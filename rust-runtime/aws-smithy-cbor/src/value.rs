@@ -0,0 +1,814 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A schema-less CBOR value model.
+//!
+//! [`Decoder`] and [`Encoder`] are designed for payloads whose shape is known in advance. When the
+//! shape is not known, or when a payload needs to be inspected (e.g. an rpcv2Cbor response that
+//! failed to deserialize), [`CborValue`] can hold any well-formed CBOR data item. Its [`Display`]
+//! implementation renders the value in the [diagnostic notation] described by RFC 8949.
+//!
+//! [diagnostic notation]: https://www.rfc-editor.org/rfc/rfc8949.html#name-diagnostic-notation
+//! [`Display`]: std::fmt::Display
+
+use std::collections::HashMap;
+use std::fmt;
+
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::{DateTime, Document, Number};
+
+use crate::data::Type;
+use crate::decode::DeserializeError;
+use crate::validate::DecoderConfig;
+use crate::{Decoder, Encoder};
+
+/// Well-known CBOR tag numbers.
+///
+/// See the [IANA registry](https://www.iana.org/assignments/cbor-tags/cbor-tags.xhtml) for the
+/// full list.
+pub mod tag {
+    /// Standard date/time string (RFC 3339).
+    pub const DATE_TIME_STRING: u64 = 0;
+    /// Epoch-based date/time. This is the tag used by Smithy to encode timestamps.
+    pub const EPOCH_DATE_TIME: u64 = 1;
+    /// Unsigned bignum.
+    pub const POSITIVE_BIGNUM: u64 = 2;
+    /// Negative bignum.
+    pub const NEGATIVE_BIGNUM: u64 = 3;
+    /// Decimal fraction.
+    pub const DECIMAL_FRACTION: u64 = 4;
+    /// Bigfloat.
+    pub const BIGFLOAT: u64 = 5;
+    /// Encoded CBOR data item.
+    pub const ENCODED_CBOR: u64 = 24;
+    /// Self-described CBOR.
+    pub const SELF_DESCRIBED_CBOR: u64 = 55799;
+}
+
+/// Any CBOR data item.
+///
+/// Indefinite-length strings are stored concatenated and indefinite-length arrays and maps are
+/// stored like their definite-length counterparts, so re-encoding a decoded value always produces
+/// definite-length items.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CborValue {
+    /// Major type 0: an unsigned integer.
+    Unsigned(u64),
+    /// Major type 1: a negative integer. The wrapped value `n` represents the integer `-1 - n`.
+    Negative(u64),
+    /// Major type 2: a byte string.
+    Bytes(Vec<u8>),
+    /// Major type 3: a UTF-8 text string.
+    Text(String),
+    /// Major type 4: an array of data items.
+    Array(Vec<CborValue>),
+    /// Major type 5: a map of pairs of data items, in the order they were encoded.
+    ///
+    /// CBOR allows keys of any type, so this is not a `HashMap<String, _>`.
+    Map(Vec<(CborValue, CborValue)>),
+    /// Major type 6: a tagged data item.
+    Tag(u64, Box<CborValue>),
+    /// A boolean.
+    Bool(bool),
+    /// The `null` simple value.
+    Null,
+    /// The `undefined` simple value.
+    Undefined,
+    /// Any simple value other than `false`, `true`, `null` and `undefined`.
+    ///
+    /// Values 20 to 23 are `false`, `true`, `null` and `undefined`, and values 24 to 31 are
+    /// reserved, so they can't be encoded as `Simple`.
+    Simple(u8),
+    /// A half, single or double precision floating-point number, widened to an `f64`.
+    Float(f64),
+}
+
+impl CborValue {
+    /// Decodes exactly one data item from `bytes`.
+    ///
+    /// Returns an error if `bytes` is not well-formed CBOR, if it is nested more than
+    /// [`MAX_DEPTH`] levels deep, or if data follows the first item.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let mut decoder = Decoder::new(bytes);
+        let value = decoder.value()?;
+        if decoder.position() != bytes.len() {
+            return Err(DeserializeError::expected_end_of_stream(decoder.position()));
+        }
+        Ok(value)
+    }
+
+    /// Decodes exactly one data item from `bytes`, first checking the input against `config`.
+    ///
+    /// Use this instead of [`decode`](CborValue::decode) for input from an untrusted peer. Unless
+    /// `config` sets a smaller [maximum depth](DecoderConfig::max_depth), nesting is limited to
+    /// [`MAX_DEPTH`] levels.
+    pub fn decode_with_config(
+        bytes: &[u8],
+        config: &DecoderConfig,
+    ) -> Result<Self, DeserializeError> {
+        let mut decoder = Decoder::with_config(bytes, config)?;
+        let max_depth = config
+            .max_depth()
+            .map_or(MAX_DEPTH, |max| max.min(MAX_DEPTH));
+        let value = decoder.value_with_max_depth(max_depth)?;
+        if decoder.position() != bytes.len() {
+            return Err(DeserializeError::expected_end_of_stream(decoder.position()));
+        }
+        Ok(value)
+    }
+
+    /// Encodes this value using definite-length items.
+    ///
+    /// Returns an error if the value contains a [`Simple`](CborValue::Simple) value that can't be
+    /// encoded.
+    pub fn encode(&self) -> Result<Vec<u8>, EncodeValueError> {
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.value(self)?;
+        Ok(encoder.into_writer())
+    }
+
+    /// Returns the value as an `i128` if it is an integer (major type 0 or 1).
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Self::Unsigned(n) => Some(i128::from(*n)),
+            Self::Negative(n) => Some(-1 - i128::from(*n)),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner string value if this is a text string.
+    pub fn as_text(&self) -> Option<&str> {
+        if let Self::Text(text) = self {
+            Some(text)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the inner bytes if this is a byte string.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        if let Self::Bytes(bytes) = self {
+            Some(bytes)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value stored under the text key `key` if this is a map.
+    pub fn get(&self, key: &str) -> Option<&CborValue> {
+        match self {
+            Self::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_text() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Interprets this value as a timestamp.
+    ///
+    /// Both tag 1 (epoch-based, as used by Smithy) and tag 0 (RFC 3339 string) are supported.
+    pub fn as_date_time(&self) -> Option<DateTime> {
+        match self {
+            Self::Tag(tag::EPOCH_DATE_TIME, inner) => match inner.as_ref() {
+                Self::Float(secs) => Some(DateTime::from_secs_f64(*secs)),
+                other => other
+                    .as_integer()
+                    .and_then(|secs| i64::try_from(secs).ok())
+                    .map(DateTime::from_secs),
+            },
+            Self::Tag(tag::DATE_TIME_STRING, inner) => inner
+                .as_text()
+                .and_then(|s| DateTime::from_str(s, Format::DateTime).ok()),
+            _ => None,
+        }
+    }
+}
+
+/// The maximum nesting depth of arrays, maps and tags accepted by [`Decoder::value`].
+///
+/// A top-level scalar has a depth of 0 and a top-level array containing scalars has a depth of 1.
+pub const MAX_DEPTH: usize = 128;
+
+impl<'b> Decoder<'b> {
+    /// Decodes the data item at the current position, whatever its type.
+    ///
+    /// Returns an error if the item is nested more than [`MAX_DEPTH`] levels deep.
+    pub fn value(&mut self) -> Result<CborValue, DeserializeError> {
+        self.value_with_max_depth(MAX_DEPTH)
+    }
+
+    /// Decodes the data item at the current position, whatever its type, allowing arrays, maps
+    /// and tags to be nested at most `max_depth` levels deep.
+    pub fn value_with_max_depth(
+        &mut self,
+        max_depth: usize,
+    ) -> Result<CborValue, DeserializeError> {
+        let position = self.position();
+        // Every nested item is decoded with one less level of depth available.
+        let nested = |decoder: &mut Self| match max_depth.checked_sub(1) {
+            Some(depth) => decoder.value_with_max_depth(depth),
+            None => Err(DeserializeError::custom(
+                "nesting depth exceeds the maximum",
+                position,
+            )),
+        };
+        Ok(match self.datatype()? {
+            Type::Bool => CborValue::Bool(self.boolean()?),
+            Type::Null => {
+                self.null()?;
+                CborValue::Null
+            }
+            Type::Undefined => {
+                self.undefined()?;
+                CborValue::Undefined
+            }
+            Type::U8 | Type::U16 | Type::U32 | Type::U64 => CborValue::Unsigned(self.int()? as u64),
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::Int => {
+                let n = self.int()?;
+                if n >= 0 {
+                    CborValue::Unsigned(n as u64)
+                } else {
+                    CborValue::Negative((-1 - n) as u64)
+                }
+            }
+            Type::F16 | Type::F32 | Type::F64 => CborValue::Float(self.double()?),
+            Type::Simple => match self.simple()? {
+                n @ 20..=31 => {
+                    return Err(DeserializeError::custom(
+                        format!("encountered reserved simple value {n}"),
+                        position,
+                    ))
+                }
+                n => CborValue::Simple(n),
+            },
+            Type::Bytes | Type::BytesIndef => CborValue::Bytes(self.blob()?.into_inner()),
+            Type::String | Type::StringIndef => CborValue::Text(self.string()?),
+            Type::Array | Type::ArrayIndef => {
+                let mut items = Vec::new();
+                match self.list()? {
+                    Some(len) => {
+                        for _ in 0..len {
+                            items.push(nested(self)?);
+                        }
+                    }
+                    None => {
+                        while self.datatype()? != Type::Break {
+                            items.push(nested(self)?);
+                        }
+                        self.end()?;
+                    }
+                }
+                CborValue::Array(items)
+            }
+            Type::Map | Type::MapIndef => {
+                let mut entries = Vec::new();
+                match self.map()? {
+                    Some(len) => {
+                        for _ in 0..len {
+                            entries.push((nested(self)?, nested(self)?));
+                        }
+                    }
+                    None => {
+                        while self.datatype()? != Type::Break {
+                            entries.push((nested(self)?, nested(self)?));
+                        }
+                        self.end()?;
+                    }
+                }
+                CborValue::Map(entries)
+            }
+            Type::Tag => {
+                let tag = self.tag()?;
+                CborValue::Tag(tag, Box::new(nested(self)?))
+            }
+            Type::Break => {
+                return Err(DeserializeError::custom(
+                    "encountered unexpected break",
+                    position,
+                ))
+            }
+            Type::Unknown(byte) => {
+                return Err(DeserializeError::custom(
+                    format!("encountered unknown initial byte 0x{byte:02x}"),
+                    position,
+                ))
+            }
+        })
+    }
+}
+
+impl Encoder {
+    /// Writes any data item.
+    ///
+    /// Returns an error if the value contains a [`Simple`](CborValue::Simple) value between 20
+    /// and 31. Nothing is written for the offending value, but items before it may have been.
+    pub fn value(&mut self, value: &CborValue) -> Result<&mut Self, EncodeValueError> {
+        Ok(match value {
+            CborValue::Unsigned(n) => self.int(i128::from(*n)),
+            CborValue::Negative(n) => self.int(-1 - i128::from(*n)),
+            CborValue::Bytes(bytes) => self.bytes(bytes),
+            CborValue::Text(text) => self.str(text),
+            CborValue::Array(items) => {
+                self.array(items.len());
+                for item in items {
+                    self.value(item)?;
+                }
+                self
+            }
+            CborValue::Map(entries) => {
+                self.map(entries.len());
+                for (key, value) in entries {
+                    self.value(key)?.value(value)?;
+                }
+                self
+            }
+            CborValue::Tag(tag, inner) => self.tag(*tag).value(inner)?,
+            CborValue::Bool(b) => self.boolean(*b),
+            CborValue::Null => self.null(),
+            CborValue::Undefined => self.undefined(),
+            CborValue::Simple(n @ 20..=31) => {
+                return Err(EncodeValueError::ReservedSimpleValue(*n))
+            }
+            CborValue::Simple(n) => self.simple(*n),
+            CborValue::Float(f) => self.double(*f),
+        })
+    }
+}
+
+/// The error type returned when a [`CborValue`] cannot be encoded.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EncodeValueError {
+    /// The simple value is one of `false`, `true`, `null` and `undefined` (20 to 23), which have
+    /// their own variants, or is reserved (24 to 31).
+    ReservedSimpleValue(u8),
+}
+
+impl fmt::Display for EncodeValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReservedSimpleValue(n) => write!(f, "simple value {n} cannot be encoded"),
+        }
+    }
+}
+
+impl std::error::Error for EncodeValueError {}
+
+/// Renders the value in RFC 8949 diagnostic notation, e.g. `{"a": [1, h'ff', 1(1.5)]}`.
+impl fmt::Display for CborValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(n) => write!(f, "{n}"),
+            Self::Negative(n) => write!(f, "{}", -1 - i128::from(*n)),
+            Self::Bytes(bytes) => {
+                f.write_str("h'")?;
+                for byte in bytes {
+                    write!(f, "{byte:02x}")?;
+                }
+                f.write_str("'")
+            }
+            Self::Text(text) => write_escaped(f, text),
+            Self::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_str("]")
+            }
+            Self::Map(entries) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                f.write_str("}")
+            }
+            Self::Tag(tag, inner) => write!(f, "{tag}({inner})"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Null => f.write_str("null"),
+            Self::Undefined => f.write_str("undefined"),
+            Self::Simple(n) => write!(f, "simple({n})"),
+            Self::Float(v) if v.is_nan() => f.write_str("NaN"),
+            Self::Float(v) if v.is_infinite() => {
+                f.write_str(if *v > 0.0 { "Infinity" } else { "-Infinity" })
+            }
+            // `Debug` always includes a decimal point or an exponent, which distinguishes floats
+            // from integers as the notation requires.
+            Self::Float(v) => write!(f, "{v:?}"),
+        }
+    }
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Decodes `bytes` and renders every top-level data item in RFC 8949 diagnostic notation,
+/// separated by newlines.
+///
+/// This is meant for debugging payloads, such as rpcv2Cbor bodies that failed to deserialize.
+pub fn diagnostic_notation(bytes: &[u8]) -> Result<String, DeserializeError> {
+    let mut decoder = Decoder::new(bytes);
+    let mut out = String::new();
+    while decoder.position() < bytes.len() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&decoder.value()?.to_string());
+    }
+    Ok(out)
+}
+
+#[derive(Debug)]
+enum TryFromCborValueErrorKind {
+    NonTextMapKey(CborValue),
+    IntegerOutOfRange,
+    Unrepresentable(&'static str),
+}
+
+/// The error type returned when a [`CborValue`] cannot be converted into a [`Document`].
+#[derive(Debug)]
+pub struct TryFromCborValueError {
+    kind: TryFromCborValueErrorKind,
+}
+
+impl TryFromCborValueError {
+    fn new(kind: TryFromCborValueErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl fmt::Display for TryFromCborValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TryFromCborValueErrorKind::*;
+        match &self.kind {
+            NonTextMapKey(key) => write!(f, "map key {key} is not a text string"),
+            IntegerOutOfRange => write!(f, "integer does not fit in a 64-bit number"),
+            Unrepresentable(what) => write!(f, "{what} cannot be represented in a document"),
+        }
+    }
+}
+
+impl std::error::Error for TryFromCborValueError {}
+
+/// Converts a [`CborValue`] into a [`Document`].
+///
+/// Tags are unwrapped: the tagged item is converted in their place, except for bignums (tags 2 and
+/// 3), which are converted into numbers if they fit. `undefined` is converted into `null`. Byte
+/// strings, other simple values and maps with non-text keys cannot be converted.
+impl TryFrom<CborValue> for Document {
+    type Error = TryFromCborValueError;
+
+    fn try_from(value: CborValue) -> Result<Self, Self::Error> {
+        use TryFromCborValueErrorKind::*;
+        Ok(match value {
+            CborValue::Unsigned(n) => Document::Number(Number::PosInt(n)),
+            CborValue::Negative(n) => Document::Number(negative_number(n)?),
+            CborValue::Float(v) => Document::Number(Number::Float(v)),
+            CborValue::Text(text) => Document::String(text),
+            CborValue::Bool(b) => Document::Bool(b),
+            CborValue::Null | CborValue::Undefined => Document::Null,
+            CborValue::Array(items) => Document::Array(
+                items
+                    .into_iter()
+                    .map(Document::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            CborValue::Map(entries) => Document::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| match key {
+                        CborValue::Text(key) => Ok((key, Document::try_from(value)?)),
+                        key => Err(TryFromCborValueError::new(NonTextMapKey(key))),
+                    })
+                    .collect::<Result<HashMap<_, _>, _>>()?,
+            ),
+            CborValue::Tag(tag @ (tag::POSITIVE_BIGNUM | tag::NEGATIVE_BIGNUM), inner) => {
+                let bytes = inner
+                    .as_bytes()
+                    .ok_or(TryFromCborValueError::new(Unrepresentable(
+                        "a bignum whose content is not a byte string",
+                    )))?;
+                let n = bignum_to_u64(bytes)?;
+                Document::Number(if tag == tag::POSITIVE_BIGNUM {
+                    Number::PosInt(n)
+                } else {
+                    negative_number(n)?
+                })
+            }
+            CborValue::Tag(_, inner) => Document::try_from(*inner)?,
+            CborValue::Bytes(_) => {
+                return Err(TryFromCborValueError::new(Unrepresentable("a byte string")))
+            }
+            CborValue::Simple(_) => {
+                return Err(TryFromCborValueError::new(Unrepresentable(
+                    "a simple value",
+                )))
+            }
+        })
+    }
+}
+
+fn negative_number(n: u64) -> Result<Number, TryFromCborValueError> {
+    i64::try_from(n)
+        .map(|n| Number::NegInt(-1 - n))
+        .map_err(|_| TryFromCborValueError::new(TryFromCborValueErrorKind::IntegerOutOfRange))
+}
+
+fn bignum_to_u64(bytes: &[u8]) -> Result<u64, TryFromCborValueError> {
+    let significant = match bytes.iter().position(|b| *b != 0) {
+        Some(start) => &bytes[start..],
+        None => return Ok(0),
+    };
+    if significant.len() > 8 {
+        return Err(TryFromCborValueError::new(
+            TryFromCborValueErrorKind::IntegerOutOfRange,
+        ));
+    }
+    Ok(significant
+        .iter()
+        .fold(0u64, |acc, b| (acc << 8) | u64::from(*b)))
+}
+
+/// Converts a [`Document`] into a [`CborValue`]. Object entries are written as text-keyed map
+/// entries in the (unspecified) iteration order of the underlying `HashMap`.
+impl From<Document> for CborValue {
+    fn from(document: Document) -> Self {
+        match document {
+            Document::Object(object) => CborValue::Map(
+                object
+                    .into_iter()
+                    .map(|(k, v)| (CborValue::Text(k), v.into()))
+                    .collect(),
+            ),
+            Document::Array(items) => CborValue::Array(items.into_iter().map(Into::into).collect()),
+            Document::Number(Number::PosInt(n)) => CborValue::Unsigned(n),
+            Document::Number(Number::NegInt(n)) if n >= 0 => CborValue::Unsigned(n as u64),
+            Document::Number(Number::NegInt(n)) => CborValue::Negative((-1 - n) as u64),
+            Document::Number(Number::Float(v)) => CborValue::Float(v),
+            Document::String(s) => CborValue::Text(s),
+            Document::Bool(b) => CborValue::Bool(b),
+            Document::Null => CborValue::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diagnostic_notation, tag, CborValue, MAX_DEPTH};
+    use crate::validate::DecoderConfig;
+    use crate::Decoder;
+    use aws_smithy_types::{DateTime, Document, Number};
+
+    #[test]
+    fn decode_scalars() {
+        // Examples from RFC 8949 Appendix A.
+        let cases: &[(&[u8], CborValue)] = &[
+            (&[0x00], CborValue::Unsigned(0)),
+            (&[0x18, 0x64], CborValue::Unsigned(100)),
+            (
+                &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                CborValue::Unsigned(u64::MAX),
+            ),
+            (&[0x29], CborValue::Negative(9)),
+            (
+                &[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                CborValue::Negative(u64::MAX),
+            ),
+            (&[0xf9, 0x3e, 0x00], CborValue::Float(1.5)),
+            (&[0xf4], CborValue::Bool(false)),
+            (&[0xf6], CborValue::Null),
+            (&[0xf7], CborValue::Undefined),
+            (&[0xf0], CborValue::Simple(16)),
+            (&[0xf8, 0xff], CborValue::Simple(255)),
+            (
+                &[0x44, 0x01, 0x02, 0x03, 0x04],
+                CborValue::Bytes(vec![1, 2, 3, 4]),
+            ),
+            (&[0x62, 0x22, 0x5c], CborValue::Text("\"\\".into())),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(&CborValue::decode(bytes).unwrap(), expected, "{bytes:02x?}");
+        }
+    }
+
+    #[test]
+    fn decode_indefinite_length_items() {
+        // [_ 1, [2, 3], [_ 4, 5]]
+        let bytes = [0x9f, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0x05, 0xff, 0xff];
+        let value = CborValue::decode(&bytes).unwrap();
+        assert_eq!(value.to_string(), "[1, [2, 3], [4, 5]]");
+
+        // {_ "a": 1, "b": [_ 2, 3]}
+        let bytes = [
+            0xbf, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9f, 0x02, 0x03, 0xff, 0xff,
+        ];
+        let value = CborValue::decode(&bytes).unwrap();
+        assert_eq!(value.to_string(), r#"{"a": 1, "b": [2, 3]}"#);
+        assert_eq!(value.get("a"), Some(&CborValue::Unsigned(1)));
+    }
+
+    #[test]
+    fn decode_rejects_trailing_data_and_stray_break() {
+        assert!(CborValue::decode(&[0x01, 0x02]).is_err());
+        assert!(CborValue::decode(&[0xff]).is_err());
+        assert!(CborValue::decode(&[0x82, 0x01]).is_err());
+    }
+
+    #[test]
+    fn diagnostic_notation_matches_rfc_examples() {
+        let cases: &[(&[u8], &str)] = &[
+            (&[0x39, 0x03, 0xe7], "-1000"),
+            (&[0xf9, 0x7c, 0x00], "Infinity"),
+            (&[0xf9, 0x7e, 0x00], "NaN"),
+            (&[0xf9, 0xfc, 0x00], "-Infinity"),
+            (
+                &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+                "1.1",
+            ),
+            (&[0xf9, 0x80, 0x00], "-0.0"),
+            (&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0], "1(1363896240)"),
+            (&[0xd8, 0x20, 0x63, 0x61, 0x62, 0x63], "32(\"abc\")"),
+            (&[0x43, 0x01, 0xab, 0xff], "h'01abff'"),
+            (&[0x61, 0x0a], "\"\\n\""),
+            (&[0xa1, 0x01, 0x02], "{1: 2}"),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(&diagnostic_notation(bytes).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn diagnostic_notation_of_sequence() {
+        assert_eq!(
+            diagnostic_notation(&[0x01, 0x61, 0x61]).unwrap(),
+            "1\n\"a\""
+        );
+        assert_eq!(diagnostic_notation(&[]).unwrap(), "");
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = CborValue::Map(vec![
+            (
+                CborValue::Text("ts".into()),
+                CborValue::Tag(tag::EPOCH_DATE_TIME, Box::new(CborValue::Float(1.5))),
+            ),
+            (CborValue::Unsigned(7), CborValue::Negative(u64::MAX)),
+            (
+                CborValue::Text("list".into()),
+                CborValue::Array(vec![
+                    CborValue::Bytes(vec![0xde, 0xad]),
+                    CborValue::Null,
+                    CborValue::Undefined,
+                    CborValue::Simple(99),
+                    CborValue::Bool(true),
+                ]),
+            ),
+        ]);
+        assert_eq!(CborValue::decode(&value.encode().unwrap()).unwrap(), value);
+    }
+
+    #[test]
+    fn reserved_simple_values() {
+        for n in 20..=31 {
+            let err = CborValue::Array(vec![CborValue::Simple(n)])
+                .encode()
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("simple value {n} cannot be encoded")
+            );
+        }
+        assert_eq!(CborValue::Simple(19).encode().unwrap(), [0xf3]);
+        assert_eq!(CborValue::Simple(32).encode().unwrap(), [0xf8, 0x20]);
+        // Two-byte encodings of reserved simple values are not well-formed.
+        assert!(CborValue::decode(&[0xf8, 0x18]).is_err());
+    }
+
+    #[test]
+    fn max_depth() {
+        let mut nested = vec![0x81; MAX_DEPTH];
+        nested.push(0x00);
+        CborValue::decode(&nested).unwrap();
+
+        // Deep nesting is rejected instead of overflowing the stack.
+        let mut deep = vec![0x81; 100_000];
+        deep.push(0x00);
+        let err = CborValue::decode(&deep).unwrap_err();
+        assert!(err.to_string().contains("nesting depth"), "{err}");
+
+        // Tags count towards the depth too.
+        assert!(Decoder::new(&[0x81, 0xc1, 0x00])
+            .value_with_max_depth(1)
+            .is_err());
+        Decoder::new(&[0x81, 0xc1, 0x00])
+            .value_with_max_depth(2)
+            .unwrap();
+    }
+
+    #[test]
+    fn decode_with_config() {
+        // {"b": 1, "a": 2}
+        let bytes = [0xa2, 0x61, 0x62, 0x01, 0x61, 0x61, 0x02];
+        CborValue::decode_with_config(&bytes, &DecoderConfig::strict()).unwrap();
+        assert!(CborValue::decode_with_config(&bytes, &DecoderConfig::canonical()).is_err());
+        assert!(CborValue::decode_with_config(
+            &bytes,
+            &DecoderConfig::lenient().with_max_collection_len(1)
+        )
+        .is_err());
+        assert!(CborValue::decode_with_config(&[0x01, 0x02], &DecoderConfig::lenient()).is_err());
+
+        let mut deep = vec![0x81; MAX_DEPTH + 1];
+        deep.push(0x00);
+        let unlimited = DecoderConfig::lenient();
+        assert!(CborValue::decode_with_config(&deep, &unlimited).is_err());
+        assert!(CborValue::decode_with_config(&deep[1..], &unlimited.with_max_depth(2)).is_err());
+    }
+
+    #[test]
+    fn timestamps() {
+        let epoch = CborValue::decode(&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]).unwrap();
+        assert_eq!(epoch.as_date_time(), Some(DateTime::from_secs(1363896240)));
+
+        // 0("2013-03-21T20:04:00Z")
+        let string = CborValue::Tag(
+            tag::DATE_TIME_STRING,
+            Box::new(CborValue::Text("2013-03-21T20:04:00Z".into())),
+        );
+        assert_eq!(string.as_date_time(), Some(DateTime::from_secs(1363896240)));
+        assert_eq!(CborValue::Unsigned(1).as_date_time(), None);
+    }
+
+    #[test]
+    fn to_document() {
+        let value = CborValue::Map(vec![
+            (CborValue::Text("n".into()), CborValue::Negative(4)),
+            (
+                CborValue::Text("ts".into()),
+                CborValue::Tag(tag::EPOCH_DATE_TIME, Box::new(CborValue::Unsigned(10))),
+            ),
+            (
+                CborValue::Text("big".into()),
+                CborValue::Tag(
+                    tag::POSITIVE_BIGNUM,
+                    Box::new(CborValue::Bytes(vec![0, 0, 1, 0])),
+                ),
+            ),
+            (CborValue::Text("u".into()), CborValue::Undefined),
+        ]);
+        let document = Document::try_from(value).unwrap();
+        let object = document.as_object().unwrap();
+        assert_eq!(object["n"], Document::Number(Number::NegInt(-5)));
+        assert_eq!(object["ts"], Document::Number(Number::PosInt(10)));
+        assert_eq!(object["big"], Document::Number(Number::PosInt(256)));
+        assert_eq!(object["u"], Document::Null);
+
+        assert!(Document::try_from(CborValue::Bytes(vec![])).is_err());
+        assert!(Document::try_from(CborValue::Negative(u64::MAX)).is_err());
+        let err = Document::try_from(CborValue::Map(vec![(
+            CborValue::Unsigned(1),
+            CborValue::Null,
+        )]))
+        .unwrap_err();
+        assert_eq!(err.to_string(), "map key 1 is not a text string");
+    }
+
+    #[test]
+    fn from_document() {
+        let document = Document::Array(vec![
+            Document::Number(Number::NegInt(-1)),
+            Document::Number(Number::NegInt(3)),
+            Document::String("x".into()),
+        ]);
+        assert_eq!(
+            CborValue::from(document),
+            CborValue::Array(vec![
+                CborValue::Negative(0),
+                CborValue::Unsigned(3),
+                CborValue::Text("x".into()),
+            ])
+        );
+    }
+}
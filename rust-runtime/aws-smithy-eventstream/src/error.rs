@@ -22,6 +22,8 @@ pub(crate) enum ErrorKind {
     PayloadTooLong,
     PreludeChecksumMismatch(u32, u32),
     TimestampValueTooLarge(DateTime),
+    FrameTooLarge { size: u32, max: u32 },
+    TooManyHeaders { max: usize },
     Marshalling(String),
     Unmarshalling(String),
 }
@@ -52,6 +54,15 @@ impl Error {
         }
     }
 
+    /// Returns true if the error is caused by a frame exceeding one of the limits configured on
+    /// the [`MessageFrameDecoder`](crate::frame::MessageFrameDecoder)
+    pub fn is_limit_exceeded(&self) -> bool {
+        matches!(
+            self.kind,
+            ErrorKind::FrameTooLarge { .. } | ErrorKind::TooManyHeaders { .. }
+        )
+    }
+
     /// Returns true if the error is one generated during serialization
     pub fn is_invalid_message(&self) -> bool {
         use ErrorKind::*;
@@ -104,6 +115,16 @@ impl fmt::Display for Error {
                 "timestamp value {:?} is too large to fit into an i64",
                 time
             ),
+            FrameTooLarge { size, max } => write!(
+                f,
+                "event stream frame of {} bytes exceeds the maximum frame size of {} bytes",
+                size, max
+            ),
+            TooManyHeaders { max } => write!(
+                f,
+                "event stream message exceeds the maximum of {} headers",
+                max
+            ),
             Marshalling(error) => write!(f, "failed to marshall message: {}", error),
             Unmarshalling(error) => write!(f, "failed to unmarshall message: {}", error),
         }
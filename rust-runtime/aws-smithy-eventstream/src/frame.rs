@@ -48,6 +48,21 @@ pub trait SignMessage: fmt::Debug {
     fn sign_empty(&mut self) -> Option<Result<Message, SignMessageError>>;
 }
 
+/// The error returned by [`VerifyMessage::verify`] when a message can't be verified.
+pub type VerifyMessageError = Box<dyn StdError + Send + Sync + 'static>;
+
+/// Verifies the signatures of received Event Stream messages.
+///
+/// This is the receiving counterpart to [`SignMessage`]: it's given each message exactly as it
+/// was received, checks its signature, and unwraps it.
+pub trait VerifyMessage: fmt::Debug {
+    /// Verifies a signed `message` and returns the message it wraps.
+    ///
+    /// Returns `None` once the signed empty message that marks the end of the stream
+    /// has been verified. Any message received after that is an error.
+    fn verify(&mut self, message: Message) -> Result<Option<Message>, VerifyMessageError>;
+}
+
 /// A sender that gets placed in the request config to wire up an event stream signer after signing.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -346,7 +361,14 @@ fn read_prelude_from<B: Buf>(mut buffer: B) -> Result<(u32, u32), Error> {
 
 /// Reads a message from the given `buffer`. For streaming use cases, use
 /// the [`MessageFrameDecoder`] instead of this.
-pub fn read_message_from<B: Buf>(mut buffer: B) -> Result<Message, Error> {
+pub fn read_message_from<B: Buf>(buffer: B) -> Result<Message, Error> {
+    read_message_with_max_headers(buffer, None)
+}
+
+fn read_message_with_max_headers<B: Buf>(
+    mut buffer: B,
+    max_header_count: Option<usize>,
+) -> Result<Message, Error> {
     if buffer.remaining() < PRELUDE_LENGTH_BYTES_USIZE {
         return Err(ErrorKind::InvalidMessageLength.into());
     }
@@ -367,6 +389,9 @@ pub fn read_message_from<B: Buf>(mut buffer: B) -> Result<Message, Error> {
     let mut header_bytes_read = 0;
     let mut headers = Vec::new();
     while header_bytes_read < header_len as usize {
+        if let Some(max) = max_header_count.filter(|max| headers.len() >= *max) {
+            return Err(ErrorKind::TooManyHeaders { max }.into());
+        }
         let (header, bytes_read) = read_header_from(&mut crc_buffer)?;
         header_bytes_read += bytes_read;
         if header_bytes_read > header_len as usize {
@@ -616,11 +641,18 @@ pub enum DecodedFrame {
 }
 
 /// Streaming decoder for decoding a [`Message`] from a stream.
+///
+/// By default, the decoder accepts any frame the Event Stream format allows. Limits can be set
+/// with [`with_max_frame_size`](MessageFrameDecoder::with_max_frame_size) and
+/// [`with_max_header_count`](MessageFrameDecoder::with_max_header_count) so that a peer can't
+/// make the decoder buffer or allocate more than expected.
 #[non_exhaustive]
 #[derive(Default, Debug)]
 pub struct MessageFrameDecoder {
     prelude: [u8; PRELUDE_LENGTH_BYTES_USIZE],
     prelude_read: bool,
+    max_frame_size: Option<u32>,
+    max_header_count: Option<usize>,
 }
 
 impl MessageFrameDecoder {
@@ -629,6 +661,21 @@ impl MessageFrameDecoder {
         Default::default()
     }
 
+    /// Sets the largest frame, in bytes including the prelude and checksums, that will be decoded.
+    ///
+    /// A frame's size is known as soon as its prelude is read, so larger frames are rejected
+    /// before the rest of the frame is buffered.
+    pub fn with_max_frame_size(mut self, max_frame_size: u32) -> Self {
+        self.max_frame_size = Some(max_frame_size);
+        self
+    }
+
+    /// Sets the largest number of headers a decoded message can have.
+    pub fn with_max_header_count(mut self, max_header_count: usize) -> Self {
+        self.max_header_count = Some(max_header_count);
+        self
+    }
+
     /// Determines if the `buffer` has enough data in it to read a full frame.
     /// Returns `Ok(None)` if there's not enough data, or `Some(remaining)` where
     /// `remaining` is the number of bytes after the prelude that belong to the
@@ -667,11 +714,18 @@ impl MessageFrameDecoder {
         if !self.prelude_read && buffer.remaining() >= PRELUDE_LENGTH_BYTES_USIZE {
             buffer.copy_to_slice(&mut self.prelude);
             self.prelude_read = true;
+
+            let size = (&self.prelude[..]).get_u32();
+            if let Some(max) = self.max_frame_size.filter(|max| size > *max) {
+                self.reset();
+                return Err(ErrorKind::FrameTooLarge { size, max }.into());
+            }
         }
 
         if let Some(remaining_len) = self.remaining_bytes_if_frame_available(&buffer)? {
             let mut message_buf = (&self.prelude[..]).chain(buffer.take(remaining_len));
-            let result = read_message_from(&mut message_buf).map(DecodedFrame::Complete);
+            let result = read_message_with_max_headers(&mut message_buf, self.max_header_count)
+                .map(DecodedFrame::Complete);
             self.reset();
             return result;
         }
@@ -747,6 +801,59 @@ mod message_frame_decoder_tests {
             multiple_streaming_messages_chunk_size(chunk_size);
         }
     }
+
+    #[test]
+    fn frame_size_limit() {
+        let message = include_bytes!("../test_data/valid_with_all_headers_and_payload");
+
+        let mut decoder = MessageFrameDecoder::new().with_max_frame_size(message.len() as u32);
+        let mut buffer = Bytes::from_static(message);
+        assert!(matches!(
+            decoder.decode_frame(&mut buffer).unwrap(),
+            DecodedFrame::Complete(_)
+        ));
+
+        // The frame is rejected as soon as its prelude is available
+        let mut decoder = MessageFrameDecoder::new().with_max_frame_size(message.len() as u32 - 1);
+        let mut buffer = Bytes::from_static(&message[..12]);
+        let err = decoder.decode_frame(&mut buffer).unwrap_err();
+        assert!(err.is_limit_exceeded());
+        assert_eq!(
+            format!(
+                "event stream frame of {} bytes exceeds the maximum frame size of {} bytes",
+                message.len(),
+                message.len() - 1
+            ),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn header_count_limit() {
+        let message = include_bytes!("../test_data/valid_with_all_headers_and_payload");
+        let header_count = read_message_from(&mut Bytes::from_static(message))
+            .unwrap()
+            .headers()
+            .len();
+
+        let mut decoder = MessageFrameDecoder::new().with_max_header_count(header_count);
+        assert!(decoder
+            .decode_frame(&mut Bytes::from_static(message))
+            .is_ok());
+
+        let mut decoder = MessageFrameDecoder::new().with_max_header_count(header_count - 1);
+        let err = decoder
+            .decode_frame(&mut Bytes::from_static(message))
+            .unwrap_err();
+        assert!(err.is_limit_exceeded());
+        assert_eq!(
+            format!(
+                "event stream message exceeds the maximum of {} headers",
+                header_count - 1
+            ),
+            err.to_string()
+        );
+    }
 }
 
 #[cfg(test)]
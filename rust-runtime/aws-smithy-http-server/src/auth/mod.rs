@@ -0,0 +1,287 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Authentication of requests according to the auth schemes modeled on each operation.
+//!
+//! The model assigns every operation a list of [effective auth schemes], in priority order, using
+//! traits such as `@httpBearerAuth`, `@httpApiKeyAuth`, `@httpBasicAuth` and `@sigv4`. Generated
+//! operations expose that list through [`OperationAuth`].
+//!
+//! Authentication is split in two, mirroring the client's auth schemes and identity resolvers:
+//!
+//! - An [`AuthScheme`] knows how to find the [`Credentials`] for one scheme in a request, for
+//!   example the token in an `Authorization: Bearer` header. Implementations for the HTTP auth
+//!   schemes live in [`http`].
+//! - An [`Authenticate`] implementation, supplied by you, checks the credentials and turns them into
+//!   a _principal_, the type your handlers use to find out who made the request.
+//!
+//! The [`AuthPlugin`] ties them together. For every request it tries the operation's auth schemes
+//! in order, and authenticates the request with the first one that found credentials. The
+//! principal is then inserted into the request extensions, so handlers can take it as an
+//! [`Extension`](crate::Extension). Requests that can't be authenticated are rejected with an
+//! [`UnauthorizedException`] (401) or [`AccessDeniedException`] (403) error, shaped for the
+//! service's protocol.
+//!
+//! Operations marked with the [`@optionalAuth`] trait don't reject requests without credentials;
+//! their handlers should take an `Option<Extension<Principal>>`. Requests that do carry
+//! credentials are still rejected if the credentials are invalid.
+//!
+//! ```
+//! use aws_smithy_http_server::auth::http::{HttpBearerAuthScheme, Token};
+//! use aws_smithy_http_server::auth::{AuthError, AuthExt, AuthPlugin, Credentials};
+//! use aws_smithy_http_server::plugin::HttpPlugins;
+//!
+//! #[derive(Clone, Debug)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! let auth = AuthPlugin::new(|credentials: Credentials| async move {
+//!     let token = credentials.data::<Token>().expect("only bearer auth is configured");
+//!     match token.token() {
+//!         "0123456789" => Ok(User { name: "Ash".into() }),
+//!         _ => Err(AuthError::unauthenticated("unknown token")),
+//!     }
+//! })
+//! .with_scheme(HttpBearerAuthScheme::new());
+//! let http_plugins = HttpPlugins::new().auth(auth);
+//! ```
+//!
+//! Auth schemes only see the request head. Schemes that need the request body, such as SigV4,
+//! can be implemented by running an HTTP plugin that verifies the request before the
+//! [`AuthPlugin`] and records the result in the request extensions, where the [`AuthScheme`]
+//! reads it from.
+//!
+//! [effective auth schemes]: https://smithy.io/2.0/spec/authentication-traits.html#auth-trait
+//! [`@optionalAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#optionalauth-trait
+
+pub mod http;
+mod plugin;
+mod rejection;
+
+pub use plugin::{AuthExt, AuthPlugin, AuthService};
+pub use rejection::{AccessDeniedException, UnauthorizedException};
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::error::BoxError;
+
+/// Auth scheme ID for the [`@sigv4`](https://smithy.io/2.0/aws/aws-auth.html#aws-auth-sigv4-trait) trait.
+pub const SIGV4_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("sigv4");
+
+/// Auth scheme ID for the [`@sigv4a`](https://smithy.io/2.0/aws/aws-auth.html#aws-auth-sigv4a-trait) trait.
+pub const SIGV4A_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("sigv4a");
+
+/// Identifier for an auth scheme.
+///
+/// The IDs of the schemes the server knows about match the ones used by the client.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AuthSchemeId {
+    scheme_id: &'static str,
+}
+
+impl AuthSchemeId {
+    /// Creates a new auth scheme ID.
+    pub const fn new(scheme_id: &'static str) -> Self {
+        Self { scheme_id }
+    }
+
+    /// Returns the string equivalent of this auth scheme ID.
+    pub const fn as_str(&self) -> &'static str {
+        self.scheme_id
+    }
+}
+
+impl fmt::Display for AuthSchemeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.scheme_id)
+    }
+}
+
+/// Models the auth schemes of a [Smithy Operation shape].
+///
+/// This is implemented by generated operations, alongside
+/// [`OperationShape`](crate::operation::OperationShape).
+///
+/// [Smithy Operation shape]: https://smithy.io/2.0/spec/service-types.html#operation
+pub trait OperationAuth {
+    /// The effective auth schemes of the operation, in priority order.
+    ///
+    /// This is empty for operations that don't require authentication.
+    const AUTH_SCHEMES: &'static [AuthSchemeId];
+
+    /// Whether the operation is marked with the `@optionalAuth` trait, i.e. whether requests
+    /// without credentials are allowed.
+    const OPTIONAL_AUTH: bool;
+}
+
+/// Credentials extracted from a request by an [`AuthScheme`].
+///
+/// Like the client's `Identity`, this is a container for arbitrary data, and it is the
+/// responsibility of the [`Authenticate`] implementation to downcast it to the type the auth
+/// scheme produces with [`Credentials::data`].
+#[derive(Clone)]
+pub struct Credentials {
+    scheme_id: AuthSchemeId,
+    data: Arc<dyn Any + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    data_debug: Arc<dyn (Fn(&Arc<dyn Any + Send + Sync>) -> &dyn Debug) + Send + Sync>,
+}
+
+impl Credentials {
+    /// Creates new credentials for the given auth scheme.
+    pub fn new<T>(scheme_id: AuthSchemeId, data: T) -> Self
+    where
+        T: Any + Debug + Send + Sync,
+    {
+        Self {
+            scheme_id,
+            data: Arc::new(data),
+            data_debug: Arc::new(|d| d.downcast_ref::<T>().expect("type-checked") as _),
+        }
+    }
+
+    /// Returns the ID of the auth scheme that extracted these credentials.
+    pub fn scheme_id(&self) -> AuthSchemeId {
+        self.scheme_id
+    }
+
+    /// Returns the raw credentials data.
+    pub fn data<T: Any + Debug + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.data.downcast_ref()
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("scheme_id", &self.scheme_id)
+            .field("data", (self.data_debug)(&self.data))
+            .finish()
+    }
+}
+
+/// An auth scheme, which extracts [`Credentials`] from requests.
+pub trait AuthScheme: Send + Sync + Debug {
+    /// Returns the ID of the scheme.
+    ///
+    /// This is matched against the [`OperationAuth::AUTH_SCHEMES`] of each operation.
+    fn scheme_id(&self) -> AuthSchemeId;
+
+    /// Extracts the credentials for this scheme from the request.
+    ///
+    /// Returns `Ok(None)` if the request doesn't carry credentials for this scheme, so that the
+    /// next scheme can be tried, and an error if it carries credentials that are malformed.
+    fn extract(&self, request: &::http::request::Parts) -> Result<Option<Credentials>, AuthError>;
+
+    /// Returns the value of the `WWW-Authenticate` header sent with `401` responses, if any.
+    fn challenge(&self) -> Option<::http::HeaderValue> {
+        None
+    }
+}
+
+/// A future returned by [`Authenticate::authenticate`].
+pub type AuthenticateFuture<P> = Pin<Box<dyn Future<Output = Result<P, AuthError>> + Send>>;
+
+/// Authenticates [`Credentials`], turning them into a principal.
+///
+/// This is implemented for closures that take [`Credentials`] and return a future.
+pub trait Authenticate: Send + Sync {
+    /// The principal the request was authenticated as, which is inserted into the request
+    /// extensions for handlers to use.
+    type Principal: Clone + Send + Sync + 'static;
+
+    /// Authenticates the given credentials.
+    ///
+    /// Fails with [`AuthError::unauthenticated`] if the credentials are invalid, and with
+    /// [`AuthError::forbidden`] if they are valid, but their principal is not allowed to make the
+    /// request.
+    fn authenticate(&self, credentials: Credentials) -> AuthenticateFuture<Self::Principal>;
+}
+
+impl<F, Fut, P> Authenticate for F
+where
+    F: Fn(Credentials) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<P, AuthError>> + Send + 'static,
+    P: Clone + Send + Sync + 'static,
+{
+    type Principal = P;
+
+    fn authenticate(&self, credentials: Credentials) -> AuthenticateFuture<P> {
+        Box::pin(self(credentials))
+    }
+}
+
+#[derive(Debug)]
+enum AuthErrorKind {
+    Unauthenticated,
+    Forbidden,
+}
+
+/// An error returned when a request can't be authenticated.
+#[derive(Debug)]
+pub struct AuthError {
+    kind: AuthErrorKind,
+    message: Cow<'static, str>,
+    source: Option<BoxError>,
+}
+
+impl AuthError {
+    /// Creates an error for a request whose credentials are malformed or invalid.
+    ///
+    /// The request is rejected with a `401` response.
+    pub fn unauthenticated(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: AuthErrorKind::Unauthenticated,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Creates an error for a request whose principal is not allowed to call the operation.
+    ///
+    /// The request is rejected with a `403` response.
+    pub fn forbidden(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: AuthErrorKind::Forbidden,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Sets the underlying cause of the error.
+    pub fn with_source(mut self, source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Returns true if the request was rejected because its principal is not allowed to call the
+    /// operation, rather than because it couldn't be authenticated.
+    pub fn is_forbidden(&self) -> bool {
+        matches!(self.kind, AuthErrorKind::Forbidden)
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            AuthErrorKind::Unauthenticated => write!(f, "failed to authenticate the request: ")?,
+            AuthErrorKind::Forbidden => write!(f, "the request is not allowed: ")?,
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for AuthError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_ref().map(|err| err.as_ref() as _)
+    }
+}
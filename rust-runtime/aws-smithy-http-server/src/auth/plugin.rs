@@ -0,0 +1,408 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::header::WWW_AUTHENTICATE;
+use tower::Service;
+
+use super::{
+    AccessDeniedException, AuthError, AuthScheme, Authenticate, Credentials, OperationAuth, UnauthorizedException,
+};
+use crate::body::BoxBody;
+use crate::operation::OperationShape;
+use crate::plugin::{HttpMarker, HttpPlugins, Plugin, PluginStack};
+use crate::response::IntoResponse;
+use crate::service::ServiceShape;
+use crate::shape_id::ShapeId;
+
+/// A [`Plugin`] which authenticates requests to every operation according to its
+/// [`OperationAuth`].
+///
+/// See the [module documentation](crate::auth) for more information.
+pub struct AuthPlugin<A> {
+    authenticator: Arc<A>,
+    schemes: Vec<Arc<dyn AuthScheme>>,
+}
+
+impl<A> Clone for AuthPlugin<A> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: self.authenticator.clone(),
+            schemes: self.schemes.clone(),
+        }
+    }
+}
+
+impl<A> fmt::Debug for AuthPlugin<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthPlugin")
+            .field("schemes", &self.schemes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A> AuthPlugin<A> {
+    /// Creates a new `AuthPlugin` that authenticates credentials with the given authenticator.
+    ///
+    /// No auth schemes are configured, so they must be added with [`AuthPlugin::with_scheme`].
+    pub fn new(authenticator: A) -> Self {
+        Self {
+            authenticator: Arc::new(authenticator),
+            schemes: Vec::new(),
+        }
+    }
+
+    /// Adds an auth scheme.
+    ///
+    /// The scheme is used for operations that list its [`AuthScheme::scheme_id`] among their auth
+    /// schemes. Requests to operations that require authentication but none of whose auth schemes
+    /// were added are always rejected.
+    pub fn with_scheme(mut self, scheme: impl AuthScheme + 'static) -> Self {
+        self.schemes.push(Arc::new(scheme));
+        self
+    }
+}
+
+impl<Ser, Op, T, A> Plugin<Ser, Op, T> for AuthPlugin<A>
+where
+    Ser: ServiceShape,
+    Op: OperationShape + OperationAuth,
+{
+    type Output = AuthService<T, Ser::Protocol, A>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        // Keep the operation's priority order, rather than the order the schemes were added in
+        let schemes: Vec<_> = Op::AUTH_SCHEMES
+            .iter()
+            .filter_map(|id| self.schemes.iter().find(|scheme| scheme.scheme_id() == *id).cloned())
+            .collect();
+        let required = !Op::AUTH_SCHEMES.is_empty() && !Op::OPTIONAL_AUTH;
+        if required && schemes.is_empty() {
+            tracing::warn!(
+                operation = %Op::ID.absolute(),
+                auth_schemes = ?Op::AUTH_SCHEMES,
+                "none of the operation's auth schemes are configured; all requests to it will be rejected"
+            );
+        }
+        AuthService {
+            inner,
+            operation: Op::ID,
+            required,
+            schemes: schemes.into(),
+            authenticator: self.authenticator.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<A> HttpMarker for AuthPlugin<A> {}
+
+/// An extension trait for applying [`AuthPlugin`].
+pub trait AuthExt<CurrentPlugin> {
+    /// Authenticates requests to every operation. See [`AuthPlugin`] for more information.
+    fn auth<A>(self, plugin: AuthPlugin<A>) -> HttpPlugins<PluginStack<AuthPlugin<A>, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> AuthExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn auth<A>(self, plugin: AuthPlugin<A>) -> HttpPlugins<PluginStack<AuthPlugin<A>, CurrentPlugin>> {
+        self.push(plugin)
+    }
+}
+
+/// A [`Service`] which authenticates requests before calling the inner service.
+///
+/// Created by [`AuthPlugin`].
+pub struct AuthService<S, P, A> {
+    inner: S,
+    operation: ShapeId,
+    required: bool,
+    schemes: Arc<[Arc<dyn AuthScheme>]>,
+    authenticator: Arc<A>,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<S, P, A> Clone for AuthService<S, P, A>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            operation: self.operation.clone(),
+            required: self.required,
+            schemes: self.schemes.clone(),
+            authenticator: self.authenticator.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S, P, A> fmt::Debug for AuthService<S, P, A>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthService")
+            .field("inner", &self.inner)
+            .field("operation", &self.operation)
+            .field("required", &self.required)
+            .field("schemes", &self.schemes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, P, A> AuthService<S, P, A>
+where
+    UnauthorizedException: IntoResponse<P>,
+    AccessDeniedException: IntoResponse<P>,
+{
+    fn extract(&self, request: &http::request::Parts) -> Result<Option<Credentials>, AuthError> {
+        for scheme in self.schemes.iter() {
+            if let Some(credentials) = scheme.extract(request)? {
+                return Ok(Some(credentials));
+            }
+        }
+        Ok(None)
+    }
+
+    fn reject(&self, error: &AuthError) -> http::Response<BoxBody> {
+        tracing::debug!(operation = %self.operation.absolute(), %error, "rejecting unauthenticated request");
+        if error.is_forbidden() {
+            return AccessDeniedException.into_response();
+        }
+        let mut response = UnauthorizedException.into_response();
+        for challenge in self.schemes.iter().filter_map(|scheme| scheme.challenge()) {
+            response.headers_mut().append(WWW_AUTHENTICATE, challenge);
+        }
+        response
+    }
+}
+
+impl<S, P, A, B> Service<http::Request<B>> for AuthService<S, P, A>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    P: 'static,
+    A: Authenticate + 'static,
+    B: Send + 'static,
+    UnauthorizedException: IntoResponse<P>,
+    AccessDeniedException: IntoResponse<P>,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        // The inner service was driven to readiness, so use it and leave the clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let (mut parts, body) = request.into_parts();
+        let credentials = match self.extract(&parts) {
+            Ok(Some(credentials)) => credentials,
+            Ok(None) if !self.required => {
+                return Box::pin(inner.call(http::Request::from_parts(parts, body)));
+            }
+            Ok(None) => {
+                let response = self.reject(&AuthError::unauthenticated("the request carries no credentials"));
+                return Box::pin(async move { Ok(response) });
+            }
+            Err(error) => {
+                let response = self.reject(&error);
+                return Box::pin(async move { Ok(response) });
+            }
+        };
+
+        let authenticated = self.authenticator.authenticate(credentials);
+        let service = self.clone_without_inner();
+        Box::pin(async move {
+            match authenticated.await {
+                Ok(principal) => {
+                    parts.extensions.insert(principal);
+                    inner.call(http::Request::from_parts(parts, body)).await
+                }
+                Err(error) => Ok(service.reject(&error)),
+            }
+        })
+    }
+}
+
+impl<S, P, A> AuthService<S, P, A> {
+    fn clone_without_inner(&self) -> AuthService<(), P, A> {
+        AuthService {
+            inner: (),
+            operation: self.operation.clone(),
+            required: self.required,
+            schemes: self.schemes.clone(),
+            authenticator: self.authenticator.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::StatusCode;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::auth::http::{
+        HttpApiKeyAuthScheme, HttpBearerAuthScheme, Token, HTTP_API_KEY_AUTH_SCHEME_ID, HTTP_BEARER_AUTH_SCHEME_ID,
+    };
+    use crate::auth::{AuthSchemeId, SIGV4_SCHEME_ID};
+    use crate::body::boxed;
+    use crate::extension::RuntimeErrorExtension;
+    use crate::protocol::rest_json_1::RestJson1;
+
+    struct TestService;
+
+    impl ServiceShape for TestService {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = RestJson1;
+        type Operations = ();
+    }
+
+    macro_rules! operation {
+        ($name:ident, [$($scheme:expr),*], $optional:expr) => {
+            struct $name;
+
+            impl OperationShape for $name {
+                const ID: ShapeId = ShapeId::new(concat!("com.example#", stringify!($name)), "com.example", stringify!($name));
+                type Input = ();
+                type Output = ();
+                type Error = ();
+            }
+
+            impl OperationAuth for $name {
+                const AUTH_SCHEMES: &'static [AuthSchemeId] = &[$($scheme),*];
+                const OPTIONAL_AUTH: bool = $optional;
+            }
+        };
+    }
+
+    operation!(
+        Required,
+        [HTTP_BEARER_AUTH_SCHEME_ID, HTTP_API_KEY_AUTH_SCHEME_ID],
+        false
+    );
+    operation!(Optional, [HTTP_BEARER_AUTH_SCHEME_ID], true);
+    operation!(Unauthenticated, [], false);
+    operation!(Unconfigured, [SIGV4_SCHEME_ID], false);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct User(&'static str);
+
+    fn plugin() -> AuthPlugin<impl Authenticate<Principal = User>> {
+        AuthPlugin::new(|credentials: Credentials| async move {
+            match credentials.data::<Token>().unwrap().token() {
+                "ash" => Ok(User("Ash")),
+                "team-rocket" => Err(AuthError::forbidden("Team Rocket is not allowed")),
+                _ => Err(AuthError::unauthenticated("unknown token")),
+            }
+        })
+        .with_scheme(HttpApiKeyAuthScheme::header("x-api-key"))
+        .with_scheme(HttpBearerAuthScheme::new())
+    }
+
+    async fn call<Op>(headers: &[(&str, &str)]) -> http::Response<BoxBody>
+    where
+        Op: OperationShape + OperationAuth,
+    {
+        let inner = tower::service_fn(|request: http::Request<()>| async move {
+            let user = request
+                .extensions()
+                .get::<User>()
+                .map(|user| user.0)
+                .unwrap_or("anonymous");
+            Ok::<_, Infallible>(http::Response::new(boxed(user.to_owned())))
+        });
+        let service = Plugin::<TestService, Op, _>::apply(&plugin(), inner);
+        let mut request = http::Request::builder();
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        service.oneshot(request.body(()).unwrap()).await.unwrap()
+    }
+
+    async fn body(response: http::Response<BoxBody>) -> String {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    fn error_type(response: &http::Response<BoxBody>) -> &str {
+        response.headers()["x-amzn-errortype"].to_str().unwrap()
+    }
+
+    #[tokio::test]
+    async fn authenticates_with_the_first_scheme_that_has_credentials() {
+        let response = call::<Required>(&[("authorization", "Bearer ash")]).await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("Ash", body(response).await);
+
+        let response = call::<Required>(&[("x-api-key", "ash")]).await;
+        assert_eq!("Ash", body(response).await);
+
+        // Bearer auth has priority, so the API key is ignored
+        let response = call::<Required>(&[("authorization", "Bearer team-rocket"), ("x-api-key", "ash")]).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_that_cant_be_authenticated() {
+        let response = call::<Required>(&[]).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        assert_eq!("UnauthorizedException", error_type(&response));
+        assert!(response.extensions().get::<RuntimeErrorExtension>().is_some());
+        assert_eq!(
+            vec!["Bearer"],
+            response.headers().get_all(WWW_AUTHENTICATE).iter().collect::<Vec<_>>()
+        );
+
+        let response = call::<Required>(&[("authorization", "Bearer gary")]).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+        let response = call::<Required>(&[("authorization", "Bearer team-rocket")]).await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("AccessDeniedException", error_type(&response));
+        assert!(response.headers().get(WWW_AUTHENTICATE).is_none());
+
+        let response = call::<Unconfigured>(&[("authorization", "Bearer ash")]).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    }
+
+    #[tokio::test]
+    async fn optional_auth() {
+        let response = call::<Optional>(&[]).await;
+        assert_eq!("anonymous", body(response).await);
+
+        let response = call::<Optional>(&[("authorization", "Bearer ash")]).await;
+        assert_eq!("Ash", body(response).await);
+
+        let response = call::<Optional>(&[("authorization", "Bearer gary")]).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    }
+
+    #[tokio::test]
+    async fn operations_without_auth_schemes_are_not_authenticated() {
+        let response = call::<Unauthenticated>(&[]).await;
+        assert_eq!("anonymous", body(response).await);
+
+        let response = call::<Unauthenticated>(&[("authorization", "Bearer ash")]).await;
+        assert_eq!("anonymous", body(response).await);
+    }
+}
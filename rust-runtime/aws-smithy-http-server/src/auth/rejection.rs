@@ -0,0 +1,122 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! The errors requests are rejected with when they can't be authenticated.
+//!
+//! These are built the same way each protocol's `RuntimeError` is, but are kept out of its
+//! variants, so that adding them doesn't change the public `RuntimeError` enums.
+
+use bytes::Bytes;
+use http::StatusCode;
+
+use crate::body::{to_boxed, BoxBody};
+use crate::extension::RuntimeErrorExtension;
+use crate::protocol::aws_json_10::AwsJson1_0;
+use crate::protocol::aws_json_11::AwsJson1_1;
+use crate::protocol::rest_json_1::RestJson1;
+use crate::protocol::rest_xml::RestXml;
+use crate::protocol::rpc_v2_cbor::RpcV2Cbor;
+use crate::response::IntoResponse;
+use crate::runtime_error::INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE;
+
+/// A _protocol-agnostic_ type representing a request that could not be authenticated, either
+/// because it carries no credentials or because the credentials were rejected.
+///
+/// This is converted into a `401 Unauthorized` response named `UnauthorizedException`, shaped like
+/// the protocol's other framework errors.
+#[derive(Debug)]
+pub struct UnauthorizedException;
+
+/// A _protocol-agnostic_ type representing an authenticated request that is not allowed to call
+/// the operation.
+///
+/// This is converted into a `403 Forbidden` response named `AccessDeniedException`, shaped like
+/// the protocol's other framework errors.
+#[derive(Debug)]
+pub struct AccessDeniedException;
+
+fn response(
+    status: StatusCode,
+    name: &'static str,
+    content_type: &'static str,
+    error_type_header: bool,
+    body: impl Into<Bytes>,
+) -> http::Response<BoxBody> {
+    let mut res = http::Response::builder()
+        .status(status)
+        .header("Content-Type", content_type);
+    if error_type_header {
+        res = res.header("X-Amzn-Errortype", name);
+    }
+    res.extension(RuntimeErrorExtension::new(name.to_string()))
+        .body(to_boxed(body.into()))
+        .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+}
+
+macro_rules! impl_into_response {
+    ($($protocol:ty => ($content_type:expr, $error_type_header:expr, $body:expr)),* $(,)?) => {
+        $(
+            impl IntoResponse<$protocol> for UnauthorizedException {
+                fn into_response(self) -> http::Response<BoxBody> {
+                    response(
+                        StatusCode::UNAUTHORIZED,
+                        "UnauthorizedException",
+                        $content_type,
+                        $error_type_header,
+                        $body,
+                    )
+                }
+            }
+
+            impl IntoResponse<$protocol> for AccessDeniedException {
+                fn into_response(self) -> http::Response<BoxBody> {
+                    response(
+                        StatusCode::FORBIDDEN,
+                        "AccessDeniedException",
+                        $content_type,
+                        $error_type_header,
+                        $body,
+                    )
+                }
+            }
+        )*
+    };
+}
+
+// https://cbor.nemo157.com/#type=hex&value=a0
+const EMPTY_CBOR_MAP: &[u8] = &[0xa0];
+
+impl_into_response! {
+    RestJson1 => ("application/json", true, "{}"),
+    RestXml => ("application/xml", false, "{}"),
+    // See https://awslabs.github.io/smithy/2.0/aws/protocols/aws-json-1_0-protocol.html#empty-body-serialization
+    AwsJson1_0 => ("application/x-amz-json-1.0", false, "{}"),
+    AwsJson1_1 => ("application/x-amz-json-1.1", false, ""),
+    RpcV2Cbor => ("application/cbor", false, EMPTY_CBOR_MAP),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejections_are_shaped_like_runtime_errors() {
+        let response = IntoResponse::<RestJson1>::into_response(UnauthorizedException);
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        assert_eq!("UnauthorizedException", response.headers()["x-amzn-errortype"]);
+        assert_eq!(
+            "UnauthorizedException",
+            response.extensions().get::<RuntimeErrorExtension>().unwrap().as_str()
+        );
+
+        let response = IntoResponse::<RpcV2Cbor>::into_response(AccessDeniedException);
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("application/cbor", response.headers()["content-type"]);
+        assert_eq!(
+            "AccessDeniedException",
+            response.extensions().get::<RuntimeErrorExtension>().unwrap().as_str()
+        );
+    }
+}
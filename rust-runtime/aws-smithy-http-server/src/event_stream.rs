@@ -0,0 +1,409 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Verification of signed event streams.
+//!
+//! Clients sign each event stream message they send, wrapping it in a message that carries the
+//! signature in a `:chunk-signature` header. Each signature is chained to the previous one, with
+//! the first chained to the signature of the request that opened the stream. The
+//! [`EventStreamVerificationPlugin`] checks those signatures as the request body is read and
+//! unwraps the messages, so that the operation's event stream receiver only sees messages that
+//! were verified, in the order they were signed.
+//!
+//! The verification of each message is delegated to a [`VerifyMessage`] implementation, such as
+//! the SigV4 `MessageVerifier` in the `aws-sigv4` crate. A new one is created for every request by
+//! a function given the request's [`Parts`](http::request::Parts).
+//!
+//! This plugin should only be applied to operations with an event stream input, for example with
+//! [`Scoped`](crate::plugin::Scoped) or [`filter_by_operation`](crate::plugin::filter_by_operation),
+//! since every request body it's applied to is decoded as an event stream.
+//!
+//! # Example
+//!
+//! ```
+//! # use aws_smithy_eventstream::frame::{VerifyMessage, VerifyMessageError};
+//! # use aws_smithy_types::event_stream::Message;
+//! use aws_smithy_http_server::event_stream::EventStreamVerificationPlugin;
+//!
+//! # #[derive(Debug)]
+//! # struct MyVerifier;
+//! # impl MyVerifier {
+//! #     fn from_authorization(_authorization: &str) -> Result<Self, VerifyMessageError> { Ok(Self) }
+//! # }
+//! # impl VerifyMessage for MyVerifier {
+//! #     fn verify(&mut self, message: Message) -> Result<Option<Message>, VerifyMessageError> {
+//! #         Ok(Some(message))
+//! #     }
+//! # }
+//! let plugin = EventStreamVerificationPlugin::new(|parts: &http::request::Parts| {
+//!     let authorization = parts
+//!         .headers
+//!         .get(http::header::AUTHORIZATION)
+//!         .and_then(|value| value.to_str().ok())
+//!         .ok_or("missing authorization header")?;
+//!     Ok(Box::new(MyVerifier::from_authorization(authorization)?) as _)
+//! });
+//! ```
+
+use std::fmt;
+use std::future::{ready, Ready};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use aws_smithy_eventstream::frame::{write_message_to, DecodedFrame, MessageFrameDecoder, VerifyMessage};
+use bytes::{Bytes, BytesMut};
+use futures_util::{ready, Stream};
+use pin_project_lite::pin_project;
+use tower::Service;
+
+use crate::auth::AccessDeniedException;
+use crate::body::{BoxBody, HttpBody};
+use crate::error::BoxError;
+use crate::plugin::either::Either;
+use crate::plugin::{HttpMarker, Plugin};
+use crate::response::IntoResponse;
+use crate::service::ServiceShape;
+
+/// Creates the [`VerifyMessage`] for the event stream of a request.
+type MakeVerifier =
+    dyn Fn(&http::request::Parts) -> Result<Box<dyn VerifyMessage + Send + Sync>, BoxError> + Send + Sync;
+
+/// A [`Plugin`] that verifies the signature of every message of an operation's input event stream.
+///
+/// When no verifier can be created for a request, for example because it isn't signed, the
+/// request is rejected with the protocol's `AccessDeniedException` error. When a message fails
+/// verification, or the stream ends before the signed empty message that terminates it, reading
+/// the request body fails, which surfaces as an error from the operation's event stream receiver.
+/// See the [module documentation](crate::event_stream) for more information.
+#[derive(Clone)]
+pub struct EventStreamVerificationPlugin {
+    make_verifier: Arc<MakeVerifier>,
+}
+
+impl EventStreamVerificationPlugin {
+    /// Creates a plugin that uses `make_verifier` to create the verifier for each request.
+    pub fn new<F>(make_verifier: F) -> Self
+    where
+        F: Fn(&http::request::Parts) -> Result<Box<dyn VerifyMessage + Send + Sync>, BoxError> + Send + Sync + 'static,
+    {
+        Self {
+            make_verifier: Arc::new(make_verifier),
+        }
+    }
+}
+
+impl fmt::Debug for EventStreamVerificationPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStreamVerificationPlugin").finish_non_exhaustive()
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for EventStreamVerificationPlugin
+where
+    Ser: ServiceShape,
+{
+    type Output = EventStreamVerification<T, Ser::Protocol>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        EventStreamVerification {
+            inner,
+            make_verifier: self.make_verifier.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl HttpMarker for EventStreamVerificationPlugin {}
+
+/// A middleware [`Service`] that verifies the signatures of the messages of a request's event stream.
+///
+/// See [`EventStreamVerificationPlugin`].
+pub struct EventStreamVerification<S, P> {
+    inner: S,
+    make_verifier: Arc<MakeVerifier>,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<S: Clone, P> Clone for EventStreamVerification<S, P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            make_verifier: self.make_verifier.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S: fmt::Debug, P> fmt::Debug for EventStreamVerification<S, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStreamVerification")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, P, B> Service<http::Request<B>> for EventStreamVerification<S, P>
+where
+    S: Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>>,
+    B: HttpBody<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+    AccessDeniedException: IntoResponse<P>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<S::Response, S::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let (parts, body) = req.into_parts();
+        match (self.make_verifier)(&parts) {
+            Ok(verifier) => {
+                let body = hyper::Body::wrap_stream(VerifiedMessages::new(body, verifier));
+                Either::Left {
+                    value: self.inner.call(http::Request::from_parts(parts, body)),
+                }
+            }
+            Err(err) => {
+                tracing::debug!(error = %err, "rejecting event stream request that can't be verified");
+                Either::Right {
+                    value: ready(Ok(AccessDeniedException.into_response())),
+                }
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Decodes the signed messages of a request body, and yields the encoded messages they wrap
+    /// once their signatures have been verified.
+    struct VerifiedMessages<B> {
+        #[pin]
+        body: B,
+        buffer: BytesMut,
+        decoder: MessageFrameDecoder,
+        verifier: Box<dyn VerifyMessage + Send + Sync>,
+        // Whether the signed empty message that terminates the stream has been verified
+        terminated: bool,
+        done: bool,
+    }
+}
+
+impl<B> VerifiedMessages<B> {
+    fn new(body: B, verifier: Box<dyn VerifyMessage + Send + Sync>) -> Self {
+        Self {
+            body,
+            buffer: BytesMut::new(),
+            decoder: MessageFrameDecoder::new(),
+            verifier,
+            terminated: false,
+            done: false,
+        }
+    }
+}
+
+impl<B> Stream for VerifiedMessages<B>
+where
+    B: HttpBody<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if *this.done {
+                return Poll::Ready(None);
+            }
+            let result = match this.decoder.decode_frame(&mut *this.buffer) {
+                Ok(DecodedFrame::Complete(message)) => match this.verifier.verify(message) {
+                    // The signed empty message that ends the stream has nothing to pass on
+                    Ok(None) => {
+                        *this.terminated = true;
+                        continue;
+                    }
+                    Ok(Some(message)) => {
+                        let mut encoded = Vec::new();
+                        write_message_to(&message, &mut encoded)
+                            .map(|_| Bytes::from(encoded))
+                            .map_err(BoxError::from)
+                    }
+                    Err(err) => Err(err),
+                },
+                Ok(DecodedFrame::Incomplete) => match ready!(this.body.as_mut().poll_data(cx)) {
+                    Some(Ok(data)) => {
+                        this.buffer.extend_from_slice(&data);
+                        continue;
+                    }
+                    Some(Err(err)) => Err(err.into()),
+                    None => {
+                        *this.done = true;
+                        if !this.buffer.is_empty() {
+                            Err("event stream ended in the middle of a message".into())
+                        } else if !*this.terminated {
+                            // Without the signed terminating message, a truncated stream can't be
+                            // told apart from a complete one
+                            Err("event stream ended without the signed message that terminates it".into())
+                        } else {
+                            return Poll::Ready(None);
+                        }
+                    }
+                },
+                Err(err) => Err(err.into()),
+            };
+            *this.done |= result.is_err();
+            return Poll::Ready(Some(result));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_eventstream::frame::VerifyMessageError;
+    use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
+    use http::StatusCode;
+    use tower::{service_fn, ServiceExt};
+
+    use crate::protocol::rest_json_1::RestJson1;
+    use crate::shape_id::ShapeId;
+
+    struct TestService;
+
+    impl ServiceShape for TestService {
+        const ID: ShapeId = ShapeId::new("com.example#Service", "com.example", "Service");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = RestJson1;
+        type Operations = ();
+    }
+
+    /// Accepts messages that wrap a message, and whose `sequence` header counts up from zero.
+    #[derive(Debug, Default)]
+    struct SequenceVerifier {
+        next: i32,
+    }
+
+    impl VerifyMessage for SequenceVerifier {
+        fn verify(&mut self, message: Message) -> Result<Option<Message>, VerifyMessageError> {
+            match message.headers().first().map(|header| header.value()) {
+                Some(HeaderValue::Int32(sequence)) if *sequence == self.next => {
+                    self.next += 1;
+                }
+                _ => return Err("out of sequence".into()),
+            }
+            if message.payload().is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(aws_smithy_eventstream::frame::read_message_from(
+                message.payload().clone(),
+            )?))
+        }
+    }
+
+    fn encode(message: &Message) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        write_message_to(message, &mut encoded).unwrap();
+        encoded
+    }
+
+    fn signed(sequence: i32, payload: &'static str) -> Vec<u8> {
+        let inner = if payload.is_empty() {
+            Vec::new()
+        } else {
+            encode(&Message::new(payload.as_bytes()))
+        };
+        encode(&Message::new(inner).add_header(Header::new("sequence", HeaderValue::Int32(sequence))))
+    }
+
+    fn plugin() -> EventStreamVerificationPlugin {
+        EventStreamVerificationPlugin::new(|parts: &http::request::Parts| {
+            if parts.headers.contains_key("authorization") {
+                Ok(Box::<SequenceVerifier>::default() as _)
+            } else {
+                Err("unsigned".into())
+            }
+        })
+    }
+
+    /// Reads the request body and responds with the payloads of the messages in it
+    fn echo_payloads(
+    ) -> impl Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>, Error = BoxError, Future = impl Send>
+           + Clone {
+        service_fn(|req: http::Request<hyper::Body>| async move {
+            let mut body = hyper::body::to_bytes(req.into_body()).await?;
+            let mut payloads = Vec::new();
+            while !body.is_empty() {
+                let message = aws_smithy_eventstream::frame::read_message_from(&mut body)?;
+                payloads.push(String::from_utf8(message.payload().to_vec())?);
+            }
+            Ok(http::Response::new(crate::body::to_boxed(payloads.join(","))))
+        })
+    }
+
+    async fn call(body: Vec<u8>, authorization: bool) -> Result<http::Response<BoxBody>, BoxError> {
+        let mut request = http::Request::builder();
+        if authorization {
+            request = request.header("authorization", "test");
+        }
+        let request = request.body(hyper::Body::from(body)).unwrap();
+        let service = <EventStreamVerificationPlugin as Plugin<TestService, (), _>>::apply(&plugin(), echo_payloads());
+        service.oneshot(request).await
+    }
+
+    async fn response_body(response: http::Response<BoxBody>) -> String {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn unwraps_verified_messages() {
+        let body = [signed(0, "one"), signed(1, "two"), signed(2, "")].concat();
+        let response = call(body, true).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("one,two", response_body(response).await);
+    }
+
+    #[tokio::test]
+    async fn rejects_messages_that_fail_verification() {
+        let body = [signed(1, "two"), signed(0, "one")].concat();
+        let err = call(body, true).await.unwrap_err();
+        assert!(format!("{:?}", err).contains("out of sequence"), "{:?}", err);
+
+        let truncated = signed(0, "one");
+        let err = call(truncated[..truncated.len() - 1].to_vec(), true).await.unwrap_err();
+        assert!(
+            format!("{:?}", err).contains("ended in the middle of a message"),
+            "{:?}",
+            err
+        );
+
+        // Without the terminating message, the stream may have been truncated
+        let body = [signed(0, "one"), signed(1, "two")].concat();
+        let err = call(body, true).await.unwrap_err();
+        assert!(
+            format!("{:?}", err).contains("without the signed message that terminates it"),
+            "{:?}",
+            err
+        );
+        let err = call(Vec::new(), true).await.unwrap_err();
+        assert!(
+            format!("{:?}", err).contains("without the signed message that terminates it"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_verifier() {
+        let response = call(signed(0, "one"), false).await.unwrap();
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("AccessDeniedException", response.headers()["x-amzn-errortype"]);
+        assert_eq!("{}", response_body(response).await);
+    }
+}
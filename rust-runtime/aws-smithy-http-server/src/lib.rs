@@ -15,8 +15,12 @@
 #[macro_use]
 pub(crate) mod macros;
 
+pub mod auth;
 pub mod body;
 pub(crate) mod error;
+#[cfg(feature = "event-stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "event-stream")))]
+pub mod event_stream;
 pub mod extension;
 pub mod instrumentation;
 pub mod layer;
@@ -7,6 +7,119 @@ pub mod rejection;
 pub mod router;
 pub mod runtime_error;
 
+use aws_smithy_cbor::decode::DeserializeError;
+use aws_smithy_cbor::validate::DecoderConfig;
+use aws_smithy_cbor::value::MAX_DEPTH;
+use aws_smithy_cbor::Decoder;
+use tower::Layer;
+
+use crate::AddExtension;
+
 /// [Smithy RPC v2 CBOR](https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html)
 /// protocol.
 pub struct RpcV2Cbor;
+
+/// The default maximum number of items in an array, or of entries in a map, of a request body.
+pub const DEFAULT_MAX_COLLECTION_LEN: u64 = 1 << 20;
+
+/// The default maximum size of a request body, in bytes.
+pub const DEFAULT_MAX_INPUT_LEN: usize = 10 * 1024 * 1024;
+
+/// Returns the limits and validation that request bodies are checked against before they are
+/// deserialized, unless a [`DecoderConfigLayer`] sets others.
+///
+/// Bodies must be [strictly](aws_smithy_cbor::validate::Validation::Strict) well-formed, arrays,
+/// maps and tags may be nested at most [`MAX_DEPTH`] levels deep, collections may hold at most
+/// [`DEFAULT_MAX_COLLECTION_LEN`] items and bodies may be at most [`DEFAULT_MAX_INPUT_LEN`] bytes.
+pub fn decoder_config() -> DecoderConfig {
+    DecoderConfig::strict()
+        .with_max_depth(MAX_DEPTH)
+        .with_max_collection_len(DEFAULT_MAX_COLLECTION_LEN)
+        .with_max_input_len(DEFAULT_MAX_INPUT_LEN)
+}
+
+/// A [`Layer`] that sets the [`DecoderConfig`] that request bodies are checked against, in place
+/// of [`decoder_config()`].
+///
+/// ```rust
+/// # use aws_smithy_cbor::validate::DecoderConfig;
+/// # use aws_smithy_http_server::protocol::rpc_v2_cbor::{decoder_config, DecoderConfigLayer};
+/// // Accept bodies of up to 64 MiB
+/// let layer = DecoderConfigLayer::new(decoder_config().with_max_input_len(64 * 1024 * 1024));
+/// ```
+///
+/// Register it with the service config's `layer` method. Nesting is never allowed deeper than
+/// [`MAX_DEPTH`] levels, whatever the config.
+#[derive(Clone, Debug)]
+pub struct DecoderConfigLayer {
+    config: DecoderConfig,
+}
+
+impl DecoderConfigLayer {
+    /// Creates a layer that checks request bodies against `config`.
+    pub fn new(config: DecoderConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<S> Layer<S> for DecoderConfigLayer {
+    type Service = AddExtension<S, DecoderConfig>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AddExtension::new(inner, self.config.clone())
+    }
+}
+
+/// Checks a request body against the [`DecoderConfig`] set by a [`DecoderConfigLayer`], or
+/// against [`decoder_config()`] if there is none.
+pub fn check_request_body(extensions: &http::Extensions, body: &[u8]) -> Result<(), DeserializeError> {
+    let config = match extensions.get::<DecoderConfig>() {
+        // Deserializers recurse into nested data, so nesting is limited whatever the config
+        Some(config) => config.clone().with_max_depth(
+            config
+                .max_depth()
+                .map_or(MAX_DEPTH, |max_depth| max_depth.min(MAX_DEPTH)),
+        ),
+        None => decoder_config(),
+    };
+    Decoder::with_config(body, &config)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_request_body, decoder_config, DecoderConfigLayer, DEFAULT_MAX_INPUT_LEN};
+    use aws_smithy_cbor::value::MAX_DEPTH;
+    use tower::{Layer, ServiceExt};
+
+    fn array_of_len(len: usize) -> Vec<u8> {
+        // An array header with a 4-byte length, followed by `len` zeros
+        let mut body = vec![0x9a];
+        body.extend_from_slice(&(len as u32).to_be_bytes());
+        body.resize(body.len() + len, 0x00);
+        body
+    }
+
+    #[test]
+    fn default_limits() {
+        let extensions = http::Extensions::new();
+        check_request_body(&extensions, &array_of_len(16)).unwrap();
+        check_request_body(&extensions, &array_of_len(DEFAULT_MAX_INPUT_LEN)).unwrap_err();
+        check_request_body(&extensions, &[0x81; MAX_DEPTH + 1]).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn layer_sets_config() {
+        let layer = DecoderConfigLayer::new(decoder_config().with_max_collection_len(4));
+        let svc = layer.layer(tower::service_fn(|req: http::Request<()>| async move {
+            let accepted = check_request_body(req.extensions(), &array_of_len(16)).is_ok();
+            Ok::<_, std::convert::Infallible>(http::Response::new(accepted))
+        }));
+        let response = svc.oneshot(http::Request::new(())).await.unwrap();
+        assert!(!response.into_body());
+
+        let mut extensions = http::Extensions::new();
+        extensions.insert(decoder_config().with_max_depth(MAX_DEPTH + 1));
+        check_request_body(&extensions, &[0x81; MAX_DEPTH + 1]).unwrap_err();
+    }
+}
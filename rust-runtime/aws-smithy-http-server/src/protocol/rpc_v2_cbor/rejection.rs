@@ -27,6 +27,9 @@ pub enum RequestRejection {
     NotAcceptable,
     #[error("expected `Content-Type` header not found: {0}")]
     MissingContentType(#[from] MissingContentTypeReason),
+    /// The body is not valid CBOR, does not match the modeled shape, or was rejected by the limits
+    /// configured with [`aws_smithy_cbor::validate::DecoderConfig`]. The error message includes the
+    /// offset in the body at which decoding failed.
     #[error("error deserializing request HTTP body as CBOR: {0}")]
     CborDeserialize(#[from] aws_smithy_cbor::decode::DeserializeError),
     // Unlike the other protocols, RPC v2 uses CBOR, a binary serialization format, so we take in a
@@ -10,11 +10,18 @@ use std::error::Error as StdError;
 mod receiver;
 mod sender;
 
+#[cfg(feature = "event-stream-websocket")]
+pub mod websocket;
+
 /// A generic, boxed error that's `Send`, `Sync`, and `'static`.
 pub type BoxError = Box<dyn StdError + Send + Sync + 'static>;
 
 #[doc(inline)]
 pub use sender::{EventStreamSender, MessageStreamAdapter, MessageStreamError};
 
+#[cfg(feature = "event-stream-channel")]
+#[doc(inline)]
+pub use sender::{EventStreamSendError, EventStreamSenderHandle};
+
 #[doc(inline)]
 pub use receiver::{Receiver, ReceiverError};
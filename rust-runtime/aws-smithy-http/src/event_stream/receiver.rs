@@ -90,6 +90,8 @@ impl RecvBuf {
 enum ReceiverErrorKind {
     /// The stream ended before a complete message frame was received.
     UnexpectedEndOfStream,
+    /// More data was buffered for an incomplete message frame than the receiver's limit allows.
+    BufferLimitExceeded { buffered: usize, max: usize },
 }
 
 /// An error that occurs within an event stream receiver.
@@ -102,6 +104,11 @@ impl fmt::Display for ReceiverError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
             ReceiverErrorKind::UnexpectedEndOfStream => write!(f, "unexpected end of stream"),
+            ReceiverErrorKind::BufferLimitExceeded { buffered, max } => write!(
+                f,
+                "{buffered} bytes of an incomplete event stream message are buffered, \
+                 which exceeds the limit of {max} bytes"
+            ),
         }
     }
 }
@@ -109,6 +116,10 @@ impl fmt::Display for ReceiverError {
 impl StdError for ReceiverError {}
 
 /// Receives Smithy-modeled messages out of an Event Stream.
+///
+/// Once [`recv`](Receiver::recv) returns an error, whether because a message frame couldn't be
+/// decoded, a limit was exceeded, or the message was a modeled error, the stream can't be resumed:
+/// every later call to `recv` returns `Ok(None)`.
 #[derive(Debug)]
 pub struct Receiver<T, E> {
     unmarshaller: Box<dyn UnmarshallMessage<Output = T, Error = E> + Send + Sync>,
@@ -120,6 +131,7 @@ pub struct Receiver<T, E> {
     /// initial response, then the message will be stored in `buffered_message` so that it can
     /// be returned with the next call of `recv()`.
     buffered_message: Option<Message>,
+    max_buffered_bytes: Option<usize>,
     _phantom: PhantomData<E>,
 }
 
@@ -135,10 +147,36 @@ impl<T, E> Receiver<T, E> {
             buffer: RecvBuf::Empty,
             body,
             buffered_message: None,
+            max_buffered_bytes: None,
             _phantom: Default::default(),
         }
     }
 
+    /// Sets the largest message frame, in bytes, that will be received.
+    ///
+    /// Receiving a larger frame fails with a response error without buffering the frame.
+    pub fn with_max_frame_size(mut self, max_frame_size: u32) -> Self {
+        self.decoder = self.decoder.with_max_frame_size(max_frame_size);
+        self
+    }
+
+    /// Sets the largest number of headers a received message can have.
+    pub fn with_max_header_count(mut self, max_header_count: usize) -> Self {
+        self.decoder = self.decoder.with_max_header_count(max_header_count);
+        self
+    }
+
+    /// Sets the largest amount of data, in bytes, that will be buffered for a message frame
+    /// that hasn't been completely received.
+    ///
+    /// Complete frames that haven't been received yet with [`recv`](Receiver::recv) don't count
+    /// towards the limit, so this bounds the memory used by the receiver when the peer sends a
+    /// large frame. Exceeding it terminates the stream with a response error.
+    pub fn with_max_buffered_bytes(mut self, max_buffered_bytes: usize) -> Self {
+        self.max_buffered_bytes = Some(max_buffered_bytes);
+        self
+    }
+
     fn unmarshall(&self, message: Message) -> Result<Option<T>, SdkError<E, RawMessage>> {
         match self.unmarshaller.unmarshall(&message) {
             Ok(unmarshalled) => match unmarshalled {
@@ -171,22 +209,46 @@ impl<T, E> Receiver<T, E> {
         Ok(())
     }
 
+    /// Fails if the data buffered for an incomplete message frame exceeds the limit.
+    ///
+    /// This must only be called once the decoder has consumed every complete frame in the buffer.
+    fn check_buffer_limit(&mut self) -> Result<(), SdkError<E, RawMessage>> {
+        if let Some(max) = self.max_buffered_bytes {
+            let buffered = match &self.buffer {
+                RecvBuf::Partial(segments) | RecvBuf::EosPartial(segments) => segments.remaining(),
+                RecvBuf::Empty | RecvBuf::Terminated => 0,
+            };
+            if buffered > max {
+                self.buffer = RecvBuf::Terminated;
+                return Err(SdkError::response_error(
+                    ReceiverError {
+                        kind: ReceiverErrorKind::BufferLimitExceeded { buffered, max },
+                    },
+                    RawMessage::Invalid(None),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     async fn next_message(&mut self) -> Result<Option<Message>, SdkError<E, RawMessage>> {
         while !self.buffer.is_eos() {
             if self.buffer.has_data() {
-                if let DecodedFrame::Complete(message) = self
-                    .decoder
-                    .decode_frame(self.buffer.buffered())
-                    .map_err(|err| {
-                        SdkError::response_error(
+                match self.decoder.decode_frame(self.buffer.buffered()) {
+                    Ok(DecodedFrame::Complete(message)) => {
+                        trace!(message = ?message, "received complete event stream message");
+                        return Ok(Some(message));
+                    }
+                    Ok(DecodedFrame::Incomplete) => self.check_buffer_limit()?,
+                    Err(err) => {
+                        // The stream can't be resynchronized after an invalid frame
+                        self.buffer = RecvBuf::Terminated;
+                        return Err(SdkError::response_error(
                             err,
                             // the buffer has been consumed
                             RawMessage::Invalid(None),
-                        )
-                    })?
-                {
-                    trace!(message = ?message, "received complete event stream message");
-                    return Ok(Some(message));
+                        ));
+                    }
                 }
             }
 
@@ -235,6 +297,9 @@ impl<T, E> Receiver<T, E> {
     /// it returns an `Ok(None)`. If there is a transport layer error, it will return
     /// `Err(SdkError::DispatchFailure)`. Service-modeled errors will be a part of the returned
     /// messages.
+    ///
+    /// Any error other than a transport layer error ends the stream, after which this always
+    /// returns `Ok(None)`.
     pub async fn recv(&mut self) -> Result<Option<T>, SdkError<E, RawMessage>> {
         if let Some(buffered) = self.buffered_message.take() {
             return match self.unmarshall(buffered) {
@@ -501,6 +566,74 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn receive_frame_too_large() {
+        let chunks: Vec<Result<_, IOError>> =
+            vec![Ok(encode_message("one")), Ok(encode_message("three"))];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body)
+            .with_max_frame_size(encode_message("one").len() as u32);
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        let err = receiver.recv().await.unwrap_err();
+        assert!(matches!(err, SdkError::ResponseError { .. }));
+        assert_eq!(
+            "event stream frame of 21 bytes exceeds the maximum frame size of 19 bytes",
+            err.source().unwrap().to_string()
+        );
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn receive_buffer_limit_exceeded() {
+        // Complete frames don't count towards the limit, however many arrive at once
+        let two_messages = [encode_message("one"), encode_message("two")].concat();
+        let chunks: Vec<Result<_, IOError>> = vec![
+            Ok(two_messages.clone().into()),
+            Ok(Bytes::copy_from_slice(
+                &[two_messages.clone(), two_messages].concat(),
+            )),
+        ];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body)
+            .with_max_buffered_bytes(60);
+        for payload in ["one", "two", "one", "two", "one", "two"] {
+            assert_eq!(
+                TestMessage(payload.into()),
+                receiver.recv().await.unwrap().unwrap()
+            );
+        }
+        assert_eq!(None, receiver.recv().await.unwrap());
+
+        // A frame of 116 bytes, of which the first 80 have arrived. The decoder has consumed the
+        // 12-byte prelude, so 68 bytes are buffered.
+        let large = encode_message(&"x".repeat(100));
+        let chunks: Vec<Result<_, IOError>> = vec![
+            Ok(encode_message("one")),
+            Ok(large.slice(..80)),
+            Ok(large.slice(80..)),
+        ];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body)
+            .with_max_buffered_bytes(60);
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        let err = receiver.recv().await.unwrap_err();
+        assert_eq!(
+            "68 bytes of an incomplete event stream message are buffered, \
+             which exceeds the limit of 60 bytes",
+            err.source().unwrap().to_string()
+        );
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
     #[tokio::test]
     async fn receive_initial_response() {
         let chunks: Vec<Result<_, IOError>> =
@@ -14,6 +14,8 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+#[cfg(feature = "event-stream-channel")]
+use tokio::sync::mpsc;
 use tracing::trace;
 
 /// Input type for Event Streams.
@@ -41,6 +43,142 @@ impl<T, E: StdError + Send + Sync + 'static> EventStreamSender<T, E> {
     }
 }
 
+#[cfg(feature = "event-stream-channel")]
+impl<T, E> EventStreamSender<T, E>
+where
+    T: Send + 'static,
+    E: Send + 'static,
+{
+    /// Creates an `EventStreamSender` along with a handle to send its events through a channel
+    /// that holds up to `capacity` events.
+    ///
+    /// The events are only taken off the channel as fast as they can be written to the
+    /// connection. Once the channel is full, [`EventStreamSenderHandle::send`] waits for room,
+    /// so that a producer that outpaces the connection is slowed down instead of buffering
+    /// events without bound. The event stream ends once every handle has been dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn channel(capacity: usize) -> (EventStreamSenderHandle<T, E>, Self) {
+        let (tx, rx) = mpsc::channel(capacity);
+        (EventStreamSenderHandle { tx }, ChannelStream { rx }.into())
+    }
+}
+
+/// Adapts the receiving half of a channel to a `Stream`.
+#[cfg(feature = "event-stream-channel")]
+struct ChannelStream<T> {
+    rx: mpsc::Receiver<T>,
+}
+
+#[cfg(feature = "event-stream-channel")]
+impl<T> Stream for ChannelStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Sends events to an [`EventStreamSender`] created with [`EventStreamSender::channel`].
+#[cfg(feature = "event-stream-channel")]
+pub struct EventStreamSenderHandle<T, E> {
+    tx: mpsc::Sender<Result<T, E>>,
+}
+
+#[cfg(feature = "event-stream-channel")]
+impl<T, E> Clone for EventStreamSenderHandle<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "event-stream-channel")]
+impl<T, E> Debug for EventStreamSenderHandle<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_t = std::any::type_name::<T>();
+        let name_e = std::any::type_name::<E>();
+        write!(f, "EventStreamSenderHandle<{name_t}, {name_e}>")
+    }
+}
+
+#[cfg(feature = "event-stream-channel")]
+impl<T, E> EventStreamSenderHandle<T, E> {
+    /// Sends an event, waiting for room in the channel if it's full.
+    ///
+    /// Fails if the event stream has been dropped, for example because the request failed.
+    pub async fn send(&self, event: T) -> Result<(), EventStreamSendError<T>> {
+        self.tx
+            .send(Ok(event))
+            .await
+            .map_err(|err| EventStreamSendError {
+                value: match err.0 {
+                    Ok(event) => event,
+                    Err(_) => unreachable!("an event was sent"),
+                },
+            })
+    }
+
+    /// Sends a modeled error event, waiting for room in the channel if it's full.
+    ///
+    /// Fails if the event stream has been dropped, for example because the request failed.
+    pub async fn send_error(&self, error: E) -> Result<(), EventStreamSendError<E>> {
+        self.tx
+            .send(Err(error))
+            .await
+            .map_err(|err| EventStreamSendError {
+                value: match err.0 {
+                    Err(error) => error,
+                    Ok(_) => unreachable!("an error was sent"),
+                },
+            })
+    }
+
+    /// Returns the number of events that can be sent without waiting.
+    ///
+    /// This is zero when the connection isn't keeping up with the events being sent.
+    pub fn capacity(&self) -> usize {
+        self.tx.capacity()
+    }
+
+    /// Returns true if the event stream has been dropped, and events can no longer be sent.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// An error returned when sending on an [`EventStreamSenderHandle`] whose event stream has been
+/// dropped.
+#[cfg(feature = "event-stream-channel")]
+#[derive(Debug)]
+pub struct EventStreamSendError<T> {
+    value: T,
+}
+
+#[cfg(feature = "event-stream-channel")]
+impl<T> EventStreamSendError<T> {
+    /// Returns the event that couldn't be sent.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+#[cfg(feature = "event-stream-channel")]
+impl<T> fmt::Display for EventStreamSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the event stream was dropped before the event could be sent"
+        )
+    }
+}
+
+#[cfg(feature = "event-stream-channel")]
+impl<T: Debug> StdError for EventStreamSendError<T> {}
+
 impl<T, E, S> From<S> for EventStreamSender<T, E>
 where
     S: Stream<Item = Result<T, E>> + Send + Sync + 'static,
@@ -327,6 +465,62 @@ mod tests {
         ));
     }
 
+    #[cfg(feature = "event-stream-channel")]
+    #[tokio::test]
+    async fn channel_applies_backpressure() {
+        let (handle, sender) = check_send_sync(
+            EventStreamSender::<TestMessage, TestServiceError>::channel(2),
+        );
+        let mut adapter = check_compatible_with_hyper_wrap_stream(sender.into_body_stream(
+            Marshaller,
+            ErrorMarshaller,
+            NoOpSigner {},
+        ));
+
+        handle.send(TestMessage("one".into())).await.unwrap();
+        handle.send(TestMessage("two".into())).await.unwrap();
+        assert_eq!(0, handle.capacity());
+        let blocked = tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            handle.send(TestMessage("three".into())),
+        )
+        .await;
+        assert!(
+            blocked.is_err(),
+            "send should wait while the channel is full"
+        );
+
+        let mut sent = adapter.next().await.unwrap().unwrap();
+        assert_eq!(
+            &b"one"[..],
+            &read_message_from(&mut sent).unwrap().payload()[..]
+        );
+        assert_eq!(1, handle.capacity());
+        handle.send(TestMessage("three".into())).await.unwrap();
+        drop(handle);
+
+        let mut payloads = Vec::new();
+        while let Some(sent) = adapter.next().await {
+            let message = read_message_from(&mut sent.unwrap()).unwrap();
+            payloads.push(String::from_utf8(message.payload().to_vec()).unwrap());
+        }
+        assert_eq!(vec!["two", "three"], payloads);
+    }
+
+    #[cfg(feature = "event-stream-channel")]
+    #[tokio::test]
+    async fn channel_send_fails_after_stream_is_dropped() {
+        let (handle, sender) = EventStreamSender::<TestMessage, TestServiceError>::channel(1);
+        drop(sender);
+        assert!(handle.is_closed());
+        let err = handle.send(TestMessage("one".into())).await.unwrap_err();
+        assert_eq!(
+            "the event stream was dropped before the event could be sent",
+            err.to_string()
+        );
+        assert_eq!(TestMessage("one".into()), err.into_inner());
+    }
+
     // Verify the developer experience for this compiles
     #[allow(unused)]
     fn event_stream_input_ergonomics() {
@@ -0,0 +1,897 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! WebSocket transport for event streams.
+//!
+//! Some services carry binary event stream frames over a WebSocket connection instead of an
+//! HTTP/2 body. Each WebSocket binary message holds exactly one event stream frame, so the
+//! framing produced by [`MessageStreamAdapter`](crate::event_stream::MessageStreamAdapter) and
+//! consumed by [`Receiver`](crate::event_stream::Receiver) can be reused unchanged:
+//!
+//! - [`connect`] performs the opening handshake over an already established connection. For AWS
+//!   services, the handshake request is a SigV4 presigned `GET` request, and the signature it
+//!   carries seeds the signer of the outgoing event stream.
+//! - [`WebSocketSender::send_all`] writes each frame of a message stream as a binary message and
+//!   sends keepalive pings while the stream is idle.
+//! - [`WebSocketBody`] turns the incoming binary messages back into a body that a `Receiver`
+//!   can decode. Pings from the peer are answered automatically.
+//!
+//! Generated clients don't use these directly. Instead, a [`WebSocketTransport`] set on the
+//! client config is installed by [`WebSocketRuntimePlugin`] for operations with an event stream
+//! input: the request is turned into a `GET` handshake, and the response body carries the
+//! messages received over the connection.
+
+use crate::event_stream::BoxError;
+use crate::futures_stream_adapter::FuturesStreamCompatByteStream;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::interceptors::context::BeforeTransmitInterceptorContextMut;
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::byte_stream::ByteStream;
+use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer, Layer, Storable, StoreReplace};
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_util::future::{self, Either};
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::tungstenite::handshake::client::generate_key;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, WebSocketConfig};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message as WsMessage};
+use tokio_tungstenite::WebSocketStream;
+use tracing::trace;
+
+/// A connection that a WebSocket can be established over, such as a TCP or TLS stream.
+pub trait Connection: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<T> Connection for T where T: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+type BoxedConnection = Box<dyn Connection>;
+
+#[derive(Debug)]
+enum WebSocketErrorKind {
+    /// The opening handshake failed.
+    Handshake(WsError),
+    /// Reading or writing a WebSocket message failed.
+    Transport(WsError),
+    /// The peer sent a text message, but event streams are binary.
+    UnexpectedTextMessage,
+    /// The peer closed the connection with a status code other than normal closure.
+    ClosedByPeer { code: u16, reason: String },
+    /// The message stream being sent yielded an error.
+    MessageStream(BoxError),
+}
+
+/// An error that occurs while using the WebSocket event stream transport.
+#[derive(Debug)]
+pub struct WebSocketError {
+    kind: WebSocketErrorKind,
+}
+
+impl WebSocketError {
+    fn new(kind: WebSocketErrorKind) -> Self {
+        Self { kind }
+    }
+
+    /// Returns the close code and reason if the peer closed the connection abnormally.
+    pub fn close_reason(&self) -> Option<(u16, &str)> {
+        match &self.kind {
+            WebSocketErrorKind::ClosedByPeer { code, reason } => Some((*code, reason)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WebSocketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use WebSocketErrorKind::*;
+        match &self.kind {
+            Handshake(_) => write!(f, "WebSocket opening handshake failed"),
+            Transport(_) => write!(f, "WebSocket transport error"),
+            UnexpectedTextMessage => write!(
+                f,
+                "received a WebSocket text message; event stream messages must be binary"
+            ),
+            ClosedByPeer { code, reason } => {
+                write!(f, "WebSocket closed by peer with code {code}: {reason}")
+            }
+            MessageStream(_) => write!(f, "the event stream being sent failed"),
+        }
+    }
+}
+
+impl StdError for WebSocketError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use WebSocketErrorKind::*;
+        match &self.kind {
+            Handshake(err) | Transport(err) => Some(err as _),
+            MessageStream(err) => Some(err.as_ref() as _),
+            UnexpectedTextMessage | ClosedByPeer { .. } => None,
+        }
+    }
+}
+
+/// Configuration for the WebSocket event stream transport.
+#[derive(Clone, Debug, Default)]
+pub struct WebSocketTransportConfig {
+    ping_interval: Option<Duration>,
+    sleep_impl: Option<SharedAsyncSleep>,
+    max_message_size: Option<usize>,
+}
+
+impl WebSocketTransportConfig {
+    /// Creates a config without keepalive pings and with the default maximum message size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends a ping whenever the sender has been idle for `interval`, using `sleep_impl` to
+    /// wait. Keepalive pings are disabled by default.
+    pub fn with_ping_interval(mut self, interval: Duration, sleep_impl: SharedAsyncSleep) -> Self {
+        self.ping_interval = Some(interval);
+        self.sleep_impl = Some(sleep_impl);
+        self
+    }
+
+    /// Sets the largest incoming WebSocket message accepted, in bytes.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = Some(max_message_size);
+        self
+    }
+
+    fn ws_config(&self) -> Option<WebSocketConfig> {
+        self.max_message_size.map(|max| WebSocketConfig {
+            max_message_size: Some(max),
+            max_frame_size: Some(max),
+            ..Default::default()
+        })
+    }
+}
+
+/// Performs the WebSocket opening handshake over `connection` and splits the resulting socket
+/// into its sending and receiving halves.
+///
+/// `request` is the `GET` request to upgrade, typically a SigV4 presigned request. The headers
+/// required by the WebSocket protocol (`Host`, `Connection`, `Upgrade`, `Sec-WebSocket-Version`
+/// and `Sec-WebSocket-Key`) are added if they're missing.
+pub async fn connect(
+    mut request: http_02x::Request<()>,
+    connection: impl Connection,
+    config: WebSocketTransportConfig,
+) -> Result<(WebSocketSender, WebSocketBody), WebSocketError> {
+    add_handshake_headers(&mut request);
+    let connection: BoxedConnection = Box::new(connection);
+    let (socket, response) =
+        tokio_tungstenite::client_async_with_config(request, connection, config.ws_config())
+            .await
+            .map_err(|err| WebSocketError::new(WebSocketErrorKind::Handshake(err)))?;
+    trace!(response = ?response, "WebSocket opening handshake complete");
+    let (sink, stream) = socket.split();
+    Ok((
+        WebSocketSender {
+            sink,
+            ping_interval: config.ping_interval.zip(config.sleep_impl),
+        },
+        WebSocketBody {
+            stream,
+            state: BodyState::Open,
+        },
+    ))
+}
+
+fn add_handshake_headers(request: &mut http_02x::Request<()>) {
+    use http_02x::header::{HeaderValue, CONNECTION, HOST, UPGRADE};
+
+    let host = request
+        .uri()
+        .authority()
+        .and_then(|authority| HeaderValue::from_str(authority.as_str()).ok());
+    let headers = request.headers_mut();
+    if let Some(host) = host {
+        headers.entry(HOST).or_insert(host);
+    }
+    headers
+        .entry(CONNECTION)
+        .or_insert(HeaderValue::from_static("Upgrade"));
+    headers
+        .entry(UPGRADE)
+        .or_insert(HeaderValue::from_static("websocket"));
+    headers
+        .entry("sec-websocket-version")
+        .or_insert(HeaderValue::from_static("13"));
+    headers.entry("sec-websocket-key").or_insert_with(|| {
+        HeaderValue::from_str(&generate_key()).expect("generated keys are valid base64")
+    });
+}
+
+/// The sending half of a WebSocket event stream connection.
+pub struct WebSocketSender {
+    sink: SplitSink<WebSocketStream<BoxedConnection>, WsMessage>,
+    ping_interval: Option<(Duration, SharedAsyncSleep)>,
+}
+
+impl fmt::Debug for WebSocketSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketSender")
+            .field(
+                "ping_interval",
+                &self.ping_interval.as_ref().map(|(i, _)| i),
+            )
+            .finish()
+    }
+}
+
+impl WebSocketSender {
+    /// Sends a single encoded event stream frame as a binary message.
+    pub async fn send(&mut self, frame: Bytes) -> Result<(), WebSocketError> {
+        self.sink
+            .send(WsMessage::Binary(frame.into()))
+            .await
+            .map_err(|err| WebSocketError::new(WebSocketErrorKind::Transport(err)))
+    }
+
+    /// Sends every frame yielded by `frames`.
+    ///
+    /// `frames` is usually the output of
+    /// [`EventStreamSender::into_body_stream`](crate::event_stream::EventStreamSender::into_body_stream),
+    /// which ends with an empty frame signalling the end of the input. The connection is left open
+    /// afterwards so that the peer can keep responding; it's up to the peer to close it, or to the
+    /// caller to call [`close`](WebSocketSender::close). If `frames` yields an error, the
+    /// connection is closed with an internal error status and the error is returned.
+    pub async fn send_all<S, E>(&mut self, frames: S) -> Result<(), WebSocketError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send,
+        E: Into<BoxError>,
+    {
+        let mut frames = std::pin::pin!(frames);
+        loop {
+            let next =
+                match &self.ping_interval {
+                    Some((interval, sleep_impl)) => {
+                        let sleep = sleep_impl.sleep(*interval);
+                        match future::select(frames.next(), sleep).await {
+                            Either::Left((next, _)) => next,
+                            Either::Right(_) => {
+                                trace!("sending WebSocket keepalive ping");
+                                self.sink.send(WsMessage::Ping(Vec::new())).await.map_err(
+                                    |err| WebSocketError::new(WebSocketErrorKind::Transport(err)),
+                                )?;
+                                continue;
+                            }
+                        }
+                    }
+                    None => frames.next().await,
+                };
+            match next {
+                Some(Ok(frame)) => self.send(frame).await?,
+                Some(Err(err)) => {
+                    let _ = self
+                        .close_with(CloseCode::Error, "event stream failed")
+                        .await;
+                    return Err(WebSocketError::new(WebSocketErrorKind::MessageStream(
+                        err.into(),
+                    )));
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Closes the connection normally.
+    pub async fn close(mut self) -> Result<(), WebSocketError> {
+        self.close_with(CloseCode::Normal, "").await
+    }
+
+    async fn close_with(
+        &mut self,
+        code: CloseCode,
+        reason: &'static str,
+    ) -> Result<(), WebSocketError> {
+        self.sink
+            .send(WsMessage::Close(Some(CloseFrame {
+                code,
+                reason: reason.into(),
+            })))
+            .await
+            .map_err(|err| WebSocketError::new(WebSocketErrorKind::Transport(err)))
+    }
+}
+
+/// The receiving half of a WebSocket event stream connection.
+///
+/// This is an HTTP body whose data chunks are the payloads of the binary messages received. It
+/// can be converted into an [`SdkBody`] and handed to a
+/// [`Receiver`](crate::event_stream::Receiver). The body ends when the peer closes the
+/// connection normally.
+pub struct WebSocketBody {
+    stream: SplitStream<WebSocketStream<BoxedConnection>>,
+    state: BodyState,
+}
+
+#[derive(Debug)]
+enum BodyState {
+    Open,
+    /// The peer sent a close frame; holds the error to report if it wasn't a normal closure.
+    Closing(Option<WebSocketError>),
+    Done,
+}
+
+impl fmt::Debug for WebSocketBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketBody")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl http_body_04x::Body for WebSocketBody {
+    type Data = Bytes;
+    type Error = WebSocketError;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.get_mut();
+        loop {
+            if matches!(this.state, BodyState::Done) {
+                return Poll::Ready(None);
+            }
+            let polled = match this.stream.poll_next_unpin(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(polled) => polled,
+            };
+            if let BodyState::Closing(_) = this.state {
+                // The peer's close frame has been received. Keep reading until the socket reports
+                // that it's closed, which flushes our reply to the close frame.
+                if let Some(Ok(_)) = polled {
+                    continue;
+                }
+                return match mem::replace(&mut this.state, BodyState::Done) {
+                    BodyState::Closing(error) => Poll::Ready(error.map(Err)),
+                    _ => unreachable!("state was just matched as closing"),
+                };
+            }
+            let message = match polled {
+                Some(Ok(message)) => message,
+                None | Some(Err(WsError::ConnectionClosed)) => {
+                    this.state = BodyState::Done;
+                    return Poll::Ready(None);
+                }
+                Some(Err(err)) => {
+                    this.state = BodyState::Done;
+                    return Poll::Ready(Some(Err(WebSocketError::new(
+                        WebSocketErrorKind::Transport(err),
+                    ))));
+                }
+            };
+            match message {
+                WsMessage::Binary(data) => return Poll::Ready(Some(Ok(data.into()))),
+                // Pings are answered by the socket the next time it's read from or written to.
+                WsMessage::Ping(_) | WsMessage::Pong(_) | WsMessage::Frame(_) => continue,
+                WsMessage::Text(_) => {
+                    this.state = BodyState::Done;
+                    return Poll::Ready(Some(Err(WebSocketError::new(
+                        WebSocketErrorKind::UnexpectedTextMessage,
+                    ))));
+                }
+                WsMessage::Close(frame) => {
+                    this.state = BodyState::Closing(match frame {
+                        Some(frame) if frame.code != CloseCode::Normal => {
+                            Some(WebSocketError::new(WebSocketErrorKind::ClosedByPeer {
+                                code: frame.code.into(),
+                                reason: frame.reason.into_owned(),
+                            }))
+                        }
+                        _ => None,
+                    });
+                }
+            }
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http_02x::HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        matches!(self.state, BodyState::Done)
+    }
+}
+
+impl From<WebSocketBody> for SdkBody {
+    fn from(body: WebSocketBody) -> Self {
+        SdkBody::from_body_0_4(body)
+    }
+}
+
+type Connecting = Pin<Box<dyn Future<Output = Result<BoxedConnection, BoxError>> + Send>>;
+type OpenConnectionFn = dyn Fn(http_02x::Uri) -> Connecting + Send + Sync;
+type Sending = Pin<Box<dyn Future<Output = Result<(), WebSocketError>> + Send + Sync>>;
+
+/// Sends the event streams of a client's operations over WebSocket connections.
+///
+/// Set on a client config, this replaces the HTTP client for operations with an event stream
+/// input. Every such operation opens a connection with the given function, performs the opening
+/// handshake with the signed request, sends the input stream over the connection and returns
+/// the messages received as the response body.
+///
+/// ```no_run
+/// use aws_smithy_http::event_stream::websocket::WebSocketTransport;
+///
+/// let transport = WebSocketTransport::new(|uri| async move {
+///     // Open a TLS stream to the handshake request's host
+///     # let _ = uri;
+///     # Ok::<_, aws_smithy_http::event_stream::BoxError>(tokio::io::empty())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct WebSocketTransport {
+    open_connection: Arc<OpenConnectionFn>,
+    config: WebSocketTransportConfig,
+}
+
+impl WebSocketTransport {
+    /// Creates a transport that opens connections with `open_connection`, which is given the URI
+    /// of the handshake request.
+    pub fn new<F, Fut, C>(open_connection: F) -> Self
+    where
+        F: Fn(http_02x::Uri) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<C, BoxError>> + Send + 'static,
+        C: Connection,
+    {
+        Self {
+            open_connection: Arc::new(move |uri| {
+                let connecting = open_connection(uri);
+                Box::pin(async move { Ok(Box::new(connecting.await?) as BoxedConnection) })
+            }),
+            config: WebSocketTransportConfig::new(),
+        }
+    }
+
+    /// Sets the config the connections are established with.
+    pub fn with_config(mut self, config: WebSocketTransportConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl fmt::Debug for WebSocketTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketTransport")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl Storable for WebSocketTransport {
+    type Storer = StoreReplace<Self>;
+}
+
+impl HttpClient for WebSocketTransport {
+    fn http_connector(
+        &self,
+        settings: &HttpConnectorSettings,
+        components: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        SharedHttpConnector::new(WebSocketConnector {
+            transport: self.clone(),
+            connect_timeout: settings.connect_timeout().zip(components.sleep_impl()),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct WebSocketConnector {
+    transport: WebSocketTransport,
+    connect_timeout: Option<(Duration, SharedAsyncSleep)>,
+}
+
+impl HttpConnector for WebSocketConnector {
+    fn call(&self, mut request: HttpRequest) -> HttpConnectorFuture {
+        let transport = self.transport.clone();
+        let connect_timeout = self.connect_timeout.clone();
+        HttpConnectorFuture::new(async move {
+            let frames = FuturesStreamCompatByteStream::new(ByteStream::new(request.take_body()));
+            let handshake = request
+                .try_into_http02x()
+                .map_err(|err| ConnectorError::other(err.into(), None))?
+                .map(|_| ());
+            let connecting = async {
+                let connection = (transport.open_connection)(handshake.uri().clone())
+                    .await
+                    .map_err(ConnectorError::io)?;
+                connect(handshake, connection, transport.config)
+                    .await
+                    .map_err(|err| ConnectorError::other(err.into(), None))
+            };
+            let (mut sender, body) = match connect_timeout {
+                Some((timeout, sleep_impl)) => Timeout::new(connecting, sleep_impl.sleep(timeout))
+                    .await
+                    .map_err(|err| ConnectorError::timeout(err.into()))??,
+                None => connecting.await?,
+            };
+            let body = DuplexBody {
+                sending: Some(Box::pin(async move { sender.send_all(frames).await })),
+                body,
+            };
+            Ok(HttpResponse::new(
+                200.try_into().expect("valid status code"),
+                SdkBody::from_body_0_4(body),
+            ))
+        })
+    }
+}
+
+/// The response body of an operation sent over a WebSocket: sends the input stream while the
+/// messages received are read.
+struct DuplexBody {
+    sending: Option<Sending>,
+    body: WebSocketBody,
+}
+
+impl http_body_04x::Body for DuplexBody {
+    type Data = Bytes;
+    type Error = WebSocketError;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.get_mut();
+        if let Some(sending) = &mut this.sending {
+            if let Poll::Ready(sent) = sending.as_mut().poll(cx) {
+                this.sending = None;
+                if let Err(err) = sent {
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+        }
+        Pin::new(&mut this.body).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http_02x::HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+}
+
+/// Marks an operation whose request is the opening handshake of a WebSocket connection.
+///
+/// Signers that support the WebSocket transport sign such requests in the query string, since
+/// headers don't survive the upgrade.
+#[derive(Clone, Debug)]
+pub struct WebSocketUpgrade;
+
+impl Storable for WebSocketUpgrade {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Turns the request into the `GET` request of the opening handshake before it's signed.
+#[derive(Debug)]
+struct WebSocketHandshakeInterceptor;
+
+impl Intercept for WebSocketHandshakeInterceptor {
+    fn name(&self) -> &'static str {
+        "WebSocketHandshakeInterceptor"
+    }
+
+    fn modify_before_signing(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let request = context.request_mut();
+        let mut handshake = mem::replace(request, HttpRequest::empty()).try_into_http02x()?;
+        *handshake.method_mut() = http_02x::Method::GET;
+        *request = handshake.try_into()?;
+        Ok(())
+    }
+}
+
+/// Runtime plugin that sends an operation's event stream over a [`WebSocketTransport`].
+///
+/// Generated clients register it for operations with an event stream input, with the transport
+/// set on the client config, if any. Without a transport, it does nothing.
+#[derive(Debug)]
+pub struct WebSocketRuntimePlugin {
+    config: Option<FrozenLayer>,
+    runtime_components: RuntimeComponentsBuilder,
+}
+
+impl WebSocketRuntimePlugin {
+    /// Creates a runtime plugin that sends the operation over `transport`, if it's set.
+    pub fn new(transport: Option<WebSocketTransport>) -> Self {
+        let runtime_components = RuntimeComponentsBuilder::new("WebSocketRuntimePlugin");
+        match transport {
+            Some(transport) => {
+                let mut layer = Layer::new("WebSocketRuntimePlugin");
+                layer.store_put(WebSocketUpgrade);
+                Self {
+                    config: Some(layer.freeze()),
+                    runtime_components: runtime_components
+                        .with_http_client(Some(transport))
+                        .with_interceptor(WebSocketHandshakeInterceptor),
+                }
+            }
+            None => Self {
+                config: None,
+                runtime_components,
+            },
+        }
+    }
+}
+
+impl RuntimePlugin for WebSocketRuntimePlugin {
+    fn config(&self) -> Option<FrozenLayer> {
+        self.config.clone()
+    }
+
+    fn runtime_components(
+        &self,
+        _current_components: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Borrowed(&self.runtime_components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{connect, WebSocketConnector, WebSocketTransport, WebSocketTransportConfig};
+    use crate::event_stream::{EventStreamSender, Receiver};
+    use aws_smithy_async::rt::sleep::{SharedAsyncSleep, TokioSleep};
+    use aws_smithy_eventstream::error::Error as EventStreamError;
+    use aws_smithy_eventstream::frame::{
+        read_message_from, write_message_to, MarshallMessage, NoOpSigner, UnmarshallMessage,
+        UnmarshalledMessage,
+    };
+    use aws_smithy_runtime_api::client::http::HttpConnector;
+    use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::event_stream::Message;
+    use bytes::Bytes;
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    #[derive(Debug)]
+    struct Marshaller;
+    impl MarshallMessage for Marshaller {
+        type Input = String;
+
+        fn marshall(&self, input: Self::Input) -> Result<Message, EventStreamError> {
+            Ok(Message::new(input.into_bytes()))
+        }
+    }
+
+    #[derive(Debug)]
+    struct ErrorMarshaller;
+    impl MarshallMessage for ErrorMarshaller {
+        type Input = std::io::Error;
+
+        fn marshall(&self, _input: Self::Input) -> Result<Message, EventStreamError> {
+            Ok(Message::new(&b"error"[..]))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Unmarshaller;
+    impl UnmarshallMessage for Unmarshaller {
+        type Output = String;
+        type Error = EventStreamError;
+
+        fn unmarshall(
+            &self,
+            message: &Message,
+        ) -> Result<UnmarshalledMessage<Self::Output, Self::Error>, EventStreamError> {
+            Ok(UnmarshalledMessage::Event(
+                String::from_utf8(message.payload().to_vec()).unwrap(),
+            ))
+        }
+    }
+
+    fn frame(payload: &str) -> WsMessage {
+        let mut buffer = Vec::new();
+        write_message_to(&Message::new(payload.as_bytes().to_vec()), &mut buffer).unwrap();
+        WsMessage::Binary(buffer)
+    }
+
+    fn request() -> http_02x::Request<()> {
+        http_02x::Request::get("wss://example.com/stream?X-Amz-Signature=abc")
+            .body(())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_trip_through_sender_and_receiver() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            let mut socket = tokio_tungstenite::accept_async(server_io).await.unwrap();
+            let mut received = Vec::new();
+            while received.len() < 2 {
+                match socket.next().await.unwrap().unwrap() {
+                    WsMessage::Binary(data) => {
+                        let message = read_message_from(Bytes::from(data)).unwrap();
+                        received.push(String::from_utf8(message.payload().to_vec()).unwrap());
+                    }
+                    other => panic!("unexpected message: {other:?}"),
+                }
+            }
+            socket.send(WsMessage::Ping(b"hi".to_vec())).await.unwrap();
+            socket.send(frame("three")).await.unwrap();
+            socket.send(frame("four")).await.unwrap();
+            socket.close(None).await.unwrap();
+            // The ping is answered while the client reads.
+            let mut pong = false;
+            while let Some(Ok(message)) = socket.next().await {
+                pong |= matches!(message, WsMessage::Pong(ref data) if data == b"hi");
+            }
+            (received, pong)
+        });
+
+        let (mut sender, body) = connect(request(), client_io, WebSocketTransportConfig::new())
+            .await
+            .unwrap();
+        let events: EventStreamSender<String, std::io::Error> =
+            futures_util::stream::iter(vec![Ok("one".to_string()), Ok("two".to_string())]).into();
+        sender
+            .send_all(events.into_body_stream(Marshaller, ErrorMarshaller, NoOpSigner {}))
+            .await
+            .unwrap();
+
+        let mut receiver = Receiver::<String, EventStreamError>::new(Unmarshaller, body.into());
+        assert_eq!(receiver.recv().await.unwrap(), Some("three".to_string()));
+        assert_eq!(receiver.recv().await.unwrap(), Some("four".to_string()));
+        assert_eq!(receiver.recv().await.unwrap(), None);
+
+        let (received, pong) = server.await.unwrap();
+        assert_eq!(received, vec!["one", "two"]);
+        assert!(pong);
+    }
+
+    #[tokio::test]
+    async fn abnormal_close_is_an_error() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let mut socket = tokio_tungstenite::accept_async(server_io).await.unwrap();
+            socket
+                .close(Some(CloseFrame {
+                    code: CloseCode::Policy,
+                    reason: "expired".into(),
+                }))
+                .await
+                .unwrap();
+            while socket.next().await.is_some() {}
+        });
+
+        let (_sender, mut body) = connect(request(), client_io, WebSocketTransportConfig::new())
+            .await
+            .unwrap();
+        let err = http_body_04x::Body::data(&mut body)
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.close_reason(), Some((1008, "expired")));
+    }
+
+    #[tokio::test]
+    async fn idle_sender_sends_keepalive_pings() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            let mut socket = tokio_tungstenite::accept_async(server_io).await.unwrap();
+            let mut pings = 0;
+            while let Some(Ok(message)) = socket.next().await {
+                match message {
+                    WsMessage::Ping(_) => pings += 1,
+                    WsMessage::Close(_) => break,
+                    _ => {}
+                }
+            }
+            pings
+        });
+
+        let config = WebSocketTransportConfig::new().with_ping_interval(
+            Duration::from_millis(5),
+            SharedAsyncSleep::new(TokioSleep::new()),
+        );
+        let (mut sender, _body) = connect(request(), client_io, config).await.unwrap();
+        let frames = async_stream::stream! {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            yield Ok::<_, std::io::Error>(Bytes::from_static(b"late"));
+        };
+        sender.send_all(frames).await.unwrap();
+        sender.close().await.unwrap();
+        assert!(server.await.unwrap() >= 2);
+    }
+
+    #[tokio::test]
+    async fn handshake_failure() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let mut server_io = server_io;
+            let mut buf = [0; 1024];
+            let _ = server_io.read(&mut buf).await;
+            server_io
+                .write_all(b"HTTP/1.1 403 Forbidden\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+        let err = connect(request(), client_io, WebSocketTransportConfig::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "WebSocket opening handshake failed");
+    }
+
+    #[tokio::test]
+    async fn transport_sends_request_body_and_returns_messages() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            let mut socket = tokio_tungstenite::accept_async(server_io).await.unwrap();
+            let received = match socket.next().await.unwrap().unwrap() {
+                WsMessage::Binary(data) => read_message_from(Bytes::from(data)).unwrap(),
+                other => panic!("unexpected message: {other:?}"),
+            };
+            socket.send(frame("reply")).await.unwrap();
+            socket.close(None).await.unwrap();
+            while socket.next().await.is_some() {}
+            String::from_utf8(received.payload().to_vec()).unwrap()
+        });
+
+        let client_io = std::sync::Mutex::new(Some(client_io));
+        let transport = WebSocketTransport::new(move |uri| {
+            assert_eq!(uri.host(), Some("example.com"));
+            let connection = client_io.lock().unwrap().take().unwrap();
+            async move { Ok(connection) }
+        });
+        let connector = WebSocketConnector {
+            transport,
+            connect_timeout: None,
+        };
+        let mut buffer = Vec::new();
+        write_message_to(&Message::new(&b"hello"[..]), &mut buffer).unwrap();
+        let request = HttpRequest::try_from(request().map(|_| SdkBody::from(buffer))).unwrap();
+        let response = connector.call(request).await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+
+        let mut receiver =
+            Receiver::<String, EventStreamError>::new(Unmarshaller, response.into_body());
+        assert_eq!(receiver.recv().await.unwrap(), Some("reply".to_string()));
+        assert_eq!(receiver.recv().await.unwrap(), None);
+        assert_eq!(server.await.unwrap(), "hello");
+    }
+}
@@ -23,6 +23,7 @@
 //! |----------------|-------------|
 //! | `rt-tokio`     | Provides features that are dependent on `tokio` including the `ByteStream::from_path` util |
 //! | `event-stream` | Provides Sender/Receiver implementations for Event Stream codegen. |
+//! | `event-stream-websocket` | Provides a WebSocket transport for event streams. |
 
 #![allow(clippy::derive_partial_eq_without_eq)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
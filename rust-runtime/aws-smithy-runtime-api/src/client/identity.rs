@@ -9,11 +9,12 @@ use crate::client::runtime_components::{RuntimeComponents, RuntimeComponentsBuil
 use crate::impl_shared_conversions;
 use aws_smithy_types::config_bag::ConfigBag;
 use std::any::Any;
+use std::borrow::Cow;
 use std::fmt;
 use std::fmt::Debug;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 #[cfg(feature = "http-auth")]
 pub mod http;
@@ -82,8 +83,133 @@ pub trait ResolveCachedIdentity: fmt::Debug + Send + Sync {
         let _ = (runtime_components, cfg);
         Ok(())
     }
+
+    /// Returns a summary of the identities currently held by this cache.
+    ///
+    /// The summaries don't include the identity data, so they are safe to log.
+    /// Caches that don't support introspection return an empty list.
+    fn cached_identities(&self) -> Vec<IdentityCacheEntry> {
+        Vec::new()
+    }
+
+    /// Removes the cached identity for the given partition, if any.
+    ///
+    /// The next request that uses the partition will resolve a new identity.
+    fn invalidate(&self, partition: IdentityCachePartition) {
+        let _ = partition;
+    }
+}
+
+/// Summary of an identity held by an identity cache.
+///
+/// See [`ResolveCachedIdentity::cached_identities`].
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IdentityCacheEntry {
+    partition: IdentityCachePartition,
+    expiration: Option<SystemTime>,
+    provider_name: Option<Cow<'static, str>>,
+}
+
+impl IdentityCacheEntry {
+    /// Creates a summary of the given identity cached in the given partition.
+    pub fn new(partition: IdentityCachePartition, identity: &Identity) -> Self {
+        Self {
+            partition,
+            expiration: identity.expiration(),
+            provider_name: identity.provider_name.clone(),
+        }
+    }
+
+    /// Returns the cache partition the identity is stored in.
+    pub fn partition(&self) -> IdentityCachePartition {
+        self.partition
+    }
+
+    /// Returns the expiration time of the identity, if any.
+    pub fn expiration(&self) -> Option<SystemTime> {
+        self.expiration
+    }
+
+    /// Returns the name of the provider that resolved the identity, if known.
+    pub fn provider_name(&self) -> Option<&str> {
+        self.provider_name.as_deref()
+    }
 }
 
+/// Event reported by an identity cache to an [`ObserveIdentityCache`] implementation.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum IdentityCacheEvent<'a> {
+    /// The cache started resolving an identity because none was cached, or because the cached
+    /// one is about to expire.
+    LoadStarted {
+        /// Partition the identity is being loaded into
+        partition: IdentityCachePartition,
+    },
+    /// The cache finished resolving an identity.
+    LoadFinished {
+        /// Partition the identity was loaded into
+        partition: IdentityCachePartition,
+        /// The resolved identity, or the reason resolving it failed
+        result: Result<&'a Identity, &'a BoxError>,
+        /// How long resolving the identity took
+        elapsed: Duration,
+    },
+    /// A cached identity was returned without resolving a new one.
+    CacheHit {
+        /// Partition the identity was found in
+        partition: IdentityCachePartition,
+        /// Expiration time of the returned identity
+        expiration: Option<SystemTime>,
+    },
+    /// A partition was removed from the cache with [`ResolveCachedIdentity::invalidate`].
+    Invalidated {
+        /// The invalidated partition
+        partition: IdentityCachePartition,
+    },
+}
+
+impl IdentityCacheEvent<'_> {
+    /// Returns the cache partition this event applies to.
+    pub fn partition(&self) -> IdentityCachePartition {
+        match self {
+            Self::LoadStarted { partition }
+            | Self::LoadFinished { partition, .. }
+            | Self::CacheHit { partition, .. }
+            | Self::Invalidated { partition } => *partition,
+        }
+    }
+}
+
+/// Observer for events reported by an identity cache.
+///
+/// Observers are called synchronously while the identity is being resolved, so they should
+/// return quickly and must not block.
+pub trait ObserveIdentityCache: Send + Sync + Debug {
+    /// Called for every event reported by the identity cache.
+    fn on_event(&self, event: &IdentityCacheEvent<'_>);
+}
+
+/// Shared identity cache observer.
+#[derive(Clone, Debug)]
+pub struct SharedIdentityCacheObserver(Arc<dyn ObserveIdentityCache>);
+
+impl SharedIdentityCacheObserver {
+    /// Creates a new [`SharedIdentityCacheObserver`] from the given observer.
+    pub fn new(observer: impl ObserveIdentityCache + 'static) -> Self {
+        Self(Arc::new(observer))
+    }
+}
+
+impl ObserveIdentityCache for SharedIdentityCacheObserver {
+    fn on_event(&self, event: &IdentityCacheEvent<'_>) {
+        self.0.on_event(event)
+    }
+}
+
+impl_shared_conversions!(convert SharedIdentityCacheObserver from ObserveIdentityCache using SharedIdentityCacheObserver::new);
+
 /// Shared identity cache.
 #[derive(Clone, Debug)]
 pub struct SharedIdentityCache(Arc<dyn ResolveCachedIdentity>);
@@ -105,6 +231,14 @@ impl ResolveCachedIdentity for SharedIdentityCache {
         self.0
             .resolve_cached_identity(resolver, runtime_components, config_bag)
     }
+
+    fn cached_identities(&self) -> Vec<IdentityCacheEntry> {
+        self.0.cached_identities()
+    }
+
+    fn invalidate(&self, partition: IdentityCachePartition) {
+        self.0.invalidate(partition)
+    }
 }
 
 impl ValidateConfig for SharedIdentityResolver {}
@@ -261,6 +395,7 @@ pub struct Identity {
     #[allow(clippy::type_complexity)]
     data_debug: Arc<dyn (Fn(&Arc<dyn Any + Send + Sync>) -> &dyn Debug) + Send + Sync>,
     expiration: Option<SystemTime>,
+    provider_name: Option<Cow<'static, str>>,
 }
 
 impl Identity {
@@ -273,9 +408,18 @@ impl Identity {
             data: Arc::new(data),
             data_debug: Arc::new(|d| d.downcast_ref::<T>().expect("type-checked") as _),
             expiration,
+            provider_name: None,
         }
     }
 
+    /// Sets the name of the provider that resolved this identity.
+    ///
+    /// This is reported by identity caches, for example in [`IdentityCacheEntry`].
+    pub fn with_provider_name(mut self, provider_name: impl Into<Cow<'static, str>>) -> Self {
+        self.provider_name = Some(provider_name.into());
+        self
+    }
+
     /// Returns the raw identity data.
     pub fn data<T: Any + Debug + Send + Sync + 'static>(&self) -> Option<&T> {
         self.data.downcast_ref()
@@ -285,6 +429,11 @@ impl Identity {
     pub fn expiration(&self) -> Option<SystemTime> {
         self.expiration
     }
+
+    /// Returns the name of the provider that resolved this identity, if known.
+    pub fn provider_name(&self) -> Option<&str> {
+        self.provider_name.as_deref()
+    }
 }
 
 impl Debug for Identity {
@@ -292,6 +441,7 @@ impl Debug for Identity {
         f.debug_struct("Identity")
             .field("data", (self.data_debug)(&self.data))
             .field("expiration", &self.expiration)
+            .field("provider_name", &self.provider_name)
             .finish()
     }
 }
@@ -329,4 +479,16 @@ mod tests {
         assert_eq!("bar", identity.data::<MyIdentityData>().unwrap().last);
         assert_eq!(Some(expiration), identity.expiration());
     }
+
+    #[test]
+    fn cache_entry_omits_identity_data() {
+        let expiration = SystemTime::UNIX_EPOCH;
+        let identity = Identity::new("secret", Some(expiration)).with_provider_name("TestProvider");
+        let partition = IdentityCachePartition::new();
+        let entry = IdentityCacheEntry::new(partition, &identity);
+        assert_eq!(partition, entry.partition());
+        assert_eq!(Some(expiration), entry.expiration());
+        assert_eq!(Some("TestProvider"), entry.provider_name());
+        assert!(!format!("{entry:?}").contains("secret"));
+    }
 }
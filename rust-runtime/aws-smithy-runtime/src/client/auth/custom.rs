@@ -0,0 +1,420 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Closure-based custom auth schemes.
+//!
+//! Registering a custom auth scheme normally requires implementing [`AuthScheme`], [`Sign`], and
+//! [`ResolveIdentity`], and then wiring them together with a [`RuntimePlugin`]. The
+//! [`CustomAuthScheme`] builder does that wiring for schemes that can be expressed as a
+//! signing closure and an identity resolver.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use aws_smithy_runtime::client::auth::custom::CustomAuthScheme;
+//! use aws_smithy_runtime_api::client::auth::AuthSchemeId;
+//! use aws_smithy_runtime_api::client::identity::Identity;
+//! use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+//! # fn hmac_signature(key: &str, request: &HttpRequest) -> String { unimplemented!() }
+//!
+//! const PARTNER_HMAC: AuthSchemeId = AuthSchemeId::new("partner-hmac");
+//!
+//! #[derive(Debug)]
+//! struct PartnerKey(String);
+//!
+//! let plugin = CustomAuthScheme::builder(PARTNER_HMAC)
+//!     .identity_fn(|| async { Ok(Identity::new(PartnerKey("secret".into()), None)) })
+//!     .signer(|request, identity, _runtime_components, _config_bag| {
+//!         let key = identity.data::<PartnerKey>().expect("registered identity type");
+//!         let signature = hmac_signature(&key.0, request);
+//!         request.headers_mut().insert("x-partner-signature", signature);
+//!         Ok(())
+//!     })
+//!     .auth_scheme_order_for_operation("GetWidget", [PARTNER_HMAC])
+//!     .build();
+//!
+//! // Then register the plugin on a generated client's config:
+//! // let config = my_service::Config::builder().runtime_plugin(plugin).build();
+//! # let _ = plugin;
+//! ```
+
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::auth::{
+    AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, SharedAuthScheme, Sign,
+};
+use aws_smithy_runtime_api::client::identity::{
+    Identity, IdentityFuture, ResolveIdentity, SharedIdentityResolver,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, Metadata};
+use aws_smithy_runtime_api::client::runtime_components::{
+    GetIdentityResolver, RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer, Layer, Storable, StoreAppend};
+use std::borrow::Cow;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+type SignFn = dyn Fn(&mut HttpRequest, &Identity, &RuntimeComponents, &ConfigBag) -> Result<(), BoxError>
+    + Send
+    + Sync;
+
+/// An auth scheme that signs requests with a closure.
+///
+/// Use [`CustomAuthScheme::builder`] to create a [`CustomAuthRuntimePlugin`] that registers the
+/// scheme and its identity resolver with a client.
+#[derive(Clone, Debug)]
+pub struct CustomAuthScheme {
+    scheme_id: AuthSchemeId,
+    signer: FnSigner,
+}
+
+impl CustomAuthScheme {
+    /// Creates a new builder for a custom auth scheme with the given ID.
+    pub fn builder(scheme_id: AuthSchemeId) -> CustomAuthSchemeBuilder {
+        CustomAuthSchemeBuilder::new(scheme_id)
+    }
+}
+
+impl AuthScheme for CustomAuthScheme {
+    fn scheme_id(&self) -> AuthSchemeId {
+        self.scheme_id
+    }
+
+    fn identity_resolver(
+        &self,
+        identity_resolvers: &dyn GetIdentityResolver,
+    ) -> Option<SharedIdentityResolver> {
+        identity_resolvers.identity_resolver(self.scheme_id)
+    }
+
+    fn signer(&self) -> &dyn Sign {
+        &self.signer
+    }
+}
+
+#[derive(Clone)]
+struct FnSigner(Arc<SignFn>);
+
+impl fmt::Debug for FnSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FnSigner")
+    }
+}
+
+impl Sign for FnSigner {
+    fn sign_http_request(
+        &self,
+        request: &mut HttpRequest,
+        identity: &Identity,
+        _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        (self.0)(request, identity, runtime_components, config_bag)
+    }
+}
+
+struct FnIdentityResolver<F>(F);
+
+impl<F> fmt::Debug for FnIdentityResolver<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FnIdentityResolver")
+    }
+}
+
+impl<F, Fut> ResolveIdentity for FnIdentityResolver<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Identity, BoxError>> + Send + 'static,
+{
+    fn resolve_identity<'a>(
+        &'a self,
+        _runtime_components: &'a RuntimeComponents,
+        _config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        IdentityFuture::new((self.0)())
+    }
+}
+
+/// Builder for a custom auth scheme.
+///
+/// See the [module docs](crate::client::auth::custom) for an example.
+pub struct CustomAuthSchemeBuilder {
+    scheme_id: AuthSchemeId,
+    identity_resolver: Option<SharedIdentityResolver>,
+    signer: Option<Arc<SignFn>>,
+    order_overrides: Vec<AuthSchemeOrderOverride>,
+}
+
+impl fmt::Debug for CustomAuthSchemeBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomAuthSchemeBuilder")
+            .field("scheme_id", &self.scheme_id)
+            .field("identity_resolver", &self.identity_resolver)
+            .field("order_overrides", &self.order_overrides)
+            .finish()
+    }
+}
+
+impl CustomAuthSchemeBuilder {
+    fn new(scheme_id: AuthSchemeId) -> Self {
+        Self {
+            scheme_id,
+            identity_resolver: None,
+            signer: None,
+            order_overrides: Vec::new(),
+        }
+    }
+
+    /// Sets the identity resolver for this auth scheme.
+    ///
+    /// If no identity resolver is set, then one must be registered for this scheme's ID
+    /// elsewhere, or the scheme will be skipped during auth scheme selection.
+    pub fn identity_resolver(mut self, identity_resolver: impl ResolveIdentity + 'static) -> Self {
+        self.identity_resolver = Some(identity_resolver.into_shared());
+        self
+    }
+
+    /// Sets a closure that resolves the identity for this auth scheme.
+    ///
+    /// The resolved identity is cached by the client's identity cache until its expiration.
+    pub fn identity_fn<F, Fut>(self, identity_fn: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Identity, BoxError>> + Send + 'static,
+    {
+        self.identity_resolver(FnIdentityResolver(identity_fn))
+    }
+
+    /// Sets the closure that signs requests with the resolved identity.
+    pub fn signer<F>(mut self, signer: F) -> Self
+    where
+        F: Fn(&mut HttpRequest, &Identity, &RuntimeComponents, &ConfigBag) -> Result<(), BoxError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.signer = Some(Arc::new(signer));
+        self
+    }
+
+    /// Overrides the order of auth schemes tried for the given operation.
+    ///
+    /// This replaces the auth scheme options from the model for that operation, so the list
+    /// should include every scheme that should be tried, in order of preference. The operation
+    /// name is the one from the model, such as `GetWidget`.
+    pub fn auth_scheme_order_for_operation(
+        mut self,
+        operation: impl Into<Cow<'static, str>>,
+        order: impl IntoIterator<Item = AuthSchemeId>,
+    ) -> Self {
+        self.order_overrides.push(AuthSchemeOrderOverride {
+            operation: Some(operation.into()),
+            order: order.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Overrides the order of auth schemes tried for every operation.
+    ///
+    /// Overrides for specific operations take precedence over this.
+    pub fn auth_scheme_order(mut self, order: impl IntoIterator<Item = AuthSchemeId>) -> Self {
+        self.order_overrides.push(AuthSchemeOrderOverride {
+            operation: None,
+            order: order.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Builds a [`CustomAuthRuntimePlugin`] that registers this auth scheme.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if no signer was set.
+    pub fn build(self) -> CustomAuthRuntimePlugin {
+        let scheme = CustomAuthScheme {
+            scheme_id: self.scheme_id,
+            signer: FnSigner(
+                self.signer
+                    .expect("a signer is required to build a custom auth scheme"),
+            ),
+        };
+        let mut components = RuntimeComponentsBuilder::new("CustomAuthRuntimePlugin")
+            .with_auth_scheme(SharedAuthScheme::new(scheme));
+        if let Some(identity_resolver) = self.identity_resolver {
+            components = components.with_identity_resolver(self.scheme_id, identity_resolver);
+        }
+        let config = if self.order_overrides.is_empty() {
+            None
+        } else {
+            let mut layer = Layer::new("CustomAuthRuntimePlugin");
+            for order_override in self.order_overrides {
+                layer.store_append(order_override);
+            }
+            Some(layer.freeze())
+        };
+        CustomAuthRuntimePlugin { components, config }
+    }
+}
+
+/// A [`RuntimePlugin`] that registers a [`CustomAuthScheme`] and its identity resolver.
+///
+/// Created with [`CustomAuthScheme::builder`].
+#[derive(Debug)]
+pub struct CustomAuthRuntimePlugin {
+    components: RuntimeComponentsBuilder,
+    config: Option<FrozenLayer>,
+}
+
+impl RuntimePlugin for CustomAuthRuntimePlugin {
+    fn config(&self) -> Option<FrozenLayer> {
+        self.config.clone()
+    }
+
+    fn runtime_components(
+        &self,
+        _: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Borrowed(&self.components)
+    }
+}
+
+/// Replaces the auth scheme options resolved from the model for one or all operations.
+#[derive(Clone, Debug)]
+pub(crate) struct AuthSchemeOrderOverride {
+    operation: Option<Cow<'static, str>>,
+    order: Vec<AuthSchemeId>,
+}
+
+impl Storable for AuthSchemeOrderOverride {
+    type Storer = StoreAppend<Self>;
+}
+
+/// Returns the overridden auth scheme order for the current operation, if there is one.
+///
+/// Overrides for the specific operation win over overrides for all operations, and among
+/// those, the most recently configured override wins.
+pub(crate) fn auth_scheme_order_override(cfg: &ConfigBag) -> Option<&[AuthSchemeId]> {
+    let operation = cfg.load::<Metadata>().map(Metadata::name);
+    let mut for_all_operations = None;
+    for order_override in cfg.load::<AuthSchemeOrderOverride>() {
+        match order_override.operation.as_deref() {
+            Some(name) if Some(name) == operation => return Some(&order_override.order),
+            None if for_all_operations.is_none() => {
+                for_all_operations = Some(order_override.order.as_slice())
+            }
+            _ => {}
+        }
+    }
+    for_all_operations
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use aws_smithy_runtime_api::client::identity::ResolveCachedIdentity;
+    use aws_smithy_types::body::SdkBody;
+
+    const TEST_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("test-scheme");
+    const OTHER_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("other-scheme");
+
+    #[derive(Debug)]
+    struct ApiKey(&'static str);
+
+    fn plugin() -> CustomAuthRuntimePlugin {
+        CustomAuthScheme::builder(TEST_SCHEME_ID)
+            .identity_fn(|| async { Ok(Identity::new(ApiKey("secret"), None)) })
+            .signer(|request, identity, _, _| {
+                let key = identity.data::<ApiKey>().ok_or("unexpected identity")?;
+                request.headers_mut().insert("x-api-key", key.0);
+                Ok(())
+            })
+            .build()
+    }
+
+    #[tokio::test]
+    async fn registers_scheme_and_identity_resolver() {
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .merge_from(&plugin().runtime_components(&RuntimeComponentsBuilder::for_tests()))
+            .build()
+            .unwrap();
+        let cfg = ConfigBag::base();
+
+        let scheme = runtime_components
+            .auth_scheme(TEST_SCHEME_ID)
+            .expect("scheme registered");
+        let identity_resolver = scheme
+            .identity_resolver(&runtime_components)
+            .expect("identity resolver registered");
+        let identity = runtime_components
+            .identity_cache()
+            .resolve_cached_identity(identity_resolver, &runtime_components, &cfg)
+            .await
+            .unwrap();
+
+        let mut request = HttpRequest::new(SdkBody::empty());
+        scheme
+            .signer()
+            .sign_http_request(
+                &mut request,
+                &identity,
+                AuthSchemeEndpointConfig::empty(),
+                &runtime_components,
+                &cfg,
+            )
+            .unwrap();
+        assert_eq!(Some("secret"), request.headers().get("x-api-key"));
+    }
+
+    #[test]
+    fn signer_errors_are_returned() {
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .merge_from(&plugin().runtime_components(&RuntimeComponentsBuilder::for_tests()))
+            .build()
+            .unwrap();
+        let scheme = runtime_components.auth_scheme(TEST_SCHEME_ID).unwrap();
+        let err = scheme
+            .signer()
+            .sign_http_request(
+                &mut HttpRequest::new(SdkBody::empty()),
+                &Identity::new("wrong type", None),
+                AuthSchemeEndpointConfig::empty(),
+                &runtime_components,
+                &ConfigBag::base(),
+            )
+            .expect_err("wrong identity type");
+        assert_eq!("unexpected identity", err.to_string());
+    }
+
+    #[test]
+    fn resolves_auth_scheme_order_overrides() {
+        let plugin = CustomAuthScheme::builder(TEST_SCHEME_ID)
+            .signer(|_, _, _, _| Ok(()))
+            .auth_scheme_order([OTHER_SCHEME_ID])
+            .auth_scheme_order_for_operation("GetWidget", [TEST_SCHEME_ID, OTHER_SCHEME_ID])
+            .build();
+        let config_for = |operation: &'static str| {
+            let mut layer = Layer::new("operation");
+            layer.store_put(Metadata::new(operation, "test-service"));
+            let mut cfg = ConfigBag::base();
+            cfg.push_shared_layer(plugin.config().unwrap())
+                .push_layer(layer);
+            cfg
+        };
+
+        assert_eq!(
+            Some(&[TEST_SCHEME_ID, OTHER_SCHEME_ID][..]),
+            auth_scheme_order_override(&config_for("GetWidget"))
+        );
+        assert_eq!(
+            Some(&[OTHER_SCHEME_ID][..]),
+            auth_scheme_order_override(&config_for("PutWidget"))
+        );
+        assert_eq!(None, auth_scheme_order_override(&ConfigBag::base()));
+    }
+}
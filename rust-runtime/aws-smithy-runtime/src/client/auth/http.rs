@@ -16,10 +16,21 @@ use aws_smithy_runtime_api::client::auth::{
 };
 use aws_smithy_runtime_api::client::identity::http::{Login, Token};
 use aws_smithy_runtime_api::client::identity::{Identity, SharedIdentityResolver};
+use aws_smithy_runtime_api::client::interceptors::context::{
+    BeforeDeserializationInterceptorContextRef, BeforeTransmitInterceptorContextRef,
+    InterceptorContext,
+};
+use aws_smithy_runtime_api::client::interceptors::Intercept;
 use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+use aws_smithy_runtime_api::client::retries::classifiers::{ClassifyRetry, RetryAction};
 use aws_smithy_runtime_api::client::runtime_components::{GetIdentityResolver, RuntimeComponents};
 use aws_smithy_types::base64::encode;
-use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use aws_smithy_types::retry::ErrorKind;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Destination for the API key
 #[derive(Copy, Clone, Debug)]
@@ -231,6 +242,17 @@ impl Sign for BearerAuthSigner {
 }
 
 /// Auth implementation for Smithy's `@httpDigestAuth` auth scheme
+///
+/// Digest auth ([RFC 7616](https://datatracker.ietf.org/doc/html/rfc7616)) is a challenge-response
+/// scheme: the first request is sent without credentials, and the server answers with a `401`
+/// carrying a `WWW-Authenticate: Digest ...` challenge. The challenge is remembered by the scheme
+/// and used to sign subsequent requests, tracking the nonce count as it goes.
+///
+/// For the challenge to be picked up and the rejected request to be retried, the scheme's
+/// [`interceptor`](DigestAuthScheme::interceptor) and
+/// [`retry_classifier`](DigestAuthScheme::retry_classifier) must be registered in the same
+/// runtime components as the scheme itself. Note that the retry counts towards the configured
+/// max attempts, so digest auth requires at least two attempts to succeed on a fresh challenge.
 #[derive(Debug, Default)]
 pub struct DigestAuthScheme {
     signer: DigestAuthSigner,
@@ -239,8 +261,20 @@ pub struct DigestAuthScheme {
 impl DigestAuthScheme {
     /// Creates a new `DigestAuthScheme`.
     pub fn new() -> Self {
-        Self {
-            signer: DigestAuthSigner,
+        Self::default()
+    }
+
+    /// Returns an interceptor that records challenges returned by the server for this scheme.
+    pub fn interceptor(&self) -> DigestAuthInterceptor {
+        DigestAuthInterceptor {
+            state: self.signer.state.clone(),
+        }
+    }
+
+    /// Returns a retry classifier that retries requests rejected with a fresh challenge for this scheme.
+    pub fn retry_classifier(&self) -> DigestAuthRetryClassifier {
+        DigestAuthRetryClassifier {
+            state: self.signer.state.clone(),
         }
     }
 }
@@ -262,21 +296,500 @@ impl AuthScheme for DigestAuthScheme {
     }
 }
 
+/// The maximum number of challenges that can be waiting for their retry at the same time
+const MAX_PENDING_RETRIES: usize = 16;
+
+/// Digest auth state shared between the signer, the interceptor, and the retry classifier
+#[derive(Debug, Default)]
+struct DigestAuthState {
+    /// The most recent challenge received from the server
+    challenge: Option<DigestChallenge>,
+    /// The number of requests signed with the current challenge's nonce
+    nonce_count: u32,
+    /// Nonces of challenges whose `401` response should be retried
+    pending_retries: VecDeque<String>,
+}
+
+impl DigestAuthState {
+    fn set_challenge(&mut self, challenge: DigestChallenge) {
+        self.challenge = Some(challenge);
+        self.nonce_count = 0;
+    }
+
+    fn retry_with(&mut self, nonce: &str) {
+        if !self.pending_retries.iter().any(|pending| pending == nonce) {
+            if self.pending_retries.len() == MAX_PENDING_RETRIES {
+                self.pending_retries.pop_front();
+            }
+            self.pending_retries.push_back(nonce.to_string());
+        }
+    }
+}
+
+type SharedDigestAuthState = Arc<Mutex<DigestAuthState>>;
+
 #[derive(Debug, Default)]
-struct DigestAuthSigner;
+struct DigestAuthSigner {
+    state: SharedDigestAuthState,
+}
+
+impl DigestAuthSigner {
+    fn authorization(&self, login: &Login, method: &str, uri: &str) -> Option<String> {
+        let (challenge, nonce_count) = {
+            let mut state = self.state.lock().unwrap();
+            let challenge = state.challenge.clone()?;
+            state.nonce_count = state.nonce_count.wrapping_add(1);
+            state
+                .pending_retries
+                .retain(|pending| pending != &challenge.nonce);
+            (challenge, state.nonce_count)
+        };
+        Some(challenge.authorization(login, method, uri, nonce_count, &new_cnonce()))
+    }
+}
 
 impl Sign for DigestAuthSigner {
     fn sign_http_request(
         &self,
-        _request: &mut HttpRequest,
-        _identity: &Identity,
+        request: &mut HttpRequest,
+        identity: &Identity,
         _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
         _runtime_components: &RuntimeComponents,
         _config_bag: &ConfigBag,
     ) -> Result<(), BoxError> {
-        unimplemented!(
-            "support for signing with Smithy's `@httpDigestAuth` auth scheme is not implemented yet"
-        )
+        let login = identity
+            .data::<Login>()
+            .ok_or("HTTP digest auth requires a `Login` identity")?;
+        let uri: http_02x::Uri = request.uri().parse()?;
+        let uri = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+        // Until the server has sent a challenge, the request goes out without credentials.
+        // The `401` it is answered with carries the challenge, and the request is retried.
+        if let Some(authorization) = self.authorization(login, request.method(), uri) {
+            request.headers_mut().insert(
+                http_02x::header::AUTHORIZATION,
+                http_02x::HeaderValue::from_str(&authorization).map_err(|_| {
+                    "Login contains characters that can't be included in a HTTP header"
+                })?,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Hash algorithms supported by digest auth, from weakest to strongest
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum DigestAlgorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl DigestAlgorithm {
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::Md5, Self::Md5Sess, Self::Sha256, Self::Sha256Sess]
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(name))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Md5Sess => "MD5-sess",
+            Self::Sha256 => "SHA-256",
+            Self::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_session(self) -> bool {
+        matches!(self, Self::Md5Sess | Self::Sha256Sess)
+    }
+
+    fn hash(self, data: &str) -> String {
+        use md5::Digest;
+        let digest = match self {
+            Self::Md5 | Self::Md5Sess => md5::Md5::digest(data).to_vec(),
+            Self::Sha256 | Self::Sha256Sess => sha2::Sha256::digest(data).to_vec(),
+        };
+        hex_encode(&digest)
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+fn new_cnonce() -> String {
+    format!("{:032x}", fastrand::u128(..))
+}
+
+/// A `Digest` challenge from a `WWW-Authenticate` header
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: DigestAlgorithm,
+    /// Whether the server offered `qop=auth`. When it didn't, the legacy RFC 2069 response is used.
+    qop_auth: bool,
+    stale: bool,
+    userhash: bool,
+}
+
+impl DigestChallenge {
+    /// Builds a challenge from its parameters, returning `None` if it can't be answered
+    fn from_params(params: &[(String, String)]) -> Option<Self> {
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+        let flag = |name: &str| param(name).map_or(false, |v| v.eq_ignore_ascii_case("true"));
+        let algorithm = match param("algorithm") {
+            Some(name) => DigestAlgorithm::from_name(name)?,
+            None => DigestAlgorithm::Md5,
+        };
+        let qop_auth = match param("qop") {
+            Some(qop) => {
+                // `auth-int` isn't supported, so a challenge that only offers it can't be answered
+                if !qop
+                    .split(',')
+                    .any(|q| q.trim().eq_ignore_ascii_case("auth"))
+                {
+                    return None;
+                }
+                true
+            }
+            None => false,
+        };
+        // Session algorithms mix in a client nonce, which requires a `qop`
+        if algorithm.is_session() && !qop_auth {
+            return None;
+        }
+        Some(Self {
+            realm: param("realm")?.to_string(),
+            nonce: param("nonce")?.to_string(),
+            opaque: param("opaque").map(str::to_string),
+            algorithm,
+            qop_auth,
+            stale: flag("stale"),
+            userhash: flag("userhash"),
+        })
+    }
+
+    /// Returns the strongest `Digest` challenge in the given `WWW-Authenticate` header values
+    fn select<'a>(headers: impl Iterator<Item = &'a str>) -> Option<Self> {
+        headers
+            .flat_map(parse_challenges)
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("digest"))
+            .filter_map(|(_, params)| Self::from_params(&params))
+            .max_by_key(|challenge| challenge.algorithm)
+    }
+
+    /// Computes the `Authorization` header value for a request
+    fn authorization(
+        &self,
+        login: &Login,
+        method: &str,
+        uri: &str,
+        nonce_count: u32,
+        cnonce: &str,
+    ) -> String {
+        let algorithm = self.algorithm;
+        let mut ha1 = algorithm.hash(&format!(
+            "{}:{}:{}",
+            login.user(),
+            self.realm,
+            login.password()
+        ));
+        if algorithm.is_session() {
+            ha1 = algorithm.hash(&format!("{ha1}:{}:{cnonce}", self.nonce));
+        }
+        let ha2 = algorithm.hash(&format!("{method}:{uri}"));
+        let nc = format!("{nonce_count:08x}");
+        let response = if self.qop_auth {
+            algorithm.hash(&format!("{ha1}:{}:{nc}:{cnonce}:auth:{ha2}", self.nonce))
+        } else {
+            algorithm.hash(&format!("{ha1}:{}:{ha2}", self.nonce))
+        };
+
+        let username = if self.userhash {
+            algorithm.hash(&format!("{}:{}", login.user(), self.realm))
+        } else {
+            login.user().to_string()
+        };
+        let mut out = format!(
+            "Digest username={}, realm={}, uri={}, algorithm={}, nonce={}",
+            quote(&username),
+            quote(&self.realm),
+            quote(uri),
+            algorithm.name(),
+            quote(&self.nonce),
+        );
+        if self.qop_auth {
+            let _ = write!(out, ", nc={nc}, cnonce={}, qop=auth", quote(cnonce));
+        }
+        let _ = write!(out, ", response={}", quote(&response));
+        if let Some(opaque) = &self.opaque {
+            let _ = write!(out, ", opaque={}", quote(opaque));
+        }
+        if self.userhash {
+            out.push_str(", userhash=true");
+        }
+        out
+    }
+}
+
+fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+/// Parses the challenges in a `WWW-Authenticate` header value into their scheme and parameters
+///
+/// A single header value may hold several comma-separated challenges, each made up of a scheme
+/// followed by either a token68 or a list of `name=value` parameters.
+fn parse_challenges(header: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut cursor = ChallengeCursor {
+        input: header,
+        pos: 0,
+    };
+    let mut challenges: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    loop {
+        cursor.skip(|b| b == b' ' || b == b'\t' || b == b',');
+        if cursor.is_done() {
+            break;
+        }
+        let scheme = cursor.token();
+        if scheme.is_empty() {
+            // Not something we understand (e.g. a token68 for another scheme), so skip over it
+            cursor.pos += 1;
+            continue;
+        }
+        let mut params = Vec::new();
+        loop {
+            cursor.skip(|b| b == b' ' || b == b'\t');
+            let start = cursor.pos;
+            let name = cursor.token();
+            cursor.skip(|b| b == b' ' || b == b'\t');
+            if name.is_empty() || !cursor.eat(b'=') {
+                // This is the start of the next challenge
+                cursor.pos = start;
+                break;
+            }
+            cursor.skip(|b| b == b' ' || b == b'\t');
+            let value = if cursor.peek() == Some(b'"') {
+                cursor.quoted_string()
+            } else {
+                cursor.token().to_string()
+            };
+            params.push((name.to_string(), value));
+            cursor.skip(|b| b == b' ' || b == b'\t');
+            if !cursor.eat(b',') {
+                break;
+            }
+        }
+        challenges.push((scheme.to_string(), params));
+    }
+    challenges
+}
+
+struct ChallengeCursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> ChallengeCursor<'a> {
+    fn is_done(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let matches = self.peek() == Some(byte);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn skip(&mut self, predicate: impl Fn(u8) -> bool) {
+        while self.peek().map_or(false, &predicate) {
+            self.pos += 1;
+        }
+    }
+
+    fn token(&mut self) -> &'a str {
+        let start = self.pos;
+        self.skip(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+        &self.input[start..self.pos]
+    }
+
+    fn quoted_string(&mut self) -> String {
+        let mut value = Vec::new();
+        self.pos += 1; // opening quote
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    if let Some(escaped) = self.peek() {
+                        value.push(escaped);
+                        self.pos += 1;
+                    }
+                }
+                _ => value.push(byte),
+            }
+        }
+        String::from_utf8_lossy(&value).into_owned()
+    }
+}
+
+/// How the request of the current attempt was authenticated, as seen by the digest auth interceptor
+#[derive(Clone, Debug)]
+enum DigestAuthAttempt {
+    /// The request had no `Authorization` header
+    Unauthenticated {
+        /// Whether a challenge was already known when the request was sent. If it was, the
+        /// request wasn't signed by the digest signer and a new challenge won't change that.
+        challenge_known: bool,
+    },
+    /// The request was signed with a digest challenge
+    Digest,
+    /// The request was signed with another auth scheme
+    OtherScheme,
+}
+
+impl Storable for DigestAuthAttempt {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Interceptor that records the challenges a server returns for [`DigestAuthScheme`]
+///
+/// Created with [`DigestAuthScheme::interceptor`].
+#[derive(Debug)]
+pub struct DigestAuthInterceptor {
+    state: SharedDigestAuthState,
+}
+
+impl Intercept for DigestAuthInterceptor {
+    fn name(&self) -> &'static str {
+        "DigestAuthInterceptor"
+    }
+
+    fn read_before_transmit(
+        &self,
+        context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let attempt = match context.request().headers().get("authorization") {
+            None => DigestAuthAttempt::Unauthenticated {
+                challenge_known: self.state.lock().unwrap().challenge.is_some(),
+            },
+            Some(value) if value.starts_with("Digest ") => DigestAuthAttempt::Digest,
+            Some(_) => DigestAuthAttempt::OtherScheme,
+        };
+        cfg.interceptor_state().store_put(attempt);
+        Ok(())
+    }
+
+    fn read_before_deserialization(
+        &self,
+        context: &BeforeDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let retry_on_challenge = match cfg.load::<DigestAuthAttempt>() {
+            Some(DigestAuthAttempt::Unauthenticated { challenge_known }) => !challenge_known,
+            Some(DigestAuthAttempt::Digest) => false,
+            Some(DigestAuthAttempt::OtherScheme) | None => return Ok(()),
+        };
+        let response = context.response();
+        let mut state = self.state.lock().unwrap();
+        if response.status().as_u16() == 401 {
+            if let Some(challenge) =
+                DigestChallenge::select(response.headers().get_all("www-authenticate"))
+            {
+                // A stale nonce means the credentials were fine, so the request can be retried
+                // with the new nonce. Otherwise, a signed request was rejected because of its
+                // credentials, and retrying it won't help.
+                if retry_on_challenge || challenge.stale {
+                    state.retry_with(&challenge.nonce);
+                }
+                state.set_challenge(challenge);
+            }
+        } else if let Some(next_nonce) = response
+            .headers()
+            .get("authentication-info")
+            .and_then(|info| {
+                parse_challenges(&format!("Digest {info}"))
+                    .into_iter()
+                    .flat_map(|(_, params)| params)
+                    .find(|(name, _)| name.eq_ignore_ascii_case("nextnonce"))
+            })
+            .map(|(_, value)| value)
+        {
+            if let Some(mut challenge) = state.challenge.clone() {
+                challenge.nonce = next_nonce;
+                state.set_challenge(challenge);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Retry classifier that retries requests answered with a fresh challenge for [`DigestAuthScheme`]
+///
+/// Created with [`DigestAuthScheme::retry_classifier`].
+#[derive(Debug)]
+pub struct DigestAuthRetryClassifier {
+    state: SharedDigestAuthState,
+}
+
+impl ClassifyRetry for DigestAuthRetryClassifier {
+    fn classify_retry(&self, ctx: &InterceptorContext) -> RetryAction {
+        let Some(response) = ctx.response() else {
+            return RetryAction::NoActionIndicated;
+        };
+        if response.status().as_u16() != 401 {
+            return RetryAction::NoActionIndicated;
+        }
+        let Some(challenge) =
+            DigestChallenge::select(response.headers().get_all("www-authenticate"))
+        else {
+            return RetryAction::NoActionIndicated;
+        };
+        let state = self.state.lock().unwrap();
+        if state
+            .pending_retries
+            .iter()
+            .any(|pending| pending == &challenge.nonce)
+        {
+            // Answering a challenge isn't a failure, so retry immediately
+            RetryAction::retryable_error_with_explicit_delay(ErrorKind::ClientError, Duration::ZERO)
+        } else {
+            RetryAction::NoActionIndicated
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "DigestAuthRetryClassifier"
     }
 }
 
@@ -432,4 +945,279 @@ mod tests {
             request.headers().get("Authorization").unwrap()
         );
     }
+
+    const RFC_7616_CHALLENGES: &str =
+        "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+        algorithm=SHA-256, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+        opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\", \
+        Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", algorithm=MD5, \
+        nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+        opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"";
+    const RFC_7616_CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn rfc_7616_challenge(algorithm: DigestAlgorithm) -> DigestChallenge {
+        DigestChallenge {
+            realm: "http-auth@example.org".into(),
+            nonce: "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v".into(),
+            opaque: Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS".into()),
+            algorithm,
+            qop_auth: true,
+            stale: false,
+            userhash: false,
+        }
+    }
+
+    #[test]
+    fn test_digest_auth_rfc_7616_examples() {
+        let login = Login::new("Mufasa", "Circle of Life", None);
+        assert_eq!(
+            "Digest username=\"Mufasa\", realm=\"http-auth@example.org\", uri=\"/dir/index.html\", \
+             algorithm=SHA-256, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", nc=00000001, \
+             cnonce=\"f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ\", qop=auth, \
+             response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\", \
+             opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"",
+            rfc_7616_challenge(DigestAlgorithm::Sha256).authorization(
+                &login,
+                "GET",
+                "/dir/index.html",
+                1,
+                RFC_7616_CNONCE
+            )
+        );
+        assert!(rfc_7616_challenge(DigestAlgorithm::Md5)
+            .authorization(&login, "GET", "/dir/index.html", 1, RFC_7616_CNONCE)
+            .contains("response=\"8ca523f5e9506fed4657c9700eebdbec\""));
+    }
+
+    #[test]
+    fn test_digest_auth_session_algorithms_and_userhash() {
+        let login = Login::new("Mufasa", "Circle of Life", None);
+        let algorithm = DigestAlgorithm::Sha256Sess;
+        let mut challenge = rfc_7616_challenge(algorithm);
+        challenge.userhash = true;
+        let authorization =
+            challenge.authorization(&login, "GET", "/dir/index.html", 1, RFC_7616_CNONCE);
+        let ha1 = algorithm.hash(&format!(
+            "{}:7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v:{RFC_7616_CNONCE}",
+            algorithm.hash("Mufasa:http-auth@example.org:Circle of Life")
+        ));
+        let ha2 = algorithm.hash("GET:/dir/index.html");
+        let response = algorithm.hash(&format!(
+            "{ha1}:7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v:00000001:{RFC_7616_CNONCE}:auth:{ha2}"
+        ));
+        assert!(authorization.contains("algorithm=SHA-256-sess"));
+        assert!(authorization.contains(&format!("response=\"{response}\"")));
+        assert!(authorization.contains(&format!(
+            "username=\"{}\"",
+            algorithm.hash("Mufasa:http-auth@example.org")
+        )));
+        assert!(authorization.ends_with(", userhash=true"));
+    }
+
+    #[test]
+    fn test_digest_auth_challenge_parsing() {
+        assert_eq!(
+            Some(rfc_7616_challenge(DigestAlgorithm::Sha256)),
+            DigestChallenge::select([RFC_7616_CHALLENGES].into_iter())
+        );
+        // challenges may be split across headers, mixed with other schemes, and out of order
+        let challenge = DigestChallenge::select(
+            [
+                "Basic realm=\"foo\", Bearer abc/def==",
+                "Digest realm=\"a \\\"quoted\\\" realm\", nonce=xyz, algorithm=md5-sess, qop=auth, stale=TRUE",
+                "Digest realm=other, nonce=abc, algorithm=SHA-512-256, qop=auth",
+            ]
+            .into_iter(),
+        )
+        .expect("a supported challenge");
+        assert_eq!("a \"quoted\" realm", challenge.realm);
+        assert_eq!("xyz", challenge.nonce);
+        assert_eq!(DigestAlgorithm::Md5Sess, challenge.algorithm);
+        assert!(challenge.stale);
+        // without an algorithm, MD5 is used, and without a qop, the legacy response is computed
+        let challenge = DigestChallenge::select(["Digest realm=r, nonce=n"].into_iter()).unwrap();
+        assert_eq!(DigestAlgorithm::Md5, challenge.algorithm);
+        assert!(!challenge.qop_auth);
+        let login = Login::new("user", "pass", None);
+        let authorization = challenge.authorization(&login, "GET", "/", 1, "unused");
+        assert!(!authorization.contains("cnonce"));
+        let expected = DigestAlgorithm::Md5.hash(&format!(
+            "{}:n:{}",
+            DigestAlgorithm::Md5.hash("user:r:pass"),
+            DigestAlgorithm::Md5.hash("GET:/")
+        ));
+        assert!(authorization.contains(&format!("response=\"{expected}\"")));
+
+        // unsupported challenges are ignored
+        for unsupported in [
+            "Digest realm=r, nonce=n, qop=auth-int",
+            "Digest realm=r, nonce=n, algorithm=SHA-512-256",
+            "Digest realm=r, nonce=n, algorithm=MD5-sess",
+            "Digest realm=r",
+            "Basic realm=r",
+        ] {
+            assert_eq!(
+                None,
+                DigestChallenge::select([unsupported].into_iter()),
+                "{unsupported}"
+            );
+        }
+    }
+
+    fn sign_digest(scheme: &DigestAuthScheme, identity: &Identity) -> HttpRequest {
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let config_bag = ConfigBag::base();
+        let mut request: HttpRequest = http_02x::Request::builder()
+            .method("GET")
+            .uri("http://example.com/dir/index.html?a=b")
+            .body(SdkBody::empty())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        scheme
+            .signer()
+            .sign_http_request(
+                &mut request,
+                identity,
+                AuthSchemeEndpointConfig::empty(),
+                &runtime_components,
+                &config_bag,
+            )
+            .expect("success");
+        request
+    }
+
+    #[test]
+    fn test_digest_auth_signing_tracks_nonce_count() {
+        let scheme = DigestAuthScheme::new();
+        let identity = Identity::new(Login::new("Mufasa", "Circle of Life", None), None);
+        let request = sign_digest(&scheme, &identity);
+        assert!(request.headers().get("Authorization").is_none());
+
+        scheme
+            .signer
+            .state
+            .lock()
+            .unwrap()
+            .set_challenge(rfc_7616_challenge(DigestAlgorithm::Sha256));
+        for nc in ["00000001", "00000002"] {
+            let request = sign_digest(&scheme, &identity);
+            let authorization = request.headers().get("Authorization").unwrap();
+            assert!(authorization.starts_with("Digest username=\"Mufasa\""));
+            assert!(authorization.contains("uri=\"/dir/index.html?a=b\""));
+            assert!(authorization.contains(&format!("nc={nc}")));
+        }
+
+        let err = scheme
+            .signer()
+            .sign_http_request(
+                &mut HttpRequest::empty(),
+                &Identity::new(Token::new("some-token", None), None),
+                AuthSchemeEndpointConfig::empty(),
+                &RuntimeComponentsBuilder::for_tests().build().unwrap(),
+                &ConfigBag::base(),
+            )
+            .expect_err("a login is required");
+        assert_eq!(
+            "HTTP digest auth requires a `Login` identity",
+            err.to_string()
+        );
+    }
+
+    /// Runs the digest auth interceptor and retry classifier over a single attempt
+    fn digest_attempt(
+        scheme: &DigestAuthScheme,
+        request: HttpRequest,
+        status: u16,
+        www_authenticate: &str,
+    ) -> RetryAction {
+        use aws_smithy_runtime_api::client::interceptors::context::Input;
+        use aws_smithy_types::config_bag::Layer;
+
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let mut cfg = ConfigBag::of_layers(vec![Layer::new("test")]);
+        let interceptor = scheme.interceptor();
+        let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+        ctx.enter_serialization_phase();
+        ctx.set_request(request);
+        let _ = ctx.take_input();
+        ctx.enter_before_transmit_phase();
+        interceptor
+            .read_before_transmit(&(&ctx).into(), &runtime_components, &mut cfg)
+            .unwrap();
+        ctx.enter_transmit_phase();
+        let _ = ctx.take_request();
+        ctx.set_response(
+            http_02x::Response::builder()
+                .status(status)
+                .header("WWW-Authenticate", www_authenticate)
+                .body(SdkBody::empty())
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        ctx.enter_before_deserialization_phase();
+        interceptor
+            .read_before_deserialization(&(&ctx).into(), &runtime_components, &mut cfg)
+            .unwrap();
+        scheme.retry_classifier().classify_retry(&ctx)
+    }
+
+    #[test]
+    fn test_digest_auth_retry_flow() {
+        let scheme = DigestAuthScheme::new();
+        let identity = Identity::new(Login::new("Mufasa", "Circle of Life", None), None);
+        let retry = RetryAction::retryable_error_with_explicit_delay(
+            ErrorKind::ClientError,
+            Duration::ZERO,
+        );
+
+        // the first request is sent without credentials and is retried with the challenge
+        let request = sign_digest(&scheme, &identity);
+        assert_eq!(
+            retry,
+            digest_attempt(&scheme, request, 401, RFC_7616_CHALLENGES)
+        );
+
+        // a signed request rejected because of its credentials is not retried
+        let request = sign_digest(&scheme, &identity);
+        assert!(request.headers().get("Authorization").is_some());
+        assert_eq!(
+            RetryAction::NoActionIndicated,
+            digest_attempt(
+                &scheme,
+                request,
+                401,
+                &RFC_7616_CHALLENGES.replace("7ypf", "8ypf")
+            )
+        );
+
+        // but a signed request rejected because of a stale nonce is
+        let request = sign_digest(&scheme, &identity);
+        assert!(request
+            .headers()
+            .get("Authorization")
+            .unwrap()
+            .contains("8ypf"));
+        assert_eq!(
+            retry,
+            digest_attempt(
+                &scheme,
+                request,
+                401,
+                "Digest realm=\"http-auth@example.org\", nonce=fresh, qop=auth, stale=true"
+            )
+        );
+        let request = sign_digest(&scheme, &identity);
+        let authorization = request.headers().get("Authorization").unwrap();
+        assert!(authorization.contains("nonce=\"fresh\""));
+        assert!(authorization.contains("nc=00000001"));
+
+        // successful responses are not retried
+        let request = sign_digest(&scheme, &identity);
+        assert_eq!(
+            RetryAction::NoActionIndicated,
+            digest_attempt(&scheme, request, 200, "")
+        );
+    }
 }
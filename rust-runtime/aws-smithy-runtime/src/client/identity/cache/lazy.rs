@@ -9,8 +9,9 @@ use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
 use aws_smithy_async::time::{SharedTimeSource, TimeSource};
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::identity::{
-    Identity, IdentityCachePartition, IdentityFuture, ResolveCachedIdentity, ResolveIdentity,
-    SharedIdentityCache, SharedIdentityResolver,
+    Identity, IdentityCacheEntry, IdentityCacheEvent, IdentityCachePartition, IdentityFuture,
+    ObserveIdentityCache, ResolveCachedIdentity, ResolveIdentity, SharedIdentityCache,
+    SharedIdentityCacheObserver, SharedIdentityResolver,
 };
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 use aws_smithy_runtime_api::shared::IntoShared;
@@ -22,6 +23,9 @@ use std::sync::RwLock;
 use std::time::Duration;
 use tracing::Instrument;
 
+#[cfg(feature = "rt-tokio")]
+mod eager;
+
 const DEFAULT_LOAD_TIMEOUT: Duration = Duration::from_secs(5);
 const DEFAULT_EXPIRATION: Duration = Duration::from_secs(15 * 60);
 const DEFAULT_BUFFER_TIME: Duration = Duration::from_secs(10);
@@ -36,6 +40,9 @@ pub struct LazyCacheBuilder {
     buffer_time: Option<Duration>,
     buffer_time_jitter_fraction: Option<fn() -> f64>,
     default_expiration: Option<Duration>,
+    observer: Option<SharedIdentityCacheObserver>,
+    #[cfg(feature = "rt-tokio")]
+    eager_refresh: Option<Duration>,
 }
 
 impl LazyCacheBuilder {
@@ -161,6 +168,52 @@ impl LazyCacheBuilder {
         self
     }
 
+    /// Set an observer that is notified when identities are loaded, served from the cache, or invalidated.
+    ///
+    /// This can be used to alert on identities that fail to refresh before they expire.
+    pub fn observer(mut self, observer: impl ObserveIdentityCache + 'static) -> Self {
+        self.set_observer(Some(observer.into_shared()));
+        self
+    }
+
+    /// Set an observer that is notified when identities are loaded, served from the cache, or invalidated.
+    ///
+    /// This can be used to alert on identities that fail to refresh before they expire.
+    pub fn set_observer(&mut self, observer: Option<SharedIdentityCacheObserver>) -> &mut Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Refresh cached identities in the background before they expire.
+    ///
+    /// Without this, an identity is reloaded by the first request that finds it within the
+    /// [buffer time](Self::buffer_time), and that request waits for the reload. With eager refresh,
+    /// a background task reloads the identity `refresh_window` before that point (plus a random jitter
+    /// of up to half the refresh window), and requests keep receiving the previous identity in the meantime.
+    /// Failed refreshes are retried with exponential backoff until the identity goes stale, after which
+    /// the next request loads it as usual.
+    ///
+    /// The background tasks are spawned onto the current Tokio runtime, use the configured sleep
+    /// implementation, and are stopped when the cache is dropped.
+    ///
+    /// Disabled by default.
+    #[cfg(feature = "rt-tokio")]
+    pub fn eager_refresh(mut self, refresh_window: Duration) -> Self {
+        self.set_eager_refresh(Some(refresh_window));
+        self
+    }
+
+    /// Refresh cached identities in the background before they expire.
+    ///
+    /// See [`eager_refresh`](Self::eager_refresh) for details.
+    ///
+    /// Disabled by default.
+    #[cfg(feature = "rt-tokio")]
+    pub fn set_eager_refresh(&mut self, refresh_window: Option<Duration>) -> &mut Self {
+        self.eager_refresh = refresh_window;
+        self
+    }
+
     /// Builds a [`SharedIdentityCache`] from this builder.
     ///
     /// # Panics
@@ -172,14 +225,17 @@ impl LazyCacheBuilder {
             default_expiration >= DEFAULT_EXPIRATION,
             "default_expiration must be at least 15 minutes"
         );
-        LazyCache::new(
+        let cache = LazyCache::new(
             self.load_timeout.unwrap_or(DEFAULT_LOAD_TIMEOUT),
             self.buffer_time.unwrap_or(DEFAULT_BUFFER_TIME),
             self.buffer_time_jitter_fraction
                 .unwrap_or(DEFAULT_BUFFER_TIME_JITTER_FRACTION),
             default_expiration,
         )
-        .into_shared()
+        .with_observer(self.observer);
+        #[cfg(feature = "rt-tokio")]
+        let cache = cache.with_eager_refresh(self.eager_refresh);
+        cache.into_shared()
     }
 }
 
@@ -200,7 +256,7 @@ impl CachePartitions {
     fn partition(&self, key: IdentityCachePartition) -> ExpiringCache<Identity, BoxError> {
         let mut partition = self.partitions.read().unwrap().get(&key).cloned();
         // Add the partition to the cache if it doesn't already exist.
-        // Partitions will never be removed.
+        // Partitions are only removed when they are invalidated.
         if partition.is_none() {
             let mut partitions = self.partitions.write().unwrap();
             // Another thread could have inserted the partition before we acquired the lock,
@@ -214,6 +270,21 @@ impl CachePartitions {
         }
         partition.expect("inserted above if not present")
     }
+
+    fn remove(&self, key: IdentityCachePartition) {
+        self.partitions.write().unwrap().remove(&key);
+    }
+
+    fn entries(&self) -> Vec<IdentityCacheEntry> {
+        let partitions = self.partitions.read().unwrap();
+        let mut entries: Vec<_> = partitions
+            .iter()
+            // Partitions that are being written to are skipped rather than waited on
+            .filter_map(|(key, cache)| Some(IdentityCacheEntry::new(*key, &cache.try_get()?)))
+            .collect();
+        entries.sort_by_key(|entry| entry.partition());
+        entries
+    }
 }
 
 #[derive(Debug)]
@@ -223,6 +294,11 @@ struct LazyCache {
     buffer_time: Duration,
     buffer_time_jitter_fraction: fn() -> f64,
     default_expiration: Duration,
+    observer: Option<SharedIdentityCacheObserver>,
+    #[cfg(feature = "rt-tokio")]
+    eager_refresh: Option<Duration>,
+    #[cfg(feature = "rt-tokio")]
+    refresh_tasks: eager::RefreshTasks,
 }
 
 impl LazyCache {
@@ -238,8 +314,30 @@ impl LazyCache {
             buffer_time,
             buffer_time_jitter_fraction,
             default_expiration,
+            observer: None,
+            #[cfg(feature = "rt-tokio")]
+            eager_refresh: None,
+            #[cfg(feature = "rt-tokio")]
+            refresh_tasks: Default::default(),
         }
     }
+
+    fn with_observer(mut self, observer: Option<SharedIdentityCacheObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    fn with_eager_refresh(mut self, refresh_window: Option<Duration>) -> Self {
+        self.eager_refresh = refresh_window;
+        self
+    }
+}
+
+fn notify(observer: Option<&SharedIdentityCacheObserver>, event: IdentityCacheEvent<'_>) {
+    if let Some(observer) = observer {
+        observer.on_event(&event);
+    }
 }
 
 macro_rules! required_err {
@@ -290,6 +388,8 @@ impl ResolveCachedIdentity for LazyCache {
         Ok(())
     }
 
+    // `result` is only inspected before being returned when eager refresh is available
+    #[cfg_attr(not(feature = "rt-tokio"), allow(clippy::let_and_return))]
     fn resolve_cached_identity<'a>(
         &'a self,
         resolver: SharedIdentityResolver,
@@ -307,16 +407,35 @@ impl ResolveCachedIdentity for LazyCache {
         let partition = resolver.cache_partition();
         let cache = self.partitions.partition(partition);
         let default_expiration = self.default_expiration;
+        #[cfg(feature = "rt-tokio")]
+        let eager_refresh = self.eager_refresh.map(|refresh_window| {
+            let settings = eager::RefreshSettings {
+                refresh_window,
+                load_timeout,
+                buffer_time: self.buffer_time,
+                buffer_time_jitter_fraction: self.buffer_time_jitter_fraction,
+                default_expiration,
+                observer: self.observer.clone(),
+            };
+            (settings, resolver.clone(), cache.clone())
+        });
 
         IdentityFuture::new(async move {
             // Attempt to get cached identity, or clear the cache if they're expired
-            if let Some(identity) = cache.yield_or_clear_if_expired(now).await {
+            let result = if let Some(identity) = cache.yield_or_clear_if_expired(now).await {
                 tracing::debug!(
                     buffer_time=?self.buffer_time,
                     cached_expiration=?identity.expiration(),
                     now=?now,
                     "loaded identity from cache"
                 );
+                notify(
+                    self.observer.as_ref(),
+                    IdentityCacheEvent::CacheHit {
+                        partition,
+                        expiration: identity.expiration(),
+                    },
+                );
                 Ok(identity)
             } else {
                 // If we didn't get identity from the cache, then we need to try and load.
@@ -328,19 +447,32 @@ impl ResolveCachedIdentity for LazyCache {
                     .get_or_load(|| {
                         let span = tracing::info_span!("lazy_load_identity");
                         async move {
+                            notify(
+                                self.observer.as_ref(),
+                                IdentityCacheEvent::LoadStarted { partition },
+                            );
                             let fut = Timeout::new(
                                 resolver.resolve_identity(runtime_components, config_bag),
                                 timeout_future,
                             );
-                            let identity = match fut.await {
-                                Ok(result) => result?,
-                                Err(_err) => match resolver.fallback_on_interrupt() {
-                                    Some(identity) => identity,
-                                    None => {
-                                        return Err(BoxError::from(TimedOutError(load_timeout)))
-                                    }
-                                },
+                            let result = match fut.await {
+                                Ok(result) => result,
+                                Err(_err) => resolver
+                                    .fallback_on_interrupt()
+                                    .ok_or_else(|| BoxError::from(TimedOutError(load_timeout))),
                             };
+                            notify(
+                                self.observer.as_ref(),
+                                IdentityCacheEvent::LoadFinished {
+                                    partition,
+                                    result: result.as_ref(),
+                                    elapsed: time_source
+                                        .now()
+                                        .duration_since(start_time)
+                                        .unwrap_or_default(),
+                                },
+                            );
+                            let identity = result?;
                             // If the identity don't have an expiration time, then create a default one
                             let expiration =
                                 identity.expiration().unwrap_or(now + default_expiration);
@@ -371,9 +503,36 @@ impl ResolveCachedIdentity for LazyCache {
                     .await;
                 tracing::debug!("loaded identity");
                 result
+            };
+            #[cfg(feature = "rt-tokio")]
+            if let (Ok(_), Some((settings, resolver, cache))) = (&result, eager_refresh) {
+                self.refresh_tasks.ensure_running(
+                    partition,
+                    cache,
+                    resolver,
+                    runtime_components,
+                    config_bag,
+                    settings,
+                );
             }
+            result
         })
     }
+
+    fn cached_identities(&self) -> Vec<IdentityCacheEntry> {
+        self.partitions.entries()
+    }
+
+    fn invalidate(&self, partition: IdentityCachePartition) {
+        self.partitions.remove(partition);
+        #[cfg(feature = "rt-tokio")]
+        self.refresh_tasks.stop(partition);
+        tracing::debug!(partition = ?partition, "invalidated identity cache partition");
+        notify(
+            self.observer.as_ref(),
+            IdentityCacheEvent::Invalidated { partition },
+        );
+    }
 }
 
 #[derive(Debug)]
@@ -768,4 +927,215 @@ mod tests {
         assert_eq!(1, resolver_a_calls.load(Ordering::Relaxed));
         assert_eq!(1, resolver_b_calls.load(Ordering::Relaxed));
     }
+
+    #[cfg(feature = "rt-tokio")]
+    fn eager_cache(
+        load_list: Vec<Result<Identity, BoxError>>,
+    ) -> (
+        LazyCache,
+        SharedIdentityResolver,
+        aws_smithy_async::test_util::tick_advance_sleep::TickAdvanceTime,
+        RuntimeComponents,
+    ) {
+        let (time, sleep) =
+            aws_smithy_async::test_util::tick_advance_sleep::tick_advance_time_and_sleep();
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time.clone()))
+            .with_sleep_impl(Some(sleep))
+            .build()
+            .unwrap();
+        let (cache, resolver) = test_cache(BUFFER_TIME_NO_JITTER, load_list);
+        let cache = cache.with_eager_refresh(Some(Duration::from_secs(60)));
+        (cache, resolver, time, components)
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn eager_refresh_before_expiry() {
+        let (cache, resolver, time, components) =
+            eager_cache(vec![Ok(test_identity(1000)), Ok(test_identity(2000))]);
+
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        // The refresh is due 60 seconds before the 10 second buffer time
+        time.tick(Duration::from_secs(929)).await;
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        time.tick(Duration::from_secs(1)).await;
+        // The second identity was loaded in the background, so the resolver isn't called again
+        // (it would panic since there are no more identities)
+        expect_identity(2000, &cache, &components, resolver.clone()).await;
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn eager_refresh_retries_with_backoff() {
+        let (cache, resolver, time, components) = eager_cache(vec![
+            Ok(test_identity(1000)),
+            Err("failed".into()),
+            Err("failed".into()),
+            Ok(test_identity(2000)),
+        ]);
+
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        // First attempt at 930 fails and is retried after 1 second, then after 2 more seconds
+        time.tick(Duration::from_secs(931)).await;
+        // The previous identity is still served while the refresh is being retried
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        time.tick(Duration::from_secs(2)).await;
+        expect_identity(2000, &cache, &components, resolver.clone()).await;
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn eager_refresh_gives_up_when_identity_goes_stale() {
+        let (cache, resolver, time, components) = eager_cache(vec![
+            Ok(test_identity(1000)),
+            Err("failed".into()),
+            Err("failed".into()),
+            Err("failed".into()),
+            Err("failed".into()),
+            Err("failed".into()),
+            Err("failed".into()),
+            Ok(test_identity(2000)),
+        ]);
+
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        // Attempts at 930, 931, 933, 937, 945 and 961 fail, and the next retry
+        // would be after the identity goes stale at 990
+        time.tick(Duration::from_secs(990)).await;
+        // The identity is now loaded by the request instead
+        expect_identity(2000, &cache, &components, resolver.clone()).await;
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn eager_refresh_stops_when_cache_is_dropped() {
+        #[derive(Debug)]
+        struct Resolver {
+            _liveness: Arc<()>,
+        }
+        impl ResolveIdentity for Resolver {
+            fn resolve_identity<'a>(
+                &'a self,
+                _: &'a RuntimeComponents,
+                _config_bag: &'a ConfigBag,
+            ) -> IdentityFuture<'a> {
+                IdentityFuture::ready(Ok(test_identity(1000)))
+            }
+        }
+
+        let (_, _, _, components) = eager_cache(Vec::new());
+        let liveness = Arc::new(());
+        let resolver = SharedIdentityResolver::new(Resolver {
+            _liveness: liveness.clone(),
+        });
+        let cache = LazyCache::new(
+            DEFAULT_LOAD_TIMEOUT,
+            DEFAULT_BUFFER_TIME,
+            BUFFER_TIME_NO_JITTER,
+            DEFAULT_EXPIRATION,
+        )
+        .with_eager_refresh(Some(Duration::from_secs(60)));
+
+        expect_identity(1000, &cache, &components, resolver).await;
+        tokio::task::yield_now().await;
+        // The background task holds on to the resolver
+        assert_eq!(2, Arc::strong_count(&liveness));
+
+        drop(cache);
+        tokio::task::yield_now().await;
+        assert_eq!(1, Arc::strong_count(&liveness));
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordingObserver(Arc<Mutex<Vec<String>>>);
+    impl ObserveIdentityCache for RecordingObserver {
+        fn on_event(&self, event: &IdentityCacheEvent<'_>) {
+            let event = match event {
+                IdentityCacheEvent::LoadStarted { .. } => "load started".to_string(),
+                IdentityCacheEvent::LoadFinished { result, .. } => match result {
+                    Ok(identity) => format!("loaded {:?}", identity.expiration().unwrap()),
+                    Err(err) => format!("load failed: {err}"),
+                },
+                IdentityCacheEvent::CacheHit { expiration, .. } => {
+                    format!("cache hit {:?}", expiration.unwrap())
+                }
+                IdentityCacheEvent::Invalidated { .. } => "invalidated".to_string(),
+                _ => unreachable!(),
+            };
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn observer_is_notified() {
+        let config_bag = ConfigBag::base();
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time.clone()))
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap();
+        let (cache, resolver) = test_cache(
+            BUFFER_TIME_NO_JITTER,
+            vec![Ok(test_identity(1000)), Err("failed".into())],
+        );
+        let observer = RecordingObserver::default();
+        let cache = cache.with_observer(Some(SharedIdentityCacheObserver::new(observer.clone())));
+
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        time.set_time(epoch_secs(1500));
+        assert!(cache
+            .resolve_cached_identity(resolver.clone(), &components, &config_bag)
+            .await
+            .is_err());
+        cache.invalidate(resolver.cache_partition());
+
+        assert_eq!(
+            vec![
+                "load started".to_string(),
+                format!("loaded {:?}", epoch_secs(1000)),
+                format!("cache hit {:?}", epoch_secs(1000)),
+                "load started".to_string(),
+                "load failed: failed".to_string(),
+                "invalidated".to_string(),
+            ],
+            *observer.0.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_identities_and_invalidation() {
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time.clone()))
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap();
+        let (cache, resolver) = test_cache(
+            BUFFER_TIME_NO_JITTER,
+            vec![
+                Ok(test_identity(1000).with_provider_name("TestProvider")),
+                Ok(test_identity(2000)),
+            ],
+        );
+        assert!(cache.cached_identities().is_empty());
+
+        expect_identity(1000, &cache, &components, resolver.clone()).await;
+        let entries = cache.cached_identities();
+        assert_eq!(1, entries.len());
+        assert_eq!(resolver.cache_partition(), entries[0].partition());
+        assert_eq!(Some(epoch_secs(1000)), entries[0].expiration());
+        assert_eq!(Some("TestProvider"), entries[0].provider_name());
+
+        // Invalidating the partition causes the next request to load a new identity
+        // even though the cached one hasn't expired
+        cache.invalidate(resolver.cache_partition());
+        assert!(cache.cached_identities().is_empty());
+        expect_identity(2000, &cache, &components, resolver.clone()).await;
+        assert_eq!(
+            Some(epoch_secs(2000)),
+            cache.cached_identities()[0].expiration()
+        );
+    }
 }
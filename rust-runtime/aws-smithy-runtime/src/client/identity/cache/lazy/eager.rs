@@ -0,0 +1,219 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Background refresh for the lazy identity cache.
+
+use super::{notify, TimedOutError};
+use crate::client::identity::IdentityCache;
+use crate::expiring_cache::ExpiringCache;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::identity::{
+    Identity, IdentityCacheEvent, IdentityCachePartition, ResolveIdentity,
+    SharedIdentityCacheObserver, SharedIdentityResolver,
+};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::DateTime;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Settings for background refresh, copied out of the cache so that tasks don't borrow it.
+#[derive(Clone, Debug)]
+pub(super) struct RefreshSettings {
+    pub(super) refresh_window: Duration,
+    pub(super) load_timeout: Duration,
+    pub(super) buffer_time: Duration,
+    pub(super) buffer_time_jitter_fraction: fn() -> f64,
+    pub(super) default_expiration: Duration,
+    pub(super) observer: Option<SharedIdentityCacheObserver>,
+}
+
+/// Background refresh tasks, keyed by cache partition.
+///
+/// All tasks are aborted when this is dropped, which happens when the cache is dropped along with
+/// the client that owns it.
+#[derive(Debug, Default)]
+pub(super) struct RefreshTasks {
+    tasks: Mutex<HashMap<IdentityCachePartition, JoinHandle<()>>>,
+}
+
+impl RefreshTasks {
+    /// Spawns a refresh task for the given partition unless one is already running.
+    pub(super) fn ensure_running(
+        &self,
+        partition: IdentityCachePartition,
+        cache: ExpiringCache<Identity, BoxError>,
+        resolver: SharedIdentityResolver,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+        settings: RefreshSettings,
+    ) {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks
+            .get(&partition)
+            .map_or(false, |task| !task.is_finished())
+        {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::debug!("not running in a Tokio runtime; identities will not be refreshed in the background");
+            return;
+        };
+        // The runtime components hold on to this identity cache. Swap it out so that the task
+        // doesn't keep the cache alive, since that would prevent the task from ever being aborted.
+        let runtime_components = runtime_components
+            .to_builder()
+            .with_identity_cache(Some(IdentityCache::no_cache()))
+            .build()
+            .expect("the runtime components were already validated");
+        let task = runtime.spawn(
+            refresh(
+                partition,
+                cache,
+                resolver,
+                runtime_components,
+                config_bag.snapshot(),
+                settings,
+            )
+            .instrument(tracing::debug_span!("eager_identity_refresh", partition = ?partition)),
+        );
+        tasks.insert(partition, task);
+    }
+}
+
+impl RefreshTasks {
+    /// Stops the refresh task for the given partition, if there is one.
+    pub(super) fn stop(&self, partition: IdentityCachePartition) {
+        if let Some(task) = self.tasks.lock().unwrap().remove(&partition) {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for RefreshTasks {
+    fn drop(&mut self) {
+        for task in self.tasks.get_mut().unwrap().values() {
+            task.abort();
+        }
+    }
+}
+
+/// Refreshes the cached identity ahead of its expiration for as long as there is one to refresh.
+///
+/// If the identity can't be refreshed before it goes stale, the task exits and the next request
+/// loads the identity the same way it would without eager refresh.
+async fn refresh(
+    partition: IdentityCachePartition,
+    cache: ExpiringCache<Identity, BoxError>,
+    resolver: SharedIdentityResolver,
+    runtime_components: RuntimeComponents,
+    config_bag: ConfigBag,
+    settings: RefreshSettings,
+) {
+    let (time_source, sleep_impl) = (
+        runtime_components.time_source().expect("validated"),
+        runtime_components.sleep_impl().expect("validated"),
+    );
+    while let Some(expiration) = cache.expiration().await {
+        // This is the point at which the cache stops returning the identity
+        let stale_at = expiration - settings.buffer_time;
+        let jitter = settings
+            .refresh_window
+            .mul_f64((settings.buffer_time_jitter_fraction)());
+        let refresh_at = stale_at - settings.refresh_window - jitter;
+        match refresh_at.duration_since(time_source.now()) {
+            Ok(delay) if !delay.is_zero() => sleep_impl.sleep(delay).await,
+            // The identity doesn't live longer than the refresh window, so leave it to the cache
+            _ => return,
+        }
+
+        let mut backoff = INITIAL_RETRY_BACKOFF;
+        loop {
+            match load(
+                &resolver,
+                partition,
+                &runtime_components,
+                &config_bag,
+                &settings,
+                &time_source,
+                &sleep_impl,
+            )
+            .await
+            {
+                Ok((identity, expiration)) => {
+                    tracing::debug!(
+                        new_expiration = %DateTime::from(expiration),
+                        "refreshed identity in the background"
+                    );
+                    cache.set(identity, expiration).await;
+                    break;
+                }
+                Err(err) if time_source.now() + backoff < stale_at => {
+                    tracing::debug!(
+                        error = %DisplayErrorContext(err.as_ref()),
+                        "failed to refresh identity in the background; retrying in {backoff:?}"
+                    );
+                    sleep_impl.sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        error = %DisplayErrorContext(err.as_ref()),
+                        "failed to refresh identity in the background; it will be loaded on the next request"
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn load(
+    resolver: &SharedIdentityResolver,
+    partition: IdentityCachePartition,
+    runtime_components: &RuntimeComponents,
+    config_bag: &ConfigBag,
+    settings: &RefreshSettings,
+    time_source: &SharedTimeSource,
+    sleep_impl: &SharedAsyncSleep,
+) -> Result<(Identity, SystemTime), BoxError> {
+    let now = time_source.now();
+    notify(
+        settings.observer.as_ref(),
+        IdentityCacheEvent::LoadStarted { partition },
+    );
+    let result = Timeout::new(
+        resolver.resolve_identity(runtime_components, config_bag),
+        sleep_impl.sleep(settings.load_timeout),
+    )
+    .await
+    .unwrap_or_else(|_err| Err(TimedOutError(settings.load_timeout).into()));
+    notify(
+        settings.observer.as_ref(),
+        IdentityCacheEvent::LoadFinished {
+            partition,
+            result: result.as_ref(),
+            elapsed: time_source.now().duration_since(now).unwrap_or_default(),
+        },
+    );
+    let identity = result?;
+    let expiration = identity
+        .expiration()
+        .unwrap_or(now + settings.default_expiration);
+    let jitter = settings
+        .buffer_time
+        .mul_f64((settings.buffer_time_jitter_fraction)());
+    Ok((identity, expiration + jitter))
+}
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::client::auth::custom::auth_scheme_order_override;
 use crate::client::auth::no_auth::NO_AUTH_SCHEME_ID;
 use crate::client::identity::IdentityCache;
 use aws_smithy_runtime_api::box_error::BoxError;
@@ -118,7 +119,10 @@ pub(super) async fn orchestrate_auth(
         .load::<AuthSchemeOptionResolverParams>()
         .expect("auth scheme option resolver params must be set");
     let option_resolver = runtime_components.auth_scheme_option_resolver();
-    let options = option_resolver.resolve_auth_scheme_options(params)?;
+    let options = match auth_scheme_order_override(cfg) {
+        Some(order) => Cow::Borrowed(order),
+        None => option_resolver.resolve_auth_scheme_options(params)?,
+    };
     let endpoint = cfg
         .load::<Endpoint>()
         .expect("endpoint added to config bag by endpoint orchestrator");
@@ -621,6 +625,58 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn auth_scheme_order_override_replaces_resolved_options() {
+        use crate::client::auth::custom::CustomAuthScheme;
+        use aws_smithy_runtime_api::client::orchestrator::Metadata;
+        use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+
+        const CUSTOM_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("custom-scheme");
+
+        let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+        ctx.enter_serialization_phase();
+        ctx.set_request(HttpRequest::empty());
+        let _ = ctx.take_input();
+        ctx.enter_before_transmit_phase();
+
+        let plugin = CustomAuthScheme::builder(CUSTOM_SCHEME_ID)
+            .identity_fn(|| async { Ok(Identity::new("doesntmatter", None)) })
+            .signer(|request, _, _, _| {
+                request.headers_mut().insert("Authorization", "custom");
+                Ok(())
+            })
+            .auth_scheme_order_for_operation("GetWidget", [CUSTOM_SCHEME_ID])
+            .build();
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .merge_from(&plugin.runtime_components(&RuntimeComponentsBuilder::for_tests()))
+            .with_auth_scheme_option_resolver(Some(SharedAuthSchemeOptionResolver::new(
+                StaticAuthSchemeOptionResolver::new(vec![AuthSchemeId::new("modeled-scheme")]),
+            )))
+            .build()
+            .unwrap();
+
+        let mut layer = Layer::new("test");
+        layer.store_put(Metadata::new("GetWidget", "test-service"));
+        layer.store_put(Endpoint::builder().url("dontcare").build());
+        layer.store_put(AuthSchemeOptionResolverParams::new("doesntmatter"));
+        let mut config_bag = ConfigBag::base();
+        config_bag
+            .push_shared_layer(plugin.config().unwrap())
+            .push_layer(layer);
+
+        orchestrate_auth(&mut ctx, &runtime_components, &config_bag)
+            .await
+            .expect("success");
+        assert_eq!(
+            "custom",
+            ctx.request()
+                .expect("request is set")
+                .headers()
+                .get("Authorization")
+                .unwrap()
+        );
+    }
+
     #[test]
     fn friendly_error_messages() {
         let err = NoMatchingAuthSchemeError(ExploredList::default());
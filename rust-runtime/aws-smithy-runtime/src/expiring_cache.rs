@@ -74,6 +74,36 @@ where
         future.await.map(|(value, _expiry)| value.clone())
     }
 
+    /// Returns the expiration time of the cached value, if there is one.
+    #[cfg(feature = "rt-tokio")]
+    pub async fn expiration(&self) -> Option<SystemTime> {
+        self.value
+            .read()
+            .await
+            .get()
+            .map(|(_value, expiry)| *expiry)
+    }
+
+    /// Replaces the cached value, regardless of whether the current one has expired.
+    ///
+    /// Callers keep receiving the previous value until this completes, which allows a value to be
+    /// refreshed ahead of its expiration without blocking readers.
+    #[cfg(feature = "rt-tokio")]
+    pub async fn set(&self, value: T, expiration: SystemTime) {
+        *self.value.write().await = OnceCell::new_with(Some((value, expiration)));
+    }
+
+    /// Returns the cached value without waiting, regardless of whether it has expired.
+    ///
+    /// Returns `None` if there is no value, or if the cache is currently being written to.
+    pub fn try_get(&self) -> Option<T> {
+        self.value
+            .try_read()
+            .ok()?
+            .get()
+            .map(|(value, _expiry)| value.clone())
+    }
+
     /// If the value is expired, clears the cache. Otherwise, yields the current value.
     pub async fn yield_or_clear_if_expired(&self, now: SystemTime) -> Option<T> {
         // Short-circuit if the value is not expired
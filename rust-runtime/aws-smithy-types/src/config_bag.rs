@@ -572,6 +572,17 @@ impl ConfigBag {
         self
     }
 
+    /// Create a new config bag that shares the frozen layers of this bag.
+    ///
+    /// The interceptor state is not carried over, so the returned bag starts with an empty
+    /// interceptor state. This is useful for work that outlives a single operation.
+    pub fn snapshot(&self) -> ConfigBag {
+        ConfigBag {
+            interceptor_state: Layer::new("interceptor_state"),
+            tail: self.tail.clone(),
+        }
+    }
+
     /// Return a reference to the mutable interceptor state.
     pub fn interceptor_state(&mut self) -> &mut Layer {
         &mut self.interceptor_state
@@ -895,6 +906,23 @@ mod test {
         assert_eq!(bag.load::<Interceptor>().count(), 0);
     }
 
+    #[test]
+    fn snapshot_drops_interceptor_state() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Prop(u32);
+        impl Storable for Prop {
+            type Storer = StoreReplace<Self>;
+        }
+        let mut layer = Layer::new("frozen");
+        layer.store_put(Prop(1));
+        let mut bag = ConfigBag::of_layers(vec![layer]);
+        bag.interceptor_state().store_put(Prop(2));
+        assert_eq!(Some(&Prop(2)), bag.load::<Prop>());
+
+        let snapshot = bag.snapshot();
+        assert_eq!(Some(&Prop(1)), snapshot.load::<Prop>());
+    }
+
     #[test]
     fn store_append_many_layers() {
         #[derive(Debug, PartialEq, Eq, Clone)]
@@ -18,6 +18,28 @@ impl<T, E> EventReceiver<T, E> {
         Self { inner }
     }
 
+    /// Sets the largest message frame, in bytes, that will be received.
+    ///
+    /// See [`Receiver::with_max_frame_size`].
+    pub fn with_max_frame_size(self, max_frame_size: u32) -> Self {
+        Self::new(self.inner.with_max_frame_size(max_frame_size))
+    }
+
+    /// Sets the largest number of headers a received message can have.
+    ///
+    /// See [`Receiver::with_max_header_count`].
+    pub fn with_max_header_count(self, max_header_count: usize) -> Self {
+        Self::new(self.inner.with_max_header_count(max_header_count))
+    }
+
+    /// Sets the largest amount of data, in bytes, that will be buffered while waiting for a
+    /// complete message frame.
+    ///
+    /// See [`Receiver::with_max_buffered_bytes`].
+    pub fn with_max_buffered_bytes(self, max_buffered_bytes: usize) -> Self {
+        Self::new(self.inner.with_max_buffered_bytes(max_buffered_bytes))
+    }
+
     /// Asynchronously tries to receive an event from the stream. If the stream has ended, it
     /// returns an `Ok(None)`. If there is a transport layer error, it will return
     /// `Err(SdkError::DispatchFailure)`. Service-modeled errors will be a part of the returned